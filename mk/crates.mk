@@ -84,16 +84,16 @@ DEPS_flate := std native:miniz
 DEPS_arena := std
 DEPS_graphviz := std
 DEPS_glob := std
-DEPS_serialize := std log
+DEPS_serialize := std
 DEPS_rbml := std log serialize
-DEPS_term := std log
+DEPS_term := std log libc
 DEPS_getopts := std
 DEPS_collections := core alloc unicode
 DEPS_num := std
 DEPS_test := std getopts serialize rbml term time regex native:rust_test_helpers
 DEPS_time := std serialize
 DEPS_rand := core
-DEPS_log := std regex
+DEPS_log := std libc regex serialize
 DEPS_regex := std
 DEPS_regex_macros = rustc syntax std regex
 DEPS_fmt_macros = std
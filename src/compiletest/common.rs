@@ -22,7 +22,8 @@ pub enum Mode {
     Pretty,
     DebugInfoGdb,
     DebugInfoLldb,
-    Codegen
+    Codegen,
+    Coverage
 }
 
 impl Copy for Mode {}
@@ -38,6 +39,7 @@ impl FromStr for Mode {
           "debuginfo-lldb" => Some(DebugInfoLldb),
           "debuginfo-gdb" => Some(DebugInfoGdb),
           "codegen" => Some(Codegen),
+          "coverage" => Some(Coverage),
           _ => None,
         }
     }
@@ -54,6 +56,7 @@ impl fmt::Show for Mode {
             DebugInfoGdb => "debuginfo-gdb",
             DebugInfoLldb => "debuginfo-lldb",
             Codegen => "codegen",
+            Coverage => "coverage",
         };
         msg.fmt(f)
     }
@@ -73,6 +76,9 @@ pub struct Config {
     // The clang executable
     pub clang_path: Option<Path>,
 
+    // The gcov executable, for coverage tests
+    pub gcov_path: Option<Path>,
+
     // The llvm binaries path
     pub llvm_bin_path: Option<Path>,
 
@@ -56,6 +56,7 @@ pub fn parse_config(args: Vec<String> ) -> Config {
           reqopt("", "run-lib-path", "path to target shared libraries", "PATH"),
           reqopt("", "rustc-path", "path to rustc to use for compiling", "PATH"),
           optopt("", "clang-path", "path to  executable for codegen tests", "PATH"),
+          optopt("", "gcov-path", "path to gcov executable for coverage tests", "PATH"),
           optopt("", "valgrind-path", "path to Valgrind executable for Valgrind tests", "PROGRAM"),
           optflag("", "force-valgrind", "fail if Valgrind tests cannot be run under Valgrind"),
           optopt("", "llvm-bin-path", "path to directory holding llvm binaries", "DIR"),
@@ -133,6 +134,7 @@ pub fn parse_config(args: Vec<String> ) -> Config {
         run_lib_path: matches.opt_str("run-lib-path").unwrap(),
         rustc_path: opt_path(matches, "rustc-path"),
         clang_path: matches.opt_str("clang-path").map(|s| Path::new(s)),
+        gcov_path: matches.opt_str("gcov-path").map(|s| Path::new(s)),
         valgrind_path: matches.opt_str("valgrind-path"),
         force_valgrind: matches.opt_present("force-valgrind"),
         llvm_bin_path: matches.opt_str("llvm-bin-path").map(|s| Path::new(s)),
@@ -282,9 +284,15 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
         ratchet_metrics: config.ratchet_metrics.clone(),
         ratchet_noise_percent: config.ratchet_noise_percent.clone(),
         save_metrics: config.save_metrics.clone(),
+        save_baseline: None,
+        compare_baseline: None,
         test_shard: config.test_shard.clone(),
         nocapture: false,
+        isolate: false,
         color: test::AutoColor,
+        format: test::PrettyFormat,
+        test_timeout: None,
+        warn_time: None,
         show_boxplot: false,
         boxplot_width: 50,
         show_all_stats: false,
@@ -346,6 +354,7 @@ pub fn make_test(config: &Config, testfile: &Path, f: || -> test::TestFn)
             name: make_test_name(config, testfile),
             ignore: header::is_test_ignored(config, testfile),
             should_fail: test::ShouldFail::No,
+            timeout: None,
         },
         testfn: f(),
     }
@@ -12,7 +12,7 @@ use self::TargetLocation::*;
 
 use common::Config;
 use common::{CompileFail, Pretty, RunFail, RunPass, RunPassValgrind, DebugInfoGdb};
-use common::{Codegen, DebugInfoLldb};
+use common::{Codegen, Coverage, DebugInfoLldb};
 use errors;
 use header::TestProps;
 use header;
@@ -73,6 +73,7 @@ pub fn run_metrics(config: Config, testfile: String, mm: &mut MetricMap) {
       DebugInfoGdb => run_debuginfo_gdb_test(&config, &props, &testfile),
       DebugInfoLldb => run_debuginfo_lldb_test(&config, &props, &testfile),
       Codegen => run_codegen_test(&config, &props, &testfile, mm),
+      Coverage => run_coverage_test(&config, &props, &testfile),
     }
 }
 
@@ -1753,3 +1754,121 @@ fn run_codegen_test(config: &Config, props: &TestProps,
                      (base_lines as f64) / (clang_lines as f64),
                      0.001);
 }
+
+// coverage tests (vs. gcov)
+//
+// The test is built with `-Z coverage` and run once; gcov then annotates the
+// source with execution counts. Every line carrying a `// count: N` comment
+// must have been executed exactly N times, and `// count: #####` marks a line
+// which is code but was never executed.
+
+fn run_coverage_test(config: &Config, props: &TestProps, testfile: &Path) {
+    let gcov = match config.gcov_path {
+        Some(ref gcov) => gcov.clone(),
+        None => fatal("missing --gcov-path"),
+    };
+
+    let expected = parse_expected_counts(testfile);
+    if expected.is_empty() {
+        fatal("coverage test has no `// count:` annotations");
+    }
+
+    // Counts accumulate across runs, so start from a clean slate.
+    let base = output_base_name(config, testfile);
+    let gcno = os::make_absolute(&base.with_extension("0.gcno")).unwrap();
+    let gcda = base.with_extension("0.gcda");
+    if gcda.exists() {
+        fs::unlink(&gcda).unwrap();
+    }
+
+    let mut proc_res = compile_test_(config, props, testfile,
+                                     &["-Z".to_string(), "coverage".to_string()]);
+    if !proc_res.status.success() {
+        fatal_proc_rec("compilation failed!", &proc_res);
+    }
+
+    proc_res = exec_compiled_test(config, props, testfile);
+    if !proc_res.status.success() {
+        fatal_proc_rec("test run failed!", &proc_res);
+    }
+
+    // gcov always writes its reports into the current directory, so give each
+    // test a directory of its own.
+    let mut dirname = base.filename().unwrap().to_vec();
+    dirname.extend("gcov".bytes());
+    let gcov_dir = base.with_filename(dirname);
+    ensure_dir(&gcov_dir);
+
+    let source = os::make_absolute(testfile).unwrap();
+    let mut cmd = process::Command::new(&gcov);
+    cmd.cwd(&gcov_dir).arg("-o").arg(&gcno).arg(&source);
+    let cmdline = format!("{}", cmd);
+    logv(config, format!("executing {}", cmdline));
+    let output = cmd.output().ok().expect(format!("failed to exec `{}`",
+                                                  gcov.display()).as_slice());
+    proc_res = ProcRes {
+        status: output.status,
+        stdout: String::from_utf8_lossy(output.output.as_slice()).into_owned(),
+        stderr: String::from_utf8_lossy(output.error.as_slice()).into_owned(),
+        cmdline: cmdline,
+    };
+    if !proc_res.status.success() {
+        fatal_proc_rec("gcov failed!", &proc_res);
+    }
+
+    let mut report_name = testfile.filename().unwrap().to_vec();
+    report_name.extend(".gcov".bytes());
+    let report = match File::open(&gcov_dir.join(report_name)).read_to_string() {
+        Ok(report) => report,
+        Err(e) => fatal_proc_rec(format!("couldn't read gcov report: {}",
+                                         e).as_slice(), &proc_res),
+    };
+    let actual = parse_gcov_counts(report.as_slice());
+
+    let mut failed = false;
+    for &(line, ref count) in expected.iter() {
+        let found = actual.iter().find(|&&(l, _)| l == line).map(|&(_, ref c)| c.as_slice());
+        if found != Some(count.as_slice()) {
+            error(format!("line {}: expected count {}, found {}",
+                          line, count, found.unwrap_or("-")).as_slice());
+            failed = true;
+        }
+    }
+    if failed {
+        println!("gcov report:\n------------------------------------------\n{}\n\
+                  ------------------------------------------", report);
+        panic!();
+    }
+}
+
+/// The `// count:` annotations of a coverage test, as (line, count) pairs.
+fn parse_expected_counts(testfile: &Path) -> Vec<(uint, String)> {
+    use std::io::{BufferedReader, File};
+
+    let mut expected = Vec::new();
+    let mut reader = BufferedReader::new(File::open(testfile).unwrap());
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.unwrap();
+        match header::parse_name_value_directive(line.as_slice(), "// count") {
+            Some(count) => expected.push((i + 1, count.as_slice().trim().to_string())),
+            None => {}
+        }
+    }
+    expected
+}
+
+/// The execution counts of a gcov report, as (line, count) pairs. Each line
+/// of the report looks like `    COUNT:  LINE:SOURCE`, where COUNT is `-`
+/// for lines without code and `#####` for lines which never ran.
+fn parse_gcov_counts(report: &str) -> Vec<(uint, String)> {
+    report.lines().filter_map(|line| {
+        let mut parts = line.splitn(2, ':');
+        let count = parts.next().unwrap_or("").trim();
+        let line = parts.next().and_then(|l| l.trim().parse::<uint>());
+        match line {
+            // Line 0 holds the report's header.
+            Some(0) | None => None,
+            Some(line) => Some((line, count.trim_right_matches('*').to_string())),
+        }
+    }).collect()
+}
@@ -18,6 +18,11 @@
 //! This crate has two arenas implemented: `TypedArena`, which is a simpler
 //! arena but can only hold objects of a single type, and `Arena`, which is a
 //! more complex, slower arena which can hold objects of any type.
+//!
+//! An `Arena` can also copy slices and strings into itself, collect an
+//! iterator into a slice, report how much memory it is using with `stats`,
+//! and be `reset` to drop everything in it while keeping its largest chunks
+//! for reuse, e.g. by a server allocating for one request at a time.
 
 #![crate_name = "arena"]
 #![experimental]
@@ -40,8 +45,10 @@ use std::intrinsics;
 use std::mem;
 use std::num::{Int, UnsignedInt};
 use std::ptr;
+use std::raw;
 use std::rc::Rc;
 use std::rt::heap::{allocate, deallocate};
+use std::str;
 
 // The way arena uses arrays is really deeply awful. The arrays are
 // allocated, and have capacities reserved, but the fill for the array
@@ -297,6 +304,99 @@ impl Arena {
             }
         }
     }
+
+    // Moves `len` objects without destructors from `src` into the POD part
+    // of the arena.
+    #[inline]
+    unsafe fn alloc_copy_slice<T>(&self, src: *const T, len: uint) -> &mut [T] {
+        let n_bytes = mem::size_of::<T>().checked_mul(len).unwrap();
+        let ptr = self.alloc_copy_inner(n_bytes, mem::min_align_of::<T>()) as *mut T;
+        ptr::copy_nonoverlapping_memory(ptr, src, len);
+        mem::transmute(raw::Slice { data: ptr as *const T, len: len })
+    }
+
+    /// Copies `src` into the arena, returning a reference to the copy.
+    #[inline]
+    pub fn alloc_slice<T: Copy>(&self, src: &[T]) -> &mut [T] {
+        unsafe {
+            self.alloc_copy_slice(src.as_ptr(), src.len())
+        }
+    }
+
+    /// Copies `src` into the arena, returning a reference to the copy.
+    #[inline]
+    pub fn alloc_str(&self, src: &str) -> &str {
+        unsafe {
+            str::from_utf8_unchecked(self.alloc_slice(src.as_bytes()))
+        }
+    }
+
+    /// Allocates the items produced by `iter` in the arena, returning a
+    /// reference to them as a slice.
+    ///
+    /// Items with destructors are collected into a vector owned by the arena,
+    /// so only the vector itself is allocated in the arena's chunks.
+    pub fn alloc_extend<T, I: Iterator<T>>(&self, iter: I) -> &mut [T] {
+        let mut items: Vec<T> = iter.collect();
+        unsafe {
+            if intrinsics::needs_drop::<T>() {
+                self.alloc_noncopy(move || items).as_mut_slice()
+            } else {
+                let slice = self.alloc_copy_slice(items.as_ptr(), items.len());
+                items.set_len(0);
+                slice
+            }
+        }
+    }
+
+    /// Destroys all the objects in the arena, so that its memory can be used
+    /// again.
+    ///
+    /// The most recently allocated chunks are kept, as they are the largest;
+    /// the rest are freed.
+    pub fn reset(&mut self) {
+        // Empty the arena before running any destructors, so that if one of
+        // them panics the rest are leaked rather than destroyed twice.
+        let head = self.head.borrow().clone();
+        self.head.borrow().fill.set(0);
+        self.copy_head.borrow().fill.set(0);
+        let chunks = mem::replace(&mut *self.chunks.borrow_mut(), Vec::new());
+        unsafe {
+            destroy_chunk(&head);
+            for chunk in chunks.iter() {
+                if !chunk.is_copy.get() {
+                    destroy_chunk(chunk);
+                }
+            }
+        }
+    }
+
+    /// Returns statistics about the memory used by the arena.
+    pub fn stats(&self) -> ArenaStats {
+        let head = self.head.borrow();
+        let copy_head = self.copy_head.borrow();
+        let chunks = self.chunks.borrow();
+        let heads = [&*head, &*copy_head];
+        let mut stats = ArenaStats { chunks: 0, capacity: 0, used: 0 };
+        for chunk in heads.iter().map(|c| *c).chain(chunks.iter()) {
+            stats.chunks += 1;
+            stats.capacity += chunk.capacity();
+            stats.used += chunk.fill.get();
+        }
+        stats
+    }
+}
+
+/// Statistics about the memory used by an `Arena`.
+#[deriving(Clone, Copy, PartialEq, Eq, Show)]
+pub struct ArenaStats {
+    /// The number of chunks of memory the arena holds.
+    pub chunks: uint,
+    /// The total size of the chunks, in bytes.
+    pub capacity: uint,
+    /// The number of bytes allocated from the chunks, including type
+    /// descriptors and padding.
+    pub used: uint,
 }
 
 #[test]
@@ -345,6 +445,63 @@ fn test_arena_destructors_fail() {
     });
 }
 
+#[test]
+fn test_arena_alloc_slice() {
+    let arena = Arena::new();
+    let a = arena.alloc_slice(&[1u16, 2, 3]);
+    let b = arena.alloc_str("hello");
+    let c = arena.alloc_slice::<u64>(&[]);
+    a[0] = 4;
+    assert_eq!(a.as_slice(), [4u16, 2, 3].as_slice());
+    assert_eq!(b, "hello");
+    assert!(c.is_empty());
+    assert_eq!(a.as_ptr() as uint % mem::min_align_of::<u16>(), 0);
+}
+
+#[test]
+fn test_arena_alloc_extend() {
+    let arena = Arena::new();
+    let rc = Rc::new(0i);
+    {
+        let squares = arena.alloc_extend(range(0u, 100).map(|i| i * i));
+        assert_eq!(squares.len(), 100);
+        assert_eq!(squares[99], 99 * 99);
+        let rcs = arena.alloc_extend(range(0u, 10).map(|_| rc.clone()));
+        assert_eq!(rcs.len(), 10);
+    }
+    assert_eq!(std::rc::strong_count(&rc), 11);
+    drop(arena);
+    assert_eq!(std::rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn test_arena_reset() {
+    let mut arena = Arena::new();
+    let rc = Rc::new(0i);
+    for i in range(0u, 1000) {
+        arena.alloc(|| rc.clone());
+        arena.alloc(|| i);
+    }
+    assert_eq!(std::rc::strong_count(&rc), 1001);
+    let before = arena.stats();
+    assert!(before.chunks > 2);
+    assert!(before.used <= before.capacity);
+
+    arena.reset();
+    assert_eq!(std::rc::strong_count(&rc), 1);
+    let after = arena.stats();
+    assert_eq!(after.chunks, 2);
+    assert_eq!(after.used, 0);
+    assert!(after.capacity > 0 && after.capacity < before.capacity);
+
+    // The memory kept is reused.
+    arena.alloc(|| rc.clone());
+    assert_eq!(arena.stats().chunks, 2);
+    assert!(arena.stats().used > 0);
+    drop(arena);
+    assert_eq!(std::rc::strong_count(&rc), 1);
+}
+
 /// A faster arena that can hold objects of only one type.
 ///
 /// Safety note: Modifying objects in the arena that have already had their
@@ -21,14 +21,18 @@
 //! this library exposes two traits that clients can implement on their
 //! own structs before handing them over to the rendering function.
 //!
-//! Note: This library does not yet provide access to the full
-//! expressiveness of the [DOT language](
-//! http://www.graphviz.org/doc/info/lang.html). For example, there are
-//! many [attributes](http://www.graphviz.org/content/attrs) related to
-//! providing layout hints (e.g. left-to-right versus top-down, which
-//! algorithm to use, etc). The current intention of this library is to
-//! emit a human-readable .dot file with very regular structure suitable
-//! for easy post-processing.
+//! Besides labels, `Labeller` can give nodes and edges a style, color
+//! and shape, set arbitrary [attributes](http://www.graphviz.org/content/attrs)
+//! on them and on the graph itself (e.g. `rankdir` for left-to-right
+//! layouts), and ask for an undirected graph. `GraphWalk` can group nodes
+//! into (possibly nested) subgraphs, which Graphviz draws boxed as clusters
+//! when their names begin with `cluster`. All of these are optional. The
+//! intention of this library is to emit a human-readable .dot file with
+//! very regular structure suitable for easy post-processing.
+//!
+//! Going the other way, the `parse` module reads DOT source into a graph
+//! that implements both traits, so existing .dot files can be inspected,
+//! transformed and rendered again.
 //!
 //! # Examples
 //!
@@ -277,11 +281,13 @@
 pub use self::LabelText::*;
 
 use std::borrow::IntoCow;
+use std::collections::HashSet;
 use std::io;
 use std::str::CowString;
 use std::vec::CowVec;
 
 pub mod maybe_owned_vec;
+pub mod parse;
 
 /// The text for a graphviz label on a node or edge.
 pub enum LabelText<'a> {
@@ -422,6 +428,135 @@ pub trait Labeller<'a,N,E> {
         let _ignored = e;
         LabelStr("".into_cow())
     }
+
+    /// Whether `e` is given a `label` attribute at all. The default is
+    /// that every edge is.
+    fn edge_has_label(&'a self, e: &E) -> bool {
+        let _ignored = e;
+        true
+    }
+
+    /// Whether the graph is directed. The default is a directed graph.
+    fn kind(&self) -> Kind {
+        Kind::Digraph
+    }
+
+    /// Maps `n` to the name of the shape it is drawn as (e.g. `box`), if
+    /// not the default ellipse.
+    fn node_shape(&'a self, n: &N) -> Option<LabelText<'a>> {
+        let _ignored = n;
+        None
+    }
+
+    /// Maps `n` to the style it is drawn in.
+    fn node_style(&'a self, n: &N) -> Style {
+        let _ignored = n;
+        Style::None
+    }
+
+    /// Maps `n` to a color (a name such as `red`, or `#rrggbb`) to draw it
+    /// in, if not the default.
+    fn node_color(&'a self, n: &N) -> Option<LabelText<'a>> {
+        let _ignored = n;
+        None
+    }
+
+    /// Maps `n` to any further attributes to set on it.
+    fn node_attrs(&'a self, n: &N) -> Attrs<'a> {
+        let _ignored = n;
+        Vec::new()
+    }
+
+    /// Maps `e` to the style it is drawn in.
+    fn edge_style(&'a self, e: &E) -> Style {
+        let _ignored = e;
+        Style::None
+    }
+
+    /// Maps `e` to a color to draw it in, if not the default.
+    fn edge_color(&'a self, e: &E) -> Option<LabelText<'a>> {
+        let _ignored = e;
+        None
+    }
+
+    /// Maps `e` to any further attributes to set on it.
+    fn edge_attrs(&'a self, e: &E) -> Attrs<'a> {
+        let _ignored = e;
+        Vec::new()
+    }
+
+    /// Returns attributes to set on the graph as a whole, such as
+    /// `rankdir` or `label`.
+    fn graph_attrs(&'a self) -> Attrs<'a> {
+        Vec::new()
+    }
+}
+
+/// Attributes, as pairs of names and values.
+pub type Attrs<'a> = Vec<(Id<'a>, LabelText<'a>)>;
+
+/// Whether a graph is directed or undirected.
+#[deriving(Clone, Copy, PartialEq, Eq, Show)]
+pub enum Kind {
+    /// A directed graph, whose edges are drawn as arrows
+    Digraph,
+    /// An undirected graph, whose edges are drawn as lines
+    Graph,
+}
+
+impl Kind {
+    /// The keyword introducing a graph of this kind.
+    pub fn keyword(&self) -> &'static str {
+        match *self {
+            Kind::Digraph => "digraph",
+            Kind::Graph => "graph",
+        }
+    }
+
+    /// The operator joining the two ends of an edge.
+    pub fn edgeop(&self) -> &'static str {
+        match *self {
+            Kind::Digraph => "->",
+            Kind::Graph => "--",
+        }
+    }
+}
+
+/// The style a node or edge is drawn in.
+///
+/// Not all styles apply to both nodes and edges: see the
+/// [Graphviz documentation](http://www.graphviz.org/content/attrs#kstyle).
+#[deriving(Copy, PartialEq, Eq, Show)]
+pub enum Style {
+    /// The default style, leaving the `style` attribute unset
+    None,
+    Solid,
+    Dashed,
+    Dotted,
+    Bold,
+    Rounded,
+    Diagonals,
+    Filled,
+    Striped,
+    Wedged,
+}
+
+impl Style {
+    /// The value of the `style` attribute for this style.
+    pub fn as_slice(self) -> &'static str {
+        match self {
+            Style::None => "",
+            Style::Solid => "solid",
+            Style::Dashed => "dashed",
+            Style::Dotted => "dotted",
+            Style::Bold => "bold",
+            Style::Rounded => "rounded",
+            Style::Diagonals => "diagonals",
+            Style::Filled => "filled",
+            Style::Striped => "striped",
+            Style::Wedged => "wedged",
+        }
+    }
 }
 
 impl<'a> LabelText<'a> {
@@ -515,18 +650,50 @@ pub trait GraphWalk<'a, N, E> {
     fn source(&'a self, edge: &E) -> N;
     /// The target node for `edge`.
     fn target(&'a self, edge: &E) -> N;
+    /// Returns the subgraphs grouping some of the nodes in this graph. By
+    /// default there are none.
+    fn subgraphs(&'a self) -> Vec<Subgraph<'a, N>> {
+        Vec::new()
+    }
+}
+
+/// A group of nodes within a graph, possibly containing further groups.
+///
+/// Graphviz draws subgraphs whose names begin with `cluster` inside a box,
+/// with their `label` attribute as its title. Other subgraphs only serve
+/// to set attributes, such as `rank`, on their nodes.
+pub struct Subgraph<'a, N> {
+    /// The name of the subgraph.
+    pub id: Id<'a>,
+    /// Attributes of the subgraph.
+    pub attrs: Attrs<'a>,
+    /// The nodes directly in this subgraph. A node should be in at most
+    /// one subgraph at each level of nesting.
+    pub nodes: Vec<N>,
+    /// The subgraphs nested within this one.
+    pub subgraphs: Vec<Subgraph<'a, N>>,
+}
+
+impl<'a, N> Subgraph<'a, N> {
+    /// Creates a subgraph named `id` holding `nodes`, without attributes
+    /// or nested subgraphs.
+    pub fn new(id: Id<'a>, nodes: Vec<N>) -> Subgraph<'a, N> {
+        Subgraph { id: id, attrs: Vec::new(), nodes: nodes, subgraphs: Vec::new() }
+    }
 }
 
 #[deriving(Copy, PartialEq, Eq, Show)]
 pub enum RenderOption {
     NoEdgeLabels,
     NoNodeLabels,
+    NoEdgeStyles,
+    NoNodeStyles,
 }
 
 /// Returns vec holding all the default render options.
 pub fn default_options() -> Vec<RenderOption> { vec![] }
 
-/// Renders directed graph `g` into the writer `w` in DOT syntax.
+/// Renders graph `g` into the writer `w` in DOT syntax.
 /// (Simple wrapper around `render_opts` that passes a default set of options.)
 pub fn render<'a, N:Clone+'a, E:Clone+'a, G:Labeller<'a,N,E>+GraphWalk<'a,N,E>, W:Writer>(
               g: &'a G,
@@ -534,60 +701,160 @@ pub fn render<'a, N:Clone+'a, E:Clone+'a, G:Labeller<'a,N,E>+GraphWalk<'a,N,E>,
     render_opts(g, w, &[])
 }
 
-/// Renders directed graph `g` into the writer `w` in DOT syntax.
+/// Renders graph `g` into the writer `w` in DOT syntax.
 /// (Main entry point for the library.)
 pub fn render_opts<'a, N:Clone+'a, E:Clone+'a, G:Labeller<'a,N,E>+GraphWalk<'a,N,E>, W:Writer>(
               g: &'a G,
               w: &mut W,
               options: &[RenderOption]) -> io::IoResult<()>
 {
-    fn writeln<W:Writer>(w: &mut W, arg: &[&str]) -> io::IoResult<()> {
-        for &s in arg.iter() { try!(w.write_str(s)); }
-        w.write_char('\n')
-    }
-
-    fn indent<W:Writer>(w: &mut W) -> io::IoResult<()> {
-        w.write_str("    ")
+    try!(writeln(w, &[g.kind().keyword(), " ", g.graph_id().as_slice(), " {"]));
+    for (name, value) in g.graph_attrs().into_iter() {
+        try!(indent(w, 1));
+        try!(writeln(w, &[name.as_slice(), "=\"", value.escape()[], "\";"]));
     }
 
-    try!(writeln(w, &["digraph ", g.graph_id().as_slice(), " {"]));
+    // Nodes in subgraphs are written out there instead of at the top.
+    let subgraphs = g.subgraphs();
+    let mut in_subgraph = HashSet::new();
+    collect_ids(g, subgraphs[], &mut in_subgraph);
     for n in g.nodes().iter() {
-        try!(indent(w));
-        let id = g.node_id(n);
-        if options.contains(&RenderOption::NoNodeLabels) {
-            try!(writeln(w, &[id.as_slice(), ";"]));
-        } else {
-            let escaped = g.node_label(n).escape();
-            try!(writeln(w, &[id.as_slice(),
-                              "[label=\"", escaped.as_slice(), "\"];"]));
+        if !in_subgraph.contains(g.node_id(n).as_slice()) {
+            try!(render_node(g, n, w, 1, options));
         }
     }
+    for subgraph in subgraphs.iter() {
+        try!(render_subgraph(g, subgraph, w, 1, options));
+    }
 
     for e in g.edges().iter() {
-        let escaped_label = g.edge_label(e).escape();
-        try!(indent(w));
+        let mut attrs = Vec::new();
+        if !options.contains(&RenderOption::NoEdgeLabels) && g.edge_has_label(e) {
+            attrs.push(attr("label", g.edge_label(e)));
+        }
+        if !options.contains(&RenderOption::NoEdgeStyles) {
+            push_style(&mut attrs, g.edge_style(e), g.edge_color(e), None);
+        }
+        for (name, value) in g.edge_attrs(e).into_iter() {
+            attrs.push(attr(name.as_slice(), value));
+        }
+
+        try!(indent(w, 1));
         let source = g.source(e);
         let target = g.target(e);
         let source_id = g.node_id(&source);
         let target_id = g.node_id(&target);
-        if options.contains(&RenderOption::NoEdgeLabels) {
-            try!(writeln(w, &[source_id.as_slice(),
-                              " -> ", target_id.as_slice(), ";"]));
-        } else {
-            try!(writeln(w, &[source_id.as_slice(),
-                              " -> ", target_id.as_slice(),
-                              "[label=\"", escaped_label.as_slice(), "\"];"]));
+        try!(writeln(w, &[source_id.as_slice(), " ", g.kind().edgeop(), " ",
+                          target_id.as_slice(), attr_list(attrs)[], ";"]));
+    }
+
+    writeln(w, &["}"])
+}
+
+fn writeln<W:Writer>(w: &mut W, arg: &[&str]) -> io::IoResult<()> {
+    for &s in arg.iter() { try!(w.write_str(s)); }
+    w.write_char('\n')
+}
+
+fn indent<W:Writer>(w: &mut W, depth: uint) -> io::IoResult<()> {
+    for _ in range(0, depth) {
+        try!(w.write_str("    "));
+    }
+    Ok(())
+}
+
+/// Formats an attribute as `name="value"`.
+fn attr(name: &str, value: LabelText) -> String {
+    format!("{}=\"{}\"", name, value.escape())
+}
+
+/// Formats attributes as a bracketed list to follow a node or edge, or
+/// nothing if there are none.
+fn attr_list(attrs: Vec<String>) -> String {
+    if attrs.is_empty() {
+        String::new()
+    } else {
+        format!("[{}]", attrs.connect(", "))
+    }
+}
+
+fn push_style(attrs: &mut Vec<String>, style: Style, color: Option<LabelText>,
+              shape: Option<LabelText>) {
+    if style != Style::None {
+        attrs.push(attr("style", LabelStr(style.as_slice().into_cow())));
+    }
+    match color {
+        Some(color) => attrs.push(attr("color", color)),
+        None => {}
+    }
+    match shape {
+        Some(shape) => attrs.push(attr("shape", shape)),
+        None => {}
+    }
+}
+
+fn collect_ids<'a, N:Clone+'a, E:Clone+'a, G:Labeller<'a,N,E>+GraphWalk<'a,N,E>>(
+               g: &'a G,
+               subgraphs: &[Subgraph<'a, N>],
+               ids: &mut HashSet<String>) {
+    for subgraph in subgraphs.iter() {
+        for n in subgraph.nodes.iter() {
+            ids.insert(g.node_id(n).as_slice().to_string());
         }
+        collect_ids(g, subgraph.subgraphs[], ids);
+    }
+}
+
+fn render_node<'a, N:Clone+'a, E:Clone+'a, G:Labeller<'a,N,E>+GraphWalk<'a,N,E>, W:Writer>(
+               g: &'a G,
+               n: &N,
+               w: &mut W,
+               depth: uint,
+               options: &[RenderOption]) -> io::IoResult<()> {
+    let mut attrs = Vec::new();
+    if !options.contains(&RenderOption::NoNodeLabels) {
+        attrs.push(attr("label", g.node_label(n)));
     }
+    if !options.contains(&RenderOption::NoNodeStyles) {
+        push_style(&mut attrs, g.node_style(n), g.node_color(n), g.node_shape(n));
+    }
+    for (name, value) in g.node_attrs(n).into_iter() {
+        attrs.push(attr(name.as_slice(), value));
+    }
+
+    try!(indent(w, depth));
+    let id = g.node_id(n);
+    writeln(w, &[id.as_slice(), attr_list(attrs)[], ";"])
+}
 
+fn render_subgraph<'a, N:Clone+'a, E:Clone+'a, G:Labeller<'a,N,E>+GraphWalk<'a,N,E>, W:Writer>(
+                   g: &'a G,
+                   subgraph: &Subgraph<'a, N>,
+                   w: &mut W,
+                   depth: uint,
+                   options: &[RenderOption]) -> io::IoResult<()> {
+    try!(indent(w, depth));
+    try!(writeln(w, &["subgraph ", subgraph.id.as_slice(), " {"]));
+    for &(ref name, ref value) in subgraph.attrs.iter() {
+        try!(indent(w, depth + 1));
+        try!(writeln(w, &[name.as_slice(), "=\"", value.escape()[], "\";"]));
+    }
+    for n in subgraph.nodes.iter() {
+        try!(render_node(g, n, w, depth + 1, options));
+    }
+    for nested in subgraph.subgraphs.iter() {
+        try!(render_subgraph(g, nested, w, depth + 1, options));
+    }
+    try!(indent(w, depth));
     writeln(w, &["}"])
 }
 
 #[cfg(test)]
 mod tests {
     use self::NodeLabels::*;
-    use super::{Id, LabelText, LabelStr, EscStr, Labeller};
-    use super::{Nodes, Edges, GraphWalk, render};
+    use super::{Id, LabelText, LabelStr, EscStr, Labeller, Attrs};
+    use super::{Nodes, Edges, GraphWalk, Subgraph, Kind, Style, RenderOption};
+    use super::{render, render_opts};
     use std::io::IoResult;
     use std::str;
     use std::borrow::IntoCow;
@@ -868,6 +1135,120 @@ r#"digraph syntax_tree {
 "#);
     }
 
+    // A wrapper around LabelledGraph that draws it undirected, with
+    // styles, attributes and a cluster holding N1 and N2.
+    struct StyledGraph {
+        graph: LabelledGraph
+    }
+
+    impl<'a> Labeller<'a, Node, &'a Edge> for StyledGraph {
+        fn graph_id(&'a self) -> Id<'a> { self.graph.graph_id() }
+        fn node_id(&'a self, n: &Node) -> Id<'a> { self.graph.node_id(n) }
+        fn node_label(&'a self, n: &Node) -> LabelText<'a> { self.graph.node_label(n) }
+        fn edge_label(&'a self, e: & &'a Edge) -> LabelText<'a> { self.graph.edge_label(e) }
+        fn kind(&self) -> Kind { Kind::Graph }
+        fn node_shape(&'a self, n: &Node) -> Option<LabelText<'a>> {
+            if *n == 0 { Some(LabelStr("box".into_cow())) } else { None }
+        }
+        fn node_style(&'a self, n: &Node) -> Style {
+            if *n == 0 { Style::Filled } else { Style::None }
+        }
+        fn node_color(&'a self, n: &Node) -> Option<LabelText<'a>> {
+            if *n == 0 { Some(LabelStr("lightgrey".into_cow())) } else { None }
+        }
+        fn edge_style(&'a self, e: & &'a Edge) -> Style {
+            if e.from == e.to { Style::Dashed } else { Style::None }
+        }
+        fn edge_attrs(&'a self, e: & &'a Edge) -> Attrs<'a> {
+            if e.from == 0 {
+                vec![(Id::new("penwidth").unwrap(), LabelStr("2".into_cow()))]
+            } else {
+                vec![]
+            }
+        }
+        fn graph_attrs(&'a self) -> Attrs<'a> {
+            vec![(Id::new("rankdir").unwrap(), LabelStr("LR".into_cow()))]
+        }
+    }
+
+    impl<'a> GraphWalk<'a, Node, &'a Edge> for StyledGraph {
+        fn nodes(&'a self) -> Nodes<'a,Node> { self.graph.nodes() }
+        fn edges(&'a self) -> Edges<'a,&'a Edge> { self.graph.edges() }
+        fn source(&'a self, edge: & &'a Edge) -> Node { edge.from }
+        fn target(&'a self, edge: & &'a Edge) -> Node { edge.to }
+        fn subgraphs(&'a self) -> Vec<Subgraph<'a, Node>> {
+            let mut inner = Subgraph::new(Id::new("inner").unwrap(), vec![2]);
+            inner.attrs.push((Id::new("rank").unwrap(), LabelStr("same".into_cow())));
+            let mut cluster = Subgraph::new(Id::new("cluster_0").unwrap(), vec![1]);
+            cluster.attrs.push((Id::new("label").unwrap(), LabelStr("Cluster \"0\"".into_cow())));
+            cluster.subgraphs.push(inner);
+            vec![cluster]
+        }
+    }
+
+    fn styled_graph() -> StyledGraph {
+        let labels : Trivial = UnlabelledNodes(4);
+        StyledGraph {
+            graph: LabelledGraph::new("styled", labels,
+                                      vec!(edge(0, 1, "a"), edge(1, 2, "b"),
+                                           edge(2, 2, "c"), edge(0, 3, "d")))
+        }
+    }
+
+    #[test]
+    fn styles_and_clusters() {
+        let mut writer = Vec::new();
+        render(&styled_graph(), &mut writer).unwrap();
+        let r = (&mut writer.as_slice()).read_to_string();
+        assert_eq!(r.unwrap(),
+r#"graph styled {
+    rankdir="LR";
+    N0[label="N0", style="filled", color="lightgrey", shape="box"];
+    N3[label="N3"];
+    subgraph cluster_0 {
+        label="Cluster \"0\"";
+        N1[label="N1"];
+        subgraph inner {
+            rank="same";
+            N2[label="N2"];
+        }
+    }
+    N0 -- N1[label="a", penwidth="2"];
+    N1 -- N2[label="b"];
+    N2 -- N2[label="c", style="dashed"];
+    N0 -- N3[label="d", penwidth="2"];
+}
+"#);
+    }
+
+    #[test]
+    fn no_labels_or_styles() {
+        let mut writer = Vec::new();
+        render_opts(&styled_graph(), &mut writer,
+                    &[RenderOption::NoNodeLabels, RenderOption::NoEdgeLabels,
+                      RenderOption::NoNodeStyles, RenderOption::NoEdgeStyles]).unwrap();
+        let r = (&mut writer.as_slice()).read_to_string();
+        assert_eq!(r.unwrap(),
+r#"graph styled {
+    rankdir="LR";
+    N0;
+    N3;
+    subgraph cluster_0 {
+        label="Cluster \"0\"";
+        N1;
+        subgraph inner {
+            rank="same";
+            N2;
+        }
+    }
+    N0 -- N1[penwidth="2"];
+    N1 -- N2;
+    N2 -- N2;
+    N0 -- N3[penwidth="2"];
+}
+"#);
+    }
+
     #[test]
     fn simple_id_construction() {
         let id1 = Id::new("hello");
@@ -0,0 +1,1036 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A parser for the [DOT language](http://www.graphviz.org/doc/info/lang.html).
+//!
+//! `parse` reads a DOT graph into a `Graph`, which can be inspected and
+//! modified directly, and which implements `Labeller` and `GraphWalk` so
+//! that it can be written out again with `render`:
+//!
+//! ```rust
+//! use graphviz as dot;
+//!
+//! let g = dot::parse::parse("digraph g { a -> b [label=\"x\"]; }").unwrap();
+//! assert_eq!(g.nodes.len(), 2);
+//! assert_eq!(g.edges[0].attr("label"), Some("x"));
+//!
+//! let mut out = Vec::new();
+//! dot::render(&g, &mut out).unwrap();
+//! ```
+//!
+//! The whole of the language is accepted, but some of it is simplified
+//! on the way in:
+//!
+//! * Default attributes set with `node [...]` and `edge [...]` are copied
+//!   onto each node and edge created after them in the same subgraph.
+//! * Ports on the ends of an edge (`a:p -> b:q`) become its `tailport`
+//!   and `headport` attributes.
+//! * An edge whose end is a subgraph becomes one edge per node in it.
+//! * Repeated edges in a `strict` graph are merged into one.
+//! * HTML strings (`<...>`) are read as ordinary strings.
+//!
+//! Strings keep their backslashes, as the escape sequences in them are
+//! interpreted by Graphviz according to the attribute they are given to.
+//! Only the `\"` escape is removed.
+//!
+//! When rendered, the names of nodes and subgraphs that are not valid
+//! `Id`s are replaced by generated ones (a node keeps its name as its
+//! label). Attributes with such names are left out. Edges only get a
+//! `label` if they had one.
+
+use std::ascii::AsciiExt;
+use std::borrow::IntoCow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use {Id, Kind, LabelText, LabelStr, EscStr, Labeller, GraphWalk, Nodes, Edges};
+
+/// A graph read from DOT source.
+pub struct Graph {
+    /// Whether the graph is directed.
+    pub kind: Kind,
+    /// Whether the graph was declared `strict`.
+    pub strict: bool,
+    /// The name of the graph, if it has one.
+    pub name: Option<String>,
+    /// Attributes of the graph itself.
+    pub attrs: Vec<(String, String)>,
+    /// The nodes, in the order they were first mentioned.
+    pub nodes: Vec<Node>,
+    /// The edges, in the order they were declared.
+    pub edges: Vec<Edge>,
+    /// The subgraphs at the top level of the graph.
+    pub subgraphs: Vec<Subgraph>,
+}
+
+/// A node of a `Graph`.
+pub struct Node {
+    /// The name of the node, unique within its graph.
+    pub name: String,
+    /// Attributes of the node.
+    pub attrs: Vec<(String, String)>,
+}
+
+/// An edge of a `Graph`.
+pub struct Edge {
+    /// The index of the node the edge starts at.
+    pub source: uint,
+    /// The index of the node the edge ends at.
+    pub target: uint,
+    /// Attributes of the edge.
+    pub attrs: Vec<(String, String)>,
+}
+
+/// A subgraph of a `Graph`.
+pub struct Subgraph {
+    /// The name of the subgraph, if it has one.
+    pub name: Option<String>,
+    /// Attributes of the subgraph.
+    pub attrs: Vec<(String, String)>,
+    /// The indices of the nodes mentioned directly in the subgraph,
+    /// rather than in one nested within it.
+    pub nodes: Vec<uint>,
+    /// The subgraphs nested within this one.
+    pub subgraphs: Vec<Subgraph>,
+}
+
+/// An error encountered while parsing, with the position it occurred at.
+#[deriving(Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line of the error, counting from 1.
+    pub line: uint,
+    /// The column of the error, counting from 1.
+    pub col: uint,
+    /// A description of the error.
+    pub msg: String,
+}
+
+impl fmt::Show for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.col, self.msg)
+    }
+}
+
+/// Parses a graph written in the DOT language.
+pub fn parse(src: &str) -> Result<Graph, ParseError> {
+    let tokens = try!(lex(src));
+    let mut parser = Parser { tokens: tokens, pos: 0, graph: None, index: HashMap::new() };
+    parser.parse_graph()
+}
+
+fn find<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs.iter().rev().find(|&&(ref n, _)| n[] == name).map(|&(_, ref v)| v[])
+}
+
+fn set(attrs: &mut Vec<(String, String)>, name: String, value: String) {
+    let existing = attrs.iter().position(|&(ref n, _)| *n == name);
+    match existing {
+        Some(i) => attrs[i] = (name, value),
+        None => attrs.push((name, value)),
+    }
+}
+
+impl Graph {
+    /// Creates an empty graph.
+    pub fn new(kind: Kind, name: Option<String>) -> Graph {
+        Graph {
+            kind: kind,
+            strict: false,
+            name: name,
+            attrs: Vec::new(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            subgraphs: Vec::new(),
+        }
+    }
+
+    /// Returns the value of the graph attribute `name`, if set.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        find(self.attrs[], name)
+    }
+
+    /// Returns the index of the node named `name`, if there is one.
+    pub fn find_node(&self, name: &str) -> Option<uint> {
+        self.nodes.iter().position(|n| n.name[] == name)
+    }
+}
+
+impl Node {
+    /// Returns the value of the attribute `name`, if set.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        find(self.attrs[], name)
+    }
+}
+
+impl Edge {
+    /// Returns the value of the attribute `name`, if set.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        find(self.attrs[], name)
+    }
+}
+
+impl Subgraph {
+    /// Returns the value of the attribute `name`, if set.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        find(self.attrs[], name)
+    }
+
+    /// Returns the indices of all the nodes in this subgraph and those
+    /// nested within it.
+    pub fn all_nodes(&self) -> Vec<uint> {
+        let mut nodes = self.nodes.clone();
+        for sub in self.subgraphs.iter() {
+            for n in sub.all_nodes().into_iter() {
+                if !nodes.contains(&n) {
+                    nodes.push(n);
+                }
+            }
+        }
+        nodes
+    }
+}
+
+/// Converts attributes for rendering, leaving out `skip` and any whose
+/// names are not valid `Id`s.
+fn render_attrs<'a>(attrs: &'a [(String, String)], skip: &str) -> ::Attrs<'a> {
+    attrs.iter().filter(|&&(ref name, _)| name[] != skip).filter_map(|&(ref name, ref value)| {
+        Id::new(name[]).ok().map(|id| (id, EscStr(value[].into_cow())))
+    }).collect()
+}
+
+/// Adds the names of `subs` and the subgraphs nested within them to `names`.
+fn subgraph_names<'a>(subs: &'a [Subgraph], names: &mut HashSet<&'a str>) {
+    for sub in subs.iter() {
+        match sub.name {
+            Some(ref name) => { names.insert(name[]); }
+            None => {}
+        }
+        subgraph_names(sub.subgraphs[], names);
+    }
+}
+
+fn render_subgraph<'a>(sub: &'a Subgraph, names: &HashSet<&str>,
+                       count: &mut uint) -> ::Subgraph<'a, uint> {
+    *count += 1;
+    let id = match sub.name.as_ref().and_then(|name| Id::new(name[]).ok()) {
+        Some(id) => id,
+        None => {
+            let cluster = sub.name.as_ref().map_or(false, |name| name.starts_with("cluster"));
+            let prefix = if cluster { "cluster" } else { "" };
+            // Not the name of any subgraph, as those are left as they are.
+            let mut id = format!("{}_S{}", prefix, *count);
+            while names.contains(&id[]) {
+                *count += 1;
+                id = format!("{}_S{}", prefix, *count);
+            }
+            Id::new(id).unwrap()
+        }
+    };
+    let mut out = ::Subgraph::new(id, sub.nodes.clone());
+    out.attrs = render_attrs(sub.attrs[], "");
+    for nested in sub.subgraphs.iter() {
+        out.subgraphs.push(render_subgraph(nested, names, count));
+    }
+    out
+}
+
+impl<'a> Labeller<'a, uint, &'a Edge> for Graph {
+    fn graph_id(&'a self) -> Id<'a> {
+        match self.name.as_ref().and_then(|name| Id::new(name[]).ok()) {
+            Some(id) => id,
+            None => Id::new("G").unwrap(),
+        }
+    }
+    fn node_id(&'a self, n: &uint) -> Id<'a> {
+        match Id::new(self.nodes[*n].name[]) {
+            Ok(id) => id,
+            Err(()) => {
+                // Not the name of any node, as those are left as they are.
+                let mut id = format!("_N{}", *n);
+                while self.find_node(id[]).is_some() {
+                    id.push('_');
+                }
+                Id::new(id).unwrap()
+            }
+        }
+    }
+    fn node_label(&'a self, n: &uint) -> LabelText<'a> {
+        let node = &self.nodes[*n];
+        match node.attr("label") {
+            Some(label) => EscStr(label.into_cow()),
+            None => LabelStr(node.name[].into_cow()),
+        }
+    }
+    fn edge_label(&'a self, e: & &'a Edge) -> LabelText<'a> {
+        let e: &'a Edge = *e;
+        EscStr(e.attr("label").unwrap_or("").into_cow())
+    }
+    fn edge_has_label(&'a self, e: & &'a Edge) -> bool {
+        e.attr("label").is_some()
+    }
+    fn kind(&self) -> Kind {
+        self.kind
+    }
+    fn node_attrs(&'a self, n: &uint) -> ::Attrs<'a> {
+        render_attrs(self.nodes[*n].attrs[], "label")
+    }
+    fn edge_attrs(&'a self, e: & &'a Edge) -> ::Attrs<'a> {
+        let e: &'a Edge = *e;
+        render_attrs(e.attrs[], "label")
+    }
+    fn graph_attrs(&'a self) -> ::Attrs<'a> {
+        render_attrs(self.attrs[], "")
+    }
+}
+
+impl<'a> GraphWalk<'a, uint, &'a Edge> for Graph {
+    fn nodes(&'a self) -> Nodes<'a, uint> {
+        range(0, self.nodes.len()).collect()
+    }
+    fn edges(&'a self) -> Edges<'a, &'a Edge> {
+        self.edges.iter().collect()
+    }
+    fn source(&'a self, edge: & &'a Edge) -> uint {
+        edge.source
+    }
+    fn target(&'a self, edge: & &'a Edge) -> uint {
+        edge.target
+    }
+    fn subgraphs(&'a self) -> Vec<::Subgraph<'a, uint>> {
+        let mut names = HashSet::new();
+        subgraph_names(self.subgraphs[], &mut names);
+        let mut count = 0;
+        self.subgraphs.iter().map(|sub| render_subgraph(sub, &names, &mut count)).collect()
+    }
+}
+
+#[deriving(Clone, PartialEq, Show)]
+enum Token {
+    /// An unquoted identifier or numeral, which may be a keyword.
+    Ident(String),
+    /// A double-quoted or HTML string.
+    Str(String),
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Semi,
+    Comma,
+    Colon,
+    Equals,
+    Plus,
+    /// `->` or `--`
+    EdgeOp(Kind),
+    Eof,
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    pos: uint,
+    line: uint,
+    col: uint,
+}
+
+impl<'a> Lexer<'a> {
+    fn peek(&self) -> Option<char> {
+        if self.pos < self.src.len() {
+            Some(self.src.char_at(self.pos))
+        } else {
+            None
+        }
+    }
+
+    fn peek_next(&self) -> Option<char> {
+        match self.peek() {
+            Some(c) if self.pos + c.len_utf8_bytes() < self.src.len() => {
+                Some(self.src.char_at(self.pos + c.len_utf8_bytes()))
+            }
+            _ => None,
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+        match c {
+            Some('\n') => { self.line += 1; self.col = 1; }
+            Some(_) => self.col += 1,
+            None => {}
+        }
+        self.pos += c.map_or(0, |c| c.len_utf8_bytes());
+        c
+    }
+
+    fn error(&self, msg: &str) -> ParseError {
+        ParseError { line: self.line, col: self.col, msg: msg.to_string() }
+    }
+
+    /// Skips whitespace, comments and lines of preprocessor output.
+    fn skip_space(&mut self) -> Result<(), ParseError> {
+        loop {
+            match (self.peek(), self.peek_next()) {
+                (Some('#'), _) if self.col == 1 => {
+                    while self.peek().map_or(false, |c| c != '\n') { self.bump(); }
+                }
+                (Some('/'), Some('/')) => {
+                    while self.peek().map_or(false, |c| c != '\n') { self.bump(); }
+                }
+                (Some('/'), Some('*')) => {
+                    let err = self.error("unterminated comment");
+                    self.bump();
+                    self.bump();
+                    loop {
+                        match (self.bump(), self.peek()) {
+                            (Some('*'), Some('/')) => { self.bump(); break }
+                            (Some(_), _) => {}
+                            (None, _) => return Err(err),
+                        }
+                    }
+                }
+                (Some(c), _) if c.is_whitespace() => { self.bump(); }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn next(&mut self) -> Result<Token, ParseError> {
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Ok(Token::Eof),
+        };
+        let single = match c {
+            '{' => Some(Token::LBrace),
+            '}' => Some(Token::RBrace),
+            '[' => Some(Token::LBracket),
+            ']' => Some(Token::RBracket),
+            ';' => Some(Token::Semi),
+            ',' => Some(Token::Comma),
+            ':' => Some(Token::Colon),
+            '=' => Some(Token::Equals),
+            '+' => Some(Token::Plus),
+            _ => None,
+        };
+        match single {
+            Some(token) => { self.bump(); return Ok(token) }
+            None => {}
+        }
+        match (c, self.peek_next()) {
+            ('-', Some('>')) => { self.bump(); self.bump(); Ok(Token::EdgeOp(Kind::Digraph)) }
+            ('-', Some('-')) => { self.bump(); self.bump(); Ok(Token::EdgeOp(Kind::Graph)) }
+            ('-', _) | ('.', _) | ('0'...'9', _) => self.numeral(),
+            ('"', _) => self.quoted(),
+            ('<', _) => self.html(),
+            _ if is_ident_start(c) => {
+                let start = self.pos;
+                while self.peek().map_or(false, |c| is_ident_start(c) || c.is_digit(10)) {
+                    self.bump();
+                }
+                Ok(Token::Ident(self.src[start..self.pos].to_string()))
+            }
+            _ => Err(self.error(format!("unexpected character `{}`", c)[])),
+        }
+    }
+
+    fn numeral(&mut self) -> Result<Token, ParseError> {
+        let start = self.pos;
+        if self.peek() == Some('-') { self.bump(); }
+        let mut digits = 0u;
+        while self.peek().map_or(false, |c| c.is_digit(10)) { self.bump(); digits += 1; }
+        if self.peek() == Some('.') {
+            self.bump();
+            while self.peek().map_or(false, |c| c.is_digit(10)) { self.bump(); digits += 1; }
+        }
+        if digits == 0 {
+            return Err(self.error("expected a number"));
+        }
+        Ok(Token::Ident(self.src[start..self.pos].to_string()))
+    }
+
+    fn quoted(&mut self) -> Result<Token, ParseError> {
+        let err = self.error("unterminated string");
+        self.bump();
+        let mut s = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(Token::Str(s)),
+                Some('\\') => match self.peek() {
+                    Some('"') => { self.bump(); s.push('"') }
+                    Some('\n') => { self.bump(); }
+                    _ => s.push('\\'),
+                },
+                Some(c) => s.push(c),
+                None => return Err(err),
+            }
+        }
+    }
+
+    fn html(&mut self) -> Result<Token, ParseError> {
+        let err = self.error("unterminated HTML string");
+        self.bump();
+        let start = self.pos;
+        let mut depth = 1u;
+        loop {
+            match self.bump() {
+                Some('<') => depth += 1,
+                Some('>') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(Token::Str(self.src[start..self.pos - 1].to_string()));
+                    }
+                }
+                Some(_) => {}
+                None => return Err(err),
+            }
+        }
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c as u32 >= 0x80
+}
+
+fn lex(src: &str) -> Result<Vec<(Token, uint, uint)>, ParseError> {
+    let mut lexer = Lexer { src: src, pos: 0, line: 1, col: 1 };
+    let mut tokens = Vec::new();
+    loop {
+        try!(lexer.skip_space());
+        let (line, col) = (lexer.line, lexer.col);
+        let token = try!(lexer.next());
+        let eof = token == Token::Eof;
+        tokens.push((token, line, col));
+        if eof {
+            return Ok(tokens);
+        }
+    }
+}
+
+/// The default attributes in effect within a subgraph.
+#[deriving(Clone)]
+struct Defaults {
+    node: Vec<(String, String)>,
+    edge: Vec<(String, String)>,
+}
+
+/// One end of an edge: a node and its port, or a subgraph.
+enum End {
+    Node(uint, Option<String>),
+    Nodes(Vec<uint>),
+}
+
+struct Parser {
+    tokens: Vec<(Token, uint, uint)>,
+    pos: uint,
+    /// The graph being built, once its header has been read.
+    graph: Option<Graph>,
+    /// The index of each node by name.
+    index: HashMap<String, uint>,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        let (ref token, _, _) = self.tokens[self.pos];
+        token
+    }
+
+    fn bump(&mut self) -> Token {
+        let token = self.peek().clone();
+        if token != Token::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error(&self, msg: &str) -> ParseError {
+        let (_, line, col) = self.tokens[self.pos];
+        ParseError { line: line, col: col, msg: msg.to_string() }
+    }
+
+    fn graph(&mut self) -> &mut Graph {
+        self.graph.as_mut().unwrap()
+    }
+
+    /// Whether the next token is the keyword `kw`, in any case.
+    fn is_keyword(&self, kw: &str) -> bool {
+        match *self.peek() {
+            Token::Ident(ref s) => s[].eq_ignore_ascii_case(kw),
+            _ => false,
+        }
+    }
+
+    fn eat_keyword(&mut self, kw: &str) -> bool {
+        let is = self.is_keyword(kw);
+        if is { self.bump(); }
+        is
+    }
+
+    fn eat(&mut self, token: Token) -> bool {
+        let is = *self.peek() == token;
+        if is { self.bump(); }
+        is
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), ParseError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected {}", what)[]))
+        }
+    }
+
+    fn is_id(&self) -> bool {
+        match *self.peek() {
+            Token::Str(_) => true,
+            Token::Ident(_) => {
+                !["strict", "graph", "digraph", "node", "edge", "subgraph"].iter()
+                    .any(|kw| self.is_keyword(*kw))
+            }
+            _ => false,
+        }
+    }
+
+    fn id(&mut self) -> Result<String, ParseError> {
+        if !self.is_id() {
+            return Err(self.error("expected an identifier"));
+        }
+        match self.bump() {
+            Token::Ident(s) => Ok(s),
+            Token::Str(mut s) => {
+                while self.eat(Token::Plus) {
+                    match *self.peek() {
+                        Token::Str(ref more) => s.push_str(more[]),
+                        _ => return Err(self.error("expected a string after `+`")),
+                    }
+                    self.bump();
+                }
+                Ok(s)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn parse_graph(&mut self) -> Result<Graph, ParseError> {
+        let strict = self.eat_keyword("strict");
+        let kind = if self.eat_keyword("digraph") {
+            Kind::Digraph
+        } else if self.eat_keyword("graph") {
+            Kind::Graph
+        } else {
+            return Err(self.error("expected `graph` or `digraph`"));
+        };
+        let name = if self.is_id() { Some(try!(self.id())) } else { None };
+        let mut graph = Graph::new(kind, name);
+        graph.strict = strict;
+        self.graph = Some(graph);
+
+        try!(self.expect(Token::LBrace, "`{`"));
+        let mut root = Subgraph { name: None, attrs: Vec::new(), nodes: Vec::new(),
+                                  subgraphs: Vec::new() };
+        let mut defaults = Defaults { node: Vec::new(), edge: Vec::new() };
+        try!(self.stmt_list(&mut root, &mut defaults));
+        try!(self.expect(Token::RBrace, "`}`"));
+        if *self.peek() != Token::Eof {
+            return Err(self.error("expected end of input"));
+        }
+
+        let mut graph = self.graph.take().unwrap();
+        graph.attrs = root.attrs;
+        graph.subgraphs = root.subgraphs;
+        Ok(graph)
+    }
+
+    /// Parses statements up to the closing brace of `sub`.
+    fn stmt_list(&mut self, sub: &mut Subgraph, defaults: &mut Defaults)
+                 -> Result<(), ParseError> {
+        while *self.peek() != Token::RBrace && *self.peek() != Token::Eof {
+            try!(self.stmt(sub, defaults));
+            self.eat(Token::Semi);
+        }
+        Ok(())
+    }
+
+    fn stmt(&mut self, sub: &mut Subgraph, defaults: &mut Defaults) -> Result<(), ParseError> {
+        if self.eat_keyword("graph") {
+            return self.attr_list(&mut sub.attrs);
+        } else if self.eat_keyword("node") {
+            return self.attr_list(&mut defaults.node);
+        } else if self.eat_keyword("edge") {
+            return self.attr_list(&mut defaults.edge);
+        }
+
+        let first = if self.is_id() {
+            let name = try!(self.id());
+            if self.eat(Token::Equals) {
+                let value = try!(self.id());
+                set(&mut sub.attrs, name, value);
+                return Ok(());
+            }
+            let port = try!(self.port());
+            End::Node(self.node(name, sub, defaults), port)
+        } else {
+            End::Nodes(try!(self.subgraph(sub, defaults)))
+        };
+
+        let is_edge = match *self.peek() {
+            Token::EdgeOp(_) => true,
+            _ => false,
+        };
+        if is_edge {
+            return self.edges(first, sub, defaults);
+        }
+        match first {
+            End::Node(n, _) => {
+                let mut attrs = Vec::new();
+                try!(self.attr_list(&mut attrs));
+                for (name, value) in attrs.into_iter() {
+                    set(&mut self.graph().nodes[n].attrs, name, value);
+                }
+                Ok(())
+            }
+            End::Nodes(_) => Ok(()),
+        }
+    }
+
+    /// Parses an optional `:port` or `:port:compass_pt` after a node.
+    fn port(&mut self) -> Result<Option<String>, ParseError> {
+        if !self.eat(Token::Colon) {
+            return Ok(None);
+        }
+        let mut port = try!(self.id());
+        if self.eat(Token::Colon) {
+            port.push(':');
+            port.push_str(try!(self.id())[]);
+        }
+        Ok(Some(port))
+    }
+
+    /// Returns the index of the node `name`, creating it if needed, and
+    /// records it as a member of `sub`.
+    fn node(&mut self, name: String, sub: &mut Subgraph, defaults: &Defaults) -> uint {
+        let existing = self.index.get(&name).map(|&n| n);
+        let n = match existing {
+            Some(n) => n,
+            None => {
+                let graph = self.graph.as_mut().unwrap();
+                let n = graph.nodes.len();
+                self.index.insert(name.clone(), n);
+                graph.nodes.push(Node { name: name, attrs: defaults.node.clone() });
+                n
+            }
+        };
+        if !sub.nodes.contains(&n) {
+            sub.nodes.push(n);
+        }
+        n
+    }
+
+    /// Parses a subgraph, adding it to `parent`, and returns all the nodes
+    /// in it.
+    fn subgraph(&mut self, parent: &mut Subgraph, defaults: &Defaults)
+                -> Result<Vec<uint>, ParseError> {
+        let name = if self.eat_keyword("subgraph") {
+            if self.is_id() { Some(try!(self.id())) } else { None }
+        } else {
+            None
+        };
+        if *self.peek() != Token::LBrace {
+            return Err(self.error("expected a statement"));
+        }
+        self.bump();
+        let mut sub = Subgraph { name: name, attrs: Vec::new(), nodes: Vec::new(),
+                                 subgraphs: Vec::new() };
+        try!(self.stmt_list(&mut sub, &mut defaults.clone()));
+        try!(self.expect(Token::RBrace, "`}`"));
+        let nodes = sub.all_nodes();
+        parent.subgraphs.push(sub);
+        Ok(nodes)
+    }
+
+    /// Parses the rest of an edge statement starting at `first`.
+    fn edges(&mut self, first: End, sub: &mut Subgraph, defaults: &Defaults)
+             -> Result<(), ParseError> {
+        let mut ends = vec![first];
+        loop {
+            let kind = match *self.peek() {
+                Token::EdgeOp(kind) => kind,
+                _ => break,
+            };
+            if kind != self.graph().kind {
+                let msg = format!("`{}` is not allowed in a {}", kind.edgeop(),
+                                  self.graph().kind.keyword());
+                return Err(self.error(msg[]));
+            }
+            self.bump();
+            let end = if self.is_id() {
+                let name = try!(self.id());
+                let port = try!(self.port());
+                End::Node(self.node(name, sub, defaults), port)
+            } else {
+                End::Nodes(try!(self.subgraph(sub, defaults)))
+            };
+            ends.push(end);
+        }
+
+        let mut attrs = defaults.edge.clone();
+        try!(self.attr_list(&mut attrs));
+        for pair in ends.windows(2) {
+            for &(source, ref tailport) in nodes_of(&pair[0]).iter() {
+                for &(target, ref headport) in nodes_of(&pair[1]).iter() {
+                    let mut attrs = attrs.clone();
+                    match *tailport {
+                        Some(ref p) => set(&mut attrs, "tailport".to_string(), p.clone()),
+                        None => {}
+                    }
+                    match *headport {
+                        Some(ref p) => set(&mut attrs, "headport".to_string(), p.clone()),
+                        None => {}
+                    }
+                    self.add_edge(source, target, attrs);
+                }
+            }
+        }
+        return Ok(());
+
+        fn nodes_of(end: &End) -> Vec<(uint, Option<String>)> {
+            match *end {
+                End::Node(n, ref port) => vec![(n, port.clone())],
+                End::Nodes(ref nodes) => nodes.iter().map(|&n| (n, None)).collect(),
+            }
+        }
+    }
+
+    fn add_edge(&mut self, source: uint, target: uint, attrs: Vec<(String, String)>) {
+        let graph = self.graph();
+        if graph.strict {
+            let undirected = graph.kind == Kind::Graph;
+            let existing = graph.edges.iter_mut().find(|e| {
+                (e.source == source && e.target == target) ||
+                    (undirected && e.source == target && e.target == source)
+            });
+            match existing {
+                Some(edge) => {
+                    for (name, value) in attrs.into_iter() {
+                        set(&mut edge.attrs, name, value);
+                    }
+                    return;
+                }
+                None => {}
+            }
+        }
+        graph.edges.push(Edge { source: source, target: target, attrs: attrs });
+    }
+
+    /// Parses any number of bracketed attribute lists into `attrs`.
+    fn attr_list(&mut self, attrs: &mut Vec<(String, String)>) -> Result<(), ParseError> {
+        while self.eat(Token::LBracket) {
+            while !self.eat(Token::RBracket) {
+                let name = try!(self.id());
+                try!(self.expect(Token::Equals, "`=`"));
+                let value = try!(self.id());
+                set(attrs, name, value);
+                if !self.eat(Token::Comma) {
+                    self.eat(Token::Semi);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Graph, ParseError};
+    use {render, Kind};
+
+    fn names(g: &Graph, nodes: &[uint]) -> Vec<String> {
+        nodes.iter().map(|&n| g.nodes[n].name.clone()).collect()
+    }
+
+    fn edges(g: &Graph) -> Vec<String> {
+        g.edges.iter().map(|e| {
+            format!("{}{}{}", g.nodes[e.source].name, g.kind.edgeop(), g.nodes[e.target].name)
+        }).collect()
+    }
+
+    fn error(src: &str) -> ParseError {
+        match parse(src) {
+            Ok(_) => panic!("{} parsed", src),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn test_statements() {
+        let g = parse(r#"
+            /* a comment */
+            strict digraph "my graph" {
+                rankdir = LR
+                node [shape=box; color="red"]
+                a [label="A \"quoted\"\l", shape = ellipse];
+                b -> c -> a [weight=2.5, label=<<b>bold</b>>] // another
+                a:n -> -1:p:sw
+# not a statement
+                "multi" + "part"
+            }
+        "#).unwrap();
+        assert_eq!(g.kind, Kind::Digraph);
+        assert!(g.strict);
+        assert_eq!(g.name, Some("my graph".to_string()));
+        assert_eq!(g.attr("rankdir"), Some("LR"));
+        let all: Vec<uint> = range(0, g.nodes.len()).collect();
+        assert_eq!(names(&g, all[]), vec!["a", "b", "c", "-1", "multipart"]);
+
+        let a = &g.nodes[0];
+        assert_eq!(a.attr("label"), Some(r#"A "quoted"\l"#));
+        assert_eq!(a.attr("shape"), Some("ellipse"));
+        assert_eq!(a.attr("color"), Some("red"));
+        assert_eq!(g.nodes[1].attr("shape"), Some("box"));
+
+        assert_eq!(edges(&g), vec!["b->c", "c->a", "a->-1"]);
+        assert_eq!(g.edges[0].attr("label"), Some("<b>bold</b>"));
+        assert_eq!(g.edges[1].attr("weight"), Some("2.5"));
+        assert_eq!(g.edges[2].attr("tailport"), Some("n"));
+        assert_eq!(g.edges[2].attr("headport"), Some("p:sw"));
+    }
+
+    #[test]
+    fn test_subgraphs() {
+        let g = parse(r#"
+            graph {
+                edge [color=blue]
+                subgraph cluster_x {
+                    label = "X";
+                    edge [color=green]
+                    a -- b;
+                    { rank=same c d }
+                }
+                e -- { f g } -- h
+                a -- b
+            }
+        "#).unwrap();
+        assert_eq!(g.kind, Kind::Graph);
+        assert_eq!(g.name, None);
+        assert_eq!(edges(&g), vec!["a--b", "e--f", "e--g", "f--h", "g--h", "a--b"]);
+        assert_eq!(g.edges[0].attr("color"), Some("green"));
+        assert_eq!(g.edges[1].attr("color"), Some("blue"));
+
+        assert_eq!(g.subgraphs.len(), 2);
+        let x = &g.subgraphs[0];
+        assert_eq!(x.name, Some("cluster_x".to_string()));
+        assert_eq!(x.attr("label"), Some("X"));
+        assert_eq!(names(&g, x.nodes[]), vec!["a", "b"]);
+        assert_eq!(names(&g, x.all_nodes()[]), vec!["a", "b", "c", "d"]);
+        assert_eq!(x.subgraphs[0].attr("rank"), Some("same"));
+        assert_eq!(names(&g, g.subgraphs[1].nodes[]), vec!["f", "g"]);
+    }
+
+    #[test]
+    fn test_strict() {
+        let g = parse("strict graph { a -- b [x=1]; b -- a [y=2]; a -- c }").unwrap();
+        assert_eq!(edges(&g), vec!["a--b", "a--c"]);
+        assert_eq!(g.edges[0].attr("x"), Some("1"));
+        assert_eq!(g.edges[0].attr("y"), Some("2"));
+
+        let g = parse("graph { a -- b; b -- a }").unwrap();
+        assert_eq!(g.edges.len(), 2);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(error("digraph {\n  a -- b\n}"),
+                   ParseError { line: 2, col: 5, msg: "`--` is not allowed in a digraph".to_string() });
+        assert_eq!(error("graph {").msg[], "expected `}`");
+        assert_eq!(error("graph { a [b] }").msg[], "expected `=`");
+        assert_eq!(error("graph { a = }").msg[], "expected an identifier");
+        assert_eq!(error("tree { }").msg[], "expected `graph` or `digraph`");
+        assert_eq!(error("graph { } x").msg[], "expected end of input");
+        assert_eq!(error("graph { a -- node }").msg[], "expected a statement");
+        let e = error("graph {\n \"abc\n");
+        assert_eq!((e.line, e.col), (2, 2));
+        assert_eq!(e.msg[], "unterminated string");
+        assert_eq!(error("graph { a /* b }").msg[], "unterminated comment");
+        assert_eq!(error("graph { a & b }").msg[], "unexpected character `&`");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let src = r#"graph styled {
+    rankdir="LR";
+    N0[label="N0", style="filled", color="lightgrey", shape="box"];
+    N3[label="N3"];
+    subgraph cluster_0 {
+        label="Cluster \"0\"";
+        N1[label="N1"];
+        subgraph inner {
+            rank="same";
+            N2[label="N2"];
+        }
+    }
+    N0 -- N1[label="a\l", penwidth="2"];
+    N1 -- N2[label="b"];
+    N2 -- N2[label="c", style="dashed"];
+    N0 -- N3[label="d", penwidth="2"];
+}
+"#;
+        let mut out = Vec::new();
+        render(&parse(src).unwrap(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap()[], src);
+    }
+
+    #[test]
+    fn test_generated_ids() {
+        let g = parse(r#"digraph { "a b" -> c; subgraph "cluster 1" { d } { e } }"#).unwrap();
+        let mut out = Vec::new();
+        render(&g, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap()[],
+r#"digraph G {
+    _N0[label="a b"];
+    c[label="c"];
+    subgraph cluster_S1 {
+        d[label="d"];
+    }
+    subgraph _S2 {
+        e[label="e"];
+    }
+    _N0 -> c;
+}
+"#);
+
+        // A generated id is never the name of another node.
+        let g = parse(r#"digraph { "a b" -> _N0; "c d" -> _N0_ }"#).unwrap();
+        let mut out = Vec::new();
+        render(&g, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap()[],
+r#"digraph G {
+    _N0__[label="a b"];
+    _N0[label="_N0"];
+    _N2[label="c d"];
+    _N0_[label="_N0_"];
+    _N0__ -> _N0;
+    _N2 -> _N0_;
+}
+"#);
+
+        // Nor is a generated subgraph id the name of another subgraph.
+        let g = parse(r#"digraph { { a } subgraph _S1 { subgraph _S2 { b } } }"#).unwrap();
+        let mut out = Vec::new();
+        render(&g, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap()[],
+r#"digraph G {
+    subgraph _S3 {
+        a[label="a"];
+    }
+    subgraph _S1 {
+        subgraph _S2 {
+            b[label="b"];
+        }
+    }
+}
+"#);
+    }
+}
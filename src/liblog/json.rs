@@ -0,0 +1,138 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A logger writing one JSON object per record
+//!
+//! Each record becomes a single line such as (wrapped here):
+//!
+//! ```text
+//! {"fields":{"attempts":3,"user":"alice"},"file":"main.rs","level":"WARN",
+//!  "line":5,"message":"login failed","module":"main"}
+//! ```
+//!
+//! which makes the output easy to feed into tools that consume JSON lines.
+//!
+//! # Example
+//!
+//! ```
+//! #![feature(phase)]
+//! #[phase(plugin, link)] extern crate log;
+//!
+//! use std::io;
+//! use log::json::JsonLogger;
+//!
+//! fn main() {
+//!     log::set_logger(box JsonLogger::new(io::stderr()));
+//!     error!(code = 3i; "the build has failed");
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::io::Writer;
+
+use serialize::json::Json;
+
+use {Logger, LogRecord, LogValue};
+
+/// A `Logger` which writes each record to a `Writer` as a line of JSON.
+///
+/// The object has the keys `level`, `module`, `file`, `line` and `message`,
+/// and the record's key/value pairs under `fields`.
+pub struct JsonLogger<W> {
+    writer: W,
+}
+
+impl<W: Writer> JsonLogger<W> {
+    /// Creates a logger writing to `writer`.
+    pub fn new(writer: W) -> JsonLogger<W> {
+        JsonLogger { writer: writer }
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W { &self.writer }
+
+    /// Unwraps this logger, returning the underlying writer.
+    pub fn into_inner(self) -> W { self.writer }
+}
+
+/// Converts a record to the JSON object `JsonLogger` writes.
+pub fn record_to_json(record: &LogRecord) -> Json {
+    let mut fields = BTreeMap::new();
+    for &(key, value) in record.kvs.iter() {
+        let value = match value {
+            LogValue::Str(s) => Json::String(s.to_string()),
+            LogValue::I64(n) => Json::I64(n),
+            LogValue::U64(n) => Json::U64(n),
+            LogValue::F64(n) => Json::F64(n),
+            LogValue::Bool(b) => Json::Boolean(b),
+        };
+        fields.insert(key.to_string(), value);
+    }
+
+    let mut obj = BTreeMap::new();
+    obj.insert("level".to_string(), Json::String(record.level.to_string()));
+    obj.insert("module".to_string(), Json::String(record.module_path.to_string()));
+    obj.insert("file".to_string(), Json::String(record.file.to_string()));
+    obj.insert("line".to_string(), Json::U64(record.line as u64));
+    obj.insert("message".to_string(), Json::String(record.args.to_string()));
+    obj.insert("fields".to_string(), Json::Object(fields));
+    Json::Object(obj)
+}
+
+impl<W: Writer> Logger for JsonLogger<W> {
+    fn log(&mut self, record: &LogRecord) {
+        match writeln!(&mut self.writer, "{}", record_to_json(record)) {
+            Err(e) => panic!("failed to log: {}", e),
+            Ok(()) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonLogger;
+    use serialize::json::{mod, Json};
+    use {Logger, LogLevel, LogRecord, LogValue, INFO};
+
+    #[test]
+    fn json_lines() {
+        let mut logger = JsonLogger::new(Vec::new());
+        let kvs = [("user", LogValue::Str("al\"ice")), ("attempts", LogValue::U64(3)),
+                   ("ok", LogValue::Bool(false))];
+        logger.log(&LogRecord {
+            module_path: "main",
+            level: LogLevel(INFO),
+            args: format_args!("login {}", "failed"),
+            kvs: kvs[],
+            file: "main.rs",
+            line: 5,
+        });
+        logger.log(&LogRecord {
+            module_path: "main::sub",
+            level: LogLevel(7),
+            args: format_args!("second"),
+            kvs: &[],
+            file: "sub.rs",
+            line: 10,
+        });
+
+        let out = String::from_utf8(logger.into_inner()).unwrap();
+        let lines: Vec<&str> = out[].lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "{\"fields\":{\"attempts\":3,\"ok\":false,\"user\":\"al\\\"ice\"},\
+                              \"file\":\"main.rs\",\"level\":\"INFO\",\"line\":5,\
+                              \"message\":\"login failed\",\"module\":\"main\"}");
+
+        let second = json::from_str(lines[1]).unwrap();
+        assert_eq!(second.find("level"), Some(&Json::String("7".to_string())));
+        assert_eq!(second.find("fields").and_then(|f| f.as_object()).map(|f| f.len()),
+                   Some(0));
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Typed values attached to log records
+
+use std::fmt;
+
+/// The value of a key/value pair attached to a `LogRecord`.
+#[deriving(Copy, Clone, PartialEq)]
+pub enum LogValue<'a> {
+    /// A string.
+    Str(&'a str),
+    /// A signed integer.
+    I64(i64),
+    /// An unsigned integer.
+    U64(u64),
+    /// A floating point number.
+    F64(f64),
+    /// A boolean.
+    Bool(bool),
+}
+
+/// Numbers and booleans are shown as they are, and strings quoted and escaped
+/// as in Rust source code.
+impl<'a> fmt::Show for LogValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LogValue::Str(s) => write!(f, "\"{}\"", s.escape_default()),
+            LogValue::I64(n) => n.fmt(f),
+            LogValue::U64(n) => n.fmt(f),
+            LogValue::F64(n) => n.fmt(f),
+            LogValue::Bool(b) => b.fmt(f),
+        }
+    }
+}
+
+/// Conversion of a value into a `LogValue` borrowing from it, used by the
+/// logging macros to capture key/value pairs.
+pub trait ToLogValue for Sized? {
+    /// Returns the value to log for `self`.
+    fn to_log_value<'a>(&'a self) -> LogValue<'a>;
+}
+
+impl ToLogValue for str {
+    fn to_log_value<'a>(&'a self) -> LogValue<'a> { LogValue::Str(self) }
+}
+
+impl ToLogValue for String {
+    fn to_log_value<'a>(&'a self) -> LogValue<'a> { LogValue::Str(self[]) }
+}
+
+impl ToLogValue for bool {
+    fn to_log_value<'a>(&'a self) -> LogValue<'a> { LogValue::Bool(*self) }
+}
+
+impl<'b, Sized? T: ToLogValue> ToLogValue for &'b T {
+    fn to_log_value<'a>(&'a self) -> LogValue<'a> { (**self).to_log_value() }
+}
+
+macro_rules! to_log_value_impl {
+    ($variant:ident, $repr:ty, $($t:ty),+) => ($(
+        impl ToLogValue for $t {
+            fn to_log_value<'a>(&'a self) -> LogValue<'a> {
+                LogValue::$variant(*self as $repr)
+            }
+        }
+    )+)
+}
+
+to_log_value_impl!(I64, i64, int, i8, i16, i32, i64);
+to_log_value_impl!(U64, u64, uint, u8, u16, u32, u64);
+to_log_value_impl!(F64, f64, f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::{LogValue, ToLogValue};
+
+    #[test]
+    fn conversions() {
+        let s = "text".to_string();
+        assert_eq!(s.to_log_value(), LogValue::Str("text"));
+        assert_eq!("x".to_log_value(), LogValue::Str("x"));
+        assert_eq!((-3i8).to_log_value(), LogValue::I64(-3));
+        assert_eq!(7u.to_log_value(), LogValue::U64(7));
+        assert_eq!(0.5f32.to_log_value(), LogValue::F64(0.5));
+        assert_eq!((&&true).to_log_value(), LogValue::Bool(true));
+    }
+
+    #[test]
+    fn show() {
+        assert_eq!(LogValue::Str("a \"b\"\n").to_string(), "\"a \\\"b\\\"\\n\"");
+        assert_eq!(LogValue::I64(-1).to_string(), "-1");
+        assert_eq!(LogValue::Bool(false).to_string(), "false");
+    }
+}
@@ -79,6 +79,17 @@
 //! extension. Details about the syntax can be found in the documentation of
 //! `std::fmt` along with the Rust tutorial/manual.
 //!
+//! Any of them can also attach typed key/value pairs to the record, written
+//! before the format string and separated from it by a semicolon:
+//!
+//! ```rust,ignore
+//! info!(user = name, attempts = 3u; "login failed");
+//! ```
+//!
+//! Loggers see these pairs in `LogRecord::kvs`. The default logger prints
+//! them after the message, and `json::JsonLogger` writes each record as a
+//! line of JSON.
+//!
 //! If you want to check at runtime if a given logging level is enabled (e.g. if the
 //! information you would want to log is expensive to produce), you can use the
 //! following macro:
@@ -142,6 +153,24 @@
 //!  hello. In both cases the log message must include a single digit number
 //!  followed by 'scopes'
 //!
+//! # Changing directives at runtime
+//!
+//! `set_directives` replaces the directives read from `RUST_LOG` with a new
+//! specification in the same syntax, taking effect in every task at once:
+//!
+//! ```rust,ignore
+//! log::set_directives("warn,hello=debug");
+//! ```
+//!
+//! # Sinks
+//!
+//! By default records are written to stderr. `add_sink` registers a
+//! process-wide `Logger` together with the maximum level it receives, and
+//! once there are any sinks every record is sent to each sink whose level
+//! allows it instead. The `sink` module has loggers for writers, rotating log
+//! files and syslog. A task can still capture its own records with
+//! `set_logger`, which takes precedence over the sinks.
+//!
 //! # Performance and Side Effects
 //!
 //! Each of these macros will expand to code similar to:
@@ -167,24 +196,32 @@
 #![feature(macro_rules, unboxed_closures, slicing_syntax)]
 #![deny(missing_docs)]
 
+extern crate libc;
 extern crate regex;
+extern crate serialize;
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::cmp;
 use std::fmt;
-use std::io::LineBufferedWriter;
 use std::io;
 use std::mem;
 use std::os;
 use std::rt;
 use std::slice;
-use std::sync::{Once, ONCE_INIT};
+use std::sync::{Mutex, MutexGuard, RWLock, Once, ONCE_INIT};
+use std::sync::atomic::{AtomicUint, ATOMIC_UINT_INIT, Ordering};
 
 use regex::Regex;
 
 use directive::LOG_LEVEL_NAMES;
 
+pub use kv::{LogValue, ToLogValue};
+
+pub mod json;
 pub mod macros;
+pub mod sink;
 mod directive;
+mod kv;
 
 /// Maximum logging level of a module that can be specified. Common logging
 /// levels are found in the DEBUG/INFO/WARN/ERROR constants.
@@ -193,16 +230,43 @@ pub const MAX_LOG_LEVEL: u32 = 255;
 /// The default logging level of a crate if no other is specified.
 const DEFAULT_LOG_LEVEL: u32 = 1;
 
-/// An unsafe constant that is the maximum logging level of any module
-/// specified. This is the first line of defense to determining whether a
-/// logging statement should be run.
-static mut LOG_LEVEL: u32 = MAX_LOG_LEVEL;
+/// The maximum logging level of any module. This is the first line of defense
+/// to determining whether a logging statement should be run. It is only
+/// written with the write lock on `State::filter` held, and is `0` until
+/// logging has been initialized.
+static LOG_LEVEL: AtomicUint = ATOMIC_UINT_INIT;
+
+/// The minimum logging level of any module, below which every module is
+/// enabled without looking at the directives. It is only written with the
+/// write lock on `State::filter` held.
+static MIN_LOG_LEVEL: AtomicUint = ATOMIC_UINT_INIT;
 
-static mut DIRECTIVES: *const Vec<directive::LogDirective> =
-    0 as *const Vec<directive::LogDirective>;
+/// The process-wide logging state, created by `init`.
+static mut STATE: *const State = 0 as *const State;
 
-/// Optional regex filter.
-static mut FILTER: *const Regex = 0 as *const _;
+static INIT: Once = ONCE_INIT;
+
+struct State {
+    /// The directives sorted by the length of their names, and the optional
+    /// regex filter.
+    filter: RWLock<(Vec<directive::LogDirective>, Option<Regex>)>,
+    sinks: Mutex<Sinks>,
+}
+
+struct Sinks {
+    next_id: uint,
+    sinks: Vec<Sink>,
+}
+
+struct Sink {
+    id: SinkId,
+    level: u32,
+    logger: Box<Logger + Send>,
+}
+
+/// Identifies a sink added with `add_sink`.
+#[deriving(Copy, Clone, PartialEq, Eq, Show)]
+pub struct SinkId(uint);
 
 /// Debug log level
 pub const DEBUG: u32 = 4;
@@ -219,18 +283,18 @@ thread_local! {
     }
 }
 
-/// A trait used to represent an interface to a task-local logger. Each task
-/// can have its own custom logger which can respond to logging messages
-/// however it likes.
+// Set while this thread is running the process-wide sinks, so that a sink
+// which itself logs goes to stderr rather than deadlocking.
+thread_local! { static IN_SINKS: Cell<bool> = Cell::new(false) }
+
+/// A trait used to represent an interface to a logger. Each task can have its
+/// own custom logger which can respond to logging messages however it likes,
+/// and the process as a whole can send messages to any number of sinks.
 pub trait Logger {
     /// Logs a single message described by the `record`.
     fn log(&mut self, record: &LogRecord);
 }
 
-struct DefaultLogger {
-    handle: LineBufferedWriter<io::stdio::StdWriter>,
-}
-
 /// Wraps the log level with fmt implementations.
 #[deriving(Copy, PartialEq, PartialOrd)]
 pub struct LogLevel(pub u32);
@@ -245,28 +309,6 @@ impl fmt::Show for LogLevel {
     }
 }
 
-impl Logger for DefaultLogger {
-    fn log(&mut self, record: &LogRecord) {
-        match writeln!(&mut self.handle,
-                       "{}:{}: {}",
-                       record.level,
-                       record.module_path,
-                       record.args) {
-            Err(e) => panic!("failed to log: {}", e),
-            Ok(()) => {}
-        }
-    }
-}
-
-impl Drop for DefaultLogger {
-    fn drop(&mut self) {
-        // FIXME(#12628): is panicking the right thing to do?
-        match self.handle.flush() {
-            Err(e) => panic!("failed to flush a logger: {}", e),
-            Ok(()) => {}
-        }
-    }
-}
 
 /// This function is called directly by the compiler when using the logging
 /// macros. This function does not take into account whether the log level
@@ -277,39 +319,137 @@ impl Drop for DefaultLogger {
 /// invoked through the logging family of macros.
 #[doc(hidden)]
 pub fn log(level: u32, loc: &'static LogLocation, args: fmt::Arguments) {
+    log_kv(level, loc, &[], args)
+}
+
+/// Like `log`, but also attaches the key/value pairs `kvs` to the record.
+#[doc(hidden)]
+pub fn log_kv(level: u32, loc: &'static LogLocation, kvs: &[(&str, LogValue)],
+              args: fmt::Arguments) {
     // Test the literal string from args against the current filter, if there
     // is one.
-    match unsafe { FILTER.as_ref() } {
-        Some(filter) if !filter.is_match(args.to_string()[]) => return,
-        _ => {}
+    {
+        let guard = state().filter.read().unwrap();
+        let (_, ref filter) = *guard;
+        match *filter {
+            Some(ref filter) if !filter.is_match(args.to_string()[]) => return,
+            _ => {}
+        }
     }
 
-    // Completely remove the local logger from TLS in case anyone attempts to
-    // frob the slot while we're doing the logging. This will destroy any logger
-    // set during logging.
-    let mut logger = LOCAL_LOGGER.with(|s| {
-        s.borrow_mut().take()
-    }).unwrap_or_else(|| {
-        box DefaultLogger { handle: io::stderr() } as Box<Logger + Send>
-    });
-    logger.log(&LogRecord {
+    let record = LogRecord {
         level: LogLevel(level),
         args: args,
+        kvs: kvs,
         file: loc.file,
         module_path: loc.module_path,
         line: loc.line,
-    });
-    set_logger(logger);
+    };
+
+    // Completely remove the local logger from TLS in case anyone attempts to
+    // frob the slot while we're doing the logging. This will destroy any logger
+    // set during logging.
+    match LOCAL_LOGGER.with(|s| s.borrow_mut().take()) {
+        Some(mut logger) => {
+            logger.log(&record);
+            set_logger(logger);
+            return
+        }
+        None => {}
+    }
+
+    if !IN_SINKS.with(|c| c.get()) {
+        let mut sinks = lock_sinks();
+        if !sinks.sinks.is_empty() {
+            IN_SINKS.with(|c| c.set(true));
+            for sink in sinks.sinks.iter_mut() {
+                if level <= sink.level {
+                    sink.logger.log(&record);
+                }
+            }
+            IN_SINKS.with(|c| c.set(false));
+            return
+        }
+    }
+    sink::WriterLogger::new(io::stderr()).log(&record);
 }
 
 /// Getter for the global log level. This is a function so that it can be called
 /// safely
 #[doc(hidden)]
 #[inline(always)]
-pub fn log_level() -> u32 { unsafe { LOG_LEVEL } }
+pub fn log_level() -> u32 {
+    // Before initialization every level has to reach `mod_enabled`, which
+    // initializes logging. A level of `0` also ends up there, and is rejected.
+    match LOG_LEVEL.load(Ordering::Relaxed) as u32 {
+        0 => MAX_LOG_LEVEL,
+        level => level,
+    }
+}
+
+/// Replaces the logging directives of the whole process, as if `RUST_LOG` had
+/// been set to `spec` at startup. This takes effect immediately in every task.
+///
+/// Invalid parts of the specification are reported on stdout and ignored,
+/// just as they are for `RUST_LOG`.
+pub fn set_directives(spec: &str) {
+    let (directives, filter) = directive::parse_logging_spec(spec);
+    install(state(), directives, filter);
+}
+
+/// Adds a process-wide sink receiving every record of `level` or below, and
+/// returns its id.
+///
+/// Records go to the sinks unless the task logging them has its own logger
+/// set with `set_logger`. They are sent to stderr when there are no sinks.
+/// Note that a record is only produced if the directives enable it, so a sink
+/// level can narrow but never widen what is logged.
+pub fn add_sink(level: u32, logger: Box<Logger + Send>) -> SinkId {
+    let mut sinks = lock_sinks();
+    let id = SinkId(sinks.next_id);
+    sinks.next_id += 1;
+    sinks.sinks.push(Sink { id: id, level: level, logger: logger });
+    id
+}
+
+/// Removes a sink, returning its logger, or `None` if there is no such sink.
+pub fn remove_sink(id: SinkId) -> Option<Box<Logger + Send>> {
+    let mut sinks = lock_sinks();
+    match sinks.sinks.iter().position(|s| s.id == id) {
+        Some(i) => Some(sinks.sinks.remove(i).logger),
+        None => None,
+    }
+}
+
+/// Changes the level of a sink, returning whether the sink exists.
+pub fn set_sink_level(id: SinkId, level: u32) -> bool {
+    let mut sinks = lock_sinks();
+    match sinks.sinks.iter_mut().find(|s| s.id == id) {
+        Some(sink) => { sink.level = level; true }
+        None => false,
+    }
+}
+
+fn state() -> &'static State {
+    INIT.call_once(init);
+
+    // This assertion should never get tripped unless we're in an at_exit
+    // handler after logging has been torn down and a logging attempt was made.
+    assert!(unsafe { !STATE.is_null() });
+    unsafe { &*STATE }
+}
+
+/// Locks the sinks, ignoring poisoning by a sink which panicked.
+fn lock_sinks() -> MutexGuard<'static, Sinks> {
+    match state().sinks.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_guard(),
+    }
+}
 
 /// Replaces the task-local logger with the specified logger, returning the old
-/// logger.
+/// logger. Records logged by this task go to this logger rather than to the
+/// process-wide sinks.
 pub fn set_logger(logger: Box<Logger + Send>) -> Option<Box<Logger + Send>> {
     let mut l = Some(logger);
     LOCAL_LOGGER.with(|slot| {
@@ -331,6 +471,9 @@ pub struct LogRecord<'a> {
     /// The arguments from the log line.
     pub args: fmt::Arguments<'a>,
 
+    /// The key/value pairs attached to the log line, in the order given.
+    pub kvs: &'a [(&'a str, LogValue<'a>)],
+
     /// The file of where the LogRecord originated.
     pub file: &'a str,
 
@@ -351,21 +494,23 @@ pub struct LogLocation {
 /// module's log statement should be emitted or not.
 #[doc(hidden)]
 pub fn mod_enabled(level: u32, module: &str) -> bool {
-    static INIT: Once = ONCE_INIT;
-    INIT.call_once(init);
+    let state = state();
 
     // It's possible for many threads are in this function, only one of them
     // will perform the global initialization, but all of them will need to check
     // again to whether they should really be here or not. Hence, despite this
     // check being expanded manually in the logging macro, this function checks
     // the log level again.
-    if level > unsafe { LOG_LEVEL } { return false }
+    if level > LOG_LEVEL.load(Ordering::Relaxed) as u32 { return false }
 
-    // This assertion should never get tripped unless we're in an at_exit
-    // handler after logging has been torn down and a logging attempt was made.
-    assert!(unsafe { !DIRECTIVES.is_null() });
+    // Only a level between the two bounds depends on the module. A concurrent
+    // `set_directives` may leave the bounds of the old and new directives
+    // mixed for a moment, which at worst decides one record either way.
+    if level <= MIN_LOG_LEVEL.load(Ordering::Relaxed) as u32 { return true }
 
-    enabled(level, module, unsafe { (*DIRECTIVES).iter() })
+    let guard = state.filter.read().unwrap();
+    let (ref directives, _) = *guard;
+    enabled(level, module, directives.iter())
 }
 
 fn enabled(level: u32,
@@ -389,11 +534,33 @@ fn enabled(level: u32,
 /// This is not threadsafe at all, so initialization is performed through a
 /// `Once` primitive (and this function is called from that primitive).
 fn init() {
-    let (mut directives, filter) = match os::getenv("RUST_LOG") {
+    let (directives, filter) = match os::getenv("RUST_LOG") {
         Some(spec) => directive::parse_logging_spec(spec[]),
         None => (Vec::new(), None),
     };
 
+    let state = box State {
+        filter: RWLock::new((Vec::new(), None)),
+        sinks: Mutex::new(Sinks { next_id: 0, sinks: Vec::new() }),
+    };
+    install(&*state, directives, filter);
+
+    unsafe {
+        assert!(STATE.is_null());
+        STATE = mem::transmute(state);
+
+        // Schedule the cleanup for the globals for when the runtime exits.
+        rt::at_exit(move |:| {
+            assert!(!STATE.is_null());
+            let _state: Box<State> = mem::transmute(STATE);
+            STATE = 0 as *const State;
+        });
+    }
+}
+
+/// Makes `directives` and `filter` the current ones.
+fn install(state: &State, mut directives: Vec<directive::LogDirective>,
+           filter: Option<Regex>) {
     // Sort the provided directives by length of their name, this allows a
     // little more efficient lookup at runtime.
     directives.sort_by(|a, b| {
@@ -402,41 +569,38 @@ fn init() {
         alen.cmp(&blen)
     });
 
-    let max_level = {
-        let max = directives.iter().max_by(|d| d.level);
-        max.map(|d| d.level).unwrap_or(DEFAULT_LOG_LEVEL)
-    };
-
-    unsafe {
-        LOG_LEVEL = max_level;
+    let (min_level, max_level) = level_bounds(directives.iter());
 
-        assert!(FILTER.is_null());
-        match filter {
-            Some(f) => FILTER = mem::transmute(box f),
-            None => {}
-        }
-
-        assert!(DIRECTIVES.is_null());
-        DIRECTIVES = mem::transmute(box directives);
+    let mut guard = state.filter.write().unwrap();
+    *guard = (directives, filter);
+    MIN_LOG_LEVEL.store(min_level as uint, Ordering::Relaxed);
+    LOG_LEVEL.store(max_level as uint, Ordering::Relaxed);
+}
 
-        // Schedule the cleanup for the globals for when the runtime exits.
-        rt::at_exit(move |:| {
-            assert!(!DIRECTIVES.is_null());
-            let _directives: Box<Vec<directive::LogDirective>> =
-                mem::transmute(DIRECTIVES);
-            DIRECTIVES = 0 as *const Vec<directive::LogDirective>;
-
-            if !FILTER.is_null() {
-                let _filter: Box<Regex> = mem::transmute(FILTER);
-                FILTER = 0 as *const _;
-            }
+/// The lowest and highest levels that `directives` enable any module at,
+/// including the modules that none of them name.
+fn level_bounds(directives: slice::Iter<directive::LogDirective>) -> (u32, u32) {
+    let mut has_default = false;
+    let mut bounds: Option<(u32, u32)> = None;
+    for directive in directives {
+        has_default |= directive.name.is_none();
+        bounds = Some(match bounds {
+            Some((min, max)) => (cmp::min(min, directive.level),
+                                 cmp::max(max, directive.level)),
+            None => (directive.level, directive.level),
         });
     }
+    match bounds {
+        Some(bounds) if has_default => bounds,
+        Some((min, max)) => (cmp::min(min, DEFAULT_LOG_LEVEL),
+                             cmp::max(max, DEFAULT_LOG_LEVEL)),
+        None => (DEFAULT_LOG_LEVEL, DEFAULT_LOG_LEVEL),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::enabled;
+    use super::{enabled, level_bounds};
     use directive::LogDirective;
 
     #[test]
@@ -505,4 +669,28 @@ mod tests {
         assert!(!enabled(1, "crate1::mod1", dirs.iter()));
         assert!(enabled(3, "crate2::mod2", dirs.iter()));
     }
+
+    #[test]
+    fn bounds() {
+        assert_eq!(level_bounds([].iter()), (1, 1));
+
+        // Modules which no directive names are at the default level.
+        let dirs = [
+            LogDirective { name: Some("crate2".to_string()), level: 3 },
+            LogDirective { name: Some("crate1::mod1".to_string()), level: 2 }
+        ];
+        assert_eq!(level_bounds(dirs.iter()), (1, 3));
+
+        let dirs = [
+            LogDirective { name: None, level: 3 },
+            LogDirective { name: Some("crate1::mod1".to_string()), level: 4 }
+        ];
+        assert_eq!(level_bounds(dirs.iter()), (3, 4));
+
+        let dirs = [
+            LogDirective { name: None, level: 3 },
+            LogDirective { name: Some("crate1::mod1".to_string()), level: 0 }
+        ];
+        assert_eq!(level_bounds(dirs.iter()), (0, 3));
+    }
 }
@@ -50,8 +50,46 @@
 /// WARN:main: this is a warning message
 /// 6:main: this is a custom logging level: 6
 /// ```
+///
+/// Key/value pairs can be attached to the record before the format string,
+/// separated from it by a semicolon. Keys are identifiers and values are
+/// anything implementing `log::ToLogValue`: strings, numbers and booleans.
+///
+/// ```
+/// #![feature(phase)]
+/// #[phase(plugin, link)] extern crate log;
+///
+/// fn main() {
+///     let user = "alice";
+///     log!(log::WARN, user = user, attempts = 3u; "login failed");
+///     warn!(path = "/tmp/x", size = 12i; "file is {} bytes too big", 2i);
+/// }
+/// ```
+///
+/// The default logger prints the pairs after the message:
+///
+/// ```{.bash}
+/// $ RUST_LOG=warn ./main
+/// WARN:main: login failed user="alice" attempts=3
+/// WARN:main: file is 2 bytes too big path="/tmp/x" size=12
+/// ```
 #[macro_export]
 macro_rules! log {
+    // The key is a `tt` rather than an `ident` so that plain format strings
+    // fall through to the next arm instead of failing to parse.
+    ($lvl:expr, $($key:tt = $value:expr),+; $($arg:tt)+) => ({
+        static LOC: ::log::LogLocation = ::log::LogLocation {
+            line: line!(),
+            file: file!(),
+            module_path: module_path!(),
+        };
+        let lvl = $lvl;
+        if log_enabled!(lvl) {
+            ::log::log_kv(lvl, &LOC,
+                          &[$((stringify!($key), ::log::ToLogValue::to_log_value(&$value))),+],
+                          format_args!($($arg)+))
+        }
+    });
     ($lvl:expr, $($arg:tt)+) => ({
         static LOC: ::log::LogLocation = ::log::LogLocation {
             line: line!(),
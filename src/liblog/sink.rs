@@ -0,0 +1,357 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Loggers for use as process-wide sinks
+//!
+//! Any `Logger` can be registered with `log::add_sink`; this module provides
+//! the common destinations: a `Writer` such as stderr, a file which is rotated
+//! when it grows too large, and (on Unix) the local syslog daemon.
+//!
+//! # Example
+//!
+//! ```no_run
+//! #![feature(phase)]
+//! #[phase(plugin, link)] extern crate log;
+//!
+//! use std::io;
+//! use log::sink::{WriterLogger, RotatingFileLogger};
+//!
+//! fn main() {
+//!     log::set_directives("info");
+//!     log::add_sink(log::WARN, box WriterLogger::new(io::stderr()));
+//!     let file = RotatingFileLogger::new(&Path::new("app.log"), 1 << 20, 3).unwrap();
+//!     log::add_sink(log::INFO, box file);
+//!
+//!     info!("only in app.log");
+//!     error!("in app.log and on stderr");
+//! }
+//! ```
+
+use std::io::{File, IoResult, Writer, Append, Truncate, Write};
+use std::io::fs::{mod, PathExtensions};
+
+use {Logger, LogRecord};
+
+/// Writes `record` as a line of text, the way the default logger does:
+/// `LEVEL:module::path: message key=value ...`.
+pub fn write_record<W: Writer>(w: &mut W, record: &LogRecord) -> IoResult<()> {
+    try!(write!(w, "{}:{}: {}", record.level, record.module_path, record.args));
+    for &(key, value) in record.kvs.iter() {
+        try!(write!(w, " {}={}", key, value));
+    }
+    w.write_char('\n')
+}
+
+/// A logger writing records as lines of text to a `Writer`, flushing it after
+/// each one.
+pub struct WriterLogger<W: Writer> {
+    writer: W,
+}
+
+impl<W: Writer> WriterLogger<W> {
+    /// Creates a logger writing to `writer`.
+    pub fn new(writer: W) -> WriterLogger<W> {
+        WriterLogger { writer: writer }
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W { &self.writer }
+}
+
+impl<W: Writer> Logger for WriterLogger<W> {
+    fn log(&mut self, record: &LogRecord) {
+        // FIXME(#12628): is panicking the right thing to do?
+        match write_record(&mut self.writer, record).and_then(|()| self.writer.flush()) {
+            Err(e) => panic!("failed to log: {}", e),
+            Ok(()) => {}
+        }
+    }
+}
+
+/// A logger appending records as lines of text to a file, which is rotated
+/// when it would grow beyond a given size.
+///
+/// On rotation `path` is renamed to `path.1`, `path.1` to `path.2` and so on,
+/// keeping a fixed number of old files, and a new `path` is started.
+pub struct RotatingFileLogger {
+    path: Path,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    keep: uint,
+}
+
+impl RotatingFileLogger {
+    /// Opens `path` for appending. Once the file would grow beyond
+    /// `max_bytes` it is rotated, keeping `keep` old files; with `keep` of 0
+    /// the file is simply truncated. A single record larger than `max_bytes`
+    /// is still written whole.
+    pub fn new(path: &Path, max_bytes: u64, keep: uint) -> IoResult<RotatingFileLogger> {
+        let mut file = try!(File::open_mode(path, Append, Write));
+        let size = try!(file.stat()).size;
+        Ok(RotatingFileLogger {
+            path: path.clone(),
+            file: file,
+            size: size,
+            max_bytes: max_bytes,
+            keep: keep,
+        })
+    }
+
+    /// The path of the `n`th old file.
+    fn rotated_path(&self, n: uint) -> Path {
+        let mut name = self.path.filename().unwrap_or(b"log").to_vec();
+        name.push_all(format!(".{}", n).as_bytes());
+        self.path.with_filename(name)
+    }
+
+    fn rotate(&mut self) -> IoResult<()> {
+        if self.keep > 0 {
+            for n in range(1, self.keep).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    try!(fs::rename(&from, &self.rotated_path(n + 1)));
+                }
+            }
+            try!(fs::rename(&self.path, &self.rotated_path(1)));
+        }
+        self.file = try!(File::open_mode(&self.path, Truncate, Write));
+        self.size = 0;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &[u8]) -> IoResult<()> {
+        if self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            try!(self.rotate());
+        }
+        try!(self.file.write(line));
+        self.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+impl Logger for RotatingFileLogger {
+    fn log(&mut self, record: &LogRecord) {
+        let mut line = Vec::new();
+        let result = write_record(&mut line, record).and_then(|()| self.write_line(line[]));
+        match result {
+            Err(e) => panic!("failed to log to {}: {}", self.path.display(), e),
+            Ok(()) => {}
+        }
+    }
+}
+
+#[cfg(unix)]
+pub use self::syslog::SyslogLogger;
+
+#[cfg(unix)]
+mod syslog {
+    use libc;
+    use std::io::{mod, IoError, IoResult};
+    use std::mem;
+    use std::os;
+
+    use {Logger, LogLevel, LogRecord, ERROR, WARN, INFO};
+    use super::write_record;
+
+    /// The `user` syslog facility, for generic user-level messages.
+    const LOG_USER: u32 = 1;
+
+    /// A logger sending records to a syslog daemon over a Unix datagram
+    /// socket, such as `/dev/log`.
+    ///
+    /// Each record is sent as `<PRI>tag[pid]: LEVEL:module: message`, where
+    /// the severity in `PRI` is derived from the log level.
+    pub struct SyslogLogger {
+        fd: libc::c_int,
+        tag: String,
+        facility: u32,
+    }
+
+    impl SyslogLogger {
+        /// Connects to the local syslog daemon at `/dev/log`, tagging
+        /// messages with `tag` (usually the program name) and the `user`
+        /// facility.
+        pub fn new(tag: &str) -> IoResult<SyslogLogger> {
+            SyslogLogger::connect(&Path::new("/dev/log"), tag)
+        }
+
+        /// Connects to a syslog daemon listening on the Unix datagram socket
+        /// at `path`.
+        pub fn connect(path: &Path, tag: &str) -> IoResult<SyslogLogger> {
+            let bytes = path.as_vec();
+            let mut addr: libc::sockaddr_un = unsafe { mem::zeroed() };
+            if bytes.len() >= addr.sun_path.len() {
+                return Err(IoError {
+                    kind: io::InvalidInput,
+                    desc: "path must be shorter than SUN_LEN",
+                    detail: None,
+                })
+            }
+            addr.sun_family = libc::AF_UNIX as libc::sa_family_t;
+            for (slot, &b) in addr.sun_path.iter_mut().zip(bytes.iter()) {
+                *slot = b as libc::c_char;
+            }
+
+            unsafe {
+                let fd = libc::socket(libc::AF_UNIX, libc::SOCK_DGRAM, 0);
+                if fd < 0 {
+                    return Err(IoError::last_error());
+                }
+                let ret = libc::connect(fd, &addr as *const _ as *const libc::sockaddr,
+                                        mem::size_of::<libc::sockaddr_un>() as libc::socklen_t);
+                if ret < 0 {
+                    let err = IoError::last_error();
+                    libc::close(fd);
+                    return Err(err);
+                }
+                Ok(SyslogLogger { fd: fd, tag: tag.to_string(), facility: LOG_USER })
+            }
+        }
+
+        /// Sets the syslog facility code of the messages, such as 1 for
+        /// `user` or 16 to 23 for `local0` to `local7`.
+        pub fn set_facility(&mut self, facility: u32) {
+            self.facility = facility;
+        }
+
+        /// Formats a record as a syslog message.
+        fn message(&self, record: &LogRecord) -> Vec<u8> {
+            let mut msg = Vec::new();
+            let LogLevel(level) = record.level;
+            let pri = self.facility * 8 + severity(level);
+            let _ = write!(&mut msg, "<{}>{}[{}]: ", pri, self.tag, os::getpid());
+            let _ = write_record(&mut msg, record);
+            // The daemon adds its own line ending.
+            msg.pop();
+            msg
+        }
+    }
+
+    /// The syslog severity of a log level.
+    fn severity(level: u32) -> u32 {
+        match level {
+            ERROR => 3, // err
+            WARN => 4, // warning
+            INFO => 6, // info
+            _ => 7, // debug
+        }
+    }
+
+    impl Logger for SyslogLogger {
+        fn log(&mut self, record: &LogRecord) {
+            let msg = self.message(record);
+            // Like syslog(3), drop messages the daemon can't take rather than
+            // failing the program.
+            unsafe {
+                libc::send(self.fd, msg.as_ptr() as *const libc::c_void,
+                           msg.len() as libc::size_t, 0);
+            }
+        }
+    }
+
+    impl Drop for SyslogLogger {
+        fn drop(&mut self) {
+            unsafe { libc::close(self.fd); }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{File, TempDir};
+    use std::io::fs::PathExtensions;
+
+    use {Logger, LogLevel, LogRecord, LogValue, INFO};
+    use super::{RotatingFileLogger, write_record};
+
+    fn with_record<F: FnOnce(&LogRecord)>(msg: &str, f: F) {
+        let kvs = [("n", LogValue::U64(1))];
+        f(&LogRecord {
+            module_path: "main",
+            level: LogLevel(INFO),
+            args: format_args!("{}", msg),
+            kvs: kvs[],
+            file: "main.rs",
+            line: 1,
+        })
+    }
+
+    #[test]
+    fn text_format() {
+        let mut out = Vec::new();
+        with_record("hello", |r| write_record(&mut out, r).unwrap());
+        assert_eq!(out, b"INFO:main: hello n=1\n".to_vec());
+    }
+
+    #[test]
+    fn rotation() {
+        let dir = TempDir::new("log-rotation").unwrap();
+        let path = dir.path().join("app.log");
+        let read = |&: p: &Path| File::open(p).read_to_string().unwrap();
+        {
+            // Each record is 21 bytes, so two fit in 45.
+            let mut logger = RotatingFileLogger::new(&path, 45, 2).unwrap();
+            for msg in ["first", "secnd", "third", "forth", "fifth", "sixth", "svnth"].iter() {
+                with_record(*msg, |r| logger.log(r));
+            }
+        }
+        assert_eq!(read(&path), "INFO:main: svnth n=1\n");
+        assert_eq!(read(&dir.path().join("app.log.1")),
+                   "INFO:main: fifth n=1\nINFO:main: sixth n=1\n");
+        assert_eq!(read(&dir.path().join("app.log.2")),
+                   "INFO:main: third n=1\nINFO:main: forth n=1\n");
+        assert!(!dir.path().join("app.log.3").exists());
+
+        // Reopening appends, counting the existing size.
+        let mut logger = RotatingFileLogger::new(&path, 45, 0).unwrap();
+        with_record("eight", |r| logger.log(r));
+        with_record("ninth", |r| logger.log(r));
+        drop(logger);
+        assert_eq!(read(&path), "INFO:main: ninth n=1\n");
+        assert!(!dir.path().join("app.log.3").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn syslog() {
+        use libc;
+        use std::mem;
+        use super::SyslogLogger;
+
+        let dir = TempDir::new("log-syslog").unwrap();
+        let path = dir.path().join("log.sock");
+        let fd = unsafe {
+            let fd = libc::socket(libc::AF_UNIX, libc::SOCK_DGRAM, 0);
+            assert!(fd >= 0);
+            let mut addr: libc::sockaddr_un = mem::zeroed();
+            addr.sun_family = libc::AF_UNIX as libc::sa_family_t;
+            for (slot, &b) in addr.sun_path.iter_mut().zip(path.as_vec().iter()) {
+                *slot = b as libc::c_char;
+            }
+            assert_eq!(libc::bind(fd, &addr as *const _ as *const libc::sockaddr,
+                                  mem::size_of::<libc::sockaddr_un>() as libc::socklen_t), 0);
+            fd
+        };
+
+        let mut logger = SyslogLogger::connect(&path, "test").unwrap();
+        logger.set_facility(16);
+        with_record("hello", |r| logger.log(r));
+
+        let mut buf = [0u8; 256];
+        let n = unsafe {
+            libc::recv(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len() as libc::size_t, 0)
+        };
+        unsafe { libc::close(fd); }
+        let msg = String::from_utf8(buf[..n as uint].to_vec()).unwrap();
+        assert!(msg.starts_with("<134>test["), "{}", msg);
+        assert!(msg.ends_with("]: INFO:main: hello n=1"), "{}", msg);
+    }
+}
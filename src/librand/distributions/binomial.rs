@@ -0,0 +1,232 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Bernoulli and binomial distributions.
+
+use core::prelude::*;
+
+use Rng;
+use distributions::{Sample, IndependentSample};
+use distributions::gamma::Beta;
+
+/// The Bernoulli distribution `Bernoulli(p)`, which yields `true`
+/// with probability `p` and `false` otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rand;
+/// use std::rand::distributions::{Bernoulli, IndependentSample};
+///
+/// let coin = Bernoulli::new(0.5);
+/// let v = coin.ind_sample(&mut rand::thread_rng());
+/// println!("{} is from a Bernoulli(0.5) distribution", v);
+/// ```
+#[deriving(Clone, Copy)]
+pub struct Bernoulli {
+    p: f64
+}
+
+impl Bernoulli {
+    /// Construct a new `Bernoulli` with the probability of success
+    /// `p`. Panics if `p` is not in `[0, 1]`.
+    pub fn new(p: f64) -> Bernoulli {
+        assert!(p >= 0.0, "Bernoulli::new called with `p` < 0");
+        assert!(p <= 1.0, "Bernoulli::new called with `p` > 1");
+        Bernoulli { p: p }
+    }
+}
+
+impl Sample<bool> for Bernoulli {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> bool { self.ind_sample(rng) }
+}
+impl IndependentSample<bool> for Bernoulli {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> bool {
+        // `gen` is in [0, 1), so p == 1 is always true and p == 0
+        // never is.
+        rng.gen::<f64>() < self.p
+    }
+}
+
+/// The binomial distribution `Binomial(n, p)`, the number of
+/// successes in `n` independent trials that each succeed with
+/// probability `p`.
+///
+/// Small `n` are sampled by running the trials. Larger ones use the
+/// exact method from Knuth[1] that splits the trials at the order
+/// statistic of a `Beta` sample, which takes `O(log n)` steps.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rand;
+/// use std::rand::distributions::{Binomial, IndependentSample};
+///
+/// let bin = Binomial::new(20, 0.3);
+/// let v = bin.ind_sample(&mut rand::thread_rng());
+/// println!("{} is from a Binomial(20, 0.3) distribution", v);
+/// ```
+///
+/// [1]: Donald E. Knuth. 1997. *The Art of Computer Programming,
+/// Volume 2: Seminumerical Algorithms*, 3rd ed., section 3.4.1.
+#[deriving(Clone, Copy)]
+pub struct Binomial {
+    n: u64,
+    p: f64
+}
+
+/// Above this many trials, `Binomial` splits the trials rather than
+/// running them one by one.
+const DIRECT_TRIALS: u64 = 32;
+
+impl Binomial {
+    /// Construct a new `Binomial` with `n` trials, each with the
+    /// probability of success `p`. Panics if `p` is not in `[0, 1]`.
+    pub fn new(n: u64, p: f64) -> Binomial {
+        assert!(p >= 0.0, "Binomial::new called with `p` < 0");
+        assert!(p <= 1.0, "Binomial::new called with `p` > 1");
+        Binomial { n: n, p: p }
+    }
+}
+
+impl Sample<u64> for Binomial {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> u64 { self.ind_sample(rng) }
+}
+impl IndependentSample<u64> for Binomial {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        let mut n = self.n;
+        let mut p = self.p;
+        let mut successes = 0;
+
+        while n > DIRECT_TRIALS {
+            // `x` is the `a`th smallest of `n` uniform samples; the
+            // trials below it and those above it are each uniform on
+            // their side, so only one side is left undecided.
+            let a = 1 + n / 2;
+            let b = 1 + n - a;
+            let x = Beta::new(a as f64, b as f64).ind_sample(rng);
+            if x >= p {
+                n = a - 1;
+                p /= x;
+            } else {
+                successes += a;
+                n = b - 1;
+                p = (p - x) / (1.0 - x);
+            }
+        }
+
+        for _ in range(0, n) {
+            if rng.gen::<f64>() < p {
+                successes += 1;
+            }
+        }
+        successes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::prelude::v1::*;
+
+    use distributions::{Sample, IndependentSample};
+    use super::{Bernoulli, Binomial};
+
+    #[test]
+    fn test_bernoulli() {
+        let mut b = Bernoulli::new(0.3);
+        let mut rng = ::test::rng();
+        for _ in range(0u, 1000) {
+            b.sample(&mut rng);
+            b.ind_sample(&mut rng);
+        }
+    }
+    #[test]
+    fn test_bernoulli_extremes() {
+        let never = Bernoulli::new(0.0);
+        let always = Bernoulli::new(1.0);
+        let mut rng = ::test::rng();
+        for _ in range(0u, 1000) {
+            assert!(!never.ind_sample(&mut rng));
+            assert!(always.ind_sample(&mut rng));
+        }
+    }
+    #[test]
+    #[should_fail]
+    fn test_bernoulli_invalid_p_neg() {
+        Bernoulli::new(-0.1);
+    }
+    #[test]
+    #[should_fail]
+    fn test_bernoulli_invalid_p_large() {
+        Bernoulli::new(1.1);
+    }
+
+    #[test]
+    fn test_binomial() {
+        let mut rng = ::test::rng();
+        for &(n, p) in [(0u64, 0.5f64), (10, 0.3), (1000, 0.7), (1 << 40, 0.01)].iter() {
+            let mut bin = Binomial::new(n, p);
+            for _ in range(0u, 1000) {
+                assert!(bin.sample(&mut rng) <= n);
+                assert!(bin.ind_sample(&mut rng) <= n);
+            }
+        }
+    }
+    #[test]
+    fn test_binomial_extremes() {
+        let mut rng = ::test::rng();
+        for _ in range(0u, 100) {
+            assert_eq!(Binomial::new(1000, 0.0).ind_sample(&mut rng), 0);
+            assert_eq!(Binomial::new(1000, 1.0).ind_sample(&mut rng), 1000);
+        }
+    }
+    #[test]
+    fn test_binomial_mean() {
+        let bin = Binomial::new(1000, 0.3);
+        let mut rng = ::test::rng();
+        let mut sum = 0;
+        for _ in range(0u, 1000) {
+            sum += bin.ind_sample(&mut rng);
+        }
+        // the mean is 300 with a standard error of about 0.5
+        let mean = sum as f64 / 1000.0;
+        assert!(295.0 < mean && mean < 305.0);
+    }
+    #[test]
+    #[should_fail]
+    fn test_binomial_invalid_p() {
+        Binomial::new(10, 1.5);
+    }
+}
+
+#[cfg(test)]
+mod bench {
+    extern crate test;
+
+    use std::prelude::v1::*;
+
+    use self::test::Bencher;
+    use std::mem::size_of;
+    use super::Binomial;
+    use distributions::IndependentSample;
+
+    #[bench]
+    fn rand_binomial(b: &mut Bencher) {
+        let mut rng = ::test::weak_rng();
+        let bin = Binomial::new(1000, 0.3);
+
+        b.iter(|| {
+            for _ in range(0, ::RAND_BENCH_N) {
+                bin.ind_sample(&mut rng);
+            }
+        });
+        b.bytes = size_of::<u64>() as u64 * ::RAND_BENCH_N;
+    }
+}
@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! The exponential distribution.
+//! The exponential and Weibull distributions.
 
 use core::num::Float;
 
@@ -92,12 +92,56 @@ impl IndependentSample<f64> for Exp {
     }
 }
 
+/// The Weibull distribution `Weibull(scale, shape)`.
+///
+/// This distribution has density function: `f(x) = (k / lambda) *
+/// (x / lambda)^(k - 1) * exp(-(x / lambda)^k)` for `x >= 0`, where
+/// `lambda` is the scale and `k` the shape. It is sampled as `lambda
+/// * E^(1 / k)` where `E` is from `Exp(1)`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rand;
+/// use std::rand::distributions::{Weibull, IndependentSample};
+///
+/// let weibull = Weibull::new(1.0, 1.5);
+/// let v = weibull.ind_sample(&mut rand::thread_rng());
+/// println!("{} is from a Weibull(1, 1.5) distribution", v);
+/// ```
+#[deriving(Copy)]
+pub struct Weibull {
+    scale: f64,
+    /// `shape` stored as `1/shape`, since this is the power we use.
+    inv_shape: f64
+}
+
+impl Weibull {
+    /// Construct a new `Weibull` with the given `scale` and `shape`
+    /// parameters. Panics if `scale <= 0` or `shape <= 0`.
+    pub fn new(scale: f64, shape: f64) -> Weibull {
+        assert!(scale > 0.0, "Weibull::new called with `scale` <= 0");
+        assert!(shape > 0.0, "Weibull::new called with `shape` <= 0");
+        Weibull { scale: scale, inv_shape: 1.0 / shape }
+    }
+}
+
+impl Sample<f64> for Weibull {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> f64 { self.ind_sample(rng) }
+}
+impl IndependentSample<f64> for Weibull {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        let Exp1(n) = rng.gen::<Exp1>();
+        self.scale * n.powf(self.inv_shape)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::prelude::v1::*;
 
     use distributions::{Sample, IndependentSample};
-    use super::Exp;
+    use super::{Exp, Weibull};
 
     #[test]
     fn test_exp() {
@@ -118,6 +162,26 @@ mod test {
     fn test_exp_invalid_lambda_neg() {
         Exp::new(-10.0);
     }
+
+    #[test]
+    fn test_weibull() {
+        let mut weibull = Weibull::new(2.0, 0.5);
+        let mut rng = ::test::rng();
+        for _ in range(0u, 1000) {
+            assert!(weibull.sample(&mut rng) >= 0.0);
+            assert!(weibull.ind_sample(&mut rng) >= 0.0);
+        }
+    }
+    #[test]
+    #[should_fail]
+    fn test_weibull_invalid_scale() {
+        Weibull::new(0.0, 1.0);
+    }
+    #[test]
+    #[should_fail]
+    fn test_weibull_invalid_shape() {
+        Weibull::new(1.0, -1.0);
+    }
 }
 
 #[cfg(test)]
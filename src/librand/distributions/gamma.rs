@@ -15,6 +15,7 @@
 use self::GammaRepr::*;
 use self::ChiSquaredRepr::*;
 
+use core::prelude::*;
 use core::num::Float;
 
 use {Rng, Open01};
@@ -321,12 +322,122 @@ impl IndependentSample<f64> for StudentT {
     }
 }
 
+/// The Beta distribution `Beta(alpha, beta)`, over `[0, 1]`.
+///
+/// This is sampled as `X / (X + Y)`, where `X` is from `Gamma(alpha,
+/// 1)` and `Y` from `Gamma(beta, 1)`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rand;
+/// use std::rand::distributions::{Beta, IndependentSample};
+///
+/// let beta = Beta::new(2.0, 5.0);
+/// let v = beta.ind_sample(&mut rand::thread_rng());
+/// println!("{} is from a Beta(2, 5) distribution", v)
+/// ```
+pub struct Beta {
+    gamma_a: Gamma,
+    gamma_b: Gamma,
+}
+
+impl Beta {
+    /// Create a new Beta distribution with the shape parameters
+    /// `alpha` and `beta`. Panics if either is not positive.
+    pub fn new(alpha: f64, beta: f64) -> Beta {
+        assert!(alpha > 0.0, "Beta::new called with `alpha <= 0`");
+        assert!(beta > 0.0, "Beta::new called with `beta <= 0`");
+        Beta {
+            gamma_a: Gamma::new(alpha, 1.0),
+            gamma_b: Gamma::new(beta, 1.0)
+        }
+    }
+}
+impl Sample<f64> for Beta {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> f64 { self.ind_sample(rng) }
+}
+impl IndependentSample<f64> for Beta {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        loop {
+            let x = self.gamma_a.ind_sample(rng);
+            let y = self.gamma_b.ind_sample(rng);
+            // both can underflow to 0 for very small shapes
+            if x + y > 0.0 {
+                return x / (x + y)
+            }
+        }
+    }
+}
+
+/// The Dirichlet distribution `Dir(alpha)`, over vectors of
+/// non-negative numbers that sum to 1.
+///
+/// This is the multivariate generalisation of `Beta`: each element
+/// is sampled from `Gamma(alpha[i], 1)` and the vector is then
+/// divided by its sum. Samples are written into a slice provided by
+/// the caller, so this does not implement `Sample` or
+/// `IndependentSample`; `std::rand::distributions::Dirichlet` does,
+/// returning each sample as a new vector.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rand;
+/// use std::rand::distributions::gamma::Dirichlet;
+///
+/// let alpha = [1.0, 2.0, 3.0];
+/// let dir = Dirichlet::new(&alpha);
+/// let mut v = [0.0; 3];
+/// dir.ind_sample(&mut rand::thread_rng(), &mut v);
+/// println!("{} is from a Dir([1, 2, 3]) distribution", v.as_slice())
+/// ```
+#[deriving(Copy)]
+pub struct Dirichlet<'a> {
+    alpha: &'a [f64],
+}
+
+impl<'a> Dirichlet<'a> {
+    /// Create a new Dirichlet distribution with the concentration
+    /// parameters `alpha`. Panics if `alpha` has fewer than 2
+    /// elements or any of them is not positive.
+    pub fn new(alpha: &'a [f64]) -> Dirichlet<'a> {
+        assert!(alpha.len() >= 2, "Dirichlet::new called with fewer than 2 parameters");
+        for &a in alpha.iter() {
+            assert!(a > 0.0, "Dirichlet::new called with a parameter <= 0");
+        }
+        Dirichlet { alpha: alpha }
+    }
+
+    /// Write a sample into `out`, which is the same length as
+    /// `alpha`, using `rng` as the source of randomness.
+    pub fn ind_sample<R: Rng>(&self, rng: &mut R, out: &mut [f64]) {
+        assert!(out.len() == self.alpha.len(),
+                "Dirichlet::ind_sample called with `out` not the length of `alpha`");
+        loop {
+            let mut sum = 0.0;
+            for (x, &a) in out.iter_mut().zip(self.alpha.iter()) {
+                *x = Gamma::new(a, 1.0).ind_sample(rng);
+                sum += *x;
+            }
+            // as for `Beta`, every element can underflow to 0
+            if sum > 0.0 {
+                for x in out.iter_mut() {
+                    *x /= sum;
+                }
+                return
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::prelude::v1::*;
+    use core::num::Float;
 
     use distributions::{Sample, IndependentSample};
-    use super::{ChiSquared, StudentT, FisherF};
+    use super::{ChiSquared, StudentT, FisherF, Beta, Dirichlet};
 
     #[test]
     fn test_chi_squared_one() {
@@ -380,6 +491,60 @@ mod test {
             t.ind_sample(&mut rng);
         }
     }
+
+    #[test]
+    fn test_beta() {
+        let mut rng = ::test::rng();
+        for &(a, b) in [(0.5f64, 0.5f64), (1.0, 3.0), (2.0, 5.0), (1e-3, 1e-3)].iter() {
+            let mut beta = Beta::new(a, b);
+            for _ in range(0u, 1000) {
+                let x = beta.sample(&mut rng);
+                assert!(0.0 <= x && x <= 1.0);
+                let x = beta.ind_sample(&mut rng);
+                assert!(0.0 <= x && x <= 1.0);
+            }
+        }
+    }
+    #[test]
+    #[should_fail]
+    fn test_beta_invalid_alpha() {
+        Beta::new(0.0, 1.0);
+    }
+    #[test]
+    #[should_fail]
+    fn test_beta_invalid_beta() {
+        Beta::new(1.0, -1.0);
+    }
+
+    #[test]
+    fn test_dirichlet() {
+        let alpha = [0.1, 1.0, 2.0, 30.0];
+        let dir = Dirichlet::new(&alpha);
+        let mut rng = ::test::rng();
+        let mut v = [0.0; 4];
+        for _ in range(0u, 1000) {
+            dir.ind_sample(&mut rng, &mut v);
+            assert!(v.iter().all(|&x| x >= 0.0));
+            let sum = v.iter().fold(0.0, |a, &b| a + b);
+            assert!((sum - 1.0).abs() < 1e-10);
+        }
+    }
+    #[test]
+    #[should_fail]
+    fn test_dirichlet_wrong_length() {
+        let alpha = [1.0, 2.0];
+        Dirichlet::new(&alpha).ind_sample(&mut ::test::rng(), &mut [0.0; 3]);
+    }
+    #[test]
+    #[should_fail]
+    fn test_dirichlet_too_few_params() {
+        Dirichlet::new(&[1.0]);
+    }
+    #[test]
+    #[should_fail]
+    fn test_dirichlet_invalid_param() {
+        Dirichlet::new(&[1.0, 0.0, 1.0]);
+    }
 }
 
 #[cfg(test)]
@@ -25,14 +25,20 @@ use core::num::{Float, Int};
 use {Rng, Rand};
 
 pub use self::range::Range;
-pub use self::gamma::{Gamma, ChiSquared, FisherF, StudentT};
+pub use self::gamma::{Gamma, ChiSquared, FisherF, StudentT, Beta, Dirichlet};
 pub use self::normal::{Normal, LogNormal};
-pub use self::exponential::Exp;
+pub use self::exponential::{Exp, Weibull};
+pub use self::binomial::{Bernoulli, Binomial};
+pub use self::poisson::Poisson;
+pub use self::zipf::Zipf;
 
 pub mod range;
 pub mod gamma;
 pub mod normal;
 pub mod exponential;
+pub mod binomial;
+pub mod poisson;
+pub mod zipf;
 
 /// Types that can be used to create a random instance of `Support`.
 pub trait Sample<Support> {
@@ -190,6 +196,136 @@ impl<'a, T: Clone> IndependentSample<T> for WeightedChoice<'a, T> {
     }
 }
 
+/// A distribution that selects an index into a list of weights, with
+/// probability proportional to the weight at that index.
+///
+/// Unlike `WeightedChoice`, this uses Vose's alias method[1], which
+/// takes `O(n)` time to set up and then samples in constant time
+/// however many weights there are. The two tables it samples from
+/// are written into slices provided by the caller, each as long as
+/// the list of weights.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rand;
+/// use std::rand::distributions::{WeightedIndex, IndependentSample};
+///
+/// let items = ['a', 'b', 'c'];
+/// let (mut prob, mut alias) = ([0.0; 3], [0u; 3]);
+/// let wi = WeightedIndex::new(&[2.0, 4.0, 1.0], &mut prob, &mut alias);
+/// let mut rng = rand::thread_rng();
+/// for _ in range(0u, 16) {
+///      // on average prints 'a' 4 times, 'b' 8 and 'c' twice.
+///      println!("{}", items[wi.ind_sample(&mut rng)]);
+/// }
+/// ```
+///
+/// [1]: Michael D. Vose. 1991. "A Linear Algorithm for Generating
+/// Random Numbers with a Given Distribution" *IEEE Trans. Softw.
+/// Eng.* 17, 9 (September 1991), 972-975.
+pub struct WeightedIndex<'a> {
+    // the probability of keeping each index rather than its alias
+    prob: &'a [f64],
+    alias: &'a [uint],
+    index_range: Range<uint>
+}
+
+impl<'a> WeightedIndex<'a> {
+    /// Create a new `WeightedIndex` from a list of weights, writing
+    /// its tables into `prob` and `alias`. What they held before is
+    /// ignored.
+    ///
+    /// Panics if:
+    /// - `weights` is empty
+    /// - `prob` or `alias` is not the same length as `weights`
+    /// - any weight is negative or not finite
+    /// - the total weight is 0 or not finite.
+    pub fn new(weights: &[f64],
+               prob: &'a mut [f64],
+               alias: &'a mut [uint]) -> WeightedIndex<'a> {
+        assert!(!weights.is_empty(), "WeightedIndex::new called with no weights");
+        assert!(prob.len() == weights.len() && alias.len() == weights.len(),
+                "WeightedIndex::new called with tables not the length of `weights`");
+
+        let mut total = 0.0;
+        for &w in weights.iter() {
+            assert!(w >= 0.0 && w.is_finite(),
+                    "WeightedIndex::new called with a negative or infinite weight");
+            total += w;
+        }
+        assert!(total > 0.0, "WeightedIndex::new called with a total weight of 0");
+        assert!(total.is_finite(), "WeightedIndex::new called with an infinite total weight");
+
+        // scale so that the average weight is 1, then pair each
+        // index with weight below 1 with one that has weight to
+        // spare, moving the excess over.
+        let n = prob.len();
+        let scale = n as f64 / total;
+        for (i, &w) in weights.iter().enumerate() {
+            prob[i] = w * scale;
+            alias[i] = i;
+        }
+
+        // Rather than keeping lists of the small and large indices,
+        // `next_small` scans forwards for unpaired small ones, and
+        // `large` is the first index which may still have weight to
+        // spare. A large index that turns small behind the scan is
+        // paired straight away.
+        fn next(prob: &[f64], from: uint, small: bool) -> uint {
+            let mut i = from;
+            while i < prob.len() && (prob[i] < 1.0) != small {
+                i += 1;
+            }
+            i
+        }
+        let mut next_small = next(prob, 0, true);
+        let mut small = next_small;
+        let mut large = next(prob, 0, false);
+        while small < n && large < n {
+            alias[small] = large;
+            prob[large] += prob[small] - 1.0;
+            if prob[large] < 1.0 && large < next_small {
+                small = large;
+            } else {
+                next_small = next(prob, next_small + 1, true);
+                small = next_small;
+            }
+            if prob[large] < 1.0 {
+                large = next(prob, large + 1, false);
+            }
+        }
+        // whatever is left unpaired only differs from 1 by rounding
+        // error
+        for i in range(0, n) {
+            if alias[i] == i {
+                prob[i] = 1.0;
+            }
+        }
+
+        WeightedIndex {
+            prob: prob,
+            alias: alias,
+            index_range: Range::new(0, n)
+        }
+    }
+}
+
+impl<'a> Sample<uint> for WeightedIndex<'a> {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> uint { self.ind_sample(rng) }
+}
+
+impl<'a> IndependentSample<uint> for WeightedIndex<'a> {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> uint {
+        let i = self.index_range.ind_sample(rng);
+        if rng.gen::<f64>() < self.prob[i] {
+            i
+        } else {
+            self.alias[i]
+        }
+    }
+}
+
 mod ziggurat_tables;
 
 /// Sample a random number using the Ziggurat method (specifically the
@@ -261,7 +397,8 @@ mod tests {
     use std::prelude::v1::*;
 
     use {Rng, Rand};
-    use super::{RandSample, WeightedChoice, Weighted, Sample, IndependentSample};
+    use super::{RandSample, WeightedChoice, Weighted, WeightedIndex};
+    use super::{Sample, IndependentSample};
 
     #[deriving(PartialEq, Show)]
     struct ConstRand(uint);
@@ -361,4 +498,81 @@ mod tests {
                                   Weighted { weight: x, item: 2i },
                                   Weighted { weight: 1, item: 3i }]);
     }
+
+    #[test]
+    fn test_weighted_index() {
+        let mut rng = ::test::rng();
+
+        let (mut prob, mut alias) = ([0.0], [0u]);
+        let mut wi = WeightedIndex::new(&[3.0], &mut prob, &mut alias);
+        for _ in range(0u, 100) {
+            assert_eq!(wi.sample(&mut rng), 0);
+            assert_eq!(wi.ind_sample(&mut rng), 0);
+        }
+
+        // zero weights are never chosen
+        let (mut prob, mut alias) = ([0.0; 5], [0u; 5]);
+        let wi = WeightedIndex::new(&[0.0, 2.0, 0.0, 1.0, 0.0], &mut prob, &mut alias);
+        let mut counts = [0u; 5];
+        for _ in range(0u, 3000) {
+            counts[wi.ind_sample(&mut rng)] += 1;
+        }
+        assert_eq!(counts[0], 0);
+        assert_eq!(counts[2], 0);
+        assert_eq!(counts[4], 0);
+        assert!(1800 < counts[1] && counts[1] < 2200);
+        assert!(800 < counts[3] && counts[3] < 1200);
+    }
+    #[test]
+    fn test_weighted_index_tables() {
+        // The probability of each index that the tables give, which is
+        // its own share of its column plus what it is the alias for in
+        // the others.
+        fn probabilities(prob: &[f64], alias: &[uint]) -> Vec<f64> {
+            let n = prob.len() as f64;
+            let mut p = Vec::from_elem(prob.len(), 0.0);
+            for i in range(0, prob.len()) {
+                assert!(0.0 <= prob[i] && prob[i] <= 1.0);
+                p[i] += prob[i] / n;
+                p[alias[i]] += (1.0 - prob[i]) / n;
+            }
+            p
+        }
+
+        let weights: &[&[f64]] = &[&[1.0, 1.0, 1.0],
+                                   &[5.0, 1.0, 1.0, 1.0],
+                                   &[1.0, 1.0, 1.0, 5.0],
+                                   &[0.5, 3.0, 0.25, 0.25, 4.0, 0.0, 2.0],
+                                   &[3.0, 0.1, 0.1, 2.0, 0.1, 0.1, 0.1, 4.0]];
+        for w in weights.iter() {
+            let total = w.iter().fold(0.0, |a, &b| a + b);
+            let mut prob = Vec::from_elem(w.len(), 0.0);
+            let mut alias = Vec::from_elem(w.len(), 0u);
+            WeightedIndex::new(*w, prob.as_mut_slice(), alias.as_mut_slice());
+            let p = probabilities(prob.as_slice(), alias.as_slice());
+            for (&p, &w) in p.iter().zip(w.iter()) {
+                assert!((p - w / total).abs() < 1e-12);
+            }
+        }
+    }
+    #[test] #[should_fail]
+    fn test_weighted_index_no_weights() {
+        WeightedIndex::new(&[], &mut [], &mut []);
+    }
+    #[test] #[should_fail]
+    fn test_weighted_index_prob_length() {
+        WeightedIndex::new(&[1.0, 2.0], &mut [0.0], &mut [0u; 2]);
+    }
+    #[test] #[should_fail]
+    fn test_weighted_index_alias_length() {
+        WeightedIndex::new(&[1.0, 2.0], &mut [0.0; 2], &mut [0u]);
+    }
+    #[test] #[should_fail]
+    fn test_weighted_index_zero_weight() {
+        WeightedIndex::new(&[0.0, 0.0], &mut [0.0; 2], &mut [0u; 2]);
+    }
+    #[test] #[should_fail]
+    fn test_weighted_index_negative_weight() {
+        WeightedIndex::new(&[1.0, -1.0, 1.0], &mut [0.0; 3], &mut [0u; 3]);
+    }
 }
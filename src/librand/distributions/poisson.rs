@@ -0,0 +1,161 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Poisson distribution.
+
+use core::prelude::*;
+use core::num::Float;
+
+use Rng;
+use distributions::{Sample, IndependentSample, Gamma};
+use distributions::binomial::Binomial;
+
+/// The Poisson distribution `Poisson(lambda)`, the number of events
+/// in an interval where they occur independently at an average rate
+/// of `lambda`.
+///
+/// Small `lambda` are sampled by multiplying uniform samples, which
+/// takes `O(lambda)` time. Larger ones are reduced with the exact
+/// method of Ahrens & Dieter[1], which draws the time of one of the
+/// events from a `Gamma` distribution and recurses on the part of the
+/// interval that is left.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rand;
+/// use std::rand::distributions::{Poisson, IndependentSample};
+///
+/// let poi = Poisson::new(2.0);
+/// let v = poi.ind_sample(&mut rand::thread_rng());
+/// println!("{} is from a Poisson(2) distribution", v);
+/// ```
+///
+/// [1]: J. H. Ahrens and U. Dieter. 1974. "Computer Methods for
+/// Sampling from Gamma, Beta, Poisson and Binomial Distributions"
+/// *Computing* 12, 3 (September 1974), 223-246.
+#[deriving(Clone, Copy)]
+pub struct Poisson {
+    lambda: f64
+}
+
+/// Above this mean, `Poisson` splits the interval rather than
+/// multiplying uniform samples.
+const DIRECT_LAMBDA: f64 = 16.0;
+
+impl Poisson {
+    /// Construct a new `Poisson` with the mean `lambda`. Panics if
+    /// `lambda <= 0`.
+    pub fn new(lambda: f64) -> Poisson {
+        assert!(lambda > 0.0, "Poisson::new called with `lambda` <= 0");
+        Poisson { lambda: lambda }
+    }
+}
+
+impl Sample<u64> for Poisson {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> u64 { self.ind_sample(rng) }
+}
+impl IndependentSample<u64> for Poisson {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        let mut lambda = self.lambda;
+        let mut events = 0;
+
+        while lambda > DIRECT_LAMBDA {
+            // `x` is the time of the `m`th event. If it falls inside
+            // the interval, the rest is a shorter interval; otherwise
+            // the first `m - 1` events are uniform on `[0, x]`.
+            let m = (lambda * (7.0 / 8.0)).floor() as u64;
+            let x = Gamma::new(m as f64, 1.0).ind_sample(rng);
+            if x < lambda {
+                events += m;
+                lambda -= x;
+            } else {
+                return events + Binomial::new(m - 1, lambda / x).ind_sample(rng);
+            }
+        }
+
+        let limit = (-lambda).exp();
+        let mut product = rng.gen::<f64>();
+        while product > limit {
+            events += 1;
+            product *= rng.gen::<f64>();
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::prelude::v1::*;
+
+    use distributions::{Sample, IndependentSample};
+    use super::Poisson;
+
+    #[test]
+    fn test_poisson() {
+        let mut rng = ::test::rng();
+        for &lambda in [0.1f64, 2.0, 16.0, 1000.0, 1e9].iter() {
+            let mut poi = Poisson::new(lambda);
+            for _ in range(0u, 1000) {
+                poi.sample(&mut rng);
+                poi.ind_sample(&mut rng);
+            }
+        }
+    }
+    #[test]
+    fn test_poisson_mean() {
+        let mut rng = ::test::rng();
+        for &lambda in [2.0f64, 100.0].iter() {
+            let poi = Poisson::new(lambda);
+            let mut sum = 0;
+            for _ in range(0u, 1000) {
+                sum += poi.ind_sample(&mut rng);
+            }
+            // the standard error is at most about 0.3
+            let mean = sum as f64 / 1000.0;
+            assert!(lambda - 3.0 < mean && mean < lambda + 3.0);
+        }
+    }
+    #[test]
+    #[should_fail]
+    fn test_poisson_invalid_lambda_zero() {
+        Poisson::new(0.0);
+    }
+    #[test]
+    #[should_fail]
+    fn test_poisson_invalid_lambda_neg() {
+        Poisson::new(-10.0);
+    }
+}
+
+#[cfg(test)]
+mod bench {
+    extern crate test;
+
+    use std::prelude::v1::*;
+
+    use self::test::Bencher;
+    use std::mem::size_of;
+    use super::Poisson;
+    use distributions::IndependentSample;
+
+    #[bench]
+    fn rand_poisson(b: &mut Bencher) {
+        let mut rng = ::test::weak_rng();
+        let poi = Poisson::new(100.0);
+
+        b.iter(|| {
+            for _ in range(0, ::RAND_BENCH_N) {
+                poi.ind_sample(&mut rng);
+            }
+        });
+        b.bytes = size_of::<u64>() as u64 * ::RAND_BENCH_N;
+    }
+}
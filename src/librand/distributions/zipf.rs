@@ -0,0 +1,210 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Zipf distribution.
+
+use core::prelude::*;
+use core::num::Float;
+
+use Rng;
+use distributions::{Sample, IndependentSample};
+
+/// The Zipf distribution `Zipf(n, s)` over the integers `1` to `n`,
+/// where `k` has probability proportional to `1 / k^s`.
+///
+/// The algorithm used is the rejection-inversion method of Hörmann &
+/// Derflinger[1], which takes constant expected time for any `n` and
+/// `s`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rand;
+/// use std::rand::distributions::{Zipf, IndependentSample};
+///
+/// let zipf = Zipf::new(10, 1.5);
+/// let v = zipf.ind_sample(&mut rand::thread_rng());
+/// println!("{} is from a Zipf(10, 1.5) distribution", v);
+/// ```
+///
+/// [1]: Wolfgang Hörmann and Gerhard Derflinger. 1996.
+/// "Rejection-inversion to generate variates from monotone discrete
+/// distributions" *ACM Trans. Model. Comput. Simul.* 6, 3 (July
+/// 1996), 169-184.
+#[deriving(Clone, Copy)]
+pub struct Zipf {
+    n: f64,
+    s: f64,
+    h_integral_x1: f64,
+    h_integral_n: f64,
+    // the width of the region around each integer where samples are
+    // accepted without evaluating `h`
+    squeeze: f64,
+}
+
+impl Zipf {
+    /// Construct a new `Zipf` over `1` to `n` with the exponent `s`.
+    /// Panics if `n == 0` or `s <= 0`.
+    pub fn new(n: u64, s: f64) -> Zipf {
+        assert!(n > 0, "Zipf::new called with `n` == 0");
+        assert!(s > 0.0, "Zipf::new called with `s` <= 0");
+
+        let mut zipf = Zipf {
+            n: n as f64,
+            s: s,
+            h_integral_x1: 0.0,
+            h_integral_n: 0.0,
+            squeeze: 0.0,
+        };
+        zipf.h_integral_x1 = zipf.h_integral(1.5) - 1.0;
+        zipf.h_integral_n = zipf.h_integral(zipf.n + 0.5);
+        zipf.squeeze = 2.0 - zipf.h_integral_inv(zipf.h_integral(2.5) - zipf.h(2.0));
+        zipf
+    }
+
+    /// The hat function `x^-s`.
+    fn h(&self, x: f64) -> f64 {
+        (-self.s * x.ln()).exp()
+    }
+
+    /// The integral of `h`, `(x^(1 - s) - 1) / (1 - s)`, which is
+    /// `ln(x)` when `s == 1`.
+    fn h_integral(&self, x: f64) -> f64 {
+        let log_x = x.ln();
+        exp_m1_div((1.0 - self.s) * log_x) * log_x
+    }
+
+    /// The inverse of `h_integral`.
+    fn h_integral_inv(&self, x: f64) -> f64 {
+        let mut t = x * (1.0 - self.s);
+        if t < -1.0 {
+            // only reachable through rounding
+            t = -1.0;
+        }
+        (ln_1p_div(t) * x).exp()
+    }
+}
+
+// `core` has neither `ln_1p` nor `exp_m1`, so near zero, where the
+// direct forms lose precision, these use the Taylor series instead.
+
+/// `ln(1 + x) / x`, continuous at `x == 0`.
+fn ln_1p_div(x: f64) -> f64 {
+    if x.abs() > 1e-4 {
+        (1.0 + x).ln() / x
+    } else {
+        1.0 - x * (0.5 - x * (1.0 / 3.0 - x * (0.25 - x * 0.2)))
+    }
+}
+
+/// `(exp(x) - 1) / x`, continuous at `x == 0`.
+fn exp_m1_div(x: f64) -> f64 {
+    if x.abs() > 1e-4 {
+        (x.exp() - 1.0) / x
+    } else {
+        1.0 + x * 0.5 * (1.0 + x / 3.0 * (1.0 + x * 0.25 * (1.0 + x * 0.2)))
+    }
+}
+
+impl Sample<u64> for Zipf {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> u64 { self.ind_sample(rng) }
+}
+impl IndependentSample<u64> for Zipf {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        loop {
+            // uniform in (h_integral_x1, h_integral_n]
+            let u = self.h_integral_n +
+                rng.gen::<f64>() * (self.h_integral_x1 - self.h_integral_n);
+            let x = self.h_integral_inv(u);
+
+            let mut k = (x + 0.5).floor();
+            if k < 1.0 {
+                k = 1.0;
+            } else if k > self.n {
+                k = self.n;
+            }
+
+            if k - x <= self.squeeze || u >= self.h_integral(k + 0.5) - self.h(k) {
+                return k as u64;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::prelude::v1::*;
+
+    use distributions::{Sample, IndependentSample};
+    use super::Zipf;
+
+    #[test]
+    fn test_zipf() {
+        let mut rng = ::test::rng();
+        for &(n, s) in [(1u64, 1.0f64), (10, 0.5), (10, 1.0), (1000, 2.0), (1 << 40, 1.1)].iter() {
+            let mut zipf = Zipf::new(n, s);
+            for _ in range(0u, 1000) {
+                let k = zipf.sample(&mut rng);
+                assert!(1 <= k && k <= n);
+                let k = zipf.ind_sample(&mut rng);
+                assert!(1 <= k && k <= n);
+            }
+        }
+    }
+    #[test]
+    fn test_zipf_frequencies() {
+        // Zipf(3, 1) is 1, 2 and 3 with probabilities 6/11, 3/11
+        // and 2/11.
+        let zipf = Zipf::new(3, 1.0);
+        let mut rng = ::test::rng();
+        let mut counts = [0u; 3];
+        for _ in range(0u, 11000) {
+            counts[zipf.ind_sample(&mut rng) as uint - 1] += 1;
+        }
+        assert!(5700 < counts[0] && counts[0] < 6300);
+        assert!(2700 < counts[1] && counts[1] < 3300);
+        assert!(1700 < counts[2] && counts[2] < 2300);
+    }
+    #[test]
+    #[should_fail]
+    fn test_zipf_invalid_n() {
+        Zipf::new(0, 1.0);
+    }
+    #[test]
+    #[should_fail]
+    fn test_zipf_invalid_s() {
+        Zipf::new(10, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod bench {
+    extern crate test;
+
+    use std::prelude::v1::*;
+
+    use self::test::Bencher;
+    use std::mem::size_of;
+    use super::Zipf;
+    use distributions::IndependentSample;
+
+    #[bench]
+    fn rand_zipf(b: &mut Bencher) {
+        let mut rng = ::test::weak_rng();
+        let zipf = Zipf::new(1000, 1.1);
+
+        b.iter(|| {
+            for _ in range(0, ::RAND_BENCH_N) {
+                zipf.ind_sample(&mut rng);
+            }
+        });
+        b.bytes = size_of::<u64>() as u64 * ::RAND_BENCH_N;
+    }
+}
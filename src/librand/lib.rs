@@ -38,6 +38,8 @@ use core::prelude::*;
 
 pub use isaac::{IsaacRng, Isaac64Rng};
 pub use chacha::ChaChaRng;
+pub use pcg::Pcg32Rng;
+pub use xoroshiro::Xoroshiro128Rng;
 
 use distributions::{Range, IndependentSample};
 use distributions::range::SampleRange;
@@ -48,6 +50,8 @@ static RAND_BENCH_N: u64 = 100;
 pub mod distributions;
 pub mod isaac;
 pub mod chacha;
+pub mod pcg;
+pub mod xoroshiro;
 pub mod reseeding;
 mod rand_impls;
 
@@ -0,0 +1,196 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The PCG random number generator.
+
+use core::prelude::*;
+use core::num::Int;
+
+use {Rng, SeedableRng, Rand};
+
+const MULTIPLIER: u64 = 6364136223846793005;
+
+/// A random number generator that uses the PCG32 algorithm [1], which
+/// produces 32-bit outputs from a 64-bit linear congruential generator
+/// using the XSH RR output function.
+///
+/// PCG is small and fast, and has good statistical quality, but it is
+/// not suitable for cryptographic purposes. Each seed picks both a
+/// position and one of 2^63 independent streams, and `advance` jumps
+/// forwards or backwards in a stream in logarithmic time.
+///
+/// [1]: M. E. O'Neill, [*PCG: A Family of Simple Fast Space-Efficient
+/// Statistically Good Algorithms for Random Number
+/// Generation*](http://www.pcg-random.org/paper.html)
+#[deriving(Clone, Copy)]
+pub struct Pcg32Rng {
+    state: u64,
+    inc: u64,
+}
+
+impl Pcg32Rng {
+    /// Creates a PCG32 generator with the default state and stream of the
+    /// reference implementation.
+    ///
+    /// All generators created by this function will yield the same stream
+    /// of random numbers. It is highly recommended that this is created
+    /// through `SeedableRng` instead of this function.
+    pub fn new_unseeded() -> Pcg32Rng {
+        Pcg32Rng { state: 0x853c49e6748fea9b, inc: 0xda3e39cb94b95bdb }
+    }
+
+    #[inline]
+    fn step(&mut self) {
+        self.state = self.state * MULTIPLIER + self.inc;
+    }
+
+    /// Moves `delta` steps forwards through the stream, as if `next_u32`
+    /// had been called `delta` times. As the stream repeats after 2^64
+    /// steps, `advance(-n as u64)` moves `n` steps backwards.
+    pub fn advance(&mut self, delta: u64) {
+        // Brown, "Random Number Generation with Arbitrary Stride":
+        // composes the affine step map with itself by repeated squaring.
+        let mut acc_mult = 1u64;
+        let mut acc_plus = 0u64;
+        let mut cur_mult = MULTIPLIER;
+        let mut cur_plus = self.inc;
+        let mut delta = delta;
+        while delta > 0 {
+            if delta & 1 == 1 {
+                acc_mult = acc_mult * cur_mult;
+                acc_plus = acc_plus * cur_mult + cur_plus;
+            }
+            cur_plus = (cur_mult + 1) * cur_plus;
+            cur_mult = cur_mult * cur_mult;
+            delta >>= 1;
+        }
+        self.state = acc_mult * self.state + acc_plus;
+    }
+
+    /// Creates a new generator on a different stream, seeded from this one.
+    ///
+    /// The new generator depends only on the state of `self`, so a program
+    /// that splits off one generator per parallel task from a seeded parent
+    /// gets the same results on every run.
+    pub fn split(&mut self) -> Pcg32Rng {
+        let state = self.next_u64();
+        let stream = self.next_u64();
+        SeedableRng::from_seed([state, stream])
+    }
+}
+
+impl Rng for Pcg32Rng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as uint;
+        xorshifted.rotate_right(rot)
+    }
+}
+
+impl SeedableRng<[u64; 2]> for Pcg32Rng {
+    /// Reseed a Pcg32Rng with an initial state and a stream number, as
+    /// `pcg32_srandom_r` does in the reference implementation. Only the
+    /// low 63 bits of the stream number are used.
+    fn reseed(&mut self, seed: [u64; 2]) {
+        self.state = 0;
+        self.inc = (seed[1] << 1) | 1;
+        self.step();
+        self.state += seed[0];
+        self.step();
+    }
+
+    /// Create a new Pcg32Rng from an initial state and a stream number.
+    fn from_seed(seed: [u64; 2]) -> Pcg32Rng {
+        let mut rng = Pcg32Rng { state: 0, inc: 1 };
+        rng.reseed(seed);
+        rng
+    }
+}
+
+impl Rand for Pcg32Rng {
+    fn rand<R: Rng>(other: &mut R) -> Pcg32Rng {
+        SeedableRng::from_seed([other.next_u64(), other.next_u64()])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::prelude::v1::*;
+
+    use core::iter::order;
+    use {Rng, SeedableRng};
+    use super::Pcg32Rng;
+
+    #[test]
+    fn test_rng_rand_seeded() {
+        let (a, b): (u64, u64) = ::test::rng().gen();
+        let s = [a, b];
+        let mut ra: Pcg32Rng = SeedableRng::from_seed(s);
+        let mut rb: Pcg32Rng = SeedableRng::from_seed(s);
+        assert!(order::equals(ra.gen_ascii_chars().take(100),
+                              rb.gen_ascii_chars().take(100)));
+    }
+
+    #[test]
+    fn test_rng_reseed() {
+        let mut r: Pcg32Rng = SeedableRng::from_seed([1, 2]);
+        let string1: String = r.gen_ascii_chars().take(100).collect();
+
+        r.reseed([1, 2]);
+
+        let string2: String = r.gen_ascii_chars().take(100).collect();
+        assert_eq!(string1, string2);
+    }
+
+    #[test]
+    fn test_rng_true_values() {
+        // The output of pcg32-demo from the reference implementation.
+        let mut ra: Pcg32Rng = SeedableRng::from_seed([42, 54]);
+        let v = Vec::from_fn(6, |_| ra.next_u32());
+        assert_eq!(v,
+                   vec!(0xa15c02b7, 0x7b47f409, 0xba1d3330,
+                        0x83d2f293, 0xbfa4784b, 0xcbed606e));
+
+        let mut ra = Pcg32Rng::new_unseeded();
+        let v = Vec::from_fn(3, |_| ra.next_u32());
+        assert_eq!(v, vec!(0x152ca78d, 0x027c6003, 0xcb07bbf3));
+    }
+
+    #[test]
+    fn test_rng_advance() {
+        let mut ra: Pcg32Rng = SeedableRng::from_seed([42, 54]);
+        let mut rb = ra;
+        for _ in range(0u, 1000) {
+            ra.next_u32();
+        }
+        rb.advance(1000);
+        assert_eq!(rb.next_u32(), 0xefebeab3);
+        assert_eq!(ra.next_u32(), 0xefebeab3);
+
+        // Going back to the start.
+        rb.advance(-1001 as u64);
+        assert_eq!(rb.next_u32(), 0xa15c02b7);
+    }
+
+    #[test]
+    fn test_rng_split() {
+        let mut ra: Pcg32Rng = SeedableRng::from_seed([42, 54]);
+        let mut rb: Pcg32Rng = SeedableRng::from_seed([42, 54]);
+        let mut ca = ra.split();
+        let mut cb = rb.split();
+        assert!(order::equals(ca.gen_iter::<u32>().take(100),
+                              cb.gen_iter::<u32>().take(100)));
+        assert!(!order::equals(ca.gen_iter::<u32>().take(100),
+                               ra.gen_iter::<u32>().take(100)));
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The xoroshiro128+ random number generator.
+
+use core::prelude::*;
+use core::num::Int;
+
+use {Rng, SeedableRng, Rand};
+
+/// A random number generator that uses the xoroshiro128+ algorithm [1].
+///
+/// Like `XorShiftRng`, xoroshiro128+ is very fast but not suitable for
+/// cryptographic purposes. It has a period of 2^128 - 1, and `jump`
+/// moves 2^64 steps ahead in constant time, which splits the period into
+/// 2^64 non-overlapping streams for parallel computations.
+///
+/// The lowest bits of its output are of lower quality than the rest, so
+/// `next_u32` returns the upper half of each 64-bit output.
+///
+/// [1]: D. Blackman and S. Vigna, [*Scrambled Linear Pseudorandom Number
+/// Generators*](http://xoshiro.di.unimi.it/)
+#[deriving(Clone, Copy)]
+pub struct Xoroshiro128Rng {
+    s0: u64,
+    s1: u64,
+}
+
+impl Xoroshiro128Rng {
+    /// Creates a new Xoroshiro128Rng instance which is not seeded.
+    ///
+    /// The initial values of this RNG are constants, so all generators created
+    /// by this function will yield the same stream of random numbers. It is
+    /// highly recommended that this is created through `SeedableRng` instead of
+    /// this function
+    pub fn new_unseeded() -> Xoroshiro128Rng {
+        Xoroshiro128Rng {
+            s0: 0x193a6754a8a7d469,
+            s1: 0x97830e05113ba7bb,
+        }
+    }
+
+    /// Moves 2^64 steps ahead, as if `next_u64` had been called 2^64 times.
+    pub fn jump(&mut self) {
+        static JUMP: [u64; 2] = [0xdf900294d8f554a5, 0x170865df4b3201fc];
+
+        let mut s0 = 0;
+        let mut s1 = 0;
+        for &jump in JUMP.iter() {
+            for bit in range(0u, 64) {
+                if jump & (1 << bit) != 0 {
+                    s0 ^= self.s0;
+                    s1 ^= self.s1;
+                }
+                self.next_u64();
+            }
+        }
+        self.s0 = s0;
+        self.s1 = s1;
+    }
+
+    /// Returns a copy of this generator and jumps this one ahead, so that
+    /// the two yield the first 2^64 values of non-overlapping streams.
+    ///
+    /// Calling this repeatedly on a seeded generator gives a reproducible
+    /// sequence of generators, e.g. one for each of a number of tasks.
+    pub fn split(&mut self) -> Xoroshiro128Rng {
+        let rng = *self;
+        self.jump();
+        rng
+    }
+}
+
+impl Rng for Xoroshiro128Rng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let s0 = self.s0;
+        let s1 = self.s1 ^ s0;
+        let result = s0 + self.s1;
+        self.s0 = s0.rotate_left(24) ^ s1 ^ (s1 << 16);
+        self.s1 = s1.rotate_left(37);
+        result
+    }
+}
+
+impl SeedableRng<[u64; 2]> for Xoroshiro128Rng {
+    /// Reseed a Xoroshiro128Rng. This will panic if `seed` is entirely 0.
+    fn reseed(&mut self, seed: [u64; 2]) {
+        assert!(!seed.iter().all(|&x| x == 0),
+                "Xoroshiro128Rng.reseed called with an all zero seed.");
+
+        self.s0 = seed[0];
+        self.s1 = seed[1];
+    }
+
+    /// Create a new Xoroshiro128Rng. This will panic if `seed` is entirely 0.
+    fn from_seed(seed: [u64; 2]) -> Xoroshiro128Rng {
+        assert!(!seed.iter().all(|&x| x == 0),
+                "Xoroshiro128Rng::from_seed called with an all zero seed.");
+
+        Xoroshiro128Rng { s0: seed[0], s1: seed[1] }
+    }
+}
+
+impl Rand for Xoroshiro128Rng {
+    fn rand<R: Rng>(rng: &mut R) -> Xoroshiro128Rng {
+        let mut seed: (u64, u64) = rng.gen();
+        while seed == (0, 0) {
+            seed = rng.gen();
+        }
+        let (s0, s1) = seed;
+        Xoroshiro128Rng { s0: s0, s1: s1 }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::prelude::v1::*;
+
+    use core::iter::order;
+    use {Rng, SeedableRng};
+    use super::Xoroshiro128Rng;
+
+    #[test]
+    fn test_rng_rand_seeded() {
+        let mut ra: Xoroshiro128Rng = ::test::rng().gen();
+        let mut rb = ra;
+        assert!(order::equals(ra.gen_ascii_chars().take(100),
+                              rb.gen_ascii_chars().take(100)));
+    }
+
+    #[test]
+    fn test_rng_reseed() {
+        let mut r: Xoroshiro128Rng = SeedableRng::from_seed([1, 2]);
+        let string1: String = r.gen_ascii_chars().take(100).collect();
+
+        r.reseed([1, 2]);
+
+        let string2: String = r.gen_ascii_chars().take(100).collect();
+        assert_eq!(string1, string2);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_rng_zero_seed() {
+        let _: Xoroshiro128Rng = SeedableRng::from_seed([0, 0]);
+    }
+
+    #[test]
+    fn test_rng_true_values() {
+        // From the reference implementation.
+        let mut ra: Xoroshiro128Rng = SeedableRng::from_seed([1, 2]);
+        let v = Vec::from_fn(4, |_| ra.next_u64());
+        assert_eq!(v,
+                   vec!(3u64, 412333834243, 2360170716294286339, 9295852285959843169));
+
+        // The same generator after a jump.
+        let mut ra: Xoroshiro128Rng = SeedableRng::from_seed([1, 2]);
+        ra.jump();
+        let v = Vec::from_fn(2, |_| ra.next_u64());
+        assert_eq!(v, vec!(16863749256561482023u64, 15988492901402843592));
+    }
+
+    #[test]
+    fn test_rng_split() {
+        let mut ra: Xoroshiro128Rng = SeedableRng::from_seed([1, 2]);
+        let mut ca = ra.split();
+        let mut cb = ra.split();
+        assert_eq!(ca.next_u64(), 3);
+        assert_eq!(cb.next_u64(), 16863749256561482023u64);
+        assert!(!order::equals(cb.gen_iter::<u64>().take(100),
+                               ra.gen_iter::<u64>().take(100)));
+    }
+}
@@ -279,7 +279,8 @@ debugging_opts! {
         NO_TRANS,
         NO_ANALYSIS,
         UNSTABLE_OPTIONS,
-        PRINT_ENUM_SIZES
+        PRINT_ENUM_SIZES,
+        COVERAGE
     ]
     0
 }
@@ -334,6 +335,8 @@ pub fn debugging_opts_map() -> Vec<(&'static str, &'static str, u64)> {
      ("unstable-options", "Adds unstable command line options to rustc interface",
       UNSTABLE_OPTIONS),
      ("print-enum-sizes", "Print the size of enums and their variants", PRINT_ENUM_SIZES),
+     ("coverage", "Instrument the crate to write gcov coverage data (.gcno/.gcda)",
+      COVERAGE),
     ]
 }
 
@@ -1010,6 +1013,13 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             }
         }
     };
+    // gcov maps counters back to source lines through the debug info, so
+    // coverage needs at least line tables.
+    let debuginfo = if debugging_opts & COVERAGE != 0 && debuginfo == NoDebugInfo {
+        LimitedDebugInfo
+    } else {
+        debuginfo
+    };
 
     let mut search_paths = SearchPaths::new();
     for s in matches.opt_strs("L").iter() {
@@ -207,6 +207,9 @@ impl Session {
     pub fn print_enum_sizes(&self) -> bool {
         self.debugging_opt(config::PRINT_ENUM_SIZES)
     }
+    pub fn coverage(&self) -> bool {
+        self.debugging_opt(config::COVERAGE)
+    }
     pub fn sysroot<'a>(&'a self) -> &'a Path {
         match self.opts.maybe_sysroot {
             Some (ref sysroot) => sysroot,
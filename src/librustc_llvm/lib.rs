@@ -689,6 +689,11 @@ extern {
     pub fn LLVMAddNamedMetadataOperand(M: ModuleRef,
                                        Str: *const c_char,
                                        Val: ValueRef);
+    pub fn LLVMGetNamedMetadataNumOperands(M: ModuleRef, Str: *const c_char)
+                                           -> c_uint;
+    pub fn LLVMGetNamedMetadataOperands(M: ModuleRef,
+                                        Str: *const c_char,
+                                        Dest: *mut ValueRef);
 
     /* Operations on scalar constants */
     pub fn LLVMConstInt(IntTy: TypeRef, N: c_ulonglong, SignExtend: Bool)
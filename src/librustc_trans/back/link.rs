@@ -774,6 +774,11 @@ fn link_natively(sess: &Session, trans: &CrateTranslation, dylib: bool,
     link_args(&mut cmd, sess, dylib, tmpdir.path(),
               trans, obj_filename, out_filename);
     cmd.args(sess.target.target.options.post_link_args[]);
+    // Objects instrumented by `-Z coverage` call into the gcov runtime,
+    // which writes out their counters when the program exits.
+    if sess.coverage() {
+        cmd.arg("-lrust_coverage");
+    }
     if !sess.target.target.options.no_compiler_rt {
         cmd.arg("-lcompiler-rt");
     }
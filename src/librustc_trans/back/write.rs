@@ -29,10 +29,11 @@ use std::iter::Unfold;
 use std::ptr;
 use std::str;
 use std::mem;
+use std::os;
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc::channel;
 use std::thread;
-use libc::{c_uint, c_int, c_void};
+use libc::{c_uint, c_int, c_char, c_void};
 
 #[deriving(Clone, Copy, PartialEq, PartialOrd, Ord, Eq)]
 pub enum OutputType {
@@ -280,6 +281,8 @@ struct ModuleConfig {
     no_prepopulate_passes: bool,
     no_builtins: bool,
     time_passes: bool,
+    /// Instrument the module with gcov counters (`-Z coverage`).
+    coverage: bool,
 }
 
 unsafe impl Send for ModuleConfig { }
@@ -302,6 +305,7 @@ impl ModuleConfig {
             no_prepopulate_passes: false,
             no_builtins: false,
             time_passes: false,
+            coverage: false,
         }
     }
 
@@ -453,6 +457,17 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
                 })
             }
 
+            if config.coverage {
+                // The .gcda path is baked into the binary, which may well be
+                // run from some other directory.
+                let gcno = output_names.with_extension(format!("{}.gcno", name_extra)[]);
+                let gcno = os::make_absolute(&gcno).unwrap_or(gcno);
+                add_gcov_metadata(llcx, llmod, &gcno);
+                "insert-gcov-profiling".with_c_str(|s| {
+                    assert!(llvm::LLVMRustAddPass(mpm, s));
+                });
+            }
+
             // Finally, run the actual optimization passes
             time(config.time_passes, "llvm function passes", (), |()|
                  llvm::LLVMRustRunFunctionPassManager(fpm, llmod));
@@ -539,6 +554,30 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
     llvm::LLVMRustDisposeTargetMachine(tm);
 }
 
+/// Tells the gcov instrumentation pass where to put its notes (`.gcno`) and
+/// data (`.gcda`) files. Without `llvm.gcov` metadata LLVM names them after
+/// the compile unit's source file in the current directory, so every codegen
+/// unit would clobber the others' notes.
+unsafe fn add_gcov_metadata(llcx: ContextRef, llmod: ModuleRef, gcno: &Path) {
+    let ncus = "llvm.dbg.cu".with_c_str(|s| {
+        llvm::LLVMGetNamedMetadataNumOperands(llmod, s)
+    });
+    let mut cus = Vec::from_elem(ncus as uint, ptr::null_mut());
+    "llvm.dbg.cu".with_c_str(|s| {
+        llvm::LLVMGetNamedMetadataOperands(llmod, s, cus.as_mut_ptr())
+    });
+
+    let gcno = gcno.as_vec();
+    for &cu in cus.iter() {
+        let file = llvm::LLVMMDStringInContext(llcx,
+                                               gcno.as_ptr() as *const c_char,
+                                               gcno.len() as c_uint);
+        let node = [file, cu];
+        let node = llvm::LLVMMDNodeInContext(llcx, node.as_ptr(), node.len() as c_uint);
+        "llvm.gcov".with_c_str(|s| llvm::LLVMAddNamedMetadataOperand(llmod, s, node));
+    }
+}
+
 pub fn run_passes(sess: &Session,
                   trans: &CrateTranslation,
                   output_types: &[config::OutputType],
@@ -571,6 +610,7 @@ pub fn run_passes(sess: &Session,
     let mut metadata_config = ModuleConfig::new(tm, vec!());
 
     modules_config.opt_level = Some(get_llvm_opt_level(sess.opts.optimize));
+    modules_config.coverage = sess.coverage();
 
     // Save all versions of the bytecode if we're saving our temporaries.
     if sess.opts.cg.save_temps {
@@ -199,11 +199,13 @@ use self::ParserError::*;
 use self::DecoderError::*;
 use self::ParserState::*;
 use self::InternalStackElement::*;
+use self::PathError::*;
+use self::QueryStep::*;
 
 use std;
 use std::collections::{HashMap, BTreeMap};
 use std::{char, f64, fmt, io, num, str};
-use std::mem::{swap, transmute};
+use std::mem::{replace, swap, transmute};
 use std::num::{Float, Int};
 use std::num::FpCategory as Fp;
 use std::str::FromStr;
@@ -276,6 +278,15 @@ pub enum DecoderError {
     ApplicationError(string::String)
 }
 
+/// The errors that can arise while resolving a JSON Pointer or a path query.
+#[deriving(Clone, Copy, PartialEq, Show)]
+pub enum PathError {
+    /// The pointer or query is malformed at the given character position.
+    InvalidPath(uint),
+    /// The pointer does not refer to an existing value.
+    MissingTarget,
+}
+
 /// Returns a readable error string for a given error code.
 pub fn error_str(error: ErrorCode) -> &'static str {
     match error {
@@ -310,6 +321,17 @@ pub fn decode<T: ::Decodable<Decoder, DecoderError>>(s: &str) -> DecodeResult<T>
     ::Decodable::decode(&mut decoder)
 }
 
+/// Shortcut function to decode a JSON `&str` into an object without building
+/// a `Json` value first
+pub fn decode_stream<'a, T>(s: &'a str) -> DecodeResult<T>
+                            where T: ::Decodable<StreamDecoder<str::Chars<'a>>, DecoderError>
+{
+    let mut decoder = StreamDecoder::new(s.chars());
+    let value = try!(::Decodable::decode(&mut decoder));
+    try!(decoder.end());
+    Ok(value)
+}
+
 /// Shortcut function to encode a `T` into a JSON `String`
 pub fn encode<T>(object: &T) -> string::String
                  where T: for<'a> Encodable<Encoder<'a>, fmt::Error>
@@ -989,6 +1011,166 @@ impl Json {
         }
     }
 
+    /// If the Json value is an Object, returns a mutable reference to the value
+    /// associated with the provided key. Otherwise, returns None.
+    pub fn find_mut<'a>(&'a mut self, key: &str) -> Option<&'a mut Json> {
+        match *self {
+            Json::Object(ref mut map) => map.get_mut(key),
+            _ => None
+        }
+    }
+
+    /// Like `find_path`, but returns a mutable reference to the value
+    /// associated with the final key.
+    pub fn find_path_mut<'a>(&'a mut self, keys: &[&str]) -> Option<&'a mut Json> {
+        if keys.is_empty() {
+            return Some(self);
+        }
+        match self.find_mut(keys[0]) {
+            Some(t) => t.find_path_mut(keys[1..]),
+            None => None
+        }
+    }
+
+    /// Looks up a value by a JSON Pointer (RFC 6901), such as `/foo/0/bar`.
+    /// The empty pointer refers to the value itself.
+    pub fn pointer<'a>(&'a self, pointer: &str) -> Result<&'a Json, PathError> {
+        let mut target = self;
+        for token in try!(parse_pointer(pointer)).iter() {
+            target = match pointer_step(target, token[]) {
+                Some(t) => t,
+                None => return Err(MissingTarget)
+            };
+        }
+        Ok(target)
+    }
+
+    /// Like `pointer`, but returns a mutable reference to the value.
+    pub fn pointer_mut<'a>(&'a mut self, pointer: &str) -> Result<&'a mut Json, PathError> {
+        let tokens = try!(parse_pointer(pointer));
+        match pointer_path_mut(self, tokens[]) {
+            Some(t) => Ok(t),
+            None => Err(MissingTarget)
+        }
+    }
+
+    /// Stores `value` at the location referred to by a JSON Pointer, returning
+    /// the value it replaced, if any.
+    ///
+    /// Every value along the pointer but the last must already exist. The last
+    /// reference token may name a new member of an object, or refer to the
+    /// element one past the end of an array (either by its index or by `-`),
+    /// in which case `value` is appended.
+    pub fn set_pointer(&mut self, pointer: &str, value: Json) -> Result<Option<Json>, PathError> {
+        let tokens = try!(parse_pointer(pointer));
+        let last = match tokens.last() {
+            Some(last) => last,
+            None => return Ok(Some(replace(self, value)))
+        };
+        let parent = match pointer_path_mut(self, tokens.init()) {
+            Some(parent) => parent,
+            None => return Err(MissingTarget)
+        };
+        match *parent {
+            Json::Object(ref mut map) => Ok(map.insert(last.clone(), value)),
+            Json::Array(ref mut array) => {
+                let idx = if last[] == "-" {
+                    array.len()
+                } else {
+                    match parse_index(last[]) {
+                        Some(idx) if idx <= array.len() => idx,
+                        _ => return Err(MissingTarget)
+                    }
+                };
+                if idx == array.len() {
+                    array.push(value);
+                    Ok(None)
+                } else {
+                    Ok(Some(replace(&mut array[idx], value)))
+                }
+            }
+            _ => Err(MissingTarget)
+        }
+    }
+
+    /// Removes the value referred to by a JSON Pointer from its parent object
+    /// or array and returns it. Removing the empty pointer leaves `Null` in
+    /// place of the value itself.
+    pub fn remove_pointer(&mut self, pointer: &str) -> Result<Json, PathError> {
+        let tokens = try!(parse_pointer(pointer));
+        let last = match tokens.last() {
+            Some(last) => last,
+            None => return Ok(replace(self, Json::Null))
+        };
+        let parent = match pointer_path_mut(self, tokens.init()) {
+            Some(parent) => parent,
+            None => return Err(MissingTarget)
+        };
+        let removed = match *parent {
+            Json::Object(ref mut map) => map.remove(last[]),
+            Json::Array(ref mut array) => match parse_index(last[]) {
+                Some(idx) if idx < array.len() => Some(array.remove(idx)),
+                _ => None
+            },
+            _ => None
+        };
+        removed.ok_or(MissingTarget)
+    }
+
+    /// Applies a JSON Merge Patch (RFC 7396) to the value.
+    ///
+    /// If `patch` is an Object, each of its members is merged into the
+    /// corresponding member of the value, with `Null` members removing it;
+    /// the value is first replaced by an empty Object if it isn't one.
+    /// Any other patch replaces the value.
+    pub fn merge_patch(&mut self, patch: &Json) {
+        let patch = match *patch {
+            Json::Object(ref patch) => patch,
+            _ => {
+                *self = patch.clone();
+                return;
+            }
+        };
+        if !self.is_object() {
+            *self = Json::Object(BTreeMap::new());
+        }
+        let target = self.as_object_mut().unwrap();
+        for (key, value) in patch.iter() {
+            if value.is_null() {
+                target.remove(key[]);
+            } else {
+                let mut member = target.remove(key[]).unwrap_or(Json::Null);
+                member.merge_patch(value);
+                target.insert(key.clone(), member);
+            }
+        }
+    }
+
+    /// Returns every value matched by a JSONPath-like query.
+    ///
+    /// A query starts with `$`, referring to the value itself, followed by any
+    /// number of steps:
+    ///
+    /// * `.name` or `['name']` selects the member of an Object with that key,
+    /// * `[n]` selects the element of an Array at that index,
+    /// * `.*` or `[*]` selects every member of an Object or element of an Array,
+    /// * `..name` selects the members with that key of the value and every
+    ///   Object nested in it, and `..*` selects every nested value.
+    ///
+    /// For example `$.store.books[*].author` selects the author of every book.
+    pub fn query<'a>(&'a self, query: &str) -> Result<Vec<&'a Json>, PathError> {
+        let steps = try!(parse_query(query));
+        let mut matches = vec![self];
+        for step in steps.iter() {
+            let mut next = Vec::new();
+            for json in matches.into_iter() {
+                query_step(json, step, &mut next);
+            }
+            matches = next;
+        }
+        Ok(matches)
+    }
+
     /// Returns true if the Json value is an Object. Returns false otherwise.
     pub fn is_object<'a>(&'a self) -> bool {
         self.as_object().is_some()
@@ -1003,6 +1185,15 @@ impl Json {
         }
     }
 
+    /// If the Json value is an Object, returns a mutable reference to the
+    /// associated BTreeMap. Returns None otherwise.
+    pub fn as_object_mut<'a>(&'a mut self) -> Option<&'a mut Object> {
+        match *self {
+            Json::Object(ref mut map) => Some(map),
+            _ => None
+        }
+    }
+
     /// Returns true if the Json value is an Array. Returns false otherwise.
     pub fn is_array<'a>(&'a self) -> bool {
         self.as_array().is_some()
@@ -1017,6 +1208,15 @@ impl Json {
         }
     }
 
+    /// If the Json value is an Array, returns a mutable reference to the
+    /// associated vector. Returns None otherwise.
+    pub fn as_array_mut<'a>(&'a mut self) -> Option<&'a mut Array> {
+        match *self {
+            Json::Array(ref mut array) => Some(array),
+            _ => None
+        }
+    }
+
     /// Returns true if the Json value is a String. Returns false otherwise.
     pub fn is_string<'a>(&'a self) -> bool {
         self.as_string().is_some()
@@ -1123,6 +1323,212 @@ impl Json {
     }
 }
 
+// Splits a JSON Pointer into its reference tokens, undoing the `~0` and `~1`
+// escapes.
+fn parse_pointer(pointer: &str) -> Result<Vec<string::String>, PathError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with("/") {
+        return Err(InvalidPath(0));
+    }
+    let mut tokens = Vec::new();
+    let mut token = string::String::new();
+    let mut chars = pointer.chars().enumerate().skip(1);
+    loop {
+        match chars.next() {
+            None => {
+                tokens.push(token);
+                return Ok(tokens);
+            }
+            Some((_, '/')) => tokens.push(replace(&mut token, string::String::new())),
+            Some((i, '~')) => match chars.next() {
+                Some((_, '0')) => token.push('~'),
+                Some((_, '1')) => token.push('/'),
+                _ => return Err(InvalidPath(i)),
+            },
+            Some((_, c)) => token.push(c),
+        }
+    }
+}
+
+// Parses an array index in a JSON Pointer, which may not have leading zeros.
+fn parse_index(token: &str) -> Option<uint> {
+    if token.len() > 1 && token.starts_with("0") {
+        return None;
+    }
+    if !token.chars().all(|c| c >= '0' && c <= '9') {
+        return None;
+    }
+    token.parse()
+}
+
+fn pointer_step<'a>(json: &'a Json, token: &str) -> Option<&'a Json> {
+    match *json {
+        Json::Object(ref map) => map.get(token),
+        Json::Array(ref array) => parse_index(token).and_then(|idx| array.get(idx)),
+        _ => None
+    }
+}
+
+fn pointer_path_mut<'a>(json: &'a mut Json,
+                        tokens: &[string::String]) -> Option<&'a mut Json> {
+    if tokens.is_empty() {
+        return Some(json);
+    }
+    let next = match *json {
+        Json::Object(ref mut map) => map.get_mut(tokens[0][]),
+        Json::Array(ref mut array) => match parse_index(tokens[0][]) {
+            Some(idx) => array.get_mut(idx),
+            None => None
+        },
+        _ => None
+    };
+    match next {
+        Some(next) => pointer_path_mut(next, tokens[1..]),
+        None => None
+    }
+}
+
+enum QueryStep {
+    // `.name` or `['name']`
+    Child(string::String),
+    // `[n]`
+    Element(uint),
+    // `.*` or `[*]`
+    AnyChild,
+    // `..name`, or `..*` if None
+    Descendants(Option<string::String>),
+}
+
+fn parse_query(query: &str) -> Result<Vec<QueryStep>, PathError> {
+    let chars: Vec<char> = query.chars().collect();
+    if chars.get(0) != Some(&'$') {
+        return Err(InvalidPath(0));
+    }
+    let mut pos = 1u;
+    let mut steps = Vec::new();
+    while pos < chars.len() {
+        match chars[pos] {
+            '.' => {
+                pos += 1;
+                let descend = chars.get(pos) == Some(&'.');
+                if descend {
+                    pos += 1;
+                }
+                let name = if chars.get(pos) == Some(&'*') {
+                    pos += 1;
+                    None
+                } else {
+                    let start = pos;
+                    while pos < chars.len() && chars[pos] != '.' && chars[pos] != '[' {
+                        pos += 1;
+                    }
+                    if pos == start {
+                        return Err(InvalidPath(pos));
+                    }
+                    Some(chars[start..pos].iter().map(|c| *c).collect())
+                };
+                steps.push(match (descend, name) {
+                    (true, name) => Descendants(name),
+                    (false, Some(name)) => Child(name),
+                    (false, None) => AnyChild,
+                });
+            }
+            '[' => {
+                pos += 1;
+                let step = match chars.get(pos) {
+                    Some(&'*') => {
+                        pos += 1;
+                        AnyChild
+                    }
+                    Some(&quote) if quote == '\'' || quote == '"' => {
+                        pos += 1;
+                        let mut name = string::String::new();
+                        loop {
+                            match chars.get(pos) {
+                                None => return Err(InvalidPath(pos)),
+                                Some(&c) if c == quote => break,
+                                Some(&'\\') => {
+                                    pos += 1;
+                                    match chars.get(pos) {
+                                        Some(&c) => name.push(c),
+                                        None => return Err(InvalidPath(pos)),
+                                    }
+                                }
+                                Some(&c) => name.push(c),
+                            }
+                            pos += 1;
+                        }
+                        pos += 1;
+                        Child(name)
+                    }
+                    _ => {
+                        let start = pos;
+                        while pos < chars.len() && chars[pos] >= '0' && chars[pos] <= '9' {
+                            pos += 1;
+                        }
+                        let digits: string::String = chars[start..pos].iter().map(|c| *c).collect();
+                        match digits.parse() {
+                            Some(idx) => Element(idx),
+                            None => return Err(InvalidPath(start)),
+                        }
+                    }
+                };
+                if chars.get(pos) != Some(&']') {
+                    return Err(InvalidPath(pos));
+                }
+                pos += 1;
+                steps.push(step);
+            }
+            _ => return Err(InvalidPath(pos)),
+        }
+    }
+    Ok(steps)
+}
+
+fn query_step<'a>(json: &'a Json, step: &QueryStep, out: &mut Vec<&'a Json>) {
+    match (step, json) {
+        (&Child(ref name), &Json::Object(ref map)) => match map.get(name[]) {
+            Some(value) => out.push(value),
+            None => {}
+        },
+        (&Element(idx), &Json::Array(ref array)) => match array.get(idx) {
+            Some(value) => out.push(value),
+            None => {}
+        },
+        (&AnyChild, &Json::Object(ref map)) => out.extend(map.values()),
+        (&AnyChild, &Json::Array(ref array)) => out.extend(array.iter()),
+        (&Descendants(ref name), _) => query_descendants(json, name, out),
+        _ => {}
+    }
+}
+
+fn query_descendants<'a>(json: &'a Json,
+                         name: &Option<string::String>,
+                         out: &mut Vec<&'a Json>) {
+    match *json {
+        Json::Object(ref map) => {
+            for (key, value) in map.iter() {
+                match *name {
+                    Some(ref name) if name != key => {}
+                    _ => out.push(value),
+                }
+                query_descendants(value, name, out);
+            }
+        }
+        Json::Array(ref array) => {
+            for value in array.iter() {
+                if name.is_none() {
+                    out.push(value);
+                }
+                query_descendants(value, name, out);
+            }
+        }
+        _ => {}
+    }
+}
+
 impl<'a> ops::Index<&'a str, Json>  for Json {
     fn index(&self, idx: & &str) -> &Json {
         self.find(*idx).unwrap()
@@ -2199,6 +2605,26 @@ impl ::Decoder<DecoderError> for Decoder {
         Ok(value)
     }
 
+    fn read_struct_field_opt<T, F>(&mut self,
+                                   name: &str,
+                                   _idx: uint,
+                                   f: F)
+                                   -> DecodeResult<Option<T>> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        let mut obj = try!(expect!(self.pop(), Object));
+
+        let value = match obj.remove(&name.to_string()) {
+            None => None,
+            Some(json) => {
+                self.stack.push(json);
+                Some(try!(f(self)))
+            }
+        };
+        self.stack.push(Json::Object(obj));
+        Ok(value)
+    }
+
     fn read_tuple<T, F>(&mut self, tuple_len: uint, f: F) -> DecodeResult<T> where
         F: FnOnce(&mut Decoder) -> DecodeResult<T>,
     {
@@ -2291,6 +2717,613 @@ impl ::Decoder<DecoderError> for Decoder {
     }
 }
 
+/// A structure to decode JSON to values in rust directly from the events of
+/// a `Parser`, without building a `Json` value first.
+///
+/// Struct fields are matched against the keys on the parser's stack, so
+/// nothing is buffered when fields appear in the order they are declared.
+/// Strings are still allocated by the parser, and are moved out of its events
+/// rather than cloned.
+///
+/// Not all of the input can be streamed, though. Fields that appear out of
+/// order are read ahead and kept as events until they are asked for. Arrays
+/// and objects decoded as sequences or maps are read ahead in full, since
+/// `Decoder::read_seq` and `Decoder::read_map` must report their length
+/// before any element is decoded, and decoding them element by element would
+/// need a different `Decoder` trait. A top-level array of records decoded as
+/// a `Vec` is therefore held in memory as events, although never as `Json`
+/// values; use `decode_elements` to decode the records one at a time
+/// instead. The number of events held at once is bounded by
+/// `set_buffer_limit`, and decoding fails with an `ApplicationError` when
+/// more would be needed.
+pub struct StreamDecoder<T> {
+    parser: Parser<T>,
+    // The next event to be decoded.
+    token: Option<JsonEvent>,
+    // Events that were read ahead and are handed out before asking the parser
+    // for more, the next one last. Each is paired with the key it was found
+    // under, as the parser's stack has moved on since.
+    replay: Vec<BufferedEvent>,
+    // The key `token` was found under, if it was taken from `replay`.
+    replay_key: Option<Option<string::String>>,
+    // For each struct being decoded, the fields that were read ahead while
+    // looking for another one.
+    fields: Vec<BTreeMap<string::String, Vec<BufferedEvent>>>,
+    // The number of events held in `fields`.
+    fields_len: uint,
+    // The most events that may be held in `replay` and `fields` together.
+    buffer_limit: uint,
+}
+
+type BufferedEvent = (Option<string::String>, JsonEvent);
+
+/// The default for `StreamDecoder::set_buffer_limit`.
+pub const DEFAULT_BUFFER_LIMIT: uint = 1 << 20;
+
+impl<T: Iterator<char>> StreamDecoder<T> {
+    /// Creates a new decoder instance for decoding the JSON read from `rdr`.
+    pub fn new(rdr: T) -> StreamDecoder<T> {
+        let mut d = StreamDecoder {
+            parser: Parser::new(rdr),
+            token: None,
+            replay: Vec::new(),
+            replay_key: None,
+            fields: Vec::new(),
+            fields_len: 0,
+            buffer_limit: DEFAULT_BUFFER_LIMIT,
+        };
+        d.bump();
+        d
+    }
+
+    /// Sets the most events that may be read ahead and held at once, which
+    /// is `DEFAULT_BUFFER_LIMIT` unless changed. Each number, string,
+    /// boolean or null is one event, as is the start and the end of each
+    /// array or object.
+    pub fn set_buffer_limit(&mut self, limit: uint) {
+        self.buffer_limit = limit;
+    }
+
+    /// Decodes the elements of an array one at a time, passing each to `f`
+    /// as soon as it has been read, and stops at the first error returned by
+    /// `f`.
+    ///
+    /// Unlike decoding the array as a sequence, this needs no read-ahead
+    /// beyond that of the elements themselves.
+    pub fn decode_elements<D, F>(&mut self, mut f: F) -> DecodeResult<()> where
+        D: ::Decodable<StreamDecoder<T>, DecoderError>,
+        F: FnMut(D) -> DecodeResult<()>,
+    {
+        match self.token {
+            Some(ArrayStart) => self.bump(),
+            _ => return self.expected("Array"),
+        }
+        loop {
+            match self.token {
+                Some(ArrayEnd) => break,
+                Some(Error(e)) => return Err(ParseError(e)),
+                None => return self.syntax_error(EOFWhileParsingArray),
+                Some(_) => {}
+            }
+            let value = try!(::Decodable::decode(self));
+            try!(f(value));
+        }
+        self.bump();
+        Ok(())
+    }
+
+    /// Checks that nothing but whitespace follows the decoded value.
+    pub fn end(&mut self) -> DecodeResult<()> {
+        match self.token {
+            None => Ok(()),
+            Some(Error(e)) => Err(ParseError(e)),
+            Some(_) => self.syntax_error(TrailingCharacters),
+        }
+    }
+
+    fn bump(&mut self) {
+        match self.replay.pop() {
+            Some((key, evt)) => {
+                self.replay_key = Some(key);
+                self.token = Some(evt);
+            }
+            None => {
+                self.replay_key = None;
+                self.token = self.parser.next();
+            }
+        }
+    }
+
+    // The key the current token was found under, if it is an object member.
+    fn key<'a>(&'a self) -> Option<&'a str> {
+        match self.replay_key {
+            Some(ref key) => key.as_ref().map(|k| k.as_slice()),
+            None => match self.parser.stack().top() {
+                Some(Key(k)) => Some(k),
+                _ => None,
+            }
+        }
+    }
+
+    // Moves to the member of the current object holding the field `name`,
+    // buffering the members before it. Returns false if there is none.
+    fn find_field(&mut self, name: &str) -> DecodeResult<bool> {
+        let buffered = match self.fields.last_mut() {
+            Some(fields) => fields.remove(name),
+            None => None,
+        };
+        match buffered {
+            Some(events) => {
+                self.fields_len -= events.len();
+                self.unread(events);
+                return Ok(true);
+            }
+            None => {}
+        }
+
+        loop {
+            if self.token == Some(ObjectEnd) {
+                return Ok(false);
+            }
+            if self.key() == Some(name) {
+                return Ok(true);
+            }
+
+            // Keep the member around in case a later field asks for it.
+            let key = try!(self.member_key());
+            let mut events = Vec::new();
+            try!(self.consume_value(Some(&mut events)));
+            self.fields_len += events.len();
+            match self.fields.last_mut() {
+                Some(fields) => { fields.insert(key, events); }
+                None => {}
+            }
+        }
+    }
+
+    // Like `key`, but for a token that must be an object member.
+    fn member_key(&self) -> DecodeResult<string::String> {
+        match self.token {
+            Some(Error(e)) => return Err(ParseError(e)),
+            None => return self.syntax_error(EOFWhileParsingObject),
+            _ => {}
+        }
+        match self.key() {
+            Some(key) => Ok(key.to_string()),
+            None => self.syntax_error(InvalidSyntax),
+        }
+    }
+
+    // Hands out `events` before the current token.
+    fn unread(&mut self, events: Vec<BufferedEvent>) {
+        let key = self.key().map(|k| k.to_string());
+        match self.token.take() {
+            Some(token) => self.replay.push((key, token)),
+            None => {}
+        }
+        self.replay.extend(events.into_iter().rev());
+        self.bump();
+    }
+
+    // Consumes the events making up the current value, moving them into `out`
+    // if given.
+    fn consume_value(&mut self, mut out: Option<&mut Vec<BufferedEvent>>) -> DecodeResult<()> {
+        let mut depth = 0u;
+        loop {
+            match self.token {
+                Some(ObjectStart) | Some(ArrayStart) => depth += 1,
+                Some(ObjectEnd) | Some(ArrayEnd) if depth > 0 => depth -= 1,
+                Some(ObjectEnd) | Some(ArrayEnd) => return self.syntax_error(InvalidSyntax),
+                Some(Error(e)) => return Err(ParseError(e)),
+                None => return self.syntax_error(EOFWhileParsingValue),
+                Some(_) => {}
+            }
+            match out {
+                Some(ref mut out) => {
+                    if self.replay.len() + self.fields_len + out.len() >= self.buffer_limit {
+                        return Err(ApplicationError(format!(
+                            "more than {} events would be read ahead", self.buffer_limit)));
+                    }
+                    let key = self.key().map(|k| k.to_string());
+                    out.push((key, self.token.take().unwrap()));
+                }
+                None => {}
+            }
+            self.bump();
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    // Builds the current value, so that it can be reported in errors.
+    fn build_value(&mut self) -> DecodeResult<Json> {
+        let json = match self.token.take() {
+            Some(NullValue) => Json::Null,
+            Some(I64Value(n)) => Json::I64(n),
+            Some(U64Value(n)) => Json::U64(n),
+            Some(F64Value(n)) => Json::F64(n),
+            Some(BooleanValue(b)) => Json::Boolean(b),
+            Some(StringValue(s)) => Json::String(s),
+            Some(ArrayStart) => {
+                self.bump();
+                let mut values = Vec::new();
+                while self.token != Some(ArrayEnd) {
+                    values.push(try!(self.build_value()));
+                }
+                Json::Array(values)
+            }
+            Some(ObjectStart) => {
+                self.bump();
+                let mut values = BTreeMap::new();
+                while self.token != Some(ObjectEnd) {
+                    let key = try!(self.member_key());
+                    let value = try!(self.build_value());
+                    values.insert(key, value);
+                }
+                Json::Object(values)
+            }
+            Some(Error(e)) => return Err(ParseError(e)),
+            Some(ObjectEnd) | Some(ArrayEnd) => return self.syntax_error(InvalidSyntax),
+            None => return self.syntax_error(EOFWhileParsingValue),
+        };
+        self.bump();
+        Ok(json)
+    }
+
+    fn expected<R>(&mut self, expected: &str) -> DecodeResult<R> {
+        let found = try!(self.build_value());
+        Err(ExpectedError(expected.to_string(), format!("{}", found)))
+    }
+
+    fn syntax_error<R>(&self, reason: ErrorCode) -> DecodeResult<R> {
+        self.parser.error(reason).map_err(ParseError)
+    }
+}
+
+macro_rules! read_stream_primitive {
+    ($name:ident, $ty:ty) => {
+        fn $name(&mut self) -> DecodeResult<$ty> {
+            let value = match self.token {
+                Some(I64Value(f)) => match num::cast(f) {
+                    Some(f) => Some(f),
+                    None => return Err(ExpectedError("Number".to_string(), format!("{}", f))),
+                },
+                Some(U64Value(f)) => match num::cast(f) {
+                    Some(f) => Some(f),
+                    None => return Err(ExpectedError("Number".to_string(), format!("{}", f))),
+                },
+                Some(F64Value(f)) => {
+                    return Err(ExpectedError("Integer".to_string(), format!("{}", f)))
+                }
+                // re: #12967.. a type w/ numeric keys (ie HashMap<uint, V> etc)
+                // is going to have a string here, as per JSON spec.
+                Some(StringValue(ref s)) => match std::str::from_str(s.as_slice()) {
+                    Some(f) => Some(f),
+                    None => return Err(ExpectedError("Number".to_string(), s.clone())),
+                },
+                _ => None,
+            };
+            match value {
+                Some(value) => { self.bump(); Ok(value) }
+                None => self.expected("Number"),
+            }
+        }
+    }
+}
+
+impl<T: Iterator<char>> ::Decoder<DecoderError> for StreamDecoder<T> {
+    fn read_nil(&mut self) -> DecodeResult<()> {
+        match self.token {
+            Some(NullValue) => { self.bump(); Ok(()) }
+            _ => self.expected("Null"),
+        }
+    }
+
+    read_stream_primitive! { read_uint, uint }
+    read_stream_primitive! { read_u8, u8 }
+    read_stream_primitive! { read_u16, u16 }
+    read_stream_primitive! { read_u32, u32 }
+    read_stream_primitive! { read_u64, u64 }
+    read_stream_primitive! { read_int, int }
+    read_stream_primitive! { read_i8, i8 }
+    read_stream_primitive! { read_i16, i16 }
+    read_stream_primitive! { read_i32, i32 }
+    read_stream_primitive! { read_i64, i64 }
+
+    fn read_f32(&mut self) -> DecodeResult<f32> { self.read_f64().map(|x| x as f32) }
+
+    fn read_f64(&mut self) -> DecodeResult<f64> {
+        let value = match self.token {
+            Some(I64Value(f)) => Some(f as f64),
+            Some(U64Value(f)) => Some(f as f64),
+            Some(F64Value(f)) => Some(f),
+            Some(StringValue(ref s)) => {
+                // re: #12967.. a type w/ numeric keys (ie HashMap<uint, V> etc)
+                // is going to have a string here, as per JSON spec.
+                match s.parse() {
+                    Some(f) => Some(f),
+                    None => return Err(ExpectedError("Number".to_string(), s.clone())),
+                }
+            },
+            Some(NullValue) => Some(f64::NAN),
+            _ => None,
+        };
+        match value {
+            Some(value) => { self.bump(); Ok(value) }
+            None => self.expected("Number"),
+        }
+    }
+
+    fn read_bool(&mut self) -> DecodeResult<bool> {
+        match self.token {
+            Some(BooleanValue(b)) => { self.bump(); Ok(b) }
+            _ => self.expected("Boolean"),
+        }
+    }
+
+    fn read_char(&mut self) -> DecodeResult<char> {
+        let s = try!(self.read_str());
+        {
+            let mut it = s.chars();
+            match (it.next(), it.next()) {
+                // exactly one character
+                (Some(c), None) => return Ok(c),
+                _ => ()
+            }
+        }
+        Err(ExpectedError("single character string".to_string(), format!("{}", s)))
+    }
+
+    fn read_str(&mut self) -> DecodeResult<string::String> {
+        match self.token.take() {
+            Some(StringValue(s)) => { self.bump(); Ok(s) }
+            token => { self.token = token; self.expected("String") }
+        }
+    }
+
+    fn read_enum<R, F>(&mut self, _name: &str, f: F) -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        f(self)
+    }
+
+    fn read_enum_variant<R, F>(&mut self, names: &[&str],
+                               mut f: F) -> DecodeResult<R>
+        where F: FnMut(&mut StreamDecoder<T>, uint) -> DecodeResult<R>,
+    {
+        let name = match self.token.take() {
+            Some(StringValue(s)) => { self.bump(); s }
+            Some(ObjectStart) => {
+                self.token = Some(ObjectStart);
+                return self.read_struct("", 2, move |d| {
+                    let name = try!(d.read_struct_field("variant", 0, |d| d.read_str()));
+                    let idx = match names.iter().position(|n| *n == name[]) {
+                        Some(idx) => idx,
+                        None => return Err(UnknownVariantError(name))
+                    };
+                    d.read_struct_field("fields", 1, move |d| {
+                        d.read_seq(move |d, _len| f(d, idx))
+                    })
+                });
+            }
+            token => {
+                self.token = token;
+                return self.expected("String or Object");
+            }
+        };
+        let idx = match names.iter().position(|n| *n == name[]) {
+            Some(idx) => idx,
+            None => return Err(UnknownVariantError(name))
+        };
+        f(self, idx)
+    }
+
+    fn read_enum_variant_arg<R, F>(&mut self, _idx: uint, f: F) -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        f(self)
+    }
+
+    fn read_enum_struct_variant<R, F>(&mut self, names: &[&str], f: F) -> DecodeResult<R> where
+        F: FnMut(&mut StreamDecoder<T>, uint) -> DecodeResult<R>,
+    {
+        self.read_enum_variant(names, f)
+    }
+
+
+    fn read_enum_struct_variant_field<R, F>(&mut self,
+                                         _name: &str,
+                                         idx: uint,
+                                         f: F)
+                                         -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        self.read_enum_variant_arg(idx, f)
+    }
+
+    fn read_struct<R, F>(&mut self, _name: &str, _len: uint, f: F) -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        match self.token {
+            Some(ObjectStart) => self.bump(),
+            _ => return self.expected("Object"),
+        }
+        self.fields.push(BTreeMap::new());
+        let value = try!(f(self));
+        match self.fields.pop() {
+            Some(fields) => {
+                for events in fields.values() {
+                    self.fields_len -= events.len();
+                }
+            }
+            None => {}
+        }
+
+        // Skip the members that don't belong to any field.
+        while self.token != Some(ObjectEnd) {
+            try!(self.consume_value(None));
+        }
+        self.bump();
+        Ok(value)
+    }
+
+    fn read_struct_field<R, F>(&mut self,
+                               name: &str,
+                               _idx: uint,
+                               f: F)
+                               -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        if try!(self.find_field(name)) {
+            return f(self);
+        }
+        // Add a Null and try to parse it as an Option<_>
+        // to get None as a default value.
+        self.unread(vec![(None, NullValue)]);
+        match f(self) {
+            Ok(x) => Ok(x),
+            Err(_) => Err(MissingFieldError(name.to_string())),
+        }
+    }
+
+    fn read_struct_field_opt<R, F>(&mut self,
+                                   name: &str,
+                                   _idx: uint,
+                                   f: F)
+                                   -> DecodeResult<Option<R>> where
+        F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        if try!(self.find_field(name)) {
+            f(self).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn read_tuple<R, F>(&mut self, tuple_len: uint, f: F) -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        self.read_seq(move |d, len| {
+            if len == tuple_len {
+                f(d)
+            } else {
+                Err(ExpectedError(format!("Tuple{}", tuple_len), format!("Tuple{}", len)))
+            }
+        })
+    }
+
+    fn read_tuple_arg<R, F>(&mut self, idx: uint, f: F) -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        self.read_seq_elt(idx, f)
+    }
+
+    fn read_tuple_struct<R, F>(&mut self,
+                               _name: &str,
+                               len: uint,
+                               f: F)
+                               -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        self.read_tuple(len, f)
+    }
+
+    fn read_tuple_struct_arg<R, F>(&mut self,
+                                   idx: uint,
+                                   f: F)
+                                   -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        self.read_tuple_arg(idx, f)
+    }
+
+    fn read_option<R, F>(&mut self, mut f: F) -> DecodeResult<R> where
+        F: FnMut(&mut StreamDecoder<T>, bool) -> DecodeResult<R>,
+    {
+        match self.token {
+            Some(NullValue) => { self.bump(); f(self, false) }
+            _ => f(self, true),
+        }
+    }
+
+    fn read_seq<R, F>(&mut self, f: F) -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>, uint) -> DecodeResult<R>,
+    {
+        match self.token {
+            Some(ArrayStart) => self.bump(),
+            _ => return self.expected("Array"),
+        }
+        let mut events = Vec::new();
+        let mut len = 0u;
+        while self.token != Some(ArrayEnd) {
+            try!(self.consume_value(Some(&mut events)));
+            len += 1;
+        }
+        if !events.is_empty() {
+            self.unread(events);
+        }
+        let value = try!(f(self, len));
+
+        while self.token != Some(ArrayEnd) {
+            try!(self.consume_value(None));
+        }
+        self.bump();
+        Ok(value)
+    }
+
+    fn read_seq_elt<R, F>(&mut self, _idx: uint, f: F) -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        f(self)
+    }
+
+    fn read_map<R, F>(&mut self, f: F) -> DecodeResult<R> where
+        F: FnOnce(&mut StreamDecoder<T>, uint) -> DecodeResult<R>,
+    {
+        match self.token {
+            Some(ObjectStart) => self.bump(),
+            _ => return self.expected("Object"),
+        }
+        // Each member is handed out as its key, as a string, followed by
+        // its value.
+        let mut events = Vec::new();
+        let mut len = 0u;
+        while self.token != Some(ObjectEnd) {
+            let key = try!(self.member_key());
+            events.push((None, StringValue(key)));
+            try!(self.consume_value(Some(&mut events)));
+            len += 1;
+        }
+        if !events.is_empty() {
+            self.unread(events);
+        }
+        let value = try!(f(self, len));
+
+        while self.token != Some(ObjectEnd) {
+            try!(self.consume_value(None));
+        }
+        self.bump();
+        Ok(value)
+    }
+
+    fn read_map_elt_key<R, F>(&mut self, _idx: uint, f: F) -> DecodeResult<R> where
+       F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        f(self)
+    }
+
+    fn read_map_elt_val<R, F>(&mut self, _idx: uint, f: F) -> DecodeResult<R> where
+       F: FnOnce(&mut StreamDecoder<T>) -> DecodeResult<R>,
+    {
+        f(self)
+    }
+
+    fn error(&mut self, err: &str) -> DecoderError {
+        ApplicationError(err.to_string())
+    }
+}
+
 /// A trait for converting values to JSON
 pub trait ToJson for Sized? {
     /// Converts the value of `self` to an instance of JSON
@@ -2498,10 +3531,11 @@ mod tests {
     use super::ErrorCode::*;
     use super::ParserError::*;
     use super::DecoderError::*;
+    use super::PathError::*;
     use super::JsonEvent::*;
     use super::StackElement::*;
     use super::{Json, from_str, DecodeResult, DecoderError, JsonEvent, Parser,
-                StackElement, Stack, Decoder};
+                StackElement, Stack, Decoder, StreamDecoder, decode_stream};
     use std::{i64, u64, f32, f64};
     use std::collections::BTreeMap;
     use std::num::Float;
@@ -3172,6 +4206,158 @@ mod tests {
                                 UnknownVariantError("C".to_string()));
     }
 
+    #[test]
+    fn test_decode_stream_struct() {
+        let s = "{
+            \"inner\": [
+                { \"a\": null, \"b\": 2, \"c\": [\"abc\", \"xyz\"] }
+            ]
+        }";
+
+        let v: Outer = decode_stream(s).unwrap();
+        assert_eq!(
+            v,
+            Outer {
+                inner: vec![
+                    Inner { a: (), b: 2, c: vec!["abc".to_string(), "xyz".to_string()] }
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_stream_fields_out_of_order() {
+        let s = "{ \"x\": [1, {}], \"c\": [\"abc\"], \"b\": 2, \"a\": null }";
+        let v: Inner = decode_stream(s).unwrap();
+        assert_eq!(v, Inner { a: (), b: 2, c: vec!["abc".to_string()] });
+
+        let s = "{ \"c\": [], \"a\": null, \"b\": 3 }";
+        let v: Inner = decode_stream(s).unwrap();
+        assert_eq!(v, Inner { a: (), b: 3, c: vec![] });
+    }
+
+    #[test]
+    fn test_decode_stream_option() {
+        let obj: OptionData = decode_stream("{}").unwrap();
+        assert_eq!(obj, OptionData { opt: None });
+        let obj: OptionData = decode_stream("{ \"opt\": 10 }").unwrap();
+        assert_eq!(obj, OptionData { opt: Some(10u) });
+
+        let value: Option<string::String> = decode_stream("null").unwrap();
+        assert_eq!(value, None);
+        let value: Option<string::String> = decode_stream("\"jodhpurs\"").unwrap();
+        assert_eq!(value, Some("jodhpurs".to_string()));
+    }
+
+    #[test]
+    fn test_decode_stream_enum_and_map() {
+        let value: Animal = decode_stream("\"Dog\"").unwrap();
+        assert_eq!(value, Dog);
+
+        let s = "{\"fields\":[\"Henry\",349],\"variant\":\"Frog\"}";
+        let value: Animal = decode_stream(s).unwrap();
+        assert_eq!(value, Frog("Henry".to_string(), 349));
+
+        let s = "{\"a\": \"Dog\", \"b\": {\"variant\":\"Frog\",\
+                  \"fields\":[\"Henry\", 349]}}";
+        let mut map: BTreeMap<string::String, Animal> = decode_stream(s).unwrap();
+        assert_eq!(map.remove(&"a".to_string()), Some(Dog));
+        assert_eq!(map.remove(&"b".to_string()), Some(Frog("Henry".to_string(), 349)));
+
+        let (a, b, c): (uint, uint, Vec<f64>) = decode_stream("[1, 2, [3.5, null]]").unwrap();
+        assert_eq!(a, 1u);
+        assert_eq!(b, 2u);
+        assert_eq!(c[0], 3.5);
+        assert!(c[1].is_nan());
+    }
+
+    fn check_stream_err<T: Decodable<StreamDecoder<::std::str::Chars<'static>>, DecoderError>>(
+            to_parse: &'static str, expected: DecoderError) {
+        let res: DecodeResult<T> = decode_stream(to_parse);
+        match res {
+            Ok(_) => panic!("`{}` parsed & decoded ok, expecting error `{}`",
+                              to_parse, expected),
+            Err(e) => assert_eq!(e, expected),
+        }
+    }
+    #[test]
+    fn test_decode_stream_errors() {
+        check_stream_err::<DecodeStruct>("[]",
+                                         ExpectedError("Object".to_string(), "[]".to_string()));
+        check_stream_err::<DecodeStruct>("{\"x\": true, \"y\": true, \"z\": \"\", \"w\": []}",
+                                         ExpectedError("Number".to_string(), "true".to_string()));
+        check_stream_err::<DecodeStruct>("{\"x\": 1, \"y\": true, \"z\": {}, \"w\": []}",
+                                         ExpectedError("String".to_string(), "{}".to_string()));
+        check_stream_err::<DecodeStruct>("{\"x\": 1, \"y\": true, \"z\": \"\"}",
+                                         MissingFieldError("w".to_string()));
+        check_stream_err::<DecodeEnum>("{}",
+                                       MissingFieldError("variant".to_string()));
+        check_stream_err::<DecodeEnum>("{\"variant\": \"A\", \"fields\": null}",
+                                       ExpectedError("Array".to_string(), "null".to_string()));
+        check_stream_err::<DecodeEnum>("{\"variant\": \"C\", \"fields\": []}",
+                                       UnknownVariantError("C".to_string()));
+        check_stream_err::<(uint, uint)>("[1, 2, 3]",
+                                         ExpectedError("Tuple2".to_string(),
+                                                       "Tuple3".to_string()));
+        check_stream_err::<uint>("1 2",
+                                 ParseError(SyntaxError(TrailingCharacters, 1, 3)));
+        check_stream_err::<Vec<uint>>("[1, 2",
+                                      ParseError(SyntaxError(EOFWhileParsingArray, 1, 6)));
+    }
+
+    #[test]
+    fn test_decode_stream_buffer_limit() {
+        fn decode_limited<T>(s: &str, limit: uint) -> DecodeResult<T>
+            where T: for<'a> Decodable<StreamDecoder<::std::str::Chars<'a>>, DecoderError>
+        {
+            let mut decoder = StreamDecoder::new(s.chars());
+            decoder.set_buffer_limit(limit);
+            Decodable::decode(&mut decoder)
+        }
+
+        // The elements of the outer array are ten events.
+        let s = "[[1, 2], [3, 4], []]";
+        let v: Vec<Vec<uint>> = decode_limited(s, 10).unwrap();
+        assert_eq!(v, vec![vec![1, 2], vec![3, 4], vec![]]);
+        let res: DecodeResult<Vec<Vec<uint>>> = decode_limited(s, 9);
+        assert_eq!(res, Err(ApplicationError("more than 9 events would be read ahead".to_string())));
+
+        // Fields read in order are never buffered, but `c` is three events.
+        let s = "{ \"a\": null, \"b\": 2, \"c\": [\"x\"] }";
+        let v: Inner = decode_limited(s, 2).unwrap();
+        assert_eq!(v, Inner { a: (), b: 2, c: vec!["x".to_string()] });
+        let s = "{ \"c\": [\"x\"], \"a\": null, \"b\": 2 }";
+        let res: DecodeResult<Inner> = decode_limited(s, 2);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_decode_stream_elements() {
+        // No element is read ahead of the one being decoded.
+        let s = "[{ \"a\": null, \"b\": 1, \"c\": [] }, { \"a\": null, \"b\": 2, \"c\": [] }] ";
+        let mut decoder = StreamDecoder::new(s.chars());
+        decoder.set_buffer_limit(0);
+        let mut v = Vec::new();
+        decoder.decode_elements(|e: Inner| { v.push(e.b); Ok(()) }).unwrap();
+        decoder.end().unwrap();
+        assert_eq!(v, vec![1, 2]);
+
+        // Decoding stops at the first error from the callback.
+        let mut decoder = StreamDecoder::new("[1, 2, 3]".chars());
+        let mut v = Vec::new();
+        let res = decoder.decode_elements(|e: uint| {
+            if e == 2 { return Err(ApplicationError("two".to_string())) }
+            v.push(e);
+            Ok(())
+        });
+        assert_eq!(res, Err(ApplicationError("two".to_string())));
+        assert_eq!(v, vec![1]);
+
+        let mut decoder = StreamDecoder::new("[1, 2".chars());
+        let res = decoder.decode_elements(|_: uint| Ok(()));
+        assert_eq!(res, Err(ParseError(SyntaxError(EOFWhileParsingArray, 1, 6))));
+    }
+
     #[test]
     fn test_find(){
         let json_value = from_str("{\"dog\" : \"cat\"}").unwrap();
@@ -3193,6 +4379,115 @@ mod tests {
         assert!(found_str.unwrap() == "cheese");
     }
 
+    #[test]
+    fn test_find_path_mut(){
+        let mut json_value = from_str("{\"dog\":{\"cat\": {\"mouse\" : \"cheese\"}}}").unwrap();
+        *json_value.find_path_mut(&["dog", "cat", "mouse"]).unwrap() = U64(3);
+        assert_eq!(json_value.find_path(&["dog", "cat", "mouse"]), Some(&U64(3)));
+        assert!(json_value.find_path_mut(&["dog", "mouse"]).is_none());
+    }
+
+    #[test]
+    fn test_pointer(){
+        let json_value = from_str(r#"{
+            "foo": ["bar", "baz"],
+            "": 0,
+            "a/b": 1,
+            "m~n": 8,
+            "nested": {"list": [{"x": true}]}
+        }"#).unwrap();
+        assert_eq!(json_value.pointer(""), Ok(&json_value));
+        assert_eq!(json_value.pointer("/foo/0"), Ok(&String("bar".to_string())));
+        assert_eq!(json_value.pointer("/"), Ok(&U64(0)));
+        assert_eq!(json_value.pointer("/a~1b"), Ok(&U64(1)));
+        assert_eq!(json_value.pointer("/m~0n"), Ok(&U64(8)));
+        assert_eq!(json_value.pointer("/nested/list/0/x"), Ok(&Boolean(true)));
+
+        assert_eq!(json_value.pointer("/foo/2"), Err(MissingTarget));
+        assert_eq!(json_value.pointer("/foo/01"), Err(MissingTarget));
+        assert_eq!(json_value.pointer("/foo/-"), Err(MissingTarget));
+        assert_eq!(json_value.pointer("/missing"), Err(MissingTarget));
+        assert_eq!(json_value.pointer("foo"), Err(InvalidPath(0)));
+        assert_eq!(json_value.pointer("/m~2n"), Err(InvalidPath(2)));
+    }
+
+    #[test]
+    fn test_set_and_remove_pointer(){
+        let mut json_value = from_str(r#"{"foo": ["bar"], "baz": {}}"#).unwrap();
+        assert_eq!(json_value.set_pointer("/foo/-", U64(1)), Ok(None));
+        assert_eq!(json_value.set_pointer("/foo/2", U64(2)), Ok(None));
+        assert_eq!(json_value.set_pointer("/foo/0", U64(0)),
+                   Ok(Some(String("bar".to_string()))));
+        assert_eq!(json_value.set_pointer("/foo/4", U64(4)), Err(MissingTarget));
+        assert_eq!(json_value.set_pointer("/baz/qux", Null), Ok(None));
+        assert_eq!(json_value.set_pointer("/quux/qux", Null), Err(MissingTarget));
+        assert_eq!(json_value, from_str(r#"{"foo": [0, 1, 2], "baz": {"qux": null}}"#).unwrap());
+
+        *json_value.pointer_mut("/foo/1").unwrap() = Boolean(false);
+        assert_eq!(json_value.remove_pointer("/foo/1"), Ok(Boolean(false)));
+        assert_eq!(json_value.remove_pointer("/baz/qux"), Ok(Null));
+        assert_eq!(json_value.remove_pointer("/baz/qux"), Err(MissingTarget));
+        assert_eq!(json_value, from_str(r#"{"foo": [0, 2], "baz": {}}"#).unwrap());
+
+        assert!(json_value.set_pointer("", Boolean(true)).unwrap().unwrap().is_object());
+        assert_eq!(json_value, Boolean(true));
+    }
+
+    #[test]
+    fn test_merge_patch(){
+        // The examples from RFC 7396, appendix A.
+        let cases = [
+            (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+            (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+            (r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#),
+            (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+            (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+            (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+            (r#"{"a":{"b":"c"}}"#, r#"{"a":{"b":"d","c":null}}"#, r#"{"a":{"b":"d"}}"#),
+            (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+            (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+            (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+            (r#"{"a":"foo"}"#, r#"null"#, r#"null"#),
+            (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+            (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+            (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+            (r#"{}"#, r#"{"a":{"bb":{"ccc":null}}}"#, r#"{"a":{"bb":{}}}"#),
+        ];
+        for &(target, patch, result) in cases.iter() {
+            let mut target = from_str(target).unwrap();
+            target.merge_patch(&from_str(patch).unwrap());
+            assert_eq!(target, from_str(result).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_query(){
+        let json_value = from_str(r#"{
+            "store": {
+                "books": [
+                    {"author": "Nigel Rees", "price": 8},
+                    {"author": "Evelyn Waugh", "price": 12}
+                ],
+                "bicycle": {"color": "red", "price": 19}
+            }
+        }"#).unwrap();
+
+        let authors = json_value.query("$.store.books[*].author").unwrap();
+        assert_eq!(authors, vec![&String("Nigel Rees".to_string()),
+                                 &String("Evelyn Waugh".to_string())]);
+        assert_eq!(json_value.query("$['store'].books[1].price").unwrap(), vec![&U64(12)]);
+        assert_eq!(json_value.query("$..price").unwrap(), vec![&U64(19), &U64(8), &U64(12)]);
+        assert_eq!(json_value.query("$.store.*").unwrap().len(), 2);
+        assert_eq!(json_value.query("$..*").unwrap().len(), 11);
+        assert_eq!(json_value.query("$").unwrap(), vec![&json_value]);
+        assert_eq!(json_value.query("$.missing[0]").unwrap().len(), 0);
+
+        assert_eq!(json_value.query("store"), Err(InvalidPath(0)));
+        assert_eq!(json_value.query("$.store[x]"), Err(InvalidPath(8)));
+        assert_eq!(json_value.query("$.store['books'"), Err(InvalidPath(15)));
+        assert_eq!(json_value.query("$."), Err(InvalidPath(2)));
+    }
+
     #[test]
     fn test_index(){
         let json_value = from_str("{\"animals\":[\"dog\",\"cat\",\"mouse\"]}").unwrap();
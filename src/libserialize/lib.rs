@@ -30,8 +30,6 @@ Core encoding and decoding interfaces.
 #[cfg(test)]
 extern crate test;
 
-#[phase(plugin, link)]
-extern crate log;
 extern crate unicode;
 
 extern crate collections;
@@ -45,6 +43,8 @@ mod collection_impls;
 pub mod base64;
 pub mod hex;
 pub mod json;
+pub mod msgpack;
+pub mod toml;
 
 mod rustc_serialize {
     pub use serialize::*;
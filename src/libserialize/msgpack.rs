@@ -0,0 +1,1082 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! MessagePack serialization
+//!
+//! [MessagePack](http://msgpack.org) is a compact binary format with the same
+//! data model as JSON. This module maps the `Encoder`/`Decoder` interface onto
+//! it as follows:
+//!
+//! * integers use the smallest encoding that holds their value, so the width
+//!   of the Rust type doesn't affect the size of the output,
+//! * `f32` and `f64` are written as MessagePack float 32 and float 64,
+//! * `char` and `String` are written as strings,
+//! * `()` and `None` are written as nil, `Some(v)` as `v` itself, so a
+//!   `Some` holding a nil, such as `Some(())`, can't be encoded,
+//! * structs, tuples and sequences are written as arrays, so struct fields
+//!   are identified by position rather than by name,
+//! * maps are written as maps,
+//! * enum variants without arguments are written as their name, and other
+//!   variants as an array holding the name followed by the arguments.
+//!
+//! # Example
+//!
+//! ```notrust
+//! // FIXME(#19470): this cannot be ```rust``` because it fails orphan checking at the moment
+//! extern crate serialize;
+//! use serialize::msgpack;
+//!
+//! #[deriving(RustcDecodable, RustcEncodable)]
+//! pub struct TestStruct  {
+//!     data_int: u8,
+//!     data_str: String,
+//!     data_vector: Vec<u8>,
+//! }
+//!
+//! fn main() {
+//!     let object = TestStruct {
+//!         data_int: 1,
+//!         data_str: "homura".to_string(),
+//!         data_vector: vec![2,3,4,5],
+//!     };
+//!
+//!     let encoded: Vec<u8> = msgpack::encode(&object);
+//!     let decoded: TestStruct = msgpack::decode(encoded.as_slice()).unwrap();
+//! }
+//! ```
+
+use self::DecoderError::*;
+use self::Integer::*;
+
+use std::{fmt, i8, i16, i32, io, num, str};
+use std::io::extensions::u64_from_be_bytes;
+use std::num::Int;
+use std::mem::transmute;
+use std::string;
+
+use Encodable;
+
+/// The errors that can arise while decoding MessagePack.
+#[deriving(Clone, PartialEq, Show)]
+pub enum DecoderError {
+    /// The input ended in the middle of a value.
+    EndOfInput,
+    /// A value of one kind was expected, but another one was found.
+    ExpectedError(string::String, string::String),
+    /// A string was not valid UTF-8.
+    NotUtf8,
+    UnknownVariantError(string::String),
+    ApplicationError(string::String)
+}
+
+impl ::std::error::Error for DecoderError {
+    fn description(&self) -> &str { "decoder error" }
+    fn detail(&self) -> Option<string::String> { Some(self.to_string()) }
+}
+
+pub type EncodeResult = io::IoResult<()>;
+pub type DecodeResult<T> = Result<T, DecoderError>;
+
+/// Shortcut function to encode a `T` into a MessagePack byte vector
+///
+/// Panics if `object` can't be encoded: a `Some` holding a nil, or a string,
+/// sequence or map longer than `u32::MAX`.
+pub fn encode<T>(object: &T) -> Vec<u8>
+                 where T: for<'a> Encodable<Encoder<'a>, io::IoError>
+{
+    let mut buf = Vec::new();
+    {
+        let mut encoder = Encoder::new(&mut buf);
+        // Writing to a vector can't fail, so this is an unencodable value.
+        match object.encode(&mut encoder) {
+            Ok(()) => {}
+            Err(e) => panic!("can't encode as MessagePack: {}", e),
+        }
+    }
+    buf
+}
+
+/// Shortcut function to decode a MessagePack byte slice into an object
+pub fn decode<'a, T: ::Decodable<Decoder<'a>, DecoderError>>(data: &'a [u8]) -> DecodeResult<T> {
+    let mut decoder = Decoder::new(data);
+    let value = try!(::Decodable::decode(&mut decoder));
+    try!(decoder.end());
+    Ok(value)
+}
+
+fn too_large(len: uint) -> io::IoError {
+    io::IoError {
+        kind: io::OtherIoError,
+        desc: "length too large for MessagePack",
+        detail: Some(format!("{}", len))
+    }
+}
+
+/// A structure for implementing serialization to MessagePack.
+pub struct Encoder<'a> {
+    writer: &'a mut (io::Writer+'a),
+    // Whether the value being encoded is directly inside a `Some`, which
+    // it can't be if it is a nil.
+    in_some: bool,
+}
+
+impl<'a> Encoder<'a> {
+    /// Creates a new MessagePack encoder whose output will be written to the
+    /// writer specified.
+    pub fn new(writer: &'a mut io::Writer) -> Encoder<'a> {
+        Encoder { writer: writer, in_some: false }
+    }
+
+    fn write_unsigned(&mut self, v: u64) -> EncodeResult {
+        self.in_some = false;
+        if v < 0x80 {
+            self.writer.write_u8(v as u8)
+        } else if v <= 0xff {
+            try!(self.writer.write_u8(0xcc));
+            self.writer.write_u8(v as u8)
+        } else if v <= 0xffff {
+            try!(self.writer.write_u8(0xcd));
+            self.writer.write_be_u16(v as u16)
+        } else if v <= 0xffff_ffff {
+            try!(self.writer.write_u8(0xce));
+            self.writer.write_be_u32(v as u32)
+        } else {
+            try!(self.writer.write_u8(0xcf));
+            self.writer.write_be_u64(v)
+        }
+    }
+
+    fn write_signed(&mut self, v: i64) -> EncodeResult {
+        self.in_some = false;
+        if v >= 0 {
+            self.write_unsigned(v as u64)
+        } else if v >= -32 {
+            // negative fixint
+            self.writer.write_i8(v as i8)
+        } else if v >= i8::MIN as i64 {
+            try!(self.writer.write_u8(0xd0));
+            self.writer.write_i8(v as i8)
+        } else if v >= i16::MIN as i64 {
+            try!(self.writer.write_u8(0xd1));
+            self.writer.write_be_i16(v as i16)
+        } else if v >= i32::MIN as i64 {
+            try!(self.writer.write_u8(0xd2));
+            self.writer.write_be_i32(v as i32)
+        } else {
+            try!(self.writer.write_u8(0xd3));
+            self.writer.write_be_i64(v)
+        }
+    }
+
+    // Writes the header of an array or a map: `fix` is the marker of the
+    // format holding up to 15 elements, followed by the 16 and 32 bit ones.
+    fn write_len(&mut self, len: uint, fix: u8, marker16: u8, marker32: u8) -> EncodeResult {
+        self.in_some = false;
+        if len < 16 {
+            self.writer.write_u8(fix | len as u8)
+        } else if len <= 0xffff {
+            try!(self.writer.write_u8(marker16));
+            self.writer.write_be_u16(len as u16)
+        } else if len as u64 <= 0xffff_ffff {
+            try!(self.writer.write_u8(marker32));
+            self.writer.write_be_u32(len as u32)
+        } else {
+            Err(too_large(len))
+        }
+    }
+}
+
+impl<'a> ::Encoder<io::IoError> for Encoder<'a> {
+    fn emit_nil(&mut self) -> EncodeResult {
+        if self.in_some {
+            // It would decode as `None`.
+            return Err(io::IoError {
+                kind: io::InvalidInput,
+                desc: "a nil inside `Some` can't be told apart from `None`",
+                detail: None,
+            });
+        }
+        self.writer.write_u8(0xc0)
+    }
+
+    fn emit_uint(&mut self, v: uint) -> EncodeResult { self.write_unsigned(v as u64) }
+    fn emit_u64(&mut self, v: u64) -> EncodeResult { self.write_unsigned(v) }
+    fn emit_u32(&mut self, v: u32) -> EncodeResult { self.write_unsigned(v as u64) }
+    fn emit_u16(&mut self, v: u16) -> EncodeResult { self.write_unsigned(v as u64) }
+    fn emit_u8(&mut self, v: u8) -> EncodeResult { self.write_unsigned(v as u64) }
+
+    fn emit_int(&mut self, v: int) -> EncodeResult { self.write_signed(v as i64) }
+    fn emit_i64(&mut self, v: i64) -> EncodeResult { self.write_signed(v) }
+    fn emit_i32(&mut self, v: i32) -> EncodeResult { self.write_signed(v as i64) }
+    fn emit_i16(&mut self, v: i16) -> EncodeResult { self.write_signed(v as i64) }
+    fn emit_i8(&mut self, v: i8) -> EncodeResult { self.write_signed(v as i64) }
+
+    fn emit_bool(&mut self, v: bool) -> EncodeResult {
+        self.in_some = false;
+        self.writer.write_u8(if v { 0xc3 } else { 0xc2 })
+    }
+
+    fn emit_f64(&mut self, v: f64) -> EncodeResult {
+        self.in_some = false;
+        try!(self.writer.write_u8(0xcb));
+        self.writer.write_be_f64(v)
+    }
+    fn emit_f32(&mut self, v: f32) -> EncodeResult {
+        self.in_some = false;
+        try!(self.writer.write_u8(0xca));
+        self.writer.write_be_f32(v)
+    }
+
+    fn emit_char(&mut self, v: char) -> EncodeResult {
+        let mut buf = [0, .. 4];
+        let n = v.encode_utf8(&mut buf).unwrap_or(0);
+        self.emit_str(unsafe { str::from_utf8_unchecked(buf[..n]) })
+    }
+    fn emit_str(&mut self, v: &str) -> EncodeResult {
+        self.in_some = false;
+        let len = v.len();
+        if len < 32 {
+            try!(self.writer.write_u8(0xa0 | len as u8));
+        } else if len <= 0xff {
+            try!(self.writer.write_u8(0xd9));
+            try!(self.writer.write_u8(len as u8));
+        } else if len <= 0xffff {
+            try!(self.writer.write_u8(0xda));
+            try!(self.writer.write_be_u16(len as u16));
+        } else if len as u64 <= 0xffff_ffff {
+            try!(self.writer.write_u8(0xdb));
+            try!(self.writer.write_be_u32(len as u32));
+        } else {
+            return Err(too_large(len));
+        }
+        self.writer.write(v.as_bytes())
+    }
+
+    fn emit_enum<F>(&mut self, _name: &str, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        f(self)
+    }
+
+    fn emit_enum_variant<F>(&mut self,
+                            name: &str,
+                            _id: uint,
+                            cnt: uint,
+                            f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        // enums are encoded as strings or arrays:
+        // Bunny => "Bunny"
+        // Kangaroo(34,"William") => ["Kangaroo",34,"William"]
+        if cnt == 0 {
+            self.emit_str(name)
+        } else {
+            try!(self.write_len(cnt + 1, 0x90, 0xdc, 0xdd));
+            try!(self.emit_str(name));
+            f(self)
+        }
+    }
+
+    fn emit_enum_variant_arg<F>(&mut self, _idx: uint, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        f(self)
+    }
+
+    fn emit_enum_struct_variant<F>(&mut self,
+                                   name: &str,
+                                   id: uint,
+                                   cnt: uint,
+                                   f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        self.emit_enum_variant(name, id, cnt, f)
+    }
+
+    fn emit_enum_struct_variant_field<F>(&mut self,
+                                         _: &str,
+                                         idx: uint,
+                                         f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        self.emit_enum_variant_arg(idx, f)
+    }
+
+    fn emit_struct<F>(&mut self, _: &str, len: uint, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        try!(self.write_len(len, 0x90, 0xdc, 0xdd));
+        f(self)
+    }
+
+    fn emit_struct_field<F>(&mut self, _: &str, _idx: uint, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        f(self)
+    }
+
+    fn emit_tuple<F>(&mut self, len: uint, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        self.emit_seq(len, f)
+    }
+    fn emit_tuple_arg<F>(&mut self, idx: uint, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        self.emit_seq_elt(idx, f)
+    }
+
+    fn emit_tuple_struct<F>(&mut self, _name: &str, len: uint, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        self.emit_seq(len, f)
+    }
+    fn emit_tuple_struct_arg<F>(&mut self, idx: uint, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        self.emit_seq_elt(idx, f)
+    }
+
+    fn emit_option<F>(&mut self, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        f(self)
+    }
+    fn emit_option_none(&mut self) -> EncodeResult { self.emit_nil() }
+    fn emit_option_some<F>(&mut self, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        self.in_some = true;
+        let result = f(self);
+        self.in_some = false;
+        result
+    }
+
+    fn emit_seq<F>(&mut self, len: uint, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        try!(self.write_len(len, 0x90, 0xdc, 0xdd));
+        f(self)
+    }
+
+    fn emit_seq_elt<F>(&mut self, _idx: uint, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        f(self)
+    }
+
+    fn emit_map<F>(&mut self, len: uint, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        try!(self.write_len(len, 0x80, 0xde, 0xdf));
+        f(self)
+    }
+
+    fn emit_map_elt_key<F>(&mut self, _idx: uint, mut f: F) -> EncodeResult where
+        F: FnMut(&mut Encoder<'a>) -> EncodeResult,
+    {
+        f(self)
+    }
+
+    fn emit_map_elt_val<F>(&mut self, _idx: uint, f: F) -> EncodeResult where
+        F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
+    {
+        f(self)
+    }
+}
+
+// An integer as it was found in the input, before being cast to the type
+// that was asked for.
+enum Integer {
+    Unsigned(u64),
+    Signed(i64),
+}
+
+impl fmt::Show for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Unsigned(n) => n.fmt(f),
+            Signed(n) => n.fmt(f),
+        }
+    }
+}
+
+// A name for the kind of value starting with the given marker, for use in
+// error messages.
+fn marker_name(marker: u8) -> &'static str {
+    match marker {
+        0x00 ... 0x7f | 0xcc ... 0xd3 | 0xe0 ... 0xff => "Integer",
+        0x80 ... 0x8f | 0xde | 0xdf => "Map",
+        0x90 ... 0x9f | 0xdc | 0xdd => "Array",
+        0xa0 ... 0xbf | 0xd9 ... 0xdb => "String",
+        0xc0 => "Nil",
+        0xc2 | 0xc3 => "Boolean",
+        0xc4 ... 0xc6 => "Binary",
+        0xca | 0xcb => "Float",
+        0xc7 ... 0xc9 | 0xd4 ... 0xd8 => "Extension",
+        _ => "reserved marker",
+    }
+}
+
+/// A structure to decode MessagePack to values in rust.
+pub struct Decoder<'a> {
+    data: &'a [u8],
+    pos: uint,
+    // The length of the array holding the enum variant being decoded,
+    // including its name, or 0 if it is a bare string.
+    variant_len: uint,
+    // The number of arguments of that variant read so far.
+    variant_args: uint,
+}
+
+impl<'a> Decoder<'a> {
+    /// Creates a new decoder instance for decoding the specified bytes.
+    pub fn new(data: &'a [u8]) -> Decoder<'a> {
+        Decoder { data: data, pos: 0, variant_len: 0, variant_args: 0 }
+    }
+
+    /// Checks that the whole input has been decoded.
+    pub fn end(&self) -> DecodeResult<()> {
+        if self.pos == self.data.len() {
+            Ok(())
+        } else {
+            Err(ExpectedError("end of input".to_string(),
+                              format!("{} trailing bytes", self.data.len() - self.pos)))
+        }
+    }
+
+    fn peek_byte(&self) -> DecodeResult<u8> {
+        if self.pos < self.data.len() {
+            Ok(self.data[self.pos])
+        } else {
+            Err(EndOfInput)
+        }
+    }
+
+    fn read_byte(&mut self) -> DecodeResult<u8> {
+        let byte = try!(self.peek_byte());
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn read_bytes(&mut self, len: uint) -> DecodeResult<&'a [u8]> {
+        if self.data.len() - self.pos < len {
+            return Err(EndOfInput);
+        }
+        let bytes = self.data[self.pos .. self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    // Reads a big-endian unsigned integer of `size` bytes.
+    fn read_be(&mut self, size: uint) -> DecodeResult<u64> {
+        let bytes = try!(self.read_bytes(size));
+        Ok(u64_from_be_bytes(bytes, 0, size))
+    }
+
+    fn read_integer(&mut self) -> DecodeResult<Integer> {
+        let marker = try!(self.read_byte());
+        Ok(match marker {
+            0x00 ... 0x7f => Unsigned(marker as u64),
+            0xe0 ... 0xff => Signed(marker as i8 as i64),
+            0xcc => Unsigned(try!(self.read_be(1))),
+            0xcd => Unsigned(try!(self.read_be(2))),
+            0xce => Unsigned(try!(self.read_be(4))),
+            0xcf => Unsigned(try!(self.read_be(8))),
+            0xd0 => Signed(try!(self.read_be(1)) as u8 as i8 as i64),
+            0xd1 => Signed(try!(self.read_be(2)) as u16 as i16 as i64),
+            0xd2 => Signed(try!(self.read_be(4)) as u32 as i32 as i64),
+            0xd3 => Signed(try!(self.read_be(8)) as i64),
+            _ => return self.unexpected("Integer", marker),
+        })
+    }
+
+    fn read_array_len(&mut self) -> DecodeResult<uint> {
+        let marker = try!(self.read_byte());
+        let len = match marker {
+            0x90 ... 0x9f => (marker & 0x0f) as uint,
+            0xdc => try!(self.read_be(2)) as uint,
+            0xdd => try!(self.read_be(4)) as uint,
+            _ => return self.unexpected("Array", marker),
+        };
+        // Each element takes at least a byte. Checking this stops a corrupt
+        // length from making the caller reserve room for billions of them.
+        try!(self.check_len(len, 1));
+        Ok(len)
+    }
+
+    fn read_map_len(&mut self) -> DecodeResult<uint> {
+        let marker = try!(self.read_byte());
+        let len = match marker {
+            0x80 ... 0x8f => (marker & 0x0f) as uint,
+            0xde => try!(self.read_be(2)) as uint,
+            0xdf => try!(self.read_be(4)) as uint,
+            _ => return self.unexpected("Map", marker),
+        };
+        // Each key and each value takes at least a byte.
+        try!(self.check_len(len, 2));
+        Ok(len)
+    }
+
+    // Fails unless what is left of the input could hold `len` elements of
+    // at least `size` bytes each.
+    fn check_len(&self, len: uint, size: uint) -> DecodeResult<()> {
+        match len.checked_mul(size) {
+            Some(n) if n <= self.data.len() - self.pos => Ok(()),
+            _ => Err(EndOfInput),
+        }
+    }
+
+    fn read_str_slice(&mut self) -> DecodeResult<&'a str> {
+        let marker = try!(self.read_byte());
+        let len = match marker {
+            0xa0 ... 0xbf => (marker & 0x1f) as uint,
+            0xd9 => try!(self.read_be(1)) as uint,
+            0xda => try!(self.read_be(2)) as uint,
+            0xdb => try!(self.read_be(4)) as uint,
+            _ => return self.unexpected("String", marker),
+        };
+        match str::from_utf8(try!(self.read_bytes(len))) {
+            Ok(s) => Ok(s),
+            Err(_) => Err(NotUtf8),
+        }
+    }
+
+    fn unexpected<T>(&self, expected: &str, marker: u8) -> DecodeResult<T> {
+        Err(ExpectedError(expected.to_string(), marker_name(marker).to_string()))
+    }
+}
+
+macro_rules! read_primitive {
+    ($name:ident, $ty:ty) => {
+        fn $name(&mut self) -> DecodeResult<$ty> {
+            let n = try!(self.read_integer());
+            let value = match n {
+                Unsigned(n) => num::cast(n),
+                Signed(n) => num::cast(n),
+            };
+            match value {
+                Some(v) => Ok(v),
+                None => Err(ExpectedError(stringify!($ty).to_string(), n.to_string())),
+            }
+        }
+    }
+}
+
+impl<'a> ::Decoder<DecoderError> for Decoder<'a> {
+    fn read_nil(&mut self) -> DecodeResult<()> {
+        match try!(self.read_byte()) {
+            0xc0 => Ok(()),
+            marker => self.unexpected("Nil", marker),
+        }
+    }
+
+    read_primitive! { read_uint, uint }
+    read_primitive! { read_u8, u8 }
+    read_primitive! { read_u16, u16 }
+    read_primitive! { read_u32, u32 }
+    read_primitive! { read_u64, u64 }
+    read_primitive! { read_int, int }
+    read_primitive! { read_i8, i8 }
+    read_primitive! { read_i16, i16 }
+    read_primitive! { read_i32, i32 }
+    read_primitive! { read_i64, i64 }
+
+    fn read_f32(&mut self) -> DecodeResult<f32> { self.read_f64().map(|x| x as f32) }
+
+    fn read_f64(&mut self) -> DecodeResult<f64> {
+        match try!(self.peek_byte()) {
+            0xca => {
+                self.pos += 1;
+                let bits = try!(self.read_be(4)) as u32;
+                Ok(unsafe { transmute::<u32, f32>(bits) } as f64)
+            }
+            0xcb => {
+                self.pos += 1;
+                let bits = try!(self.read_be(8));
+                Ok(unsafe { transmute::<u64, f64>(bits) })
+            }
+            _ => match try!(self.read_integer()) {
+                Unsigned(n) => Ok(n as f64),
+                Signed(n) => Ok(n as f64),
+            }
+        }
+    }
+
+    fn read_bool(&mut self) -> DecodeResult<bool> {
+        match try!(self.read_byte()) {
+            0xc2 => Ok(false),
+            0xc3 => Ok(true),
+            marker => self.unexpected("Boolean", marker),
+        }
+    }
+
+    fn read_char(&mut self) -> DecodeResult<char> {
+        let s = try!(self.read_str_slice());
+        {
+            let mut it = s.chars();
+            match (it.next(), it.next()) {
+                // exactly one character
+                (Some(c), None) => return Ok(c),
+                _ => ()
+            }
+        }
+        Err(ExpectedError("single character string".to_string(), s.to_string()))
+    }
+
+    fn read_str(&mut self) -> DecodeResult<string::String> {
+        self.read_str_slice().map(|s| s.to_string())
+    }
+
+    fn read_enum<T, F>(&mut self, _name: &str, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        f(self)
+    }
+
+    fn read_enum_variant<T, F>(&mut self, names: &[&str],
+                               mut f: F) -> DecodeResult<T>
+        where F: FnMut(&mut Decoder<'a>, uint) -> DecodeResult<T>,
+    {
+        let marker = try!(self.peek_byte());
+        let (name, len) = match marker {
+            0xa0 ... 0xbf | 0xd9 ... 0xdb => (try!(self.read_str_slice()), 0),
+            0x90 ... 0x9f | 0xdc | 0xdd => {
+                let len = try!(self.read_array_len());
+                if len == 0 {
+                    return Err(ExpectedError("variant name".to_string(),
+                                             "empty Array".to_string()));
+                }
+                (try!(self.read_str_slice()), len)
+            }
+            _ => return self.unexpected("String or Array", marker),
+        };
+        let idx = match names.iter().position(|n| *n == name) {
+            Some(idx) => idx,
+            None => return Err(UnknownVariantError(name.to_string()))
+        };
+
+        // Arguments may be enums themselves, so keep the outer variant's
+        // count aside while this one is decoded.
+        let outer = (self.variant_len, self.variant_args);
+        self.variant_len = len;
+        self.variant_args = 0;
+        let res = f(self, idx);
+        let args = self.variant_args;
+        let (outer_len, outer_args) = outer;
+        self.variant_len = outer_len;
+        self.variant_args = outer_args;
+
+        let value = try!(res);
+        if len != 0 && args + 1 != len {
+            return Err(ExpectedError(format!("Array of {} elements", args + 1),
+                                     format!("Array of {} elements", len)));
+        }
+        Ok(value)
+    }
+
+    fn read_enum_variant_arg<T, F>(&mut self, _idx: uint, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        // Only nullary variants may be written as a bare string, and an
+        // array must hold all of the arguments, so check before reading
+        // anything that doesn't belong to the variant.
+        if self.variant_len == 0 {
+            return Err(ExpectedError("Array".to_string(), "String".to_string()));
+        }
+        self.variant_args += 1;
+        if self.variant_args >= self.variant_len {
+            return Err(ExpectedError(format!("Array of {} elements", self.variant_args + 1),
+                                     format!("Array of {} elements", self.variant_len)));
+        }
+        f(self)
+    }
+
+    fn read_enum_struct_variant<T, F>(&mut self, names: &[&str], f: F) -> DecodeResult<T> where
+        F: FnMut(&mut Decoder<'a>, uint) -> DecodeResult<T>,
+    {
+        self.read_enum_variant(names, f)
+    }
+
+    fn read_enum_struct_variant_field<T, F>(&mut self,
+                                            _name: &str,
+                                            idx: uint,
+                                            f: F)
+                                            -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        self.read_enum_variant_arg(idx, f)
+    }
+
+    fn read_struct<T, F>(&mut self, _name: &str, len: uint, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        let found = try!(self.read_array_len());
+        if found != len {
+            return Err(ExpectedError(format!("Array of {} fields", len),
+                                     format!("Array of {} elements", found)));
+        }
+        f(self)
+    }
+
+    fn read_struct_field<T, F>(&mut self,
+                               _name: &str,
+                               _idx: uint,
+                               f: F)
+                               -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        f(self)
+    }
+
+    fn read_tuple<T, F>(&mut self, tuple_len: uint, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        self.read_seq(move |d, len| {
+            if len == tuple_len {
+                f(d)
+            } else {
+                Err(ExpectedError(format!("Tuple{}", tuple_len), format!("Tuple{}", len)))
+            }
+        })
+    }
+
+    fn read_tuple_arg<T, F>(&mut self, idx: uint, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        self.read_seq_elt(idx, f)
+    }
+
+    fn read_tuple_struct<T, F>(&mut self,
+                               _name: &str,
+                               len: uint,
+                               f: F)
+                               -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        self.read_tuple(len, f)
+    }
+
+    fn read_tuple_struct_arg<T, F>(&mut self,
+                                   idx: uint,
+                                   f: F)
+                                   -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        self.read_tuple_arg(idx, f)
+    }
+
+    fn read_option<T, F>(&mut self, mut f: F) -> DecodeResult<T> where
+        F: FnMut(&mut Decoder<'a>, bool) -> DecodeResult<T>,
+    {
+        if try!(self.peek_byte()) == 0xc0 {
+            self.pos += 1;
+            f(self, false)
+        } else {
+            f(self, true)
+        }
+    }
+
+    fn read_seq<T, F>(&mut self, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>, uint) -> DecodeResult<T>,
+    {
+        let len = try!(self.read_array_len());
+        f(self, len)
+    }
+
+    fn read_seq_elt<T, F>(&mut self, _idx: uint, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        f(self)
+    }
+
+    fn read_map<T, F>(&mut self, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder<'a>, uint) -> DecodeResult<T>,
+    {
+        let len = try!(self.read_map_len());
+        f(self, len)
+    }
+
+    fn read_map_elt_key<T, F>(&mut self, _idx: uint, f: F) -> DecodeResult<T> where
+       F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        f(self)
+    }
+
+    fn read_map_elt_val<T, F>(&mut self, _idx: uint, f: F) -> DecodeResult<T> where
+       F: FnOnce(&mut Decoder<'a>) -> DecodeResult<T>,
+    {
+        f(self)
+    }
+
+    fn error(&mut self, err: &str) -> DecoderError {
+        ApplicationError(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use self::Animal::*;
+    use {Encodable, Decodable};
+    use super::{encode, decode, Encoder, Decoder, DecoderError};
+    use super::DecoderError::*;
+    use std::collections::{DList, RingBuf, BTreeMap, BTreeSet, HashMap, HashSet, VecMap};
+    use std::fmt::Show;
+    use std::io::IoError;
+    use std::rc::Rc;
+    use std::thread::Thread;
+    use std::{i8, i16, i32, i64, u8, u16, u32, u64, f64};
+    use std::num::Float;
+
+    #[deriving(PartialEq, RustcEncodable, RustcDecodable, Show)]
+    enum Animal {
+        Dog,
+        Frog(String, int),
+        Cat { age: uint, name: String },
+    }
+
+    #[deriving(PartialEq, RustcEncodable, RustcDecodable, Show)]
+    struct Inner {
+        a: (),
+        b: uint,
+        c: Vec<String>,
+    }
+
+    #[deriving(PartialEq, RustcEncodable, RustcDecodable, Show)]
+    struct Outer {
+        inner: Vec<Inner>,
+        pets: BTreeMap<String, Animal>,
+        nickname: Option<String>,
+    }
+
+    fn round_trip<T>(value: T) where
+        T: PartialEq + Show,
+        T: for<'a> Encodable<Encoder<'a>, IoError>,
+        T: for<'a> Decodable<Decoder<'a>, DecoderError>,
+    {
+        let bytes = encode(&value);
+        let decoded: T = decode(bytes.as_slice()).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn test_integer_encoding() {
+        assert_eq!(encode(&0u), vec![0x00]);
+        assert_eq!(encode(&127u8), vec![0x7f]);
+        assert_eq!(encode(&128u64), vec![0xcc, 0x80]);
+        assert_eq!(encode(&256u), vec![0xcd, 0x01, 0x00]);
+        assert_eq!(encode(&65536u32), vec![0xce, 0x00, 0x01, 0x00, 0x00]);
+        assert_eq!(encode(&(1u64 << 32)), vec![0xcf, 0, 0, 0, 1, 0, 0, 0, 0]);
+        assert_eq!(encode(&5i64), vec![0x05]);
+        assert_eq!(encode(&-1i), vec![0xff]);
+        assert_eq!(encode(&-32i8), vec![0xe0]);
+        assert_eq!(encode(&-33i8), vec![0xd0, 0xdf]);
+        assert_eq!(encode(&-129i16), vec![0xd1, 0xff, 0x7f]);
+    }
+
+    #[test]
+    fn test_integers() {
+        round_trip(0u8);
+        round_trip(u8::MAX);
+        round_trip(u16::MAX);
+        round_trip(u32::MAX);
+        round_trip(u64::MAX);
+        round_trip(i8::MIN);
+        round_trip(i16::MIN);
+        round_trip(i32::MIN);
+        round_trip(i64::MIN);
+        round_trip(i64::MAX);
+        round_trip(-7i);
+        round_trip(12345u);
+    }
+
+    #[test]
+    fn test_integer_out_of_range() {
+        let res: Result<u8, DecoderError> = decode(encode(&256u).as_slice());
+        assert_eq!(res, Err(ExpectedError("u8".to_string(), "256".to_string())));
+        let res: Result<uint, DecoderError> = decode(encode(&-1i).as_slice());
+        assert_eq!(res, Err(ExpectedError("uint".to_string(), "-1".to_string())));
+    }
+
+    #[test]
+    fn test_scalars() {
+        round_trip(());
+        round_trip(true);
+        round_trip(false);
+        round_trip(1.5f64);
+        round_trip(-0.25f32);
+        round_trip(f64::INFINITY);
+        round_trip('x');
+        round_trip('\u{1F600}');
+        round_trip("".to_string());
+        round_trip("a string that is long enough not to fit in a fixstr".to_string());
+        round_trip(String::from_char(300, 'z'));
+        round_trip(String::from_char(70000, 'z'));
+
+        let nan: f64 = decode(encode(&f64::NAN).as_slice()).unwrap();
+        assert!(nan.is_nan());
+        assert_eq!(encode(&true), vec![0xc3]);
+        assert_eq!(encode(&()), vec![0xc0]);
+        assert_eq!(encode(&"abc"), vec![0xa3, b'a', b'b', b'c']);
+    }
+
+    #[test]
+    fn test_collections() {
+        round_trip(vec![1u, 2, 3]);
+        round_trip(Vec::<String>::new());
+        round_trip(Vec::from_fn(100, |i| i as i32 - 50));
+        round_trip(box 5u);
+        round_trip(Rc::new("rc".to_string()));
+        round_trip((1u, "two".to_string(), (3.0f64, '4')));
+        round_trip(Some(Some(3u)));
+        round_trip(None::<uint>);
+
+        let list: DList<uint> = range(0u, 20).collect();
+        round_trip(list);
+        let ring: RingBuf<int> = range(-10i, 10).collect();
+        round_trip(ring);
+
+        let mut btree = BTreeMap::new();
+        for i in range(0u, 40) {
+            btree.insert(i.to_string(), Vec::from_elem(3, i));
+        }
+        round_trip(btree);
+        let set: BTreeSet<char> = "hello world".chars().collect();
+        round_trip(set);
+
+        let mut hash = HashMap::new();
+        hash.insert(1u, "one".to_string());
+        hash.insert(2u, "two".to_string());
+        round_trip(hash);
+        let hash_set: HashSet<i64> = vec![-1, 0, 1].into_iter().collect();
+        round_trip(hash_set);
+
+        let mut vec_map = VecMap::new();
+        vec_map.insert(3, 'c');
+        vec_map.insert(17, 'q');
+        round_trip(vec_map);
+    }
+
+    #[test]
+    fn test_derived() {
+        round_trip(Dog);
+        round_trip(Frog("Henry".to_string(), -349));
+        round_trip(Cat { age: 3, name: "Tom".to_string() });
+        assert_eq!(encode(&Dog), vec![0xa3, b'D', b'o', b'g']);
+
+        let mut pets = BTreeMap::new();
+        pets.insert("a".to_string(), Dog);
+        pets.insert("b".to_string(), Frog("Henry".to_string(), 349));
+        round_trip(Outer {
+            inner: vec![
+                Inner { a: (), b: 2, c: vec!["abc".to_string(), "xyz".to_string()] },
+                Inner { a: (), b: 1 << 40, c: vec![] },
+            ],
+            pets: pets,
+            nickname: None,
+        });
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let res: Result<uint, DecoderError> = decode(&[]);
+        assert_eq!(res, Err(EndOfInput));
+        let res: Result<String, DecoderError> = decode(&[0xa3, b'a']);
+        assert_eq!(res, Err(EndOfInput));
+        let res: Result<String, DecoderError> = decode(&[0xa1, 0xff]);
+        assert_eq!(res, Err(NotUtf8));
+        let res: Result<bool, DecoderError> = decode(&[0x01]);
+        assert_eq!(res, Err(ExpectedError("Boolean".to_string(), "Integer".to_string())));
+        let res: Result<uint, DecoderError> = decode(&[0x01, 0x02]);
+        assert_eq!(res, Err(ExpectedError("end of input".to_string(),
+                                          "1 trailing bytes".to_string())));
+        let res: Result<Animal, DecoderError> = decode(&[0xa3, b'C', b'o', b'w']);
+        assert_eq!(res, Err(UnknownVariantError("Cow".to_string())));
+        let res: Result<(uint, uint), DecoderError> = decode(encode(&(1u, 2u, 3u)).as_slice());
+        assert_eq!(res, Err(ExpectedError("Tuple2".to_string(), "Tuple3".to_string())));
+        let res: Result<Inner, DecoderError> = decode(encode(&(1u, 2u)).as_slice());
+        assert_eq!(res, Err(ExpectedError("Array of 3 fields".to_string(),
+                                          "Array of 2 elements".to_string())));
+    }
+
+    #[test]
+    fn test_decode_truncated_lengths() {
+        // Lengths are checked against what is left of the input before
+        // anything is allocated for the elements.
+        let res: Result<Vec<u8>, DecoderError> = decode(&[0xdd, 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(res, Err(EndOfInput));
+        let res: Result<Vec<u8>, DecoderError> = decode(&[0xdc, 0xff, 0xff]);
+        assert_eq!(res, Err(EndOfInput));
+        let res: Result<Vec<u8>, DecoderError> = decode(&[0xdc, 0x00, 0x03, 0x01, 0x02]);
+        assert_eq!(res, Err(EndOfInput));
+        let res: Result<HashMap<u8, u8>, DecoderError> =
+            decode(&[0xdf, 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(res, Err(EndOfInput));
+        let res: Result<HashMap<u8, u8>, DecoderError> = decode(&[0xde, 0xff, 0xff]);
+        assert_eq!(res, Err(EndOfInput));
+        let res: Result<HashMap<u8, u8>, DecoderError> = decode(&[0xde, 0x00, 0x02, 0x01, 0x02]);
+        assert_eq!(res, Err(EndOfInput));
+
+        // A header cut short is an error too.
+        let res: Result<Vec<u8>, DecoderError> = decode(&[0xdd, 0x00]);
+        assert_eq!(res, Err(EndOfInput));
+        let res: Result<HashMap<u8, u8>, DecoderError> = decode(&[0xdf, 0x00, 0x00]);
+        assert_eq!(res, Err(EndOfInput));
+
+        let res: Result<Vec<u8>, DecoderError> = decode(&[0xdc, 0x00, 0x02, 0x01, 0x02]);
+        assert_eq!(res, Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_encode_nil_in_some() {
+        // These would all decode as `None`.
+        fn fails<T>(value: T) -> bool where
+            T: for<'a> Encodable<Encoder<'a>, IoError> + Send
+        {
+            Thread::spawn(move || { encode(&value); }).join().is_err()
+        }
+        assert!(fails(Some(())));
+        assert!(fails(Some(None::<uint>)));
+        assert!(fails(Some(Some(()))));
+
+        round_trip(Some(vec![()]));
+        round_trip(Some(Some(Vec::<()>::new())));
+        round_trip(vec![Some(1u), None, Some(2)]);
+    }
+
+    #[test]
+    fn test_decode_enum_arity() {
+        // Nullary variants may also be written as an array of their name.
+        let res: Result<Animal, DecoderError> = decode(encode(&("Dog",)).as_slice());
+        assert_eq!(res, Ok(Dog));
+
+        let res: Result<Animal, DecoderError> = decode(encode(&"Frog").as_slice());
+        assert_eq!(res, Err(ExpectedError("Array".to_string(), "String".to_string())));
+        let res: Result<Animal, DecoderError> = decode(encode(&("Dog", 1u)).as_slice());
+        assert_eq!(res, Err(ExpectedError("Array of 1 elements".to_string(),
+                                          "Array of 2 elements".to_string())));
+        let res: Result<Animal, DecoderError> = decode(encode(&("Frog", "Henry")).as_slice());
+        assert_eq!(res, Err(ExpectedError("Array of 3 elements".to_string(),
+                                          "Array of 2 elements".to_string())));
+        let res: Result<Animal, DecoderError> =
+            decode(encode(&("Frog", "Henry", 349i, 1u)).as_slice());
+        assert_eq!(res, Err(ExpectedError("Array of 3 elements".to_string(),
+                                          "Array of 4 elements".to_string())));
+
+        // Enum arguments don't disturb the count of the outer variant.
+        #[deriving(PartialEq, RustcEncodable, RustcDecodable, Show)]
+        enum Pair {
+            Two(Animal, Animal),
+        }
+        round_trip(Pair::Two(Frog("Henry".to_string(), 349),
+                             Cat { age: 3, name: "Tom".to_string() }));
+        round_trip(Pair::Two(Dog, Dog));
+    }
+}
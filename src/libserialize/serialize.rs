@@ -142,6 +142,18 @@ pub trait Decoder<E> {
                                -> Result<T, E> where
         F: FnOnce(&mut Self) -> Result<T, E>;
 
+    /// Reads a struct field which may be missing, returning `None` if it is.
+    /// Formats that cannot tell whether a field is missing always read it.
+    fn read_struct_field_opt<T, F>(&mut self,
+                                   f_name: &str,
+                                   f_idx: uint,
+                                   f: F)
+                                   -> Result<Option<T>, E> where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+    {
+        self.read_struct_field(f_name, f_idx, f).map(Some)
+    }
+
     fn read_tuple<T, F>(&mut self, len: uint, f: F) -> Result<T, E> where
         F: FnOnce(&mut Self) -> Result<T, E>;
     fn read_tuple_arg<T, F>(&mut self, a_idx: uint, f: F) -> Result<T, E> where
@@ -0,0 +1,1527 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! TOML parsing and decoding
+//!
+//! [TOML](https://github.com/toml-lang/toml) is a configuration file format
+//! in the spirit of INI files, with key/value pairs grouped into tables:
+//!
+//! ```ignore
+//! # This is a comment.
+//! title = "TOML Example"
+//!
+//! [database]
+//! server = "192.168.1.1"
+//! ports = [ 8001, 8001, 8002 ]
+//! enabled = true
+//!
+//! [servers.alpha]
+//! ip = "10.0.0.1"
+//!
+//! [[products]]
+//! name = "Hammer"
+//! sku = 738594937
+//! ```
+//!
+//! A document is parsed into a `Table`, whose values are represented by the
+//! `Toml` enum. Dates are kept as the strings they were written as.
+//!
+//! Types implementing `Decodable` can be decoded from a document directly
+//! with `toml::decode`, in the same way as `json::decode`. Tables are
+//! decoded as structs or maps, arrays as sequences or tuples, and enum
+//! variants either from a string naming them, or from a table holding the
+//! name of the variant in `variant` and its arguments in `fields`. Missing
+//! fields are decoded as `None` if they are of type `Option<T>`.
+//!
+//! ```notrust
+//! // FIXME(#19470): this cannot be ```rust``` because it fails orphan checking at the moment
+//! extern crate serialize;
+//! use serialize::toml;
+//!
+//! #[deriving(RustcDecodable)]
+//! struct Database {
+//!     server: String,
+//!     ports: Vec<u16>,
+//!     enabled: bool,
+//!     timeout: Option<uint>,
+//! }
+//!
+//! #[deriving(RustcDecodable)]
+//! struct Config {
+//!     title: String,
+//!     database: Database,
+//! }
+//!
+//! fn main() {
+//!     let config: Config = toml::decode(r#"
+//!         title = "TOML Example"
+//!
+//!         [database]
+//!         server = "192.168.1.1"
+//!         ports = [ 8001, 8001, 8002 ]
+//!         enabled = true
+//!     "#).unwrap();
+//! }
+//! ```
+
+use self::ErrorCode::*;
+use self::ParserError::*;
+use self::DecoderError::*;
+
+use std::collections::BTreeMap;
+use std::{char, fmt, i64, io, num, str};
+use std::num::Float;
+use std::string;
+
+/// Represents a TOML value
+#[deriving(Clone, PartialEq, PartialOrd)]
+pub enum Toml {
+    String(string::String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Datetime(string::String),
+    Array(self::Array),
+    Table(self::Table),
+}
+
+pub type Array = Vec<Toml>;
+pub type Table = BTreeMap<string::String, Toml>;
+
+/// The errors that can arise while parsing a TOML document.
+#[deriving(Clone, Copy, PartialEq)]
+pub enum ErrorCode {
+    InvalidSyntax,
+    InvalidNumber,
+    InvalidDatetime,
+    ExpectedKey,
+    ExpectedEquals,
+    ExpectedNewline,
+    EOFWhileParsingString,
+    EOFWhileParsingArray,
+    EOFWhileParsingTable,
+    NewlineInString,
+    InvalidEscape,
+    InvalidUnicodeCodePoint,
+    DuplicateKey,
+    DuplicateTable,
+    KeyIsNotATable,
+    NotUtf8,
+}
+
+#[deriving(Clone, Copy, PartialEq, Show)]
+pub enum ParserError {
+    /// msg, line, col
+    SyntaxError(ErrorCode, uint, uint),
+    IoError(io::IoErrorKind, &'static str),
+}
+
+#[deriving(Clone, PartialEq, Show)]
+pub enum DecoderError {
+    ParseError(ParserError),
+    ExpectedError(string::String, string::String),
+    MissingFieldError(string::String),
+    UnknownVariantError(string::String),
+    ApplicationError(string::String)
+}
+
+/// Returns a readable error string for a given error code.
+pub fn error_str(error: ErrorCode) -> &'static str {
+    match error {
+        InvalidSyntax => "invalid syntax",
+        InvalidNumber => "invalid number",
+        InvalidDatetime => "invalid datetime",
+        ExpectedKey => "expected a key",
+        ExpectedEquals => "expected `=`",
+        ExpectedNewline => "expected a newline",
+        EOFWhileParsingString => "EOF While parsing string",
+        EOFWhileParsingArray => "EOF While parsing array",
+        EOFWhileParsingTable => "EOF While parsing table",
+        NewlineInString => "newline in single-line string",
+        InvalidEscape => "invalid escape",
+        InvalidUnicodeCodePoint => "invalid Unicode code point",
+        DuplicateKey => "duplicate key",
+        DuplicateTable => "table defined twice",
+        KeyIsNotATable => "key does not refer to a table",
+        NotUtf8 => "contents not utf-8",
+    }
+}
+
+impl fmt::Show for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        error_str(*self).fmt(f)
+    }
+}
+
+impl ::std::error::Error for DecoderError {
+    fn description(&self) -> &str { "decoder error" }
+    fn detail(&self) -> Option<string::String> { Some(self.to_string()) }
+}
+
+pub type DecodeResult<T> = Result<T, DecoderError>;
+
+/// Shortcut function to decode a TOML document into an object
+pub fn decode<T: ::Decodable<Decoder, DecoderError>>(s: &str) -> DecodeResult<T> {
+    let table = match from_str(s) {
+        Ok(x) => x,
+        Err(e) => return Err(ParseError(e))
+    };
+
+    let mut decoder = Decoder::new(Toml::Table(table));
+    ::Decodable::decode(&mut decoder)
+}
+
+/// Parses a TOML document from an `&mut io::Reader`
+pub fn from_reader(rdr: &mut io::Reader) -> Result<Table, ParserError> {
+    let contents = match rdr.read_to_end() {
+        Ok(c)  => c,
+        Err(e) => return Err(IoError(e.kind, e.desc))
+    };
+    let s = match str::from_utf8(contents.as_slice()).ok() {
+        Some(s) => s,
+        _       => return Err(SyntaxError(NotUtf8, 0, 0))
+    };
+    Parser::new(s.chars()).parse()
+}
+
+/// Parses a TOML document from a string
+pub fn from_str(s: &str) -> Result<Table, ParserError> {
+    Parser::new(s.chars()).parse()
+}
+
+impl Toml {
+    /// If the Toml value is a Table, returns the value associated with the
+    /// provided key. Otherwise, returns None.
+    pub fn find<'a>(&'a self, key: &str) -> Option<&'a Toml> {
+        match *self {
+            Toml::Table(ref table) => table.get(key),
+            _ => None
+        }
+    }
+
+    /// Looks up a value by a dotted path of keys, such as `servers.alpha.ip`.
+    pub fn lookup<'a>(&'a self, path: &str) -> Option<&'a Toml> {
+        let mut target = self;
+        for key in path.split('.') {
+            match target.find(key) {
+                Some(t) => { target = t; },
+                None => return None
+            }
+        }
+        Some(target)
+    }
+
+    /// If the Toml value is a String, returns the associated str.
+    /// Returns None otherwise.
+    pub fn as_string<'a>(&'a self) -> Option<&'a str> {
+        match *self {
+            Toml::String(ref s) => Some(s[]),
+            _ => None
+        }
+    }
+
+    /// If the Toml value is an Integer, returns the associated i64.
+    /// Returns None otherwise.
+    pub fn as_integer(&self) -> Option<i64> {
+        match *self {
+            Toml::Integer(n) => Some(n),
+            _ => None
+        }
+    }
+
+    /// If the Toml value is a Float, returns the associated f64.
+    /// Returns None otherwise.
+    pub fn as_float(&self) -> Option<f64> {
+        match *self {
+            Toml::Float(n) => Some(n),
+            _ => None
+        }
+    }
+
+    /// If the Toml value is a Boolean, returns the associated bool.
+    /// Returns None otherwise.
+    pub fn as_boolean(&self) -> Option<bool> {
+        match *self {
+            Toml::Boolean(b) => Some(b),
+            _ => None
+        }
+    }
+
+    /// If the Toml value is a Datetime, returns it as written in the document.
+    /// Returns None otherwise.
+    pub fn as_datetime<'a>(&'a self) -> Option<&'a str> {
+        match *self {
+            Toml::Datetime(ref s) => Some(s[]),
+            _ => None
+        }
+    }
+
+    /// If the Toml value is an Array, returns the associated vector.
+    /// Returns None otherwise.
+    pub fn as_array<'a>(&'a self) -> Option<&'a Array> {
+        match *self {
+            Toml::Array(ref array) => Some(array),
+            _ => None
+        }
+    }
+
+    /// If the Toml value is a Table, returns the associated BTreeMap.
+    /// Returns None otherwise.
+    pub fn as_table<'a>(&'a self) -> Option<&'a Table> {
+        match *self {
+            Toml::Table(ref table) => Some(table),
+            _ => None
+        }
+    }
+}
+
+fn is_bare_key_char(c: char) -> bool {
+    match c {
+        'a' ... 'z' | 'A' ... 'Z' | '0' ... '9' | '_' | '-' => true,
+        _ => false
+    }
+}
+
+fn is_bare_value_char(c: char) -> bool {
+    is_bare_key_char(c) || c == '+' || c == '.' || c == ':'
+}
+
+fn escape_str(f: &mut fmt::Formatter, v: &str) -> fmt::Result {
+    try!(write!(f, "\""));
+    for c in v.chars() {
+        try!(match c {
+            '"' => write!(f, "\\\""),
+            '\\' => write!(f, "\\\\"),
+            '\x08' => write!(f, "\\b"),
+            '\t' => write!(f, "\\t"),
+            '\n' => write!(f, "\\n"),
+            '\x0c' => write!(f, "\\f"),
+            '\r' => write!(f, "\\r"),
+            c if c < ' ' || c == '\x7f' => write!(f, "\\u{:04X}", c as u32),
+            c => write!(f, "{}", c),
+        });
+    }
+    write!(f, "\"")
+}
+
+fn write_key(f: &mut fmt::Formatter, key: &str) -> fmt::Result {
+    if !key.is_empty() && key.chars().all(is_bare_key_char) {
+        write!(f, "{}", key)
+    } else {
+        escape_str(f, key)
+    }
+}
+
+/// Values are shown as they would be written on the right hand side of a
+/// key/value pair, with tables written inline.
+impl fmt::Show for Toml {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Toml::String(ref s) => escape_str(f, s[]),
+            Toml::Integer(n) => write!(f, "{}", n),
+            Toml::Float(n) if n.fract() == 0.0 => write!(f, "{}.0", n),
+            Toml::Float(n) => write!(f, "{}", n),
+            Toml::Boolean(b) => write!(f, "{}", b),
+            Toml::Datetime(ref s) => write!(f, "{}", s),
+            Toml::Array(ref array) => {
+                try!(write!(f, "["));
+                for (i, value) in array.iter().enumerate() {
+                    if i > 0 { try!(write!(f, ", ")); }
+                    try!(write!(f, "{}", value));
+                }
+                write!(f, "]")
+            }
+            Toml::Table(ref table) => {
+                try!(write!(f, "{{"));
+                for (i, (key, value)) in table.iter().enumerate() {
+                    if i > 0 { try!(write!(f, ",")); }
+                    try!(write!(f, " "));
+                    try!(write_key(f, key[]));
+                    try!(write!(f, " = {}", value));
+                }
+                if !table.is_empty() { try!(write!(f, " ")); }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+// Checks for a date and time such as `1979-05-27T07:32:00Z`, with optional
+// fractional seconds and an optional numeric offset instead of `Z`.
+fn is_datetime(s: &str) -> bool {
+    fn matches(s: &[u8], pattern: &[u8]) -> bool {
+        s.len() == pattern.len() && s.iter().zip(pattern.iter()).all(|(&c, &p)| {
+            if p == b'd' { c >= b'0' && c <= b'9' } else { c == p }
+        })
+    }
+
+    let s = s.as_bytes();
+    if s.len() < 20 || !matches(s[..19], b"dddd-dd-ddTdd:dd:dd") {
+        return false;
+    }
+    let mut rest = s[19..];
+    if rest[0] == b'.' {
+        let digits = rest[1..].iter().take_while(|&&c| c >= b'0' && c <= b'9').count();
+        if digits == 0 {
+            return false;
+        }
+        rest = rest[digits + 1..];
+    }
+    rest == b"Z" || matches(rest, b"+dd:dd") || matches(rest, b"-dd:dd")
+}
+
+// Parses an integer or a float. Underscores may separate digits, and
+// leading zeros are not allowed.
+fn parse_number(s: &str) -> Option<Toml> {
+    let bytes = s.as_bytes();
+    let is_digit = |i: uint| i < bytes.len() && bytes[i] >= b'0' && bytes[i] <= b'9';
+    for (i, &c) in bytes.iter().enumerate() {
+        if c == b'_' && (i == 0 || !is_digit(i - 1) || !is_digit(i + 1)) {
+            return None;
+        }
+    }
+    let digits: string::String = s.chars().filter(|&c| c != '_').collect();
+    let (negative, body) = match digits.as_bytes().get(0) {
+        Some(&b'-') => (true, digits[1..]),
+        Some(&b'+') => (false, digits[1..]),
+        _ => (false, digits[]),
+    };
+
+    // int [frac] [exp], where frac is `.` followed by digits and exp is `e`
+    // followed by an optionally signed integer.
+    let b = body.as_bytes();
+    let count = |from: uint| b[from..].iter().take_while(|&&c| c >= b'0' && c <= b'9').count();
+    let int_len = count(0);
+    if int_len == 0 || (int_len > 1 && b[0] == b'0') {
+        return None;
+    }
+    let mut pos = int_len;
+    let mut float = false;
+    if pos < b.len() && b[pos] == b'.' {
+        let frac_len = count(pos + 1);
+        if frac_len == 0 {
+            return None;
+        }
+        pos += frac_len + 1;
+        float = true;
+    }
+    if pos < b.len() && (b[pos] == b'e' || b[pos] == b'E') {
+        pos += 1;
+        if pos < b.len() && (b[pos] == b'+' || b[pos] == b'-') {
+            pos += 1;
+        }
+        let exp_len = count(pos);
+        if exp_len == 0 {
+            return None;
+        }
+        pos += exp_len;
+        float = true;
+    }
+    if pos != b.len() {
+        return None;
+    }
+
+    if float {
+        body.parse::<f64>().map(|n| Toml::Float(if negative { -n } else { n }))
+    } else {
+        let n: u64 = match body.parse() {
+            Some(n) => n,
+            None => return None,
+        };
+        if negative && n == i64::MIN as u64 {
+            Some(Toml::Integer(i64::MIN))
+        } else if n > i64::MAX as u64 {
+            None
+        } else if negative {
+            Some(Toml::Integer(-(n as i64)))
+        } else {
+            Some(Toml::Integer(n as i64))
+        }
+    }
+}
+
+/// A TOML parser consuming an iterator of char.
+pub struct Parser<T> {
+    rdr: T,
+    ch: Option<char>,
+    line: uint,
+    col: uint,
+}
+
+impl<T: Iterator<char>> Parser<T> {
+    /// Creates the TOML parser.
+    pub fn new(rdr: T) -> Parser<T> {
+        let mut p = Parser {
+            rdr: rdr,
+            ch: Some('\x00'),
+            line: 1,
+            col: 0,
+        };
+        p.bump();
+        return p;
+    }
+
+    /// Parses the whole document into its root table.
+    pub fn parse(&mut self) -> Result<Table, ParserError> {
+        let mut root = BTreeMap::new();
+        // The table key/value pairs are currently added to.
+        let mut current = Vec::new();
+        // The tables defined by a `[table]` header so far.
+        let mut defined: Vec<Vec<string::String>> = Vec::new();
+        // The inline tables and arrays given as values so far, which no
+        // header may add to.
+        let mut sealed: Vec<Vec<string::String>> = Vec::new();
+
+        loop {
+            self.parse_blank_lines();
+            let (line, col) = (self.line, self.col);
+            match self.ch {
+                None => return Ok(root),
+                Some('[') => {
+                    self.bump();
+                    let array = self.eat('[');
+                    let path = try!(self.parse_table_path());
+                    if !self.eat(']') || (array && !self.eat(']')) {
+                        return self.error(InvalidSyntax);
+                    }
+                    try!(self.parse_end_of_line());
+                    if sealed.iter().any(|s| path.starts_with(s[])) {
+                        return Err(SyntaxError(DuplicateKey, line, col));
+                    }
+
+                    {
+                        let (last, parents) = (path.last().unwrap(), path.init());
+                        let parent = try!(self.table_at(&mut root, parents, line, col));
+                        if array {
+                            if !parent.contains_key(last) {
+                                parent.insert(last.clone(), Toml::Array(Vec::new()));
+                            }
+                            match *parent.get_mut(last).unwrap() {
+                                Toml::Array(ref mut array) => {
+                                    if !array.iter().all(|t| t.as_table().is_some()) {
+                                        return Err(SyntaxError(DuplicateKey, line, col));
+                                    }
+                                    array.push(Toml::Table(BTreeMap::new()));
+                                }
+                                _ => return Err(SyntaxError(DuplicateKey, line, col)),
+                            }
+                            // Tables below this one now belong to the new element.
+                            defined.retain(|p| !p.starts_with(path[]));
+                            sealed.retain(|p| !p.starts_with(path[]));
+                        } else {
+                            if defined.contains(&path) {
+                                return Err(SyntaxError(DuplicateTable, line, col));
+                            }
+                            if !parent.contains_key(last) {
+                                parent.insert(last.clone(), Toml::Table(BTreeMap::new()));
+                            }
+                            match *parent.get(last).unwrap() {
+                                Toml::Table(_) => {}
+                                _ => return Err(SyntaxError(DuplicateKey, line, col)),
+                            }
+                            defined.push(path.clone());
+                        }
+                    }
+                    current = path;
+                }
+                Some(_) => {
+                    let (key, value) = try!(self.parse_key_value());
+                    try!(self.parse_end_of_line());
+                    let table = try!(self.table_at(&mut root, current[], line, col));
+                    if table.contains_key(&key) {
+                        return Err(SyntaxError(DuplicateKey, line, col));
+                    }
+                    match value {
+                        Toml::Table(_) | Toml::Array(_) => {
+                            let mut path = current.clone();
+                            path.push(key.clone());
+                            sealed.push(path);
+                        }
+                        _ => {}
+                    }
+                    table.insert(key, value);
+                }
+            }
+        }
+    }
+
+    // Finds the table at `path`, creating any missing tables along the way.
+    // Arrays of tables along the path refer to their last element.
+    fn table_at<'a>(&self,
+                    table: &'a mut Table,
+                    path: &[string::String],
+                    line: uint,
+                    col: uint) -> Result<&'a mut Table, ParserError> {
+        if path.is_empty() {
+            return Ok(table);
+        }
+        if !table.contains_key(&path[0]) {
+            table.insert(path[0].clone(), Toml::Table(BTreeMap::new()));
+        }
+        let next = match *table.get_mut(&path[0]).unwrap() {
+            Toml::Table(ref mut next) => next,
+            Toml::Array(ref mut array) => match array.last_mut() {
+                Some(last) => match *last {
+                    Toml::Table(ref mut next) => next,
+                    _ => return Err(SyntaxError(KeyIsNotATable, line, col)),
+                },
+                None => return Err(SyntaxError(KeyIsNotATable, line, col)),
+            },
+            _ => return Err(SyntaxError(KeyIsNotATable, line, col)),
+        };
+        self.table_at(next, path[1..], line, col)
+    }
+
+    fn eof(&self) -> bool { self.ch.is_none() }
+    fn bump(&mut self) {
+        self.ch = self.rdr.next();
+
+        if self.ch_is('\n') {
+            self.line += 1u;
+            self.col = 1u;
+        } else {
+            self.col += 1u;
+        }
+    }
+
+    fn ch_is(&self, c: char) -> bool {
+        self.ch == Some(c)
+    }
+
+    // Consumes `c` if it is the current character.
+    fn eat(&mut self, c: char) -> bool {
+        if self.ch_is(c) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error<U>(&self, reason: ErrorCode) -> Result<U, ParserError> {
+        Err(SyntaxError(reason, self.line, self.col))
+    }
+
+    fn parse_whitespace(&mut self) {
+        while self.ch_is(' ') || self.ch_is('\t') { self.bump(); }
+    }
+
+    fn parse_comment(&mut self) {
+        if self.ch_is('#') {
+            while !self.eof() && !self.ch_is('\n') { self.bump(); }
+        }
+    }
+
+    // Skips whitespace, comments and newlines.
+    fn parse_blank_lines(&mut self) {
+        loop {
+            self.parse_whitespace();
+            self.parse_comment();
+            if !self.eat('\n') && !self.eat('\r') {
+                return;
+            }
+        }
+    }
+
+    // Skips the rest of a line, which may only hold a comment.
+    fn parse_end_of_line(&mut self) -> Result<(), ParserError> {
+        self.parse_whitespace();
+        self.parse_comment();
+        if self.eat('\r') && !self.ch_is('\n') {
+            return self.error(ExpectedNewline);
+        }
+        if self.eof() || self.eat('\n') {
+            Ok(())
+        } else {
+            self.error(ExpectedNewline)
+        }
+    }
+
+    fn parse_key(&mut self) -> Result<string::String, ParserError> {
+        match self.ch {
+            Some('"') => self.parse_basic_string(false),
+            Some('\'') => self.parse_literal_string(false),
+            Some(c) if is_bare_key_char(c) => {
+                let mut key = string::String::new();
+                loop {
+                    match self.ch {
+                        Some(c) if is_bare_key_char(c) => key.push(c),
+                        _ => return Ok(key),
+                    }
+                    self.bump();
+                }
+            }
+            _ => self.error(ExpectedKey),
+        }
+    }
+
+    // Parses the dot-separated keys of a table header.
+    fn parse_table_path(&mut self) -> Result<Vec<string::String>, ParserError> {
+        let mut path = Vec::new();
+        loop {
+            self.parse_whitespace();
+            path.push(try!(self.parse_key()));
+            self.parse_whitespace();
+            if !self.eat('.') {
+                return Ok(path);
+            }
+        }
+    }
+
+    fn parse_key_value(&mut self) -> Result<(string::String, Toml), ParserError> {
+        let key = try!(self.parse_key());
+        self.parse_whitespace();
+        if !self.eat('=') {
+            return self.error(ExpectedEquals);
+        }
+        self.parse_whitespace();
+        let value = try!(self.parse_value());
+        Ok((key, value))
+    }
+
+    fn parse_value(&mut self) -> Result<Toml, ParserError> {
+        match self.ch {
+            Some('"') => self.parse_basic_string(true).map(Toml::String),
+            Some('\'') => self.parse_literal_string(true).map(Toml::String),
+            Some('[') => self.parse_array(),
+            Some('{') => self.parse_inline_table(),
+            Some(c) if is_bare_value_char(c) => self.parse_bare_value(),
+            None => self.error(InvalidSyntax),
+            Some(_) => self.error(InvalidSyntax),
+        }
+    }
+
+    // Parses a number, a boolean or a datetime.
+    fn parse_bare_value(&mut self) -> Result<Toml, ParserError> {
+        let (line, col) = (self.line, self.col);
+        let mut token = string::String::new();
+        loop {
+            match self.ch {
+                Some(c) if is_bare_value_char(c) => token.push(c),
+                _ => break,
+            }
+            self.bump();
+        }
+
+        match token[] {
+            "true" => return Ok(Toml::Boolean(true)),
+            "false" => return Ok(Toml::Boolean(false)),
+            _ => {}
+        }
+        if token.len() > 4 && token.as_bytes()[4] == b'-' {
+            if is_datetime(token[]) {
+                Ok(Toml::Datetime(token))
+            } else {
+                Err(SyntaxError(InvalidDatetime, line, col))
+            }
+        } else {
+            match parse_number(token[]) {
+                Some(value) => Ok(value),
+                None => Err(SyntaxError(InvalidNumber, line, col)),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Toml, ParserError> {
+        self.bump();
+        let mut values = Vec::new();
+        loop {
+            self.parse_blank_lines();
+            if self.eat(']') {
+                return Ok(Toml::Array(values));
+            }
+            if self.eof() {
+                return self.error(EOFWhileParsingArray);
+            }
+            values.push(try!(self.parse_value()));
+            self.parse_blank_lines();
+            if !self.eat(',') {
+                self.parse_blank_lines();
+                if self.eat(']') {
+                    return Ok(Toml::Array(values));
+                } else if self.eof() {
+                    return self.error(EOFWhileParsingArray);
+                } else {
+                    return self.error(InvalidSyntax);
+                }
+            }
+        }
+    }
+
+    fn parse_inline_table(&mut self) -> Result<Toml, ParserError> {
+        self.bump();
+        let mut table = BTreeMap::new();
+        self.parse_whitespace();
+        if self.eat('}') {
+            return Ok(Toml::Table(table));
+        }
+        loop {
+            self.parse_whitespace();
+            let (line, col) = (self.line, self.col);
+            let (key, value) = try!(self.parse_key_value());
+            if table.contains_key(&key) {
+                return Err(SyntaxError(DuplicateKey, line, col));
+            }
+            table.insert(key, value);
+            self.parse_whitespace();
+            if self.eat('}') {
+                return Ok(Toml::Table(table));
+            } else if self.eof() {
+                return self.error(EOFWhileParsingTable);
+            } else if !self.eat(',') {
+                return self.error(InvalidSyntax);
+            }
+        }
+    }
+
+    // Skips the newline directly following the opening delimiter of a
+    // multi-line string.
+    fn parse_leading_newline(&mut self) {
+        if self.ch_is('\r') {
+            self.bump();
+        }
+        self.eat('\n');
+    }
+
+    // Parses a string delimited by `"`, or by `"""` if `multiline` strings are
+    // allowed.
+    fn parse_basic_string(&mut self, multiline: bool) -> Result<string::String, ParserError> {
+        let mut res = string::String::new();
+        self.bump();
+        let multiline = if multiline && self.eat('"') {
+            if !self.eat('"') {
+                return Ok(res);
+            }
+            self.parse_leading_newline();
+            true
+        } else {
+            false
+        };
+
+        loop {
+            match self.ch {
+                None => return self.error(EOFWhileParsingString),
+                Some('"') => {
+                    self.bump();
+                    if !multiline {
+                        return Ok(res);
+                    }
+                    if !self.eat('"') {
+                        res.push('"');
+                    } else if !self.eat('"') {
+                        res.push_str("\"\"");
+                    } else {
+                        return Ok(res);
+                    }
+                    continue;
+                }
+                Some('\\') => {
+                    self.bump();
+                    match self.ch {
+                        Some('b') => res.push('\x08'),
+                        Some('t') => res.push('\t'),
+                        Some('n') => res.push('\n'),
+                        Some('f') => res.push('\x0c'),
+                        Some('r') => res.push('\r'),
+                        Some('"') => res.push('"'),
+                        Some('\\') => res.push('\\'),
+                        Some('u') => {
+                            res.push(try!(self.parse_unicode_escape(4)));
+                            continue;
+                        }
+                        Some('U') => {
+                            res.push(try!(self.parse_unicode_escape(8)));
+                            continue;
+                        }
+                        // A backslash at the end of a line trims the
+                        // whitespace up to the next non-whitespace character.
+                        Some(' ') | Some('\t') | Some('\r') | Some('\n') if multiline => {
+                            while self.ch_is(' ') || self.ch_is('\t') ||
+                                  self.ch_is('\r') || self.ch_is('\n') {
+                                self.bump();
+                            }
+                            continue;
+                        }
+                        None => return self.error(EOFWhileParsingString),
+                        Some(_) => return self.error(InvalidEscape),
+                    }
+                }
+                Some('\n') if !multiline => return self.error(NewlineInString),
+                Some(c) => res.push(c),
+            }
+            self.bump();
+        }
+    }
+
+    // Parses the hex digits of a `\u` or `\U` escape.
+    fn parse_unicode_escape(&mut self, digits: uint) -> Result<char, ParserError> {
+        let mut n = 0u32;
+        for _ in range(0, digits) {
+            self.bump();
+            n = match self.ch.and_then(|c| c.to_digit(16)) {
+                Some(d) => n * 16 + d as u32,
+                None => return self.error(InvalidEscape),
+            };
+        }
+        self.bump();
+        match char::from_u32(n) {
+            Some(c) => Ok(c),
+            None => self.error(InvalidUnicodeCodePoint),
+        }
+    }
+
+    // Parses a string delimited by `'`, or by `'''` if `multiline` strings are
+    // allowed. There are no escapes in these strings.
+    fn parse_literal_string(&mut self, multiline: bool) -> Result<string::String, ParserError> {
+        let mut res = string::String::new();
+        self.bump();
+        let multiline = if multiline && self.eat('\'') {
+            if !self.eat('\'') {
+                return Ok(res);
+            }
+            self.parse_leading_newline();
+            true
+        } else {
+            false
+        };
+
+        loop {
+            match self.ch {
+                None => return self.error(EOFWhileParsingString),
+                Some('\'') => {
+                    self.bump();
+                    if !multiline {
+                        return Ok(res);
+                    }
+                    if !self.eat('\'') {
+                        res.push('\'');
+                    } else if !self.eat('\'') {
+                        res.push_str("''");
+                    } else {
+                        return Ok(res);
+                    }
+                    continue;
+                }
+                Some('\n') if !multiline => return self.error(NewlineInString),
+                Some(c) => res.push(c),
+            }
+            self.bump();
+        }
+    }
+}
+
+/// A structure to decode TOML to values in rust.
+pub struct Decoder {
+    // None stands for a missing struct field.
+    stack: Vec<Option<Toml>>,
+}
+
+impl Decoder {
+    /// Creates a new decoder instance for decoding the specified TOML value.
+    pub fn new(toml: Toml) -> Decoder {
+        Decoder { stack: vec![Some(toml)] }
+    }
+}
+
+impl Decoder {
+    fn pop(&mut self) -> Option<Toml> {
+        self.stack.pop().unwrap()
+    }
+}
+
+macro_rules! expect {
+    ($e:expr, $t:ident) => ({
+        match $e {
+            Some(Toml::$t(v)) => Ok(v),
+            Some(other) => {
+                Err(ExpectedError(stringify!($t).to_string(),
+                                  format!("{}", other)))
+            }
+            None => Err(ExpectedError(stringify!($t).to_string(), "nothing".to_string())),
+        }
+    })
+}
+
+macro_rules! read_primitive {
+    ($name:ident, $ty:ty) => {
+        fn $name(&mut self) -> DecodeResult<$ty> {
+            match self.pop() {
+                Some(Toml::Integer(f)) => match num::cast(f) {
+                    Some(f) => Ok(f),
+                    None => Err(ExpectedError("Integer".to_string(), format!("{}", f))),
+                },
+                // Keys of tables decoded as maps with integer keys are strings.
+                Some(Toml::String(s)) => match ::std::str::from_str(s.as_slice()) {
+                    Some(f) => Ok(f),
+                    None => Err(ExpectedError("Integer".to_string(), s)),
+                },
+                Some(value) => Err(ExpectedError("Integer".to_string(), format!("{}", value))),
+                None => Err(ExpectedError("Integer".to_string(), "nothing".to_string())),
+            }
+        }
+    }
+}
+
+impl ::Decoder<DecoderError> for Decoder {
+    fn read_nil(&mut self) -> DecodeResult<()> {
+        match self.pop() {
+            None => Ok(()),
+            Some(value) => Err(ExpectedError("nothing".to_string(), format!("{}", value))),
+        }
+    }
+
+    read_primitive! { read_uint, uint }
+    read_primitive! { read_u8, u8 }
+    read_primitive! { read_u16, u16 }
+    read_primitive! { read_u32, u32 }
+    read_primitive! { read_u64, u64 }
+    read_primitive! { read_int, int }
+    read_primitive! { read_i8, i8 }
+    read_primitive! { read_i16, i16 }
+    read_primitive! { read_i32, i32 }
+    read_primitive! { read_i64, i64 }
+
+    fn read_f32(&mut self) -> DecodeResult<f32> { self.read_f64().map(|x| x as f32) }
+
+    fn read_f64(&mut self) -> DecodeResult<f64> {
+        match self.pop() {
+            Some(Toml::Float(f)) => Ok(f),
+            Some(Toml::Integer(f)) => Ok(f as f64),
+            Some(Toml::String(s)) => match s.parse() {
+                Some(f) => Ok(f),
+                None => Err(ExpectedError("Float".to_string(), s)),
+            },
+            Some(value) => Err(ExpectedError("Float".to_string(), format!("{}", value))),
+            None => Err(ExpectedError("Float".to_string(), "nothing".to_string())),
+        }
+    }
+
+    fn read_bool(&mut self) -> DecodeResult<bool> {
+        expect!(self.pop(), Boolean)
+    }
+
+    fn read_char(&mut self) -> DecodeResult<char> {
+        let s = try!(self.read_str());
+        {
+            let mut it = s.chars();
+            match (it.next(), it.next()) {
+                // exactly one character
+                (Some(c), None) => return Ok(c),
+                _ => ()
+            }
+        }
+        Err(ExpectedError("single character string".to_string(), format!("{}", s)))
+    }
+
+    fn read_str(&mut self) -> DecodeResult<string::String> {
+        match self.pop() {
+            Some(Toml::Datetime(s)) => Ok(s),
+            value => expect!(value, String),
+        }
+    }
+
+    fn read_enum<T, F>(&mut self, _name: &str, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        f(self)
+    }
+
+    fn read_enum_variant<T, F>(&mut self, names: &[&str],
+                               mut f: F) -> DecodeResult<T>
+        where F: FnMut(&mut Decoder, uint) -> DecodeResult<T>,
+    {
+        let name = match self.pop() {
+            Some(Toml::String(s)) => s,
+            Some(Toml::Table(mut t)) => {
+                let n = match t.remove("variant") {
+                    Some(Toml::String(s)) => s,
+                    Some(val) => {
+                        return Err(ExpectedError("String".to_string(), format!("{}", val)))
+                    }
+                    None => {
+                        return Err(MissingFieldError("variant".to_string()))
+                    }
+                };
+                match t.remove("fields") {
+                    Some(Toml::Array(l)) => {
+                        for field in l.into_iter().rev() {
+                            self.stack.push(Some(field));
+                        }
+                    },
+                    Some(val) => {
+                        return Err(ExpectedError("Array".to_string(), format!("{}", val)))
+                    }
+                    None => {}
+                }
+                n
+            }
+            Some(value) => {
+                return Err(ExpectedError("String or Table".to_string(), format!("{}", value)))
+            }
+            None => {
+                return Err(ExpectedError("String or Table".to_string(), "nothing".to_string()))
+            }
+        };
+        let idx = match names.iter().position(|n| *n == name[]) {
+            Some(idx) => idx,
+            None => return Err(UnknownVariantError(name))
+        };
+        f(self, idx)
+    }
+
+    fn read_enum_variant_arg<T, F>(&mut self, _idx: uint, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        f(self)
+    }
+
+    fn read_enum_struct_variant<T, F>(&mut self, names: &[&str], f: F) -> DecodeResult<T> where
+        F: FnMut(&mut Decoder, uint) -> DecodeResult<T>,
+    {
+        self.read_enum_variant(names, f)
+    }
+
+
+    fn read_enum_struct_variant_field<T, F>(&mut self,
+                                         _name: &str,
+                                         idx: uint,
+                                         f: F)
+                                         -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        self.read_enum_variant_arg(idx, f)
+    }
+
+    fn read_struct<T, F>(&mut self, _name: &str, _len: uint, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        let value = try!(f(self));
+        self.pop();
+        Ok(value)
+    }
+
+    fn read_struct_field<T, F>(&mut self,
+                               name: &str,
+                               _idx: uint,
+                               f: F)
+                               -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        let mut table = try!(expect!(self.pop(), Table));
+
+        let value = match table.remove(name) {
+            None => {
+                // Decode nothing, which succeeds for Option<_> and ().
+                self.stack.push(None);
+                match f(self) {
+                    Ok(x) => x,
+                    Err(_) => return Err(MissingFieldError(name.to_string())),
+                }
+            },
+            Some(toml) => {
+                self.stack.push(Some(toml));
+                try!(f(self))
+            }
+        };
+        self.stack.push(Some(Toml::Table(table)));
+        Ok(value)
+    }
+
+    fn read_struct_field_opt<T, F>(&mut self,
+                                   name: &str,
+                                   _idx: uint,
+                                   f: F)
+                                   -> DecodeResult<Option<T>> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        let mut table = try!(expect!(self.pop(), Table));
+
+        let value = match table.remove(name) {
+            None => None,
+            Some(toml) => {
+                self.stack.push(Some(toml));
+                Some(try!(f(self)))
+            }
+        };
+        self.stack.push(Some(Toml::Table(table)));
+        Ok(value)
+    }
+
+    fn read_tuple<T, F>(&mut self, tuple_len: uint, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        self.read_seq(move |d, len| {
+            if len == tuple_len {
+                f(d)
+            } else {
+                Err(ExpectedError(format!("Tuple{}", tuple_len), format!("Tuple{}", len)))
+            }
+        })
+    }
+
+    fn read_tuple_arg<T, F>(&mut self, idx: uint, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        self.read_seq_elt(idx, f)
+    }
+
+    fn read_tuple_struct<T, F>(&mut self,
+                               _name: &str,
+                               len: uint,
+                               f: F)
+                               -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        self.read_tuple(len, f)
+    }
+
+    fn read_tuple_struct_arg<T, F>(&mut self,
+                                   idx: uint,
+                                   f: F)
+                                   -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        self.read_tuple_arg(idx, f)
+    }
+
+    fn read_option<T, F>(&mut self, mut f: F) -> DecodeResult<T> where
+        F: FnMut(&mut Decoder, bool) -> DecodeResult<T>,
+    {
+        match self.pop() {
+            None => f(self, false),
+            value => { self.stack.push(value); f(self, true) }
+        }
+    }
+
+    fn read_seq<T, F>(&mut self, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder, uint) -> DecodeResult<T>,
+    {
+        let array = try!(expect!(self.pop(), Array));
+        let len = array.len();
+        for v in array.into_iter().rev() {
+            self.stack.push(Some(v));
+        }
+        f(self, len)
+    }
+
+    fn read_seq_elt<T, F>(&mut self, _idx: uint, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        f(self)
+    }
+
+    fn read_map<T, F>(&mut self, f: F) -> DecodeResult<T> where
+        F: FnOnce(&mut Decoder, uint) -> DecodeResult<T>,
+    {
+        let table = try!(expect!(self.pop(), Table));
+        let len = table.len();
+        for (key, value) in table.into_iter() {
+            self.stack.push(Some(value));
+            self.stack.push(Some(Toml::String(key)));
+        }
+        f(self, len)
+    }
+
+    fn read_map_elt_key<T, F>(&mut self, _idx: uint, f: F) -> DecodeResult<T> where
+       F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        f(self)
+    }
+
+    fn read_map_elt_val<T, F>(&mut self, _idx: uint, f: F) -> DecodeResult<T> where
+       F: FnOnce(&mut Decoder) -> DecodeResult<T>,
+    {
+        f(self)
+    }
+
+    fn error(&mut self, err: &str) -> DecoderError {
+        ApplicationError(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use self::Level::*;
+    use super::{Toml, from_str, decode};
+    use super::ErrorCode::*;
+    use super::ParserError::*;
+    use super::DecoderError::*;
+    use std::collections::BTreeMap;
+
+    fn parse(s: &str) -> Toml {
+        Toml::Table(from_str(s).unwrap())
+    }
+
+    fn table(items: &[(&str, Toml)]) -> Toml {
+        let mut t = BTreeMap::new();
+        for &(ref key, ref value) in items.iter() {
+            t.insert(key.to_string(), value.clone());
+        }
+        Toml::Table(t)
+    }
+
+    fn string(s: &str) -> Toml {
+        Toml::String(s.to_string())
+    }
+
+    #[test]
+    fn test_key_values() {
+        let doc = parse("# a comment\n\
+                         a = 1\n\
+                         b-c_d = \"two\" # trailing comment\n\
+                         \"quoted key\" = true\r\n\
+                         \n\
+                         e = 1979-05-27T07:32:00Z\n\
+                         f = 1979-05-27T00:32:00.999999-07:00");
+        assert_eq!(doc, table(&[
+            ("a", Toml::Integer(1)),
+            ("b-c_d", string("two")),
+            ("quoted key", Toml::Boolean(true)),
+            ("e", Toml::Datetime("1979-05-27T07:32:00Z".to_string())),
+            ("f", Toml::Datetime("1979-05-27T00:32:00.999999-07:00".to_string())),
+        ]));
+        assert_eq!(doc.lookup("e").and_then(|t| t.as_datetime()),
+                   Some("1979-05-27T07:32:00Z"));
+    }
+
+    #[test]
+    fn test_numbers() {
+        let doc = parse("a = +99\nb = -17\nc = 0\nd = 1_000_000\n\
+                         e = 3.1415\nf = -0.01\ng = 5e+22\nh = 6.626E-34\n\
+                         i = -9_223_372_036_854_775_808");
+        assert_eq!(doc.lookup("a"), Some(&Toml::Integer(99)));
+        assert_eq!(doc.lookup("b"), Some(&Toml::Integer(-17)));
+        assert_eq!(doc.lookup("c"), Some(&Toml::Integer(0)));
+        assert_eq!(doc.lookup("d"), Some(&Toml::Integer(1000000)));
+        assert_eq!(doc.lookup("e"), Some(&Toml::Float(3.1415)));
+        assert_eq!(doc.lookup("f"), Some(&Toml::Float(-0.01)));
+        assert_eq!(doc.lookup("g"), Some(&Toml::Float(5e22)));
+        assert_eq!(doc.lookup("h"), Some(&Toml::Float(6.626e-34)));
+        assert_eq!(doc.lookup("i"), Some(&Toml::Integer(::std::i64::MIN)));
+
+        for s in ["a = 01", "a = 1__0", "a = _1", "a = 1.", "a = .5", "a = 1e",
+                  "a = 9223372036854775808", "a = 1x"].iter() {
+            match from_str(*s) {
+                Err(SyntaxError(InvalidNumber, 1, 5)) => {}
+                other => panic!("`{}` parsed to {}", s, other),
+            }
+        }
+        assert_eq!(from_str("a = 1979-05-27"), Err(SyntaxError(InvalidDatetime, 1, 5)));
+    }
+
+    #[test]
+    fn test_strings() {
+        let doc = parse(r#"
+basic = "tab\there \"quoted\" \u00E9 \U0001F600 \\"
+literal = 'C:\Users\nodejs'
+multi = """
+Roses are red
+Violets are ""blue"""""#);
+        assert_eq!(doc.lookup("basic"),
+                   Some(&string("tab\there \"quoted\" \u{e9} \u{1F600} \\")));
+        assert_eq!(doc.lookup("literal"), Some(&string("C:\\Users\\nodejs")));
+        assert_eq!(doc.lookup("multi"),
+                   Some(&string("Roses are red\nViolets are \"\"blue\"\"")));
+
+        let doc = parse("a = \"\"\"\nThe quick brown \\\n\n   fox.\"\"\"\n\
+                         b = '''\nfirst\n  second'''\n\
+                         c = \"\"\n\
+                         d = ''");
+        assert_eq!(doc.lookup("a"), Some(&string("The quick brown fox.")));
+        assert_eq!(doc.lookup("b"), Some(&string("first\n  second")));
+        assert_eq!(doc.lookup("c"), Some(&string("")));
+        assert_eq!(doc.lookup("d"), Some(&string("")));
+
+        assert_eq!(from_str("a = \"x\ny\""), Err(SyntaxError(NewlineInString, 2, 1)));
+        assert_eq!(from_str("a = \"x"), Err(SyntaxError(EOFWhileParsingString, 1, 7)));
+        assert_eq!(from_str("a = \"\\q\""), Err(SyntaxError(InvalidEscape, 1, 7)));
+        assert_eq!(from_str("a = \"\\uD800\""),
+                   Err(SyntaxError(InvalidUnicodeCodePoint, 1, 12)));
+    }
+
+    #[test]
+    fn test_arrays_and_inline_tables() {
+        let doc = parse("a = [ 1, 2, 3 ]\n\
+                         b = [\n  \"x\", # first\n  \"y\",\n]\n\
+                         c = [ [1, 2], ['a'] ]\n\
+                         d = { x = 1, y = { z = true } }\n\
+                         e = []\n\
+                         f = {}");
+        assert_eq!(doc.lookup("a"), Some(&Toml::Array(vec![
+            Toml::Integer(1), Toml::Integer(2), Toml::Integer(3)])));
+        assert_eq!(doc.lookup("b"), Some(&Toml::Array(vec![string("x"), string("y")])));
+        assert_eq!(doc.lookup("c"), Some(&Toml::Array(vec![
+            Toml::Array(vec![Toml::Integer(1), Toml::Integer(2)]),
+            Toml::Array(vec![string("a")])])));
+        assert_eq!(doc.lookup("d.y.z"), Some(&Toml::Boolean(true)));
+        assert_eq!(doc.lookup("e"), Some(&Toml::Array(vec![])));
+        assert_eq!(doc.lookup("f"), Some(&table(&[])));
+
+        assert_eq!(from_str("a = [1, 2"), Err(SyntaxError(EOFWhileParsingArray, 1, 10)));
+        assert_eq!(from_str("a = [1 2]"), Err(SyntaxError(InvalidSyntax, 1, 8)));
+        assert_eq!(from_str("a = { x = 1, x = 2 }"), Err(SyntaxError(DuplicateKey, 1, 14)));
+        assert_eq!(from_str("a = { x = 1"), Err(SyntaxError(EOFWhileParsingTable, 1, 12)));
+    }
+
+    #[test]
+    fn test_tables() {
+        let doc = parse("title = \"example\"\n\
+                         [owner]\n\
+                         name = \"Tom\"\n\
+                         [servers.alpha]\n\
+                         ip = \"10.0.0.1\"\n\
+                         [ servers . \"beta\" ]\n\
+                         ip = \"10.0.0.2\"\n\
+                         [servers]\n\
+                         count = 2\n");
+        assert_eq!(doc.lookup("owner.name"), Some(&string("Tom")));
+        assert_eq!(doc.lookup("servers.alpha.ip"), Some(&string("10.0.0.1")));
+        assert_eq!(doc.lookup("servers.beta.ip"), Some(&string("10.0.0.2")));
+        assert_eq!(doc.lookup("servers.count"), Some(&Toml::Integer(2)));
+
+        let doc = parse("[[fruit]]\n\
+                         name = \"apple\"\n\
+                         [fruit.physical]\n\
+                         color = \"red\"\n\
+                         [[fruit.variety]]\n\
+                         name = \"red delicious\"\n\
+                         [[fruit.variety]]\n\
+                         name = \"granny smith\"\n\
+                         [[fruit]]\n\
+                         name = \"banana\"\n\
+                         [fruit.physical]\n\
+                         color = \"yellow\"\n");
+        let fruit = doc.lookup("fruit").and_then(|t| t.as_array()).unwrap();
+        assert_eq!(fruit.len(), 2);
+        assert_eq!(fruit[0].lookup("physical.color"), Some(&string("red")));
+        assert_eq!(fruit[0].lookup("variety").and_then(|t| t.as_array()).map(|a| a.len()),
+                   Some(2));
+        assert_eq!(fruit[1].lookup("name"), Some(&string("banana")));
+        assert_eq!(fruit[1].lookup("physical.color"), Some(&string("yellow")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(from_str("a = 1\na = 2"), Err(SyntaxError(DuplicateKey, 2, 1)));
+        assert_eq!(from_str("[a]\n[a]"), Err(SyntaxError(DuplicateTable, 2, 1)));
+        assert_eq!(from_str("a = 1\n[a.b]"), Err(SyntaxError(KeyIsNotATable, 2, 1)));
+        assert_eq!(from_str("a = [1]\n[[a]]"), Err(SyntaxError(DuplicateKey, 2, 1)));
+
+        // Inline tables and arrays can't be added to by headers.
+        assert_eq!(from_str("a = { x = 1 }\n[a]"), Err(SyntaxError(DuplicateKey, 2, 1)));
+        assert_eq!(from_str("a = { x = {} }\n[a.x]"), Err(SyntaxError(DuplicateKey, 2, 1)));
+        assert_eq!(from_str("[t]\na = {}\n[t.a.b]"), Err(SyntaxError(DuplicateKey, 3, 1)));
+        assert_eq!(from_str("a = [{}]\n[[a]]"), Err(SyntaxError(DuplicateKey, 2, 1)));
+        assert!(from_str("[[t]]\na = {}\n[[t]]\n[t.a]").is_ok());
+        assert_eq!(from_str("a = 1 b = 2"), Err(SyntaxError(ExpectedNewline, 1, 7)));
+        assert_eq!(from_str("a 1"), Err(SyntaxError(ExpectedEquals, 1, 3)));
+        assert_eq!(from_str("= 1"), Err(SyntaxError(ExpectedKey, 1, 1)));
+        assert_eq!(from_str("[a"), Err(SyntaxError(InvalidSyntax, 1, 3)));
+        assert_eq!(from_str("a = "), Err(SyntaxError(InvalidSyntax, 1, 5)));
+    }
+
+    #[test]
+    fn test_show() {
+        let doc = parse("a = [1, 2.5, \"x\\\"y\"]\nb = { c = true, \"d e\" = {} }");
+        assert_eq!(doc.to_string(),
+                   "{ a = [1, 2.5, \"x\\\"y\"], b = { c = true, \"d e\" = {} } }".to_string());
+    }
+
+    #[deriving(PartialEq, RustcDecodable, Show)]
+    enum Level {
+        Debug,
+        Info,
+        Custom(uint),
+    }
+
+    #[deriving(PartialEq, RustcDecodable, Show)]
+    struct Server {
+        ip: String,
+        port: u16,
+        level: Level,
+    }
+
+    #[deriving(PartialEq, RustcDecodable, Show)]
+    struct Config {
+        title: String,
+        started: String,
+        ratio: f64,
+        timeout: Option<uint>,
+        tags: Vec<String>,
+        pair: (int, char),
+        servers: Vec<Server>,
+        limits: BTreeMap<String, u32>,
+        by_id: BTreeMap<uint, Level>,
+    }
+
+    #[test]
+    fn test_decode() {
+        let config: Config = decode(r#"
+title = "example"
+started = 1979-05-27T07:32:00Z
+ratio = 2
+tags = ["a", "b"]
+pair = [-3, "x"]
+
+[[servers]]
+ip = "10.0.0.1"
+port = 8080
+level = "Debug"
+
+[[servers]]
+ip = "10.0.0.2"
+port = 8081
+level = { variant = "Custom", fields = [3] }
+
+[limits]
+files = 1024
+procs = 64
+
+[by_id]
+1 = "Info"
+"#).unwrap();
+
+        let mut limits = BTreeMap::new();
+        limits.insert("files".to_string(), 1024);
+        limits.insert("procs".to_string(), 64);
+        let mut by_id = BTreeMap::new();
+        by_id.insert(1, Info);
+        assert_eq!(config, Config {
+            title: "example".to_string(),
+            started: "1979-05-27T07:32:00Z".to_string(),
+            ratio: 2.0,
+            timeout: None,
+            tags: vec!["a".to_string(), "b".to_string()],
+            pair: (-3, 'x'),
+            servers: vec![
+                Server { ip: "10.0.0.1".to_string(), port: 8080, level: Debug },
+                Server { ip: "10.0.0.2".to_string(), port: 8081, level: Custom(3) },
+            ],
+            limits: limits,
+            by_id: by_id,
+        });
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let res: Result<Server, _> = decode("ip = \"x\"\nport = 70000\nlevel = \"Info\"");
+        assert_eq!(res, Err(ExpectedError("Integer".to_string(), "70000".to_string())));
+        let res: Result<Server, _> = decode("ip = \"x\"\nlevel = \"Info\"");
+        assert_eq!(res, Err(MissingFieldError("port".to_string())));
+        let res: Result<Server, _> = decode("ip = 1\nport = 1\nlevel = \"Info\"");
+        assert_eq!(res, Err(ExpectedError("String".to_string(), "1".to_string())));
+        let res: Result<Server, _> = decode("ip = \"x\"\nport = 1\nlevel = \"Warn\"");
+        assert_eq!(res, Err(UnknownVariantError("Warn".to_string())));
+        let res: Result<Server, _> = decode("ip = \"x\"\nport = 1\nlevel = [1]");
+        assert_eq!(res, Err(ExpectedError("String or Table".to_string(), "[1]".to_string())));
+        let res: Result<Server, _> = decode("a = ");
+        assert_eq!(res, Err(ParseError(SyntaxError(InvalidSyntax, 1, 5))));
+    }
+}
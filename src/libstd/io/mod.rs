@@ -246,6 +246,7 @@ use slice::SliceExt;
 use str::StrExt;
 use str;
 use string::String;
+use time::Duration;
 use uint;
 use unicode;
 use unicode::char::UnicodeChar;
@@ -985,6 +986,22 @@ impl<'a, R: Buffer> Buffer for RefReader<'a, R> {
     fn consume(&mut self, amt: uint) { self.inner.consume(amt) }
 }
 
+/// Converts a timeout to the milliseconds the system layer takes. A zero or
+/// negative timeout expires immediately, and a positive one lasts at least a
+/// millisecond.
+fn timeout_ms(timeout: Option<Duration>) -> Option<u64> {
+    timeout.map(|d| {
+        let ms = d.num_milliseconds();
+        if d <= Duration::zero() {
+            0
+        } else if ms == 0 {
+            1
+        } else {
+            ms as u64
+        }
+    })
+}
+
 fn extend_sign(val: u64, nbytes: uint) -> i64 {
     let shift = (8 - nbytes) * 8;
     (val << shift) as i64 >> shift
@@ -1941,8 +1958,9 @@ impl fmt::Show for FilePermission {
 #[cfg(test)]
 mod tests {
     use self::BadReaderBehavior::*;
-    use super::{IoResult, Reader, MemReader, NoProgress, InvalidInput, Writer};
-    use prelude::v1::{Ok, Vec, Buffer, SliceExt};
+    use super::{IoResult, Reader, MemReader, NoProgress, InvalidInput, Writer, timeout_ms};
+    use prelude::v1::{Ok, Vec, Buffer, SliceExt, Some, None};
+    use time::Duration;
     use uint;
 
     #[deriving(Clone, PartialEq, Show)]
@@ -2055,6 +2073,16 @@ mod tests {
         assert_eq!(format!("{}", USER_READ | USER_WRITE | OTHER_WRITE), "0602");
     }
 
+    #[test]
+    fn test_timeout_ms() {
+        assert_eq!(timeout_ms(None), None);
+        assert_eq!(timeout_ms(Some(Duration::seconds(-1))), Some(0));
+        assert_eq!(timeout_ms(Some(Duration::zero())), Some(0));
+        assert_eq!(timeout_ms(Some(Duration::nanoseconds(1))), Some(1));
+        assert_eq!(timeout_ms(Some(Duration::microseconds(1500))), Some(1));
+        assert_eq!(timeout_ms(Some(Duration::seconds(2))), Some(2000));
+    }
+
     fn _ensure_buffer_is_object_safe<T: Buffer>(x: &T) -> &Buffer {
         x as &Buffer
     }
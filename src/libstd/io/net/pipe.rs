@@ -23,7 +23,7 @@
 use prelude::v1::*;
 
 use c_str::ToCStr;
-use io::{Listener, Acceptor, IoResult, TimedOut, standard_error};
+use io::{Listener, Acceptor, IoResult, TimedOut, standard_error, timeout_ms};
 use sys::pipe::UnixAcceptor as UnixAcceptorImp;
 use sys::pipe::UnixListener as UnixListenerImp;
 use sys::pipe::UnixStream as UnixStreamImp;
@@ -104,24 +104,24 @@ impl UnixStream {
     ///
     /// For more information, see `TcpStream::set_timeout`
     #[experimental = "the timeout argument may change in type and value"]
-    pub fn set_timeout(&mut self, timeout_ms: Option<u64>) {
-        self.inner.set_timeout(timeout_ms)
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_timeout(timeout_ms(timeout))
     }
 
     /// Sets the read timeout for this socket.
     ///
     /// For more information, see `TcpStream::set_timeout`
     #[experimental = "the timeout argument may change in type and value"]
-    pub fn set_read_timeout(&mut self, timeout_ms: Option<u64>) {
-        self.inner.set_read_timeout(timeout_ms)
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_read_timeout(timeout_ms(timeout))
     }
 
     /// Sets the write timeout for this socket.
     ///
     /// For more information, see `TcpStream::set_timeout`
     #[experimental = "the timeout argument may change in type and value"]
-    pub fn set_write_timeout(&mut self, timeout_ms: Option<u64>) {
-        self.inner.set_write_timeout(timeout_ms)
+    pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_write_timeout(timeout_ms(timeout))
     }
 }
 
@@ -206,18 +206,18 @@ impl UnixAcceptor {
     /// Sets a timeout for this acceptor, after which accept() will no longer
     /// block indefinitely.
     ///
-    /// The argument specified is the amount of time, in milliseconds, into the
-    /// future after which all invocations of accept() will not block (and any
-    /// pending invocation will return). A value of `None` will clear any
-    /// existing timeout.
+    /// The argument specified is the amount of time into the future after
+    /// which all invocations of accept() will not block (and any pending
+    /// invocation will return). A value of `None` will clear any existing
+    /// timeout.
     ///
     /// When using this method, it is likely necessary to reset the timeout as
     /// appropriate, the timeout specified is specific to this object, not
     /// specific to the next request.
     #[experimental = "the name and arguments to this function are likely \
                       to change"]
-    pub fn set_timeout(&mut self, timeout_ms: Option<u64>) {
-        self.inner.set_timeout(timeout_ms)
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_timeout(timeout_ms(timeout))
     }
 
     /// Closes the accepting capabilities of this acceptor.
@@ -531,7 +531,7 @@ mod tests {
         let addr = next_test_unix();
         let mut a = UnixListener::bind(&addr).unwrap().listen().unwrap();
 
-        a.set_timeout(Some(10));
+        a.set_timeout(Some(Duration::milliseconds(10)));
 
         // Make sure we time out once and future invocations also time out
         let err = a.accept().err().unwrap();
@@ -670,11 +670,11 @@ mod tests {
         }).detach();
 
         let mut s = a.accept().unwrap();
-        s.set_timeout(Some(20));
+        s.set_timeout(Some(Duration::milliseconds(20)));
         assert_eq!(s.read(&mut [0]).err().unwrap().kind, TimedOut);
         assert_eq!(s.read(&mut [0]).err().unwrap().kind, TimedOut);
 
-        s.set_timeout(Some(20));
+        s.set_timeout(Some(Duration::milliseconds(20)));
         for i in range(0u, 1001) {
             match s.write(&[0; 128 * 1024]) {
                 Ok(()) | Err(IoError { kind: ShortWrite(..), .. }) => {},
@@ -714,7 +714,7 @@ mod tests {
         }).detach();
 
         let mut s = a.accept().unwrap();
-        s.set_read_timeout(Some(20));
+        s.set_read_timeout(Some(Duration::milliseconds(20)));
         assert_eq!(s.read(&mut [0]).err().unwrap().kind, TimedOut);
         assert_eq!(s.read(&mut [0]).err().unwrap().kind, TimedOut);
 
@@ -737,7 +737,7 @@ mod tests {
         }).detach();
 
         let mut s = a.accept().unwrap();
-        s.set_write_timeout(Some(20));
+        s.set_write_timeout(Some(Duration::milliseconds(20)));
         for i in range(0u, 1001) {
             match s.write(&[0; 128 * 1024]) {
                 Ok(()) | Err(IoError { kind: ShortWrite(..), .. }) => {},
@@ -772,7 +772,7 @@ mod tests {
             tx2.send(()).unwrap();
         });
 
-        s.set_read_timeout(Some(20));
+        s.set_read_timeout(Some(Duration::milliseconds(20)));
         assert_eq!(s.read(&mut [0]).err().unwrap().kind, TimedOut);
         tx.send(()).unwrap();
 
@@ -22,7 +22,7 @@ use io::IoResult;
 use result::Result::Err;
 use io::net::ip::{SocketAddr, ToSocketAddr};
 use io::{Reader, Writer, Listener, Acceptor};
-use io::{standard_error, TimedOut};
+use io::{standard_error, timeout_ms, TimedOut};
 use option::Option;
 use option::Option::{None, Some};
 use time::Duration;
@@ -170,14 +170,14 @@ impl TcpStream {
         self.inner.close_write()
     }
 
-    /// Sets a timeout, in milliseconds, for blocking operations on this stream.
+    /// Sets a timeout for blocking operations on this stream.
     ///
     /// This function will set a timeout for all blocking operations (including
     /// reads and writes) on this stream. The timeout specified is a relative
-    /// time, in milliseconds, into the future after which point operations will
-    /// time out. This means that the timeout must be reset periodically to keep
-    /// it from expiring. Specifying a value of `None` will clear the timeout
-    /// for this stream.
+    /// time into the future after which point operations will time out. This
+    /// means that the timeout must be reset periodically to keep it from
+    /// expiring. Specifying a value of `None` will clear the timeout for this
+    /// stream.
     ///
     /// The timeout on this stream is local to this stream only. Setting a
     /// timeout does not affect any other cloned instances of this stream, nor
@@ -188,8 +188,8 @@ impl TcpStream {
     /// For clarification on the semantics of interrupting a read and a write,
     /// take a look at `set_read_timeout` and `set_write_timeout`.
     #[experimental = "the timeout argument may change in type and value"]
-    pub fn set_timeout(&mut self, timeout_ms: Option<u64>) {
-        self.inner.set_timeout(timeout_ms)
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_timeout(timeout_ms(timeout))
     }
 
     /// Sets the timeout for read operations on this stream.
@@ -205,8 +205,8 @@ impl TcpStream {
     /// promptly return. If a timeout error is returned, then no data was read
     /// during the timeout period.
     #[experimental = "the timeout argument may change in type and value"]
-    pub fn set_read_timeout(&mut self, timeout_ms: Option<u64>) {
-        self.inner.set_read_timeout(timeout_ms)
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_read_timeout(timeout_ms(timeout))
     }
 
     /// Sets the timeout for write operations on this stream.
@@ -232,8 +232,8 @@ impl TcpStream {
     /// operation. It may be the case that bytes continue to be written in an
     /// asynchronous fashion after the call to write returns.
     #[experimental = "the timeout argument may change in type and value"]
-    pub fn set_write_timeout(&mut self, timeout_ms: Option<u64>) {
-        self.inner.set_write_timeout(timeout_ms)
+    pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_write_timeout(timeout_ms(timeout))
     }
 }
 
@@ -353,18 +353,16 @@ pub struct TcpAcceptor {
 }
 
 impl TcpAcceptor {
-    /// Prevents blocking on all future accepts after `ms` milliseconds have
-    /// elapsed.
+    /// Prevents blocking on all future accepts after `timeout` has elapsed.
     ///
     /// This function is used to set a deadline after which this acceptor will
     /// time out accepting any connections. The argument is the relative
-    /// distance, in milliseconds, to a point in the future after which all
-    /// accepts will fail.
+    /// distance to a point in the future after which all accepts will fail.
     ///
     /// If the argument specified is `None`, then any previously registered
     /// timeout is cleared.
     ///
-    /// A timeout of `0` can be used to "poll" this acceptor to see if it has
+    /// A zero timeout can be used to "poll" this acceptor to see if it has
     /// any pending connections. All pending connections will be accepted,
     /// regardless of whether the timeout has expired or not (the accept will
     /// not block in this case).
@@ -375,11 +373,12 @@ impl TcpAcceptor {
     /// # #![allow(experimental)]
     /// use std::io::TcpListener;
     /// use std::io::{Listener, Acceptor, TimedOut};
+    /// use std::time::Duration;
     ///
     /// let mut a = TcpListener::bind("127.0.0.1:8482").listen().unwrap();
     ///
     /// // After 100ms have passed, all accepts will fail
-    /// a.set_timeout(Some(100));
+    /// a.set_timeout(Some(Duration::milliseconds(100)));
     ///
     /// match a.accept() {
     ///     Ok(..) => println!("accepted a socket"),
@@ -388,7 +387,7 @@ impl TcpAcceptor {
     /// }
     ///
     /// // Reset the timeout and try again
-    /// a.set_timeout(Some(100));
+    /// a.set_timeout(Some(Duration::milliseconds(100)));
     /// let socket = a.accept();
     ///
     /// // Clear the timeout and block indefinitely waiting for a connection
@@ -397,7 +396,9 @@ impl TcpAcceptor {
     /// ```
     #[experimental = "the type of the argument and name of this function are \
                       subject to change"]
-    pub fn set_timeout(&mut self, ms: Option<u64>) { self.inner.set_timeout(ms); }
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_timeout(timeout_ms(timeout));
+    }
 
     /// Closes the accepting capabilities of this acceptor.
     ///
@@ -493,6 +494,7 @@ mod test {
     use io::{ConnectionRefused, BrokenPipe, ConnectionAborted};
     use io::{ConnectionReset, NotConnected, PermissionDenied, OtherIoError};
     use io::{Acceptor, Listener};
+    use time::Duration;
 
     // FIXME #11530 this fails on android because tests are run as root
     #[cfg_attr(any(windows, target_os = "android"), ignore)]
@@ -1138,7 +1140,7 @@ mod test {
         let addr = next_test_ip4();
         let mut a = TcpListener::bind(addr).unwrap().listen().unwrap();
 
-        a.set_timeout(Some(10));
+        a.set_timeout(Some(Duration::milliseconds(10)));
 
         // Make sure we time out once and future invocations also time out
         let err = a.accept().err().unwrap();
@@ -1253,11 +1255,11 @@ mod test {
         }).detach();
 
         let mut s = a.accept().unwrap();
-        s.set_timeout(Some(20));
+        s.set_timeout(Some(Duration::milliseconds(20)));
         assert_eq!(s.read(&mut [0]).err().unwrap().kind, TimedOut);
         assert_eq!(s.read(&mut [0]).err().unwrap().kind, TimedOut);
 
-        s.set_timeout(Some(20));
+        s.set_timeout(Some(Duration::milliseconds(20)));
         for i in range(0i, 1001) {
             match s.write(&[0; 128 * 1024]) {
                 Ok(()) | Err(IoError { kind: ShortWrite(..), .. }) => {},
@@ -1292,7 +1294,7 @@ mod test {
         }).detach();
 
         let mut s = a.accept().unwrap();
-        s.set_read_timeout(Some(20));
+        s.set_read_timeout(Some(Duration::milliseconds(20)));
         assert_eq!(s.read(&mut [0]).err().unwrap().kind, TimedOut);
         assert_eq!(s.read(&mut [0]).err().unwrap().kind, TimedOut);
 
@@ -1315,7 +1317,7 @@ mod test {
         }).detach();
 
         let mut s = a.accept().unwrap();
-        s.set_write_timeout(Some(20));
+        s.set_write_timeout(Some(Duration::milliseconds(20)));
         for i in range(0i, 1001) {
             match s.write(&[0; 128 * 1024]) {
                 Ok(()) | Err(IoError { kind: ShortWrite(..), .. }) => {},
@@ -1351,7 +1353,7 @@ mod test {
             tx2.send(()).unwrap();
         });
 
-        s.set_read_timeout(Some(20));
+        s.set_read_timeout(Some(Duration::milliseconds(20)));
         assert_eq!(s.read(&mut [0]).err().unwrap().kind, TimedOut);
         tx.send(()).unwrap();
 
@@ -17,12 +17,13 @@
 
 use clone::Clone;
 use io::net::ip::{SocketAddr, IpAddr, ToSocketAddr};
-use io::{Reader, Writer, IoResult};
+use io::{Reader, Writer, IoResult, timeout_ms};
 use ops::FnOnce;
 use option::Option;
 use result::Result::{Ok, Err};
 use sys::udp::UdpSocket as UdpSocketImp;
 use sys_common;
+use time::Duration;
 
 /// A User Datagram Protocol socket.
 ///
@@ -150,24 +151,24 @@ impl UdpSocket {
     ///
     /// For more information, see `TcpStream::set_timeout`
     #[experimental = "the timeout argument may change in type and value"]
-    pub fn set_timeout(&mut self, timeout_ms: Option<u64>) {
-        self.inner.set_timeout(timeout_ms)
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_timeout(timeout_ms(timeout))
     }
 
     /// Sets the read timeout for this socket.
     ///
     /// For more information, see `TcpStream::set_timeout`
     #[experimental = "the timeout argument may change in type and value"]
-    pub fn set_read_timeout(&mut self, timeout_ms: Option<u64>) {
-        self.inner.set_read_timeout(timeout_ms)
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_read_timeout(timeout_ms(timeout))
     }
 
     /// Sets the write timeout for this socket.
     ///
     /// For more information, see `TcpStream::set_timeout`
     #[experimental = "the timeout argument may change in type and value"]
-    pub fn set_write_timeout(&mut self, timeout_ms: Option<u64>) {
-        self.inner.set_write_timeout(timeout_ms)
+    pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+        self.inner.set_write_timeout(timeout_ms(timeout))
     }
 }
 
@@ -256,6 +257,7 @@ mod test {
     use io::{IoError, TimedOut, PermissionDenied, ShortWrite};
     use super::*;
     use thread::Thread;
+    use time::Duration;
 
     // FIXME #11530 this fails on android because tests are run as root
     #[cfg_attr(any(windows, target_os = "android"), ignore)]
@@ -575,7 +577,7 @@ mod test {
         });
 
         // Make sure that reads time out, but writes can continue
-        a.set_read_timeout(Some(20));
+        a.set_read_timeout(Some(Duration::milliseconds(20)));
         assert_eq!(a.recv_from(&mut [0]).err().unwrap().kind, TimedOut);
         assert_eq!(a.recv_from(&mut [0]).err().unwrap().kind, TimedOut);
         assert_eq!(a.send_to(&[0], addr2), Ok(()));
@@ -600,7 +602,7 @@ mod test {
         let mut a = UdpSocket::bind(addr1).unwrap();
         let _b = UdpSocket::bind(addr2).unwrap();
 
-        a.set_write_timeout(Some(1000));
+        a.set_write_timeout(Some(Duration::milliseconds(1000)));
         for _ in range(0u, 100) {
             match a.send_to(&[0;4*1024], addr2) {
                 Ok(()) | Err(IoError { kind: ShortWrite(..), .. }) => {},
@@ -33,6 +33,7 @@ use sys::fs::FileDesc;
 use sys::process::Process as ProcessImp;
 use sys;
 use thread::Thread;
+use time::Duration;
 
 #[cfg(windows)] use std::hash::sip::SipState;
 
@@ -630,11 +631,12 @@ impl Process {
         }
     }
 
-    /// Sets a timeout, in milliseconds, for future calls to wait().
+    /// Sets a timeout for future calls to wait().
     ///
-    /// The argument specified is a relative distance into the future, in
-    /// milliseconds, after which any call to wait() will return immediately
-    /// with a timeout error, and all future calls to wait() will not block.
+    /// The argument specified is a relative distance into the future after
+    /// which any call to wait() will return immediately with a timeout error,
+    /// and all future calls to wait() will not block. A positive timeout
+    /// lasts at least a millisecond.
     ///
     /// A value of `None` will clear any previous timeout, and a value of `Some`
     /// will override any previously set timeout.
@@ -645,12 +647,13 @@ impl Process {
     /// # #![allow(experimental)]
     /// use std::io::{Command, IoResult};
     /// use std::io::process::ProcessExit;
+    /// use std::time::Duration;
     ///
     /// fn run_gracefully(prog: &str) -> IoResult<ProcessExit> {
     ///     let mut p = try!(Command::new("long-running-process").spawn());
     ///
     ///     // give the process 10 seconds to finish completely
-    ///     p.set_timeout(Some(10_000));
+    ///     p.set_timeout(Some(Duration::seconds(10)));
     ///     match p.wait() {
     ///         Ok(status) => return Ok(status),
     ///         Err(..) => {}
@@ -658,7 +661,7 @@ impl Process {
     ///
     ///     // Attempt to exit gracefully, but don't wait for it too long
     ///     try!(p.signal_exit());
-    ///     p.set_timeout(Some(1_000));
+    ///     p.set_timeout(Some(Duration::seconds(1)));
     ///     match p.wait() {
     ///         Ok(status) => return Ok(status),
     ///         Err(..) => {}
@@ -671,8 +674,8 @@ impl Process {
     /// }
     /// ```
     #[experimental = "the type of the timeout is likely to change"]
-    pub fn set_timeout(&mut self, timeout_ms: Option<u64>) {
-        self.deadline = timeout_ms.map(|i| i + sys::timer::now()).unwrap_or(0);
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.deadline = io::timeout_ms(timeout).map(|i| i + sys::timer::now()).unwrap_or(0);
     }
 
     /// Simultaneously wait for the child to exit and collect all remaining
@@ -753,6 +756,7 @@ mod tests {
     use super::{InheritFd, Process, PleaseExitSignal, Command, ProcessOutput};
     use sync::mpsc::channel;
     use thread::Thread;
+use time::Duration;
     use time::Duration;
 
     // FIXME(#10380) these tests should not all be ignored on android.
@@ -1145,7 +1149,7 @@ mod tests {
     #[test]
     fn wait_timeout() {
         let mut p = sleeper();
-        p.set_timeout(Some(10));
+        p.set_timeout(Some(Duration::milliseconds(10)));
         assert_eq!(p.wait().err().unwrap().kind, TimedOut);
         assert_eq!(p.wait().err().unwrap().kind, TimedOut);
         p.signal_kill().unwrap();
@@ -1159,14 +1163,14 @@ mod tests {
         let tx2 = tx.clone();
         let _t = Thread::spawn(move|| {
             let mut p = sleeper();
-            p.set_timeout(Some(10));
+            p.set_timeout(Some(Duration::milliseconds(10)));
             assert_eq!(p.wait().err().unwrap().kind, TimedOut);
             p.signal_kill().unwrap();
             tx.send(()).unwrap();
         });
         let _t = Thread::spawn(move|| {
             let mut p = sleeper();
-            p.set_timeout(Some(10));
+            p.set_timeout(Some(Duration::milliseconds(10)));
             assert_eq!(p.wait().err().unwrap().kind, TimedOut);
             p.signal_kill().unwrap();
             tx2.send(()).unwrap();
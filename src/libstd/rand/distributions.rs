@@ -0,0 +1,108 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sampling from random distributions.
+//!
+//! This is a generalization of `Rand` to allow parameters to control the
+//! exact properties of the generated values, e.g. the mean and standard
+//! deviation of a normal distribution. The `Sample` trait is the most
+//! general, and allows for generating values that change some state
+//! internally. The `IndependentSample` trait is for generating values
+//! that do not need to record state.
+
+#![experimental]
+
+use iter::{repeat, IteratorExt};
+use rand::Rng;
+use slice::AsSlice;
+use vec::Vec;
+
+pub use core_rand::distributions::{Sample, IndependentSample, RandSample};
+pub use core_rand::distributions::{Weighted, WeightedChoice, WeightedIndex};
+pub use core_rand::distributions::{Range, Gamma, ChiSquared, FisherF, StudentT, Beta};
+pub use core_rand::distributions::{Normal, LogNormal, Exp, Weibull};
+pub use core_rand::distributions::{Bernoulli, Binomial, Poisson, Zipf};
+pub use core_rand::distributions::{range, gamma, normal, exponential};
+pub use core_rand::distributions::{binomial, poisson, zipf};
+
+/// The Dirichlet distribution `Dir(alpha)`, over vectors of
+/// non-negative numbers that sum to 1.
+///
+/// This owns its parameters and returns each sample as a new vector;
+/// `gamma::Dirichlet` borrows them and writes samples into a slice.
+///
+/// # Example
+///
+/// ```rust
+/// use std::rand;
+/// use std::rand::distributions::{Dirichlet, IndependentSample};
+///
+/// let dir = Dirichlet::new(vec![1.0, 2.0, 3.0]);
+/// let v = dir.ind_sample(&mut rand::thread_rng());
+/// println!("{} is from a Dir([1, 2, 3]) distribution", v)
+/// ```
+pub struct Dirichlet {
+    alpha: Vec<f64>,
+}
+
+impl Dirichlet {
+    /// Create a new Dirichlet distribution with the concentration
+    /// parameters `alpha`. Panics if `alpha` has fewer than 2
+    /// elements or any of them is not positive.
+    pub fn new(alpha: Vec<f64>) -> Dirichlet {
+        gamma::Dirichlet::new(alpha.as_slice());
+        Dirichlet { alpha: alpha }
+    }
+}
+
+impl Sample<Vec<f64>> for Dirichlet {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> Vec<f64> { self.ind_sample(rng) }
+}
+
+impl IndependentSample<Vec<f64>> for Dirichlet {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> Vec<f64> {
+        let mut v: Vec<f64> = repeat(0.0).take(self.alpha.len()).collect();
+        gamma::Dirichlet::new(self.alpha.as_slice()).ind_sample(rng, v.as_mut_slice());
+        v
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use prelude::v1::*;
+
+    use num::Float;
+    use rand;
+    use super::{Dirichlet, Sample, IndependentSample};
+
+    #[test]
+    fn test_dirichlet() {
+        let mut dir = Dirichlet::new(vec![0.1, 1.0, 2.0, 30.0]);
+        let mut rng = rand::thread_rng();
+        for _ in range(0u, 1000) {
+            let v = dir.sample(&mut rng);
+            assert_eq!(v.len(), 4);
+            assert!(v.iter().all(|&x| x >= 0.0));
+            let sum = v.iter().fold(0.0, |a, &b| a + b);
+            assert!((sum - 1.0).abs() < 1e-10);
+            dir.ind_sample(&mut rng);
+        }
+    }
+    #[test]
+    #[should_fail]
+    fn test_dirichlet_too_few_params() {
+        Dirichlet::new(vec![1.0]);
+    }
+    #[test]
+    #[should_fail]
+    fn test_dirichlet_invalid_param() {
+        Dirichlet::new(vec![1.0, 0.0, 1.0]);
+    }
+}
@@ -237,9 +237,11 @@ use core_rand::Isaac64Rng as IsaacWordRng;
 
 pub use core_rand::{Rand, Rng, SeedableRng, Open01, Closed01};
 pub use core_rand::{XorShiftRng, IsaacRng, Isaac64Rng, ChaChaRng};
-pub use core_rand::{distributions, reseeding};
+pub use core_rand::{Pcg32Rng, Xoroshiro128Rng};
+pub use core_rand::reseeding;
 pub use rand::os::OsRng;
 
+pub mod distributions;
 pub mod os;
 pub mod reader;
 
@@ -619,6 +621,7 @@ mod bench {
 
     use self::test::Bencher;
     use super::{XorShiftRng, StdRng, IsaacRng, Isaac64Rng, Rng, RAND_BENCH_N};
+    use super::{Pcg32Rng, Xoroshiro128Rng};
     use super::{OsRng, weak_rng};
     use mem::size_of;
 
@@ -633,6 +636,28 @@ mod bench {
         b.bytes = size_of::<uint>() as u64 * RAND_BENCH_N;
     }
 
+    #[bench]
+    fn rand_pcg32(b: &mut Bencher) {
+        let mut rng: Pcg32Rng = OsRng::new().unwrap().gen();
+        b.iter(|| {
+            for _ in range(0, RAND_BENCH_N) {
+                rng.gen::<uint>();
+            }
+        });
+        b.bytes = size_of::<uint>() as u64 * RAND_BENCH_N;
+    }
+
+    #[bench]
+    fn rand_xoroshiro128(b: &mut Bencher) {
+        let mut rng: Xoroshiro128Rng = OsRng::new().unwrap().gen();
+        b.iter(|| {
+            for _ in range(0, RAND_BENCH_N) {
+                rng.gen::<uint>();
+            }
+        });
+        b.bytes = size_of::<uint>() as u64 * RAND_BENCH_N;
+    }
+
     #[bench]
     fn rand_isaac(b: &mut Bencher) {
         let mut rng: IsaacRng = OsRng::new().unwrap().gen();
@@ -29,7 +29,8 @@ mod imp {
     use os::errno;
 
     #[cfg(all(target_os = "linux",
-              any(target_arch = "x86_64", target_arch = "x86", target_arch = "arm")))]
+              any(target_arch = "x86_64", target_arch = "x86", target_arch = "arm",
+                  target_arch = "mips", target_arch = "mipsel")))]
     fn getrandom(buf: &mut [u8]) -> libc::c_long {
         extern "C" {
             fn syscall(number: libc::c_long, ...) -> libc::c_long;
@@ -41,6 +42,8 @@ mod imp {
         const NR_GETRANDOM: libc::c_long = 355;
         #[cfg(target_arch = "arm")]
         const NR_GETRANDOM: libc::c_long = 384;
+        #[cfg(any(target_arch = "mips", target_arch = "mipsel"))]
+        const NR_GETRANDOM: libc::c_long = 4353;
 
         unsafe {
             syscall(NR_GETRANDOM, buf.as_mut_ptr(), buf.len(), 0u)
@@ -48,7 +51,8 @@ mod imp {
     }
 
     #[cfg(not(all(target_os = "linux",
-                  any(target_arch = "x86_64", target_arch = "x86", target_arch = "arm"))))]
+                  any(target_arch = "x86_64", target_arch = "x86", target_arch = "arm",
+                      target_arch = "mips", target_arch = "mipsel"))))]
     fn getrandom(_buf: &mut [u8]) -> libc::c_long { -1 }
 
     fn getrandom_fill_bytes(v: &mut [u8]) {
@@ -82,7 +86,8 @@ mod imp {
     }
 
     #[cfg(all(target_os = "linux",
-              any(target_arch = "x86_64", target_arch = "x86", target_arch = "arm")))]
+              any(target_arch = "x86_64", target_arch = "x86", target_arch = "arm",
+                  target_arch = "mips", target_arch = "mipsel")))]
     fn is_getrandom_available() -> bool {
         use sync::atomic::{AtomicBool, ATOMIC_BOOL_INIT, Relaxed};
 
@@ -93,8 +98,10 @@ mod imp {
             let mut buf: [u8; 0] = [];
             let result = getrandom(&mut buf);
             let available = if result == -1 {
+                // Sandboxes such as seccomp filters may refuse system calls
+                // they don't know about with EPERM rather than ENOSYS.
                 let err = errno() as libc::c_int;
-                err != libc::ENOSYS
+                err != libc::ENOSYS && err != libc::EPERM
             } else {
                 true
             };
@@ -107,19 +114,18 @@ mod imp {
     }
 
     #[cfg(not(all(target_os = "linux",
-                  any(target_arch = "x86_64", target_arch = "x86", target_arch = "arm"))))]
+                  any(target_arch = "x86_64", target_arch = "x86", target_arch = "arm",
+                      target_arch = "mips", target_arch = "mipsel"))))]
     fn is_getrandom_available() -> bool { false }
 
     /// A random number generator that retrieves randomness straight from
-    /// the operating system. Platform sources:
+    /// the operating system. On Linux it calls the `getrandom(2)` system call
+    /// if the kernel provides it, and reads from `/dev/urandom` if not; other
+    /// Unix-like systems (Android, Mac OSX, BSDs) read directly from
+    /// `/dev/urandom`.
     ///
-    /// - Unix-like systems (Linux, Android, Mac OSX): read directly from
-    ///   `/dev/urandom`, or from `getrandom(2)` system call if available.
-    /// - Windows: calls `CryptGenRandom`, using the default cryptographic
-    ///   service provider with the `PROV_RSA_FULL` type.
-    /// - iOS: calls SecRandomCopyBytes as /dev/(u)random is sandboxed.
-    ///
-    /// This does not block.
+    /// This does not block, except that `getrandom(2)` waits for the kernel's
+    /// entropy pool to be initialized shortly after booting.
     pub struct OsRng {
         inner: OsRngInner,
     }
@@ -178,16 +184,7 @@ mod imp {
     use self::libc::{c_int, size_t};
     use slice::SliceExt;
 
-    /// A random number generator that retrieves randomness straight from
-    /// the operating system. Platform sources:
-    ///
-    /// - Unix-like systems (Linux, Android, Mac OSX): read directly from
-    ///   `/dev/urandom`, or from `getrandom(2)` system call if available.
-    /// - Windows: calls `CryptGenRandom`, using the default cryptographic
-    ///   service provider with the `PROV_RSA_FULL` type.
-    /// - iOS: calls SecRandomCopyBytes as /dev/(u)random is sandboxed.
-    ///
-    /// This does not block.
+    /// A random number generator that retrieves randomness from `SecRandomCopyBytes`.
     #[allow(missing_copy_implementations)]
     pub struct OsRng {
         // dummy field to ensure that this struct cannot be constructed outside of this module
@@ -253,16 +250,7 @@ mod imp {
 
     type HCRYPTPROV = LONG_PTR;
 
-    /// A random number generator that retrieves randomness straight from
-    /// the operating system. Platform sources:
-    ///
-    /// - Unix-like systems (Linux, Android, Mac OSX): read directly from
-    ///   `/dev/urandom`, or from `getrandom(2)` system call if available.
-    /// - Windows: calls `CryptGenRandom`, using the default cryptographic
-    ///   service provider with the `PROV_RSA_FULL` type.
-    /// - iOS: calls SecRandomCopyBytes as /dev/(u)random is sandboxed.
-    ///
-    /// This does not block.
+    /// A random number generator that retrieves randomness from `CryptGenRandom`.
     pub struct OsRng {
         hcryptprov: HCRYPTPROV
     }
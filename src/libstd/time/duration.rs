@@ -139,9 +139,9 @@ impl Duration {
     /// Runs a closure, returning the duration of time it took to run the
     /// closure.
     pub fn span<F>(f: F) -> Duration where F: FnOnce() {
-        let before = super::precise_time_ns();
+        let before = super::Instant::now();
         f();
-        Duration::nanoseconds((super::precise_time_ns() - before) as i64)
+        before.elapsed()
     }
 
     /// Returns the total number of whole weeks in the duration.
@@ -0,0 +1,114 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Monotonic time
+
+#![experimental]
+
+use fmt;
+use ops::{Add, Sub};
+use super::Duration;
+
+/// A reading of a monotonically nondecreasing clock.
+///
+/// Instants are opaque: they can only be compared with each other or
+/// subtracted to find the time between them. Unlike `SystemTime` they are
+/// unaffected by changes to the system clock, which makes them suitable for
+/// measuring elapsed time and computing timeouts.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Instant;
+///
+/// let start = Instant::now();
+/// // ... do some work ...
+/// println!("took {}", start.elapsed());
+/// ```
+#[deriving(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Instant {
+    t: Duration, // since an unspecified point in the past
+}
+
+impl Instant {
+    /// Returns the current reading of the monotonic clock.
+    pub fn now() -> Instant {
+        Instant { t: Duration::nanoseconds(super::precise_time_ns() as i64) }
+    }
+
+    /// Returns the time elapsed from `earlier` to this instant. The result is
+    /// negative if `earlier` is actually later.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        self.t - earlier.t
+    }
+
+    /// Returns the time elapsed since this instant was taken.
+    pub fn elapsed(&self) -> Duration {
+        Instant::now().duration_since(*self)
+    }
+}
+
+impl Add<Duration, Instant> for Instant {
+    fn add(self, rhs: Duration) -> Instant {
+        Instant { t: self.t + rhs }
+    }
+}
+
+impl Sub<Duration, Instant> for Instant {
+    fn sub(self, rhs: Duration) -> Instant {
+        Instant { t: self.t - rhs }
+    }
+}
+
+impl Sub<Instant, Duration> for Instant {
+    fn sub(self, rhs: Instant) -> Duration {
+        self.duration_since(rhs)
+    }
+}
+
+impl fmt::Show for Instant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Instant({})", self.t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Instant;
+    use time::Duration;
+
+    #[test]
+    fn test_monotonic() {
+        let a = Instant::now();
+        let b = Instant::now();
+        assert!(b >= a);
+        assert!(b - a >= Duration::zero());
+        assert!(a.elapsed() >= Duration::zero());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Instant::now();
+        let d = Duration::milliseconds(1500);
+        assert_eq!((a + d) - a, d);
+        assert_eq!(a - (a + d), -d);
+        assert_eq!(a + d - d, a);
+        assert!(a + d > a);
+    }
+
+    #[test]
+    fn test_sleep_elapsed() {
+        use io::timer;
+
+        let start = Instant::now();
+        timer::sleep(Duration::milliseconds(20));
+        assert!(start.elapsed() >= Duration::milliseconds(20));
+    }
+}
@@ -13,8 +13,12 @@
 use libc;
 
 pub use self::duration::Duration;
+pub use self::instant::Instant;
+pub use self::system::{SystemTime, UNIX_EPOCH};
 
 pub mod duration;
+pub mod instant;
+pub mod system;
 
 /// Returns the current value of a high-resolution performance counter
 /// in nanoseconds since an unspecified epoch.
@@ -63,6 +67,40 @@ fn precise_time_ns() -> u64 {
     }
 }
 
+/// Returns the current wall clock time as seconds and nanoseconds since
+/// 1970-01-01T00:00:00Z.
+fn real_time() -> (i64, i32) {
+    return unsafe { os_real_time() };
+
+    #[cfg(windows)]
+    unsafe fn os_real_time() -> (i64, i32) {
+        const HECTONANOS_FROM_1601_TO_1970: u64 = 116444736000000000;
+
+        let mut time = libc::FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
+        libc::GetSystemTimeAsFileTime(&mut time);
+
+        // A FILETIME counts 100ns intervals since 1601-01-01T00:00:00Z.
+        let since_1601 = ((time.dwHighDateTime as u64) << 32) | (time.dwLowDateTime as u64);
+        let since_1970 = since_1601 - HECTONANOS_FROM_1601_TO_1970;
+        ((since_1970 / 10000000) as i64, ((since_1970 % 10000000) * 100) as i32)
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    unsafe fn os_real_time() -> (i64, i32) {
+        use ptr;
+        let mut tv = libc::timeval { tv_sec: 0, tv_usec: 0 };
+        imp::gettimeofday(&mut tv, ptr::null_mut());
+        (tv.tv_sec as i64, tv.tv_usec as i32 * 1000)
+    }
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "ios")))]
+    unsafe fn os_real_time() -> (i64, i32) {
+        let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+        imp::clock_gettime(libc::CLOCK_REALTIME, &mut ts);
+        (ts.tv_sec as i64, ts.tv_nsec as i32)
+    }
+}
+
 #[cfg(all(unix, not(target_os = "macos"), not(target_os = "ios")))]
 mod imp {
     use libc::{c_int, timespec};
@@ -79,9 +117,10 @@ mod imp {
 }
 #[cfg(any(target_os = "macos", target_os = "ios"))]
 mod imp {
-    use libc::{c_int, mach_timebase_info};
+    use libc::{c_int, mach_timebase_info, timeval, timezone};
 
     extern {
+        pub fn gettimeofday(tp: *mut timeval, tzp: *mut timezone) -> c_int;
         pub fn mach_absolute_time() -> u64;
         pub fn mach_timebase_info(info: *mut mach_timebase_info) -> c_int;
     }
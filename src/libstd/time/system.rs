@@ -0,0 +1,138 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Wall clock time
+
+#![experimental]
+
+use fmt;
+use ops::{Add, Sub};
+use super::Duration;
+
+const NANOS_PER_SEC: i32 = 1_000_000_000;
+
+/// A reading of the system's wall clock.
+///
+/// Unlike `Instant`, a `SystemTime` can be related to calendar time through
+/// `UNIX_EPOCH`, but the clock it reads may be adjusted at any moment, so a
+/// later reading can come before an earlier one.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{SystemTime, UNIX_EPOCH};
+///
+/// let now = SystemTime::now();
+/// println!("{} seconds since the epoch", now.duration_since(UNIX_EPOCH).num_seconds());
+/// ```
+#[deriving(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SystemTime {
+    secs: i64,
+    nanos: i32, // Always 0 <= nanos < NANOS_PER_SEC
+}
+
+/// 1970-01-01T00:00:00Z, the reference point of Unix time.
+pub const UNIX_EPOCH: SystemTime = SystemTime { secs: 0, nanos: 0 };
+
+impl SystemTime {
+    /// Returns the current wall clock time.
+    pub fn now() -> SystemTime {
+        let (secs, nanos) = super::real_time();
+        SystemTime { secs: secs, nanos: nanos }
+    }
+
+    /// Returns the time elapsed from `earlier` to this time. The result is
+    /// negative if `earlier` is actually later, which may happen even for two
+    /// successive calls to `now` if the clock was set back in between.
+    pub fn duration_since(&self, earlier: SystemTime) -> Duration {
+        Duration::seconds(self.secs - earlier.secs) +
+            Duration::nanoseconds((self.nanos - earlier.nanos) as i64)
+    }
+
+    /// Returns the time elapsed since this time, according to the current
+    /// wall clock.
+    pub fn elapsed(&self) -> Duration {
+        SystemTime::now().duration_since(*self)
+    }
+
+    fn offset(&self, d: Duration) -> SystemTime {
+        let d_secs = d.num_seconds();
+        // Less than a second remains, which always fits.
+        let d_nanos = (d - Duration::seconds(d_secs)).num_nanoseconds().unwrap() as i32;
+        let mut secs = self.secs + d_secs;
+        let mut nanos = self.nanos + d_nanos;
+        if nanos < 0 {
+            nanos += NANOS_PER_SEC;
+            secs -= 1;
+        } else if nanos >= NANOS_PER_SEC {
+            nanos -= NANOS_PER_SEC;
+            secs += 1;
+        }
+        SystemTime { secs: secs, nanos: nanos }
+    }
+}
+
+impl Add<Duration, SystemTime> for SystemTime {
+    fn add(self, rhs: Duration) -> SystemTime {
+        self.offset(rhs)
+    }
+}
+
+impl Sub<Duration, SystemTime> for SystemTime {
+    fn sub(self, rhs: Duration) -> SystemTime {
+        self.offset(-rhs)
+    }
+}
+
+impl Sub<SystemTime, Duration> for SystemTime {
+    fn sub(self, rhs: SystemTime) -> Duration {
+        self.duration_since(rhs)
+    }
+}
+
+impl fmt::Show for SystemTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let d = self.duration_since(UNIX_EPOCH);
+        if d < Duration::zero() {
+            write!(f, "UNIX_EPOCH - {}", -d)
+        } else {
+            write!(f, "UNIX_EPOCH + {}", d)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SystemTime, UNIX_EPOCH};
+    use time::Duration;
+    use string::ToString;
+
+    #[test]
+    fn test_now() {
+        // Some time after this was written.
+        let now = SystemTime::now();
+        assert!(now.duration_since(UNIX_EPOCH) > Duration::days(16000));
+        assert!(now > UNIX_EPOCH);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let t = UNIX_EPOCH + Duration::milliseconds(1500);
+        assert_eq!(t.duration_since(UNIX_EPOCH), Duration::milliseconds(1500));
+        assert_eq!(t.to_string(), "UNIX_EPOCH + PT1.500S");
+
+        let before = UNIX_EPOCH - Duration::milliseconds(1500);
+        assert_eq!(before.to_string(), "UNIX_EPOCH - PT1.500S");
+        assert_eq!(t - before, Duration::seconds(3));
+        assert_eq!(before - t, Duration::seconds(-3));
+        assert_eq!(before + Duration::milliseconds(1500), UNIX_EPOCH);
+        assert!(before < UNIX_EPOCH);
+    }
+}
@@ -15,6 +15,7 @@ use ast::{MetaItem, Item, Expr, MutMutable};
 use codemap::Span;
 use ext::base::ExtCtxt;
 use ext::build::AstBuilder;
+use ext::deriving::encodable::{field_options, reject_field_options};
 use ext::deriving::generic::*;
 use ext::deriving::generic::ty::*;
 use parse::token::InternedString;
@@ -97,24 +98,61 @@ fn decodable_substructure(cx: &mut ExtCtxt, trait_span: Span,
     let lambdadecode = cx.lambda_expr_1(trait_span, calldecode, blkarg);
 
     return match *substr.fields {
-        StaticStruct(_, ref summary) => {
-            let nfields = match *summary {
-                Unnamed(ref fields) => fields.len(),
-                Named(ref fields) => fields.len()
-            };
+        StaticStruct(struct_def, ref summary) => {
+            let options: Vec<_> = struct_def.fields.iter().map(|field| {
+                field_options(cx, field.node.attrs[])
+            }).collect();
+            let nfields = options.iter().filter(|o| !o.skip).count();
             let read_struct_field = cx.ident_of("read_struct_field");
+            let read_struct_field_opt = cx.ident_of("read_struct_field_opt");
 
+            // Skipped fields are left out of the field indices.
+            let mut idx = 0u;
             let path = cx.path_ident(trait_span, substr.type_ident);
             let result = decode_static_fields(cx,
                                               trait_span,
                                               path,
                                               summary,
                                               |cx, span, name, field| {
-                cx.expr_try(span,
-                    cx.expr_method_call(span, blkdecoder.clone(), read_struct_field,
-                                        vec!(cx.expr_str(span, name),
-                                          cx.expr_uint(span, field),
-                                          lambdadecode.clone())))
+                let options = &options[field];
+                if options.skip {
+                    let default = match options.default {
+                        Some(ref default) => default.clone(),
+                        None => None,
+                    };
+                    return default_value(cx, span, &default);
+                }
+                let name = match options.rename {
+                    Some(ref rename) => rename.clone(),
+                    None => name,
+                };
+                let args = vec!(cx.expr_str(span, name),
+                                cx.expr_uint(span, idx),
+                                lambdadecode.clone());
+                idx += 1;
+                match options.default {
+                    None => {
+                        cx.expr_try(span,
+                            cx.expr_method_call(span, blkdecoder.clone(), read_struct_field, args))
+                    }
+                    Some(ref default) => {
+                        // match try!(d.read_struct_field_opt(..)) {
+                        //     Some(value) => value,
+                        //     None => <default>,
+                        // }
+                        let value = cx.ident_of("__value");
+                        let read = cx.expr_try(span,
+                            cx.expr_method_call(span, blkdecoder.clone(),
+                                                read_struct_field_opt, args));
+                        let some = cx.arm(span,
+                                          vec!(cx.pat_some(span, cx.pat_ident(span, value))),
+                                          cx.expr_ident(span, value));
+                        let none = cx.arm(span,
+                                          vec!(cx.pat_none(span)),
+                                          default_value(cx, span, default));
+                        cx.expr_match(span, read, vec!(some, none))
+                    }
+                }
             });
             let result = cx.expr_ok(trait_span, result);
             cx.expr_method_call(trait_span,
@@ -126,7 +164,18 @@ fn decodable_substructure(cx: &mut ExtCtxt, trait_span: Span,
                 cx.lambda_expr_1(trait_span, result, blkarg)
             ))
         }
-        StaticEnum(_, ref fields) => {
+        StaticEnum(enum_def, ref fields) => {
+            for variant in enum_def.variants.iter() {
+                match variant.node.kind {
+                    ast::StructVariantKind(ref struct_def) => {
+                        for field in struct_def.fields.iter() {
+                            reject_field_options(cx, field.node.attrs[]);
+                        }
+                    }
+                    ast::TupleVariantKind(_) => {}
+                }
+            }
+
             let variant = cx.ident_of("i");
 
             let mut arms = Vec::new();
@@ -176,6 +225,24 @@ fn decodable_substructure(cx: &mut ExtCtxt, trait_span: Span,
     };
 }
 
+/// The value of a missing or skipped field: the result of calling the
+/// function at `path`, or `Default::default()`.
+fn default_value(cx: &mut ExtCtxt, span: Span, path: &Option<InternedString>) -> P<Expr> {
+    let path = match *path {
+        Some(ref path) => {
+            let idents = path.get().split_str("::").map(|s| cx.ident_of(s)).collect();
+            cx.path(span, idents)
+        }
+        None => {
+            cx.path_global(span, vec!(cx.ident_of("std"),
+                                      cx.ident_of("default"),
+                                      cx.ident_of("Default"),
+                                      cx.ident_of("default")))
+        }
+    };
+    cx.expr_call(span, cx.expr_path(path), Vec::new())
+}
+
 /// Create a decoder for a single enum variant/struct:
 /// - `outer_pat_path` is the path to this enum variant/struct
 /// - `getarg` should retrieve the `uint`-th field with name `@str`.
@@ -87,16 +87,108 @@
 //!     }
 //! }
 //! ```
+//!
+//! The fields of a struct can be tagged with `#[serialize(...)]` to let the
+//! stored format evolve separately from the struct:
+//!
+//! ```ignore
+//! #[deriving(Encodable, Decodable)]
+//! struct Config {
+//!     #[serialize(rename = "name")]
+//!     title: String,
+//!     #[serialize(default)]
+//!     retries: uint,
+//!     #[serialize(default = "default_port")]
+//!     port: u16,
+//!     #[serialize(skip)]
+//!     cache: Vec<u8>,
+//! }
+//! ```
+//!
+//! * `rename = "name"` stores the field under `name` instead of its own name.
+//! * `default` decodes a missing field as `Default::default()`, and
+//!   `default = "path"` as the result of calling the function `path`. Fields
+//!   are read with `read_struct_field_opt`, which only decoders of
+//!   self-describing formats can answer with `None`.
+//! * `skip` leaves the field out of the encoded struct, and decodes it as its
+//!   default value. Skipped fields are not counted in the length passed to
+//!   `emit_struct` and `read_struct`, nor in the field indices.
+//!
+//! The fields of enum struct variants are encoded by position, like those of
+//! tuple variants, so they can't take any of these options.
 
+use ast;
 use ast::{MetaItem, Item, Expr, ExprRet, MutMutable};
+use attr;
+use attr::AttrMetaMethods;
 use codemap::Span;
 use ext::base::ExtCtxt;
 use ext::build::AstBuilder;
 use ext::deriving::generic::*;
 use ext::deriving::generic::ty::*;
+use parse::token::InternedString;
 use parse::token;
 use ptr::P;
 
+/// The `#[serialize(...)]` options of a struct field.
+pub struct FieldOptions {
+    /// The name to store the field under, if it was renamed.
+    pub rename: Option<InternedString>,
+    /// Whether the field is left out of the encoded struct.
+    pub skip: bool,
+    /// Whether a missing field takes a default value. It is that of
+    /// `Default`, or the result of calling the given function.
+    pub default: Option<Option<InternedString>>,
+}
+
+/// Collects the `#[serialize(...)]` options among the attributes of a field.
+///
+/// `#[deriving(Encodable, Decodable)]` reads the options once for each
+/// trait, so errors are only reported by the first to see the attribute.
+pub fn field_options(cx: &mut ExtCtxt, attrs: &[ast::Attribute]) -> FieldOptions {
+    let mut options = FieldOptions { rename: None, skip: false, default: None };
+    for attr in attrs.iter() {
+        if !attr.check_name("serialize") {
+            continue;
+        }
+        let report = !attr::is_used(attr);
+        attr::mark_used(attr);
+        let items = match attr.meta_item_list() {
+            Some(items) => items,
+            None => {
+                if report {
+                    cx.span_err(attr.span, "expected `#[serialize(...)]`");
+                }
+                continue;
+            }
+        };
+        for item in items.iter() {
+            match (item.name().get(), item.value_str(), item.meta_item_list()) {
+                ("rename", Some(name), None) => options.rename = Some(name),
+                ("skip", None, None) => options.skip = true,
+                ("default", path, None) => options.default = Some(path),
+                _ if !report => {}
+                _ => cx.span_err(item.span, "unknown serialize option, expected one of \
+                                             `rename = \"name\"`, `skip`, `default` or \
+                                             `default = \"path\"`"),
+            }
+        }
+    }
+    options
+}
+
+/// Reports `#[serialize(...)]` attributes on the fields of an enum variant.
+/// These are encoded by position, so none of the options apply to them.
+pub fn reject_field_options(cx: &mut ExtCtxt, attrs: &[ast::Attribute]) {
+    for attr in attrs.iter() {
+        if attr.check_name("serialize") && !attr::is_used(attr) {
+            attr::mark_used(attr);
+            cx.span_err(attr.span, "`#[serialize(...)]` is not supported on fields of \
+                                    enum variants");
+        }
+    }
+}
+
 pub fn expand_deriving_rustc_encodable<F>(cx: &mut ExtCtxt,
                                           span: Span,
                                           mitem: &MetaItem,
@@ -173,17 +265,25 @@ fn encodable_substructure(cx: &mut ExtCtxt, trait_span: Span,
         Struct(ref fields) => {
             let emit_struct_field = cx.ident_of("emit_struct_field");
             let mut stmts = Vec::new();
-            let last = fields.len() - 1;
-            for (i, &FieldInfo {
+            let mut encoded = Vec::new();
+            for (i, field) in fields.iter().enumerate() {
+                let options = field_options(cx, field.attrs[]);
+                if !options.skip {
+                    encoded.push((i, field, options.rename));
+                }
+            }
+            let last = encoded.len() - 1;
+            for (i, &(field_idx, &FieldInfo {
                     name,
                     ref self_,
                     span,
                     ..
-                }) in fields.iter().enumerate() {
-                let name = match name {
-                    Some(id) => token::get_ident(id),
-                    None => {
-                        token::intern_and_get_ident(format!("_field{}", i)[])
+                }, ref rename)) in encoded.iter().enumerate() {
+                let name = match (rename, name) {
+                    (&Some(ref rename), _) => rename.clone(),
+                    (&None, Some(id)) => token::get_ident(id),
+                    (&None, None) => {
+                        token::intern_and_get_ident(format!("_field{}", field_idx)[])
                     }
                 };
                 let enc = cx.expr_method_call(span, self_.clone(),
@@ -218,7 +318,7 @@ fn encodable_substructure(cx: &mut ExtCtxt, trait_span: Span,
                                 cx.ident_of("emit_struct"),
                                 vec!(
                 cx.expr_str(trait_span, token::get_ident(substr.type_ident)),
-                cx.expr_uint(trait_span, fields.len()),
+                cx.expr_uint(trait_span, encoded.len()),
                 blk
             ))
         }
@@ -233,7 +333,8 @@ fn encodable_substructure(cx: &mut ExtCtxt, trait_span: Span,
             let emit_variant_arg = cx.ident_of("emit_enum_variant_arg");
             let mut stmts = Vec::new();
             let last = fields.len() - 1;
-            for (i, &FieldInfo { ref self_, span, .. }) in fields.iter().enumerate() {
+            for (i, &FieldInfo { ref self_, span, ref attrs, .. }) in fields.iter().enumerate() {
+                reject_field_options(cx, attrs[]);
                 let enc = cx.expr_method_call(span, self_.clone(),
                                               encode, vec!(blkencoder.clone()));
                 let lambda = cx.lambda_expr_1(span, enc, blkarg);
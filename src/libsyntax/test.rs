@@ -32,11 +32,13 @@ use fold;
 use owned_slice::OwnedSlice;
 use parse::token::InternedString;
 use parse::{token, ParseSess};
+use parse;
 use print::pprust;
 use {ast, ast_util};
 use ptr::P;
 use util::small_vector::SmallVector;
 
+#[deriving(Clone)]
 enum ShouldFail {
     No,
     Yes(Option<InternedString>),
@@ -45,9 +47,24 @@ enum ShouldFail {
 struct Test {
     span: Span,
     path: Vec<ast::Ident> ,
+    // The name to report the test by, when it is not its path.
+    name: Option<String>,
     bench: bool,
     ignore: bool,
-    should_fail: ShouldFail
+    should_fail: ShouldFail,
+    timeout: Option<u64>,
+    // Paths to the #[before_each] and #[after_each] hooks of the modules
+    // enclosing the test, outermost first.
+    before_each: Vec<Vec<ast::Ident>>,
+    after_each: Vec<Vec<ast::Ident>>,
+}
+
+// One row of the parameter table of a test: `#[case = "ARGS"]` or
+// `#[case(name = "NAME", args = "ARGS")]`.
+struct TestCase {
+    span: Span,
+    name: Option<InternedString>,
+    args: InternedString,
 }
 
 struct TestCtxt<'a> {
@@ -56,6 +73,9 @@ struct TestCtxt<'a> {
     path: Vec<ast::Ident>,
     ext_cx: ExtCtxt<'a>,
     testfns: Vec<Test>,
+    // The hooks of the modules being folded, outermost first.
+    before_each: Vec<Vec<ast::Ident>>,
+    after_each: Vec<Vec<ast::Ident>>,
     reexport_test_harness_main: Option<InternedString>,
     is_test_crate: bool,
     config: ast::CrateConfig,
@@ -121,6 +141,7 @@ impl<'a> fold::Folder for TestHarnessGenerator<'a> {
         debug!("current path: {}",
                ast_util::path_name_i(self.cx.path[]));
 
+        let mut case_fns = Vec::new();
         if is_test_fn(&self.cx, &*i) || is_bench_fn(&self.cx, &*i) {
             match i.node {
                 ast::ItemFn(_, ast::Unsafety::Unsafe, _, _, _) => {
@@ -131,27 +152,57 @@ impl<'a> fold::Folder for TestHarnessGenerator<'a> {
                 }
                 _ => {
                     debug!("this is a test function");
+                    let bench = is_bench_fn(&self.cx, &*i);
+                    let (before_each, after_each) = if bench {
+                        (Vec::new(), Vec::new())
+                    } else {
+                        (self.cx.before_each.clone(), self.cx.after_each.clone())
+                    };
                     let test = Test {
                         span: i.span,
                         path: self.cx.path.clone(),
-                        bench: is_bench_fn(&self.cx, &*i),
+                        name: None,
+                        bench: bench,
                         ignore: is_ignored(&*i),
-                        should_fail: should_fail(&*i)
+                        should_fail: should_fail(&*i),
+                        timeout: timeout(&self.cx, &*i),
+                        before_each: before_each,
+                        after_each: after_each,
                     };
-                    self.cx.testfns.push(test);
-                    self.tests.push(i.ident);
+                    let cases = test_cases(&self.cx, &*i);
+                    if cases.is_empty() {
+                        self.cx.testfns.push(test);
+                        self.tests.push(i.ident);
+                    } else {
+                        // Each case is a test of its own, calling the test
+                        // function with the arguments of the case.
+                        for case in cases.into_iter() {
+                            let (case_fn, case_test) = mk_test_case(&self.cx, &*i, &test, case);
+                            self.cx.testfns.push(case_test);
+                            self.tests.push(case_fn.ident);
+                            case_fns.push(case_fn);
+                        }
+                    }
                     // debug!("have {} test/bench functions",
                     //        cx.testfns.len());
                 }
             }
         }
 
+        if is_hook(&self.cx, &*i) {
+            // Re-exported for the test harness like the tests themselves.
+            self.tests.push(i.ident);
+        }
+
         // We don't want to recurse into anything other than mods, since
         // mods or tests inside of functions will break things
-        let res = match i.node {
+        let mut res = match i.node {
             ast::ItemMod(..) => fold::noop_fold_item(i, self),
             _ => SmallVector::one(i),
         };
+        for case_fn in case_fns.into_iter() {
+            res.push(case_fn);
+        }
         if ident.name != token::special_idents::invalid.name {
             self.cx.path.pop();
         }
@@ -159,11 +210,30 @@ impl<'a> fold::Folder for TestHarnessGenerator<'a> {
     }
 
     fn fold_mod(&mut self, m: ast::Mod) -> ast::Mod {
+        // The hooks of this module apply to all the tests inside it,
+        // including those of its submodules.
+        let before_len = self.cx.before_each.len();
+        let after_len = self.cx.after_each.len();
+        for item in m.items.iter() {
+            if has_test_signature(&**item, false) != Yes {
+                continue;
+            }
+            let mut path = self.cx.path.clone();
+            path.push(item.ident);
+            if attr::contains_name(item.attrs[], "before_each") {
+                self.cx.before_each.push(path);
+            } else if attr::contains_name(item.attrs[], "after_each") {
+                self.cx.after_each.push(path);
+            }
+        }
+
         let tests = mem::replace(&mut self.tests, Vec::new());
         let tested_submods = mem::replace(&mut self.tested_submods, Vec::new());
         let mut mod_folded = fold::noop_fold_mod(m, self);
         let tests = mem::replace(&mut self.tests, tests);
         let tested_submods = mem::replace(&mut self.tested_submods, tested_submods);
+        self.cx.before_each.truncate(before_len);
+        self.cx.after_each.truncate(after_len);
 
         // Remove any #[main] from the AST so it doesn't clash with
         // the one we're going to add. Only if compiling an executable.
@@ -248,6 +318,8 @@ fn generate_test_harness(sess: &ParseSess,
                              ExpansionConfig::default("test".to_string())),
         path: Vec::new(),
         testfns: Vec::new(),
+        before_each: Vec::new(),
+        after_each: Vec::new(),
         reexport_test_harness_main: reexport_test_harness_main,
         is_test_crate: is_test_crate(&krate),
         config: krate.config.clone(),
@@ -278,7 +350,9 @@ fn strip_test_functions(krate: ast::Crate) -> ast::Crate {
     // #[test] functions
     config::strip_items(krate, |attrs| {
         !attr::contains_name(attrs[], "test") &&
-        !attr::contains_name(attrs[], "bench")
+        !attr::contains_name(attrs[], "bench") &&
+        !attr::contains_name(attrs[], "before_each") &&
+        !attr::contains_name(attrs[], "after_each")
     })
 }
 
@@ -290,42 +364,62 @@ enum HasTestSignature {
 }
 
 
-fn is_test_fn(cx: &TestCtxt, i: &ast::Item) -> bool {
-    let has_test_attr = attr::contains_name(i.attrs[], "test");
-
-    fn has_test_signature(i: &ast::Item) -> HasTestSignature {
-        match &i.node {
-          &ast::ItemFn(ref decl, _, _, ref generics, _) => {
-            let no_output = match decl.output {
-                ast::Return(ref ret_ty) => match ret_ty.node {
-                    ast::TyTup(ref tys) if tys.is_empty() => true,
-                    _ => false,
-                },
-                ast::NoReturn(_) => false
-            };
-            if decl.inputs.is_empty()
-                   && no_output
-                   && !generics.is_parameterized() {
-                Yes
-            } else {
-                No
-            }
-          }
-          _ => NotEvenAFunction,
+// Whether `i` is a `fn() -> ()`, or when `with_args` a non-generic
+// function of any arguments returning `()`.
+fn has_test_signature(i: &ast::Item, with_args: bool) -> HasTestSignature {
+    match &i.node {
+      &ast::ItemFn(ref decl, _, _, ref generics, _) => {
+        let no_output = match decl.output {
+            ast::Return(ref ret_ty) => match ret_ty.node {
+                ast::TyTup(ref tys) if tys.is_empty() => true,
+                _ => false,
+            },
+            ast::NoReturn(_) => false
+        };
+        if (with_args || decl.inputs.is_empty())
+               && no_output
+               && !generics.is_parameterized() {
+            Yes
+        } else {
+            No
         }
+      }
+      _ => NotEvenAFunction,
     }
+}
+
+fn is_test_fn(cx: &TestCtxt, i: &ast::Item) -> bool {
+    let has_test_attr = attr::contains_name(i.attrs[], "test");
+    // Parameterized tests take the arguments of their cases.
+    let has_cases = attr::contains_name(i.attrs[], "case");
 
     if has_test_attr {
         let diag = cx.span_diagnostic;
-        match has_test_signature(i) {
+        match has_test_signature(i, has_cases) {
             Yes => {},
+            No if has_cases => diag.span_err(i.span, "parameterized tests must not be generic \
+                                                      and must return ()"),
             No => diag.span_err(i.span, "functions used as tests must have signature fn() -> ()"),
             NotEvenAFunction => diag.span_err(i.span,
                                               "only functions may be used as tests"),
         }
     }
 
-    return has_test_attr && has_test_signature(i) == Yes;
+    return has_test_attr && has_test_signature(i, has_cases) == Yes;
+}
+
+// Whether `i` is a #[before_each] or #[after_each] hook of its module.
+fn is_hook(cx: &TestCtxt, i: &ast::Item) -> bool {
+    let has_hook_attr = attr::contains_name(i.attrs[], "before_each") ||
+                        attr::contains_name(i.attrs[], "after_each");
+
+    if has_hook_attr && has_test_signature(i, false) != Yes {
+        cx.span_diagnostic.span_err(i.span, "functions used as #[before_each] or \
+                                             #[after_each] hooks must have signature \
+                                             fn() -> ()");
+    }
+
+    return has_hook_attr && has_test_signature(i, false) == Yes;
 }
 
 fn is_bench_fn(cx: &TestCtxt, i: &ast::Item) -> bool {
@@ -377,6 +471,86 @@ fn should_fail(i: &ast::Item) -> ShouldFail {
     }
 }
 
+// The number of seconds in `#[timeout = N]`.
+fn timeout(cx: &TestCtxt, i: &ast::Item) -> Option<u64> {
+    match i.attrs.iter().find(|attr| attr.check_name("timeout")) {
+        Some(attr) => match attr.node.value.node {
+            ast::MetaNameValue(_, codemap::Spanned { node: ast::LitInt(secs, _), .. }) => Some(secs),
+            _ => {
+                cx.span_diagnostic.span_err(attr.span,
+                                            "expected `#[timeout = N]`, with N a number \
+                                             of seconds");
+                None
+            }
+        },
+        None => None,
+    }
+}
+
+fn test_cases(cx: &TestCtxt, i: &ast::Item) -> Vec<TestCase> {
+    i.attrs.iter().filter(|attr| attr.check_name("case")).filter_map(|attr| {
+        match attr.value_str() {
+            Some(args) => return Some(TestCase { span: attr.span, name: None, args: args }),
+            None => {}
+        }
+        let (name, args) = match attr.meta_item_list() {
+            Some(list) => {
+                let value = |&: key| {
+                    list.iter().find(|mi| mi.check_name(key)).and_then(|mi| mi.value_str())
+                };
+                (value("name"), value("args"))
+            }
+            None => (None, None),
+        };
+        match args {
+            Some(args) => Some(TestCase { span: attr.span, name: name, args: args }),
+            None => {
+                cx.span_diagnostic.span_err(attr.span,
+                                            "expected `#[case = \"ARGS\"]` or \
+                                             `#[case(name = \"NAME\", args = \"ARGS\")]`");
+                None
+            }
+        }
+    }).collect()
+}
+
+// Builds `fn __test_case() { f(ARGS) }` to be put beside the parameterized
+// test `f`, along with the test which runs it. The case is named
+// `path::to::f::NAME`, or `path::to::f(ARGS)` when it has no name.
+fn mk_test_case(cx: &TestCtxt, i: &ast::Item, test: &Test,
+                case: TestCase) -> (P<ast::Item>, Test) {
+    let ecx = &cx.ext_cx;
+    let source = format!("{}({})", token::get_ident(i.ident), case.args);
+    let call = parse::parse_expr_from_source_str("<test case>".to_string(), source,
+                                                 cx.config.clone(), cx.sess);
+    let ident = token::gensym_ident("__test_case");
+    let case_fn = ecx.item_fn(case.span, ident, Vec::new(),
+                              ecx.ty(case.span, ast::TyTup(Vec::new())),
+                              ecx.block_expr(call));
+
+    let fn_name = ast_util::path_name_i(test.path[]);
+    let name = match case.name {
+        Some(ref name) => format!("{}::{}", fn_name, name),
+        None => format!("{}({})", fn_name, case.args),
+    };
+    let mut path = test.path.clone();
+    path.pop();
+    path.push(ident);
+
+    let case_test = Test {
+        span: case.span,
+        path: path,
+        name: Some(name),
+        bench: test.bench,
+        ignore: test.ignore,
+        should_fail: test.should_fail.clone(),
+        timeout: test.timeout,
+        before_each: test.before_each.clone(),
+        after_each: test.after_each.clone(),
+    };
+    (case_fn, case_test)
+}
+
 /*
 
 We're going to be building a module that looks more or less like:
@@ -390,6 +564,11 @@ mod __test {
   static tests : &'static [test::TestDescAndFn] = &[
     ... the list of tests in the crate ...
   ];
+
+  // for each test with #[before_each] or #[after_each] hooks
+  fn __test_fixture_N() {
+    test::run_with_fixtures(&[before...], path::to::the_test, &[after...])
+  }
 }
 
 */
@@ -431,10 +610,13 @@ fn mk_test_module(cx: &mut TestCtxt) -> (P<ast::Item>, Option<ast::ViewItem>) {
         }
     )).unwrap();
 
+    let mut items = vec!(mainfn, tests);
+    items.extend(mk_fixture_fns(cx).into_iter());
+
     let testmod = ast::Mod {
         inner: DUMMY_SP,
         view_items: view_items,
-        items: vec!(mainfn, tests),
+        items: items,
     };
     let item_ = ast::ItemMod(testmod);
 
@@ -524,8 +706,8 @@ fn mk_test_descs(cx: &TestCtxt) -> P<ast::Expr> {
         node: ast::ExprAddrOf(ast::MutImmutable,
             P(ast::Expr {
                 id: ast::DUMMY_NODE_ID,
-                node: ast::ExprVec(cx.testfns.iter().map(|test| {
-                    mk_test_desc_and_fn_rec(cx, test)
+                node: ast::ExprVec(cx.testfns.iter().enumerate().map(|(i, test)| {
+                    mk_test_desc_and_fn_rec(cx, i, test)
                 }).collect()),
                 span: DUMMY_SP,
             })),
@@ -533,7 +715,52 @@ fn mk_test_descs(cx: &TestCtxt) -> P<ast::Expr> {
     })
 }
 
-fn mk_test_desc_and_fn_rec(cx: &TestCtxt, test: &Test) -> P<ast::Expr> {
+fn has_fixtures(test: &Test) -> bool {
+    !test.before_each.is_empty() || !test.after_each.is_empty()
+}
+
+fn fixture_fn_ident(i: uint) -> ast::Ident {
+    token::str_to_ident(format!("__test_fixture_{}", i)[])
+}
+
+// The path to an item of the crate through the top-level re-export.
+fn visible_path(cx: &TestCtxt, span: Span, path: &[ast::Ident]) -> ast::Path {
+    let mut visible_path = match cx.toplevel_reexport {
+        Some(id) => vec![id],
+        None => {
+            let diag = cx.span_diagnostic;
+            diag.handler.bug("expected to find top-level re-export name, but found None");
+        }
+    };
+    visible_path.push_all(path);
+    cx.ext_cx.path_global(span, visible_path)
+}
+
+// Builds the functions running the tests which have hooks between them.
+fn mk_fixture_fns(cx: &TestCtxt) -> Vec<P<ast::Item>> {
+    let ecx = &cx.ext_cx;
+    cx.testfns.iter().enumerate().filter(|&(_, test)| has_fixtures(test)).map(|(i, test)| {
+        let span = test.span;
+        let hook = |&: path: &Vec<ast::Ident>| {
+            ecx.expr_path(visible_path(cx, span, path.as_slice()))
+        };
+        let before = test.before_each.iter().map(|path| hook(path)).collect();
+        // Inner modules tear down before outer ones.
+        let after = test.after_each.iter().rev().map(|path| hook(path)).collect();
+        let run = ecx.path(span, vec![ecx.ident_of("self"),
+                                      ecx.ident_of("test"),
+                                      ecx.ident_of("run_with_fixtures")]);
+        let call = ecx.expr_call(span, ecx.expr_path(run),
+                                 vec![ecx.expr_vec_slice(span, before),
+                                      hook(&test.path),
+                                      ecx.expr_vec_slice(span, after)]);
+        ecx.item_fn(span, fixture_fn_ident(i), Vec::new(),
+                    ecx.ty(span, ast::TyTup(Vec::new())),
+                    ecx.block_expr(call))
+    }).collect()
+}
+
+fn mk_test_desc_and_fn_rec(cx: &TestCtxt, i: uint, test: &Test) -> P<ast::Expr> {
     // FIXME #15962: should be using quote_expr, but that stringifies
     // __test_reexports, causing it to be reinterned, losing the
     // gensym information.
@@ -554,7 +781,10 @@ fn mk_test_desc_and_fn_rec(cx: &TestCtxt, test: &Test) -> P<ast::Expr> {
     debug!("encoding {}", ast_util::path_name_i(path[]));
 
     // path to the #[test] function: "foo::bar::baz"
-    let path_string = ast_util::path_name_i(path[]);
+    let path_string = match test.name {
+        Some(ref name) => name.clone(),
+        None => ast_util::path_name_i(path[]),
+    };
     let name_expr = ecx.expr_str(span, token::intern_and_get_ident(path_string[]));
 
     // self::test::StaticTestName($name_expr)
@@ -578,25 +808,30 @@ fn mk_test_desc_and_fn_rec(cx: &TestCtxt, test: &Test) -> P<ast::Expr> {
         }
     };
 
+    let timeout_expr = match test.timeout {
+        Some(secs) => {
+            let secs = ecx.expr_lit(span, ast::LitInt(secs, ast::UnsignedIntLit(ast::TyU64)));
+            ecx.expr_some(span, secs)
+        }
+        None => ecx.expr_none(span),
+    };
+
     // self::test::TestDesc { ... }
     let desc_expr = ecx.expr_struct(
         span,
         test_path("TestDesc"),
         vec![field("name", name_expr),
              field("ignore", ignore_expr),
-             field("should_fail", fail_expr)]);
+             field("should_fail", fail_expr),
+             field("timeout", timeout_expr)]);
 
 
-    let mut visible_path = match cx.toplevel_reexport {
-        Some(id) => vec![id],
-        None => {
-            let diag = cx.span_diagnostic;
-            diag.handler.bug("expected to find top-level re-export name, but found None");
-        }
+    let fn_expr = if has_fixtures(test) {
+        // self::__test_fixture_N
+        ecx.expr_path(ecx.path(span, vec![self_id, fixture_fn_ident(i)]))
+    } else {
+        ecx.expr_path(visible_path(cx, span, path[]))
     };
-    visible_path.extend(path.into_iter());
-
-    let fn_expr = ecx.expr_path(ecx.path_global(span, visible_path));
 
     let variant_name = if test.bench { "StaticBenchFn" } else { "StaticTestFn" };
     // self::test::$variant_name($fn_expr)
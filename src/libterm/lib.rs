@@ -34,6 +34,12 @@
 //! }
 //! ```
 //!
+//! Besides colors and attributes a `Terminal` can move the cursor and clear
+//! parts of the screen. The `tty` module queries the size of the terminal and
+//! switches its input to raw or no-echo mode. Built on top of these,
+//! `readline` is a line editor for interactive prompts and `progress` shows
+//! progress bars and spinners.
+//!
 //! [ansi]: https://en.wikipedia.org/wiki/ANSI_escape_code
 //! [win]: http://msdn.microsoft.com/en-us/library/windows/desktop/ms682010%28v=vs.85%29.aspx
 //! [ti]: https://en.wikipedia.org/wiki/Terminfo
@@ -53,6 +59,7 @@
 #![deny(missing_docs)]
 
 #[phase(plugin, link)] extern crate log;
+extern crate libc;
 
 pub use terminfo::TerminfoTerminal;
 #[cfg(windows)]
@@ -60,7 +67,10 @@ pub use win::WinConsole;
 
 use std::io::IoResult;
 
+pub mod progress;
+pub mod readline;
 pub mod terminfo;
+pub mod tty;
 
 #[cfg(windows)]
 mod win;
@@ -229,6 +239,44 @@ pub trait Terminal<T: Writer>: Writer {
     /// Returns `Ok()`.
     fn reset(&mut self) -> IoResult<()>;
 
+    /// Moves the cursor to the given row and column, counting from 0 at the
+    /// top left of the screen.
+    ///
+    /// Like the methods below, returns `Ok(true)` if the terminal supports
+    /// the operation, `Ok(false)` otherwise, and `Err(e)` if there was an I/O
+    /// error. The default implementation supports nothing.
+    fn cursor_goto(&mut self, _row: u16, _col: u16) -> IoResult<bool> { Ok(false) }
+
+    /// Moves the cursor up `n` rows.
+    fn cursor_up(&mut self, _n: u16) -> IoResult<bool> { Ok(false) }
+
+    /// Moves the cursor down `n` rows.
+    fn cursor_down(&mut self, _n: u16) -> IoResult<bool> { Ok(false) }
+
+    /// Moves the cursor left `n` columns.
+    fn cursor_left(&mut self, _n: u16) -> IoResult<bool> { Ok(false) }
+
+    /// Moves the cursor right `n` columns.
+    fn cursor_right(&mut self, _n: u16) -> IoResult<bool> { Ok(false) }
+
+    /// Moves the cursor to the start of the current line.
+    fn carriage_return(&mut self) -> IoResult<bool> { Ok(false) }
+
+    /// Hides the cursor.
+    fn hide_cursor(&mut self) -> IoResult<bool> { Ok(false) }
+
+    /// Shows the cursor again after `hide_cursor`.
+    fn show_cursor(&mut self) -> IoResult<bool> { Ok(false) }
+
+    /// Clears from the cursor to the end of the current line.
+    fn clear_to_eol(&mut self) -> IoResult<bool> { Ok(false) }
+
+    /// Clears from the cursor to the end of the screen.
+    fn clear_to_eos(&mut self) -> IoResult<bool> { Ok(false) }
+
+    /// Clears the whole screen and moves the cursor to the top left.
+    fn clear_screen(&mut self) -> IoResult<bool> { Ok(false) }
+
     /// Gets an immutable reference to the stream inside
     fn get_ref<'a>(&'a self) -> &'a T;
 
@@ -241,3 +289,8 @@ pub trait UnwrappableTerminal<T: Writer>: Terminal<T> {
     /// Returns the contained stream, destroying the `Terminal`
     fn unwrap(self) -> T;
 }
+
+/// The number of columns `s` takes up on screen.
+fn str_width(s: &str) -> uint {
+    s.chars().fold(0, |w, c| w + c.width(false).unwrap_or(0))
+}
@@ -0,0 +1,558 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Progress bars and spinners.
+//!
+//! A `Progress` shows any number of progress bars and spinners, one per line:
+//!
+//! ```text
+//! copying [======>         ]  42% 420/1000 ETA 0:08
+//! indexing /
+//! ```
+//!
+//! On a terminal the lines are redrawn in place when something changes, at
+//! most once per interval (a tenth of a second unless changed with
+//! `set_interval`) and whenever an item finishes. Anywhere else a plain line
+//! is written for each unfinished bar or spinner at most once per interval
+//! (five seconds by default), and one more when it finishes.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use term::progress::Progress;
+//!
+//! let mut progress = Progress::stdout();
+//! let files = progress.add_bar("copying", 1000);
+//! let index = progress.add_spinner("indexing");
+//! for _ in range(0u, 1000) {
+//!     // ... copy a file ...
+//!     progress.inc(files, 1).unwrap();
+//!     progress.tick().unwrap();
+//! }
+//! progress.finish(files).unwrap();
+//! progress.finish(index).unwrap();
+//! progress.end().unwrap();
+//! ```
+
+use std::io::{mod, IoResult};
+use std::num::Int;
+use std::time::{Duration, Instant};
+
+use {str_width, Terminal, WriterWrapper};
+use tty;
+
+/// Identifies a bar or spinner shown by a `Progress`.
+#[deriving(Copy, Clone, PartialEq, Show)]
+pub struct ProgressId(uint);
+
+/// A progress display, drawing on a terminal or writing plain lines.
+pub struct Progress<W> {
+    target: Target<W>,
+    items: Vec<Item>,
+    width: uint,
+    /// The number of lines drawn on the terminal, with the cursor at the end
+    /// of the last one
+    drawn: uint,
+    /// The number of items forgotten by `end`, so that their ids aren't
+    /// taken for those of items added later
+    ended: uint,
+    interval: Duration,
+    last_report: Option<Instant>,
+}
+
+enum Target<W> {
+    Term(Box<Terminal<W> + Send>),
+    Plain(W),
+}
+
+enum Kind {
+    Bar { pos: u64, total: u64 },
+    Spinner { frame: uint },
+}
+
+struct Item {
+    prefix: String,
+    message: String,
+    kind: Kind,
+    started: Instant,
+    finished: Option<Instant>,
+    /// Whether the line for the finished item has been written, in plain mode
+    reported: bool,
+}
+
+static SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// Bars narrower than this, brackets included, are left out.
+const MIN_BAR_WIDTH: uint = 12;
+
+impl Progress<WriterWrapper> {
+    /// Creates a display on standard output, redrawn in place if standard
+    /// output is a terminal.
+    pub fn stdout() -> Progress<WriterWrapper> {
+        if tty::isatty(tty::Stdout) {
+            match ::stdout() {
+                Some(term) => {
+                    let width = tty::size(tty::Stdout).map_or(80, |(cols, _)| cols as uint);
+                    return Progress::new(term, width)
+                }
+                None => {}
+            }
+        }
+        Progress::plain(WriterWrapper {
+            wrapped: box io::stdout() as Box<Writer + Send>,
+        })
+    }
+}
+
+impl<W: Writer> Progress<W> {
+    /// Creates a display redrawn in place on `term`, which is `width` columns
+    /// wide.
+    pub fn new(term: Box<Terminal<W> + Send>, width: uint) -> Progress<W> {
+        Progress::with_target(Target::Term(term), width, Duration::milliseconds(100))
+    }
+
+    /// Creates a display writing plain lines of text to `out`.
+    pub fn plain(out: W) -> Progress<W> {
+        Progress::with_target(Target::Plain(out), 80, Duration::seconds(5))
+    }
+
+    fn with_target(target: Target<W>, width: uint, interval: Duration) -> Progress<W> {
+        Progress {
+            target: target,
+            items: Vec::new(),
+            width: width,
+            drawn: 0,
+            ended: 0,
+            interval: interval,
+            last_report: None,
+        }
+    }
+
+    /// Sets how often the lines are redrawn on a terminal, or written for
+    /// unfinished items when not drawing on a terminal.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        match self.target {
+            Target::Term(ref term) => term.get_ref(),
+            Target::Plain(ref out) => out,
+        }
+    }
+
+    /// Adds a progress bar for `total` units of work, shown after `prefix`.
+    /// It is drawn with the next update.
+    pub fn add_bar(&mut self, prefix: &str, total: u64) -> ProgressId {
+        self.add(prefix, Kind::Bar { pos: 0, total: total })
+    }
+
+    /// Adds a spinner, shown after `prefix` and turned by `tick`. It is drawn
+    /// with the next update.
+    pub fn add_spinner(&mut self, prefix: &str) -> ProgressId {
+        self.add(prefix, Kind::Spinner { frame: 0 })
+    }
+
+    fn add(&mut self, prefix: &str, kind: Kind) -> ProgressId {
+        self.items.push(Item {
+            prefix: prefix.to_string(),
+            message: String::new(),
+            kind: kind,
+            started: Instant::now(),
+            finished: None,
+            reported: false,
+        });
+        ProgressId(self.ended + self.items.len() - 1)
+    }
+
+    fn item_mut(&mut self, id: ProgressId) -> Option<&mut Item> {
+        let ProgressId(i) = id;
+        if i < self.ended {
+            return None
+        }
+        self.items.get_mut(i - self.ended)
+    }
+
+    /// Sets the number of units of work a bar has done.
+    ///
+    /// This, like the other methods taking a `ProgressId`, does nothing if
+    /// the item has been forgotten by `end`.
+    pub fn set_position(&mut self, id: ProgressId, pos: u64) -> IoResult<()> {
+        {
+            let item = match self.item_mut(id) {
+                Some(item) => item,
+                None => return Ok(()),
+            };
+            match item.kind {
+                Kind::Bar { pos: ref mut p, total } => *p = if pos > total { total } else { pos },
+                Kind::Spinner { .. } => return Ok(()),
+            }
+        }
+        self.update(false)
+    }
+
+    /// Adds `delta` to the number of units of work a bar has done.
+    pub fn inc(&mut self, id: ProgressId, delta: u64) -> IoResult<()> {
+        let pos = match self.item_mut(id).map(|item| &item.kind) {
+            Some(&Kind::Bar { pos, .. }) => pos.saturating_add(delta),
+            _ => return Ok(()),
+        };
+        self.set_position(id, pos)
+    }
+
+    /// Sets the message shown at the end of a bar or spinner's line.
+    pub fn set_message(&mut self, id: ProgressId, message: &str) -> IoResult<()> {
+        match self.item_mut(id) {
+            Some(item) => item.message = message.to_string(),
+            None => return Ok(()),
+        }
+        self.update(false)
+    }
+
+    /// Turns all unfinished spinners one step.
+    pub fn tick(&mut self) -> IoResult<()> {
+        for item in self.items.iter_mut() {
+            if item.finished.is_some() {
+                continue
+            }
+            match item.kind {
+                Kind::Spinner { ref mut frame } => *frame += 1,
+                Kind::Bar { .. } => {}
+            }
+        }
+        self.update(false)
+    }
+
+    /// Marks a bar or spinner as finished, filling up a bar and showing how
+    /// long it took.
+    pub fn finish(&mut self, id: ProgressId) -> IoResult<()> {
+        {
+            let item = match self.item_mut(id) {
+                Some(item) => item,
+                None => return Ok(()),
+            };
+            if item.finished.is_none() {
+                item.finished = Some(Instant::now());
+            }
+            match item.kind {
+                Kind::Bar { ref mut pos, total } => *pos = total,
+                Kind::Spinner { .. } => {}
+            }
+        }
+        self.update(true)
+    }
+
+    /// Draws everything one last time and stops updating the lines shown so
+    /// far, forgetting their bars and spinners. Items added afterwards start
+    /// on a new line.
+    pub fn end(&mut self) -> IoResult<()> {
+        self.last_report = None;
+        try!(self.update(true));
+        self.ended += self.items.len();
+        self.items.clear();
+        if self.drawn > 0 {
+            self.drawn = 0;
+            match self.target {
+                Target::Term(ref mut term) => {
+                    try!(term.write(b"\n"));
+                    try!(term.flush());
+                }
+                Target::Plain(..) => {}
+            }
+        }
+        Ok(())
+    }
+
+    // Brings the display up to date if the interval has passed since the last
+    // time, or if `force` is set. In plain mode finished items are always
+    // written, and `force` makes no difference.
+    fn update(&mut self, force: bool) -> IoResult<()> {
+        let now = Instant::now();
+        let interval = self.interval;
+        let due = self.last_report.map_or(true, |t| now - t >= interval);
+        if due {
+            self.last_report = Some(now);
+        }
+        match self.target {
+            Target::Term(ref mut term) => {
+                if !due && !force {
+                    return Ok(())
+                }
+                // Go back to the start of the first line drawn last time, or
+                // if the terminal can't move up, start again below.
+                if self.drawn > 1 && !try!(term.cursor_up((self.drawn - 1) as u16)) {
+                    try!(term.write(b"\n"));
+                }
+                if !try!(term.carriage_return()) {
+                    try!(term.write(b"\r"));
+                }
+                // Stay clear of the last column, where some terminals wrap.
+                let width = if self.width > 1 { self.width - 1 } else { 1 };
+                for (i, item) in self.items.iter().enumerate() {
+                    if i > 0 {
+                        try!(term.write(b"\n"));
+                    }
+                    let line = item.render(width, now);
+                    try!(term.write_str(line[]));
+                    if !try!(term.clear_to_eol()) {
+                        for _ in range(str_width(line[]), width) {
+                            try!(term.write(b" "));
+                        }
+                    }
+                }
+                self.drawn = self.items.len();
+                term.flush()
+            }
+            Target::Plain(ref mut out) => {
+                for item in self.items.iter_mut() {
+                    if item.finished.is_some() {
+                        if item.reported {
+                            continue
+                        }
+                        item.reported = true;
+                    } else if !due {
+                        continue
+                    }
+                    try!(out.write_line(item.render(self.width, now)[]));
+                }
+                out.flush()
+            }
+        }
+    }
+}
+
+impl Item {
+    /// Returns the line showing this item at time `now`, at most `width`
+    /// columns wide.
+    fn render(&self, width: uint, now: Instant) -> String {
+        let elapsed = self.finished.unwrap_or(now) - self.started;
+        let mut parts = Vec::new();
+        if !self.prefix.is_empty() {
+            parts.push(self.prefix.clone());
+        }
+        match self.kind {
+            Kind::Bar { pos, total } => {
+                // In floating point, as `pos * 100` overflows for large totals.
+                let fraction = if total == 0 { 1.0 } else { pos as f64 / total as f64 };
+                let percent = (fraction * 100.0) as u64;
+                let mut stats = vec![format!("{:3}%", percent), format!("{}/{}", pos, total)];
+                if self.finished.is_some() {
+                    stats.push(format!("done in {}", fmt_duration(elapsed)));
+                } else if pos > 0 && pos < total {
+                    let ms = elapsed.num_milliseconds() as f64;
+                    let eta = ms * (total - pos) as f64 / pos as f64;
+                    stats.push(format!("ETA {}", fmt_duration(Duration::milliseconds(eta as i64))));
+                }
+                if !self.message.is_empty() {
+                    stats.push(self.message.clone());
+                }
+
+                // The bar gets whatever room the rest of the line leaves.
+                let others = parts.iter().chain(stats.iter()).map(|s| str_width(s[]) + 1)
+                                  .fold(0, |a, b| a + b);
+                if width >= others + MIN_BAR_WIDTH {
+                    let inner = width - others - 2;
+                    let filled = (inner as f64 * fraction) as uint;
+                    let mut bar = String::from_str("[");
+                    for i in range(0, inner) {
+                        bar.push(if i < filled {
+                            '='
+                        } else if i == filled && pos > 0 {
+                            '>'
+                        } else {
+                            ' '
+                        });
+                    }
+                    bar.push(']');
+                    parts.push(bar);
+                }
+                parts.extend(stats.into_iter());
+            }
+            Kind::Spinner { frame } => {
+                if self.finished.is_some() {
+                    parts.push(format!("done in {}", fmt_duration(elapsed)));
+                } else {
+                    parts.push(SPINNER[frame % SPINNER.len()].to_string());
+                }
+                if !self.message.is_empty() {
+                    parts.push(self.message.clone());
+                }
+            }
+        }
+
+        let line = parts.connect(" ");
+        let mut truncated = String::new();
+        let mut used = 0;
+        for c in line[].chars() {
+            used += c.width(false).unwrap_or(0);
+            if used > width {
+                break
+            }
+            truncated.push(c);
+        }
+        truncated
+    }
+}
+
+/// Formats a duration as `m:ss`, or `h:mm:ss` from an hour up.
+fn fmt_duration(d: Duration) -> String {
+    let secs = if d < Duration::zero() { 0 } else { d.num_seconds() };
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    use terminfo::{TermInfo, TerminfoTerminal};
+    use Terminal;
+    use super::{Item, Kind, Progress, fmt_duration};
+
+    fn item(prefix: &str, kind: Kind, started: Instant) -> Item {
+        Item {
+            prefix: prefix.to_string(),
+            message: String::new(),
+            kind: kind,
+            started: started,
+            finished: None,
+            reported: false,
+        }
+    }
+
+    #[test]
+    fn test_bar() {
+        let t0 = Instant::now();
+        let mut bar = item("copy", Kind::Bar { pos: 250, total: 1000 }, t0);
+        assert_eq!(bar.render(40, t0 + Duration::seconds(10)),
+                   "copy [==>       ]  25% 250/1000 ETA 0:30");
+        bar.message = "x.rs".to_string();
+        assert_eq!(bar.render(40, t0), "copy  25% 250/1000 ETA 0:00 x.rs");
+
+        // Without room for a bar it's left out, and then the line cut short.
+        bar.kind = Kind::Bar { pos: 1000, total: 1000 };
+        bar.finished = Some(t0 + Duration::seconds(75));
+        bar.message = String::new();
+        assert_eq!(bar.render(32, t0), "copy 100% 1000/1000 done in 1:15");
+        assert_eq!(bar.render(12, t0), "copy 100% 10");
+
+        // Huge totals don't overflow.
+        bar.kind = Kind::Bar { pos: 1 << 63, total: -1 };
+        bar.finished = None;
+        assert!(bar.render(80, t0).contains("]  50% 9223372036854775808/18446744073709551615"));
+    }
+
+    #[test]
+    fn test_spinner() {
+        let t0 = Instant::now();
+        let mut spinner = item("", Kind::Spinner { frame: 5 }, t0);
+        assert_eq!(spinner.render(80, t0), "/");
+        spinner.prefix = "index".to_string();
+        spinner.message = "lib.rs".to_string();
+        assert_eq!(spinner.render(80, t0), "index / lib.rs");
+        spinner.finished = Some(t0 + Duration::seconds(3725));
+        assert_eq!(spinner.render(80, t0), "index done in 1:02:05 lib.rs");
+    }
+
+    #[test]
+    fn test_fmt_duration() {
+        assert_eq!(fmt_duration(Duration::zero()), "0:00");
+        assert_eq!(fmt_duration(Duration::milliseconds(59999)), "0:59");
+        assert_eq!(fmt_duration(Duration::seconds(600)), "10:00");
+        assert_eq!(fmt_duration(Duration::seconds(-5)), "0:00");
+    }
+
+    #[test]
+    fn test_plain() {
+        let mut progress = Progress::plain(Vec::new());
+        progress.set_interval(Duration::hours(1));
+        let bar = progress.add_bar("build", 10);
+        progress.inc(bar, 5).unwrap();
+        progress.inc(bar, 1).unwrap();
+        progress.finish(bar).unwrap();
+        progress.finish(bar).unwrap();
+
+        let out = String::from_utf8(progress.get_ref().clone()).unwrap();
+        let lines: Vec<&str> = out[].lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("build [") && lines[0].contains("]  50% 5/10 ETA "));
+        assert!(lines[1].starts_with("build [=") && lines[1].contains("] 100% 10/10 done in "));
+    }
+
+    fn ansi() -> Box<TermInfo> {
+        let mut strings = HashMap::new();
+        strings.insert("el".to_string(), b"\x1b[K".to_vec());
+        strings.insert("cuu".to_string(), b"\x1b[%p1%dA".to_vec());
+        box TermInfo {
+            names: vec!("ansi".to_string()),
+            bools: HashMap::new(),
+            numbers: HashMap::new(),
+            strings: strings
+        }
+    }
+
+    #[test]
+    fn test_terminal() {
+        let term = box TerminfoTerminal::new_with_terminfo(Vec::new(), ansi());
+        let mut progress = Progress::new(term as Box<Terminal<Vec<u8>> + Send>, 40);
+        progress.set_interval(Duration::zero());
+        progress.add_spinner("a");
+        progress.add_spinner("b");
+        progress.tick().unwrap();
+        progress.tick().unwrap();
+        progress.end().unwrap();
+        progress.tick().unwrap();
+        progress.add_spinner("c");
+        progress.tick().unwrap();
+
+        let out = String::from_utf8(progress.get_ref().clone()).unwrap();
+        assert_eq!(out, "\ra /\x1b[K\nb /\x1b[K\
+                         \x1b[1A\ra -\x1b[K\nb -\x1b[K\
+                         \x1b[1A\ra -\x1b[K\nb -\x1b[K\n\
+                         \r\
+                         \rc /\x1b[K");
+    }
+
+    #[test]
+    fn test_terminal_throttled() {
+        let term = box TerminfoTerminal::new_with_terminfo(Vec::new(), ansi());
+        let mut progress = Progress::new(term as Box<Terminal<Vec<u8>> + Send>, 40);
+        progress.set_interval(Duration::hours(1));
+        let a = progress.add_spinner("a");
+        progress.tick().unwrap();
+        progress.tick().unwrap();
+        progress.set_message(a, "x").unwrap();
+        progress.finish(a).unwrap();
+
+        let out = String::from_utf8(progress.get_ref().clone()).unwrap();
+        assert!(out.starts_with("\ra /\x1b[K\ra done in "));
+        assert!(out.ends_with(" x\x1b[K"));
+    }
+
+    #[test]
+    fn test_stale_id() {
+        let mut progress = Progress::plain(Vec::new());
+        let bar = progress.add_bar("build", 10);
+        progress.end().unwrap();
+        let other = progress.add_bar("test", 10);
+        assert!(other != bar);
+        progress.inc(bar, 1).unwrap();
+        progress.set_position(bar, 2).unwrap();
+        progress.set_message(bar, "x").unwrap();
+        progress.finish(bar).unwrap();
+        assert!(progress.item_mut(other).unwrap().finished.is_none());
+    }
+}
@@ -0,0 +1,789 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Line editing for interactive prompts.
+//!
+//! `Editor::read_line` reads a line from the terminal, letting the user edit
+//! it with the usual emacs key bindings:
+//!
+//! Keys                     | Action
+//! -------------------------|--------------------------------------------
+//! ^A, Home / ^E, End       | Move to the start / end of the line
+//! ^B, Left / ^F, Right     | Move back / forward one character
+//! M-b / M-f                | Move back / forward one word
+//! ^H, Backspace / ^D, Del  | Delete the character before / under the cursor
+//! ^W, M-Backspace / M-d    | Cut the word before / after the cursor
+//! ^U / ^K                  | Cut to the start / end of the line
+//! ^Y                       | Paste the text cut last
+//! ^T                       | Swap the two characters around the cursor
+//! ^P, Up / ^N, Down        | Go back / forward in the history
+//! Tab                      | Complete the word before the cursor
+//! ^L                       | Clear the screen
+//! Enter                    | Accept the line
+//!
+//! The cursor moves by whole characters of UTF-8 input, and double width
+//! characters take up two columns on screen. Lines too long for the terminal
+//! scroll horizontally.
+//!
+//! When standard input or output isn't a terminal, the prompt is printed and
+//! a line read without any editing.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use term::readline::Editor;
+//!
+//! let mut editor = Editor::new();
+//! loop {
+//!     let line = match editor.read_line("> ") {
+//!         Ok(line) => line,
+//!         Err(..) => break,
+//!     };
+//!     editor.add_history(line[]);
+//!     println!("read {}", line);
+//! }
+//! ```
+
+use std::cmp;
+use std::io::{mod, BufferedReader, File, IoError, IoResult};
+use std::str;
+
+use {str_width, Terminal};
+use tty;
+
+/// Supplies completions to an `Editor` when Tab is pressed.
+pub trait Completer {
+    /// Returns the possible completions of the line `line` with the cursor
+    /// at byte offset `pos`, along with the byte offset at which the text
+    /// being completed starts. A completion replaces `line[start..pos]`.
+    fn complete(&self, line: &str, pos: uint) -> (uint, Vec<String>);
+}
+
+/// A line editor, keeping the history of lines read and the text cut last.
+pub struct Editor {
+    history: Vec<String>,
+    history_limit: uint,
+    completer: Option<Box<Completer + 'static>>,
+    cut: String,
+}
+
+/// The number of lines of history kept by default.
+pub const DEFAULT_HISTORY_LIMIT: uint = 1000;
+
+/// The description of the error returned when the user presses ^C.
+pub const INTERRUPTED: &'static str = "interrupted";
+
+impl Editor {
+    /// Creates an editor with an empty history and no completer.
+    pub fn new() -> Editor {
+        Editor {
+            history: Vec::new(),
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            completer: None,
+            cut: String::new(),
+        }
+    }
+
+    /// Sets the completer consulted when Tab is pressed.
+    pub fn set_completer(&mut self, completer: Box<Completer + 'static>) {
+        self.completer = Some(completer);
+    }
+
+    /// Returns the history, oldest line first.
+    pub fn history(&self) -> &[String] {
+        self.history[]
+    }
+
+    /// Adds a line to the end of the history, dropping the oldest line if the
+    /// history is full. Empty lines and repeats of the last line are not
+    /// added; returns whether `line` was.
+    pub fn add_history(&mut self, line: &str) -> bool {
+        if line.is_empty() || self.history.last().map_or(false, |l| l[] == line) {
+            return false
+        }
+        self.history.push(line.to_string());
+        self.trim_history();
+        true
+    }
+
+    /// Sets the number of lines of history to keep, dropping the oldest lines
+    /// beyond that.
+    pub fn set_history_limit(&mut self, limit: uint) {
+        self.history_limit = limit;
+        self.trim_history();
+    }
+
+    /// Adds the lines of the file at `path` to the history.
+    pub fn load_history(&mut self, path: &Path) -> IoResult<()> {
+        let mut file = BufferedReader::new(try!(File::open(path)));
+        for line in file.lines() {
+            let line = try!(line);
+            self.add_history(line.trim_right_chars(|c: char| c == '\n' || c == '\r'));
+        }
+        Ok(())
+    }
+
+    /// Writes the history to the file at `path`, one line per entry.
+    pub fn save_history(&self, path: &Path) -> IoResult<()> {
+        let mut file = try!(File::create(path));
+        for line in self.history.iter() {
+            try!(file.write_line(line[]));
+        }
+        Ok(())
+    }
+
+    fn trim_history(&mut self) {
+        if self.history.len() > self.history_limit {
+            let excess = self.history.len() - self.history_limit;
+            self.history = self.history[excess..].to_vec();
+        }
+    }
+
+    /// Prints `prompt` and reads a line from standard input, without the
+    /// trailing newline.
+    ///
+    /// Returns an `EndOfFile` error if ^D is pressed on an empty line, and an
+    /// error described by `INTERRUPTED` if ^C is pressed.
+    pub fn read_line(&mut self, prompt: &str) -> IoResult<String> {
+        if !tty::isatty(tty::Stdin) || !tty::isatty(tty::Stdout) {
+            return read_plain(prompt)
+        }
+        let mut term = match ::stdout() {
+            Some(term) => term,
+            None => return read_plain(prompt),
+        };
+        let width = tty::size(tty::Stdout).map_or(80, |(cols, _)| cols);
+        let _raw = try!(tty::raw_mode(tty::Stdin));
+        self.read_line_with(prompt, &mut io::stdin(), &mut *term, width)
+    }
+
+    /// Reads and edits a line like `read_line`, taking keys from `input` and
+    /// drawing on `term`, which is `width` columns wide.
+    ///
+    /// `input` should be a terminal in raw mode, or something standing in
+    /// for one.
+    pub fn read_line_with<R: Reader, W: Writer>(&mut self, prompt: &str, input: &mut R,
+                                                term: &mut Terminal<W>, width: u16)
+                                                -> IoResult<String> {
+        let mut line = Line {
+            prompt: prompt,
+            buf: String::new(),
+            pos: 0,
+            width: width as uint,
+            drawn: 0,
+        };
+        // Browsing the history replaces the line being edited, which is kept
+        // in `editing` until we come back to it.
+        let mut index = self.history.len();
+        let mut editing = String::new();
+
+        try!(line.refresh(term));
+        loop {
+            let key = match read_key(input) {
+                Ok(key) => key,
+                Err(ref e) if e.kind == io::EndOfFile && !line.buf.is_empty() => break,
+                Err(e) => return Err(e),
+            };
+            match key {
+                Key::Char(c) => line.insert(c),
+                Key::Ctrl('m') | Key::Ctrl('j') => break,
+
+                Key::Ctrl('a') | Key::Home => line.pos = 0,
+                Key::Ctrl('e') | Key::End => line.pos = line.buf.len(),
+                Key::Ctrl('b') | Key::Left => line.pos = line.prev(line.pos),
+                Key::Ctrl('f') | Key::Right => line.pos = line.next(line.pos),
+                Key::Meta('b') => line.pos = line.word_start(),
+                Key::Meta('f') => line.pos = line.word_end(),
+
+                Key::Ctrl('h') | Key::Backspace => {
+                    let (start, end) = (line.prev(line.pos), line.pos);
+                    line.remove(start, end);
+                }
+                Key::Ctrl('d') if line.buf.is_empty() => {
+                    return Err(io::standard_error(io::EndOfFile))
+                }
+                Key::Ctrl('d') | Key::Delete => {
+                    let (start, end) = (line.pos, line.next(line.pos));
+                    line.remove(start, end);
+                }
+                Key::Ctrl('w') | Key::Meta('\x7f') => {
+                    let (start, end) = (line.word_start(), line.pos);
+                    self.cut = line.remove(start, end);
+                }
+                Key::Meta('d') => {
+                    let (start, end) = (line.pos, line.word_end());
+                    self.cut = line.remove(start, end);
+                }
+                Key::Ctrl('u') => {
+                    let end = line.pos;
+                    self.cut = line.remove(0, end);
+                }
+                Key::Ctrl('k') => {
+                    let (start, end) = (line.pos, line.buf.len());
+                    self.cut = line.remove(start, end);
+                }
+                Key::Ctrl('y') => line.insert_str(self.cut[]),
+                Key::Ctrl('t') => line.transpose(),
+
+                Key::Ctrl('p') | Key::Up if index > 0 => {
+                    if index == self.history.len() {
+                        editing = line.buf.clone();
+                    }
+                    index -= 1;
+                    line.set(self.history[index].clone());
+                }
+                Key::Ctrl('n') | Key::Down if index < self.history.len() => {
+                    index += 1;
+                    line.set(if index == self.history.len() {
+                        editing.clone()
+                    } else {
+                        self.history[index].clone()
+                    });
+                }
+
+                Key::Ctrl('i') => {
+                    match self.completer {
+                        Some(ref completer) => try!(complete(&**completer, &mut line, term)),
+                        None => {}
+                    }
+                }
+                Key::Ctrl('l') => {
+                    try!(term.clear_screen());
+                    line.drawn = 0;
+                }
+                Key::Ctrl('c') => {
+                    return Err(IoError {
+                        kind: io::OtherIoError,
+                        desc: INTERRUPTED,
+                        detail: None,
+                    })
+                }
+                _ => {}
+            }
+            try!(line.refresh(term));
+        }
+
+        // Draw the whole line before moving on, in case it was scrolled.
+        line.pos = line.buf.len();
+        try!(line.refresh(term));
+        try!(term.write(b"\r\n"));
+        try!(term.flush());
+        Ok(line.buf)
+    }
+}
+
+/// Reads a line without editing it, for when we aren't talking to a terminal.
+fn read_plain(prompt: &str) -> IoResult<String> {
+    let mut out = io::stdout();
+    try!(out.write_str(prompt));
+    try!(out.flush());
+    let mut line = try!(io::stdin().read_line());
+    if line[].ends_with("\n") {
+        line.pop();
+        if line[].ends_with("\r") {
+            line.pop();
+        }
+    }
+    Ok(line)
+}
+
+/// A key press, decoded from the bytes a terminal sends.
+#[deriving(Copy, PartialEq, Show)]
+enum Key {
+    Char(char),
+    /// A control character, as the lowercase letter typed with Ctrl
+    Ctrl(char),
+    /// A character typed with Meta (or Alt), which sends Escape first
+    Meta(char),
+    Backspace,
+    Delete,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    Unknown,
+}
+
+fn read_key<R: Reader>(input: &mut R) -> IoResult<Key> {
+    let b = try!(input.read_byte());
+    match b {
+        0x1b => read_escape(input),
+        0x7f => Ok(Key::Backspace),
+        0x01...0x1a => Ok(Key::Ctrl((b'a' + b - 1) as char)),
+        0x20...0x7e => Ok(Key::Char(b as char)),
+        0x80...0xff => read_utf8(input, b),
+        _ => Ok(Key::Unknown),
+    }
+}
+
+fn read_escape<R: Reader>(input: &mut R) -> IoResult<Key> {
+    let b = try!(input.read_byte());
+    match b {
+        b'[' => {
+            // A CSI sequence: parameters followed by a final byte.
+            let mut params = Vec::new();
+            loop {
+                let b = try!(input.read_byte());
+                if b >= 0x40 && b <= 0x7e {
+                    return Ok(csi_key(params[], b))
+                }
+                params.push(b);
+            }
+        }
+        b'O' => Ok(csi_key(&[], try!(input.read_byte()))),
+        0x7f | 0x08 => Ok(Key::Meta('\x7f')),
+        0x20...0x7e => Ok(Key::Meta(b as char)),
+        _ => Ok(Key::Unknown),
+    }
+}
+
+fn csi_key(params: &[u8], last: u8) -> Key {
+    if params.is_empty() {
+        match last {
+            b'A' => Key::Up,
+            b'B' => Key::Down,
+            b'C' => Key::Right,
+            b'D' => Key::Left,
+            b'H' => Key::Home,
+            b'F' => Key::End,
+            _ => Key::Unknown,
+        }
+    } else if params.len() == 1 && last == b'~' {
+        match params[0] {
+            b'1' | b'7' => Key::Home,
+            b'4' | b'8' => Key::End,
+            b'3' => Key::Delete,
+            _ => Key::Unknown,
+        }
+    } else {
+        Key::Unknown
+    }
+}
+
+fn read_utf8<R: Reader>(input: &mut R, first: u8) -> IoResult<Key> {
+    let len = match first {
+        0xc0...0xdf => 2,
+        0xe0...0xef => 3,
+        0xf0...0xf7 => 4,
+        _ => return Ok(Key::Unknown),
+    };
+    let mut bytes = vec![first];
+    for _ in range(1u, len) {
+        bytes.push(try!(input.read_byte()));
+    }
+    Ok(match str::from_utf8(bytes[]) {
+        Ok(s) => Key::Char(s.char_at(0)),
+        Err(..) => Key::Unknown,
+    })
+}
+
+/// The line being edited. `pos` is the byte offset of the cursor, always on a
+/// character boundary.
+struct Line<'a> {
+    prompt: &'a str,
+    buf: String,
+    pos: uint,
+    width: uint,
+    /// The number of columns drawn by the last refresh
+    drawn: uint,
+}
+
+impl<'a> Line<'a> {
+    fn prev(&self, pos: uint) -> uint {
+        if pos == 0 { 0 } else { self.buf[].char_range_at_reverse(pos).next }
+    }
+
+    fn next(&self, pos: uint) -> uint {
+        if pos == self.buf.len() { pos } else { self.buf[].char_range_at(pos).next }
+    }
+
+    fn is_word_char(&self, pos: uint) -> bool {
+        self.buf[].char_at(pos).is_alphanumeric()
+    }
+
+    /// The start of the word before the cursor.
+    fn word_start(&self) -> uint {
+        let mut pos = self.pos;
+        while pos > 0 && !self.is_word_char(self.prev(pos)) {
+            pos = self.prev(pos);
+        }
+        while pos > 0 && self.is_word_char(self.prev(pos)) {
+            pos = self.prev(pos);
+        }
+        pos
+    }
+
+    /// The end of the word after the cursor.
+    fn word_end(&self) -> uint {
+        let mut pos = self.pos;
+        while pos < self.buf.len() && !self.is_word_char(pos) {
+            pos = self.next(pos);
+        }
+        while pos < self.buf.len() && self.is_word_char(pos) {
+            pos = self.next(pos);
+        }
+        pos
+    }
+
+    fn set(&mut self, buf: String) {
+        self.pos = buf.len();
+        self.buf = buf;
+    }
+
+    fn insert(&mut self, c: char) {
+        self.buf.insert(self.pos, c);
+        self.pos += c.len_utf8_bytes();
+    }
+
+    fn insert_str(&mut self, s: &str) {
+        for c in s.chars() {
+            self.insert(c);
+        }
+    }
+
+    /// Removes and returns `buf[start..end]`, leaving the cursor at `start`
+    /// if it was after it.
+    fn remove(&mut self, start: uint, end: uint) -> String {
+        let removed = self.buf[start..end].to_string();
+        let rest = self.buf[end..].to_string();
+        self.buf.truncate(start);
+        self.buf.push_str(rest[]);
+        if self.pos > start {
+            self.pos = cmp::max(start, self.pos - (end - start));
+        }
+        removed
+    }
+
+    /// Swaps the characters before and under the cursor, or the last two
+    /// characters at the end of the line, and moves past them.
+    fn transpose(&mut self) {
+        if self.pos == 0 || self.buf[].char_len() < 2 {
+            return
+        }
+        if self.pos == self.buf.len() {
+            self.pos = self.prev(self.pos);
+        }
+        let (start, pos, end) = (self.prev(self.pos), self.pos, self.next(self.pos));
+        let c = self.remove(start, pos);
+        self.pos = end - c.len();
+        self.insert_str(c[]);
+    }
+
+    /// Redraws the prompt and as much of the line around the cursor as fits,
+    /// and puts the cursor back in its place.
+    fn refresh<W: Writer>(&mut self, term: &mut Terminal<W>) -> IoResult<()> {
+        let prompt_width = str_width(self.prompt);
+        let avail = if self.width > prompt_width + 1 {
+            self.width - prompt_width - 1
+        } else {
+            1
+        };
+
+        // Scroll just far enough for the cursor to be on screen, then show as
+        // much of the rest as fits.
+        let mut start = 0;
+        while str_width(self.buf[start..self.pos]) > avail {
+            start = self.next(start);
+        }
+        let mut end = self.pos;
+        while end < self.buf.len() {
+            let next = self.next(end);
+            if str_width(self.buf[start..next]) > avail {
+                break
+            }
+            end = next;
+        }
+
+        try!(carriage_return(term));
+        try!(term.write_str(self.prompt));
+        try!(term.write_str(self.buf[start..end]));
+        let drawn = prompt_width + str_width(self.buf[start..end]);
+        let mut redraw = false;
+        if !try!(term.clear_to_eol()) {
+            // Blank out the rest of a longer line by hand instead.
+            if self.drawn > drawn {
+                for _ in range(drawn, self.drawn) {
+                    try!(term.write(b" "));
+                }
+                redraw = true;
+            }
+        }
+        self.drawn = drawn;
+
+        let back = str_width(self.buf[self.pos..end]);
+        if redraw || (back > 0 && !try!(term.cursor_left(back as u16))) {
+            // Without a way to move left, write everything up to the cursor
+            // again.
+            try!(carriage_return(term));
+            try!(term.write_str(self.prompt));
+            try!(term.write_str(self.buf[start..self.pos]));
+        }
+        term.flush()
+    }
+}
+
+fn carriage_return<W: Writer>(term: &mut Terminal<W>) -> IoResult<()> {
+    if !try!(term.carriage_return()) {
+        try!(term.write(b"\r"));
+    }
+    Ok(())
+}
+
+/// Completes the word before the cursor, inserting the completion if there's
+/// only one, and otherwise as much as all completions have in common. If
+/// that doesn't add anything, lists the completions below the line.
+fn complete<W: Writer>(completer: &Completer, line: &mut Line, term: &mut Terminal<W>)
+                       -> IoResult<()> {
+    let (start, completions) = completer.complete(line.buf[], line.pos);
+    if completions.is_empty() || start > line.pos || !line.buf[].is_char_boundary(start) {
+        return Ok(())
+    }
+    let prefix = if completions.len() == 1 {
+        completions[0][]
+    } else {
+        common_prefix(completions[])
+    };
+    if prefix.len() > line.pos - start {
+        let pos = line.pos;
+        line.remove(start, pos);
+        line.insert_str(prefix);
+    } else if completions.len() > 1 {
+        try!(term.write(b"\r\n"));
+        for (i, c) in completions.iter().enumerate() {
+            if i > 0 {
+                try!(term.write(b"  "));
+            }
+            try!(term.write_str(c[]));
+        }
+        try!(term.write(b"\r\n"));
+        line.drawn = 0;
+    }
+    Ok(())
+}
+
+/// The longest prefix shared by all of `strs`, which must not be empty.
+fn common_prefix<'a>(strs: &'a [String]) -> &'a str {
+    let mut prefix = strs[0][];
+    for s in strs[1..].iter() {
+        let mut len = 0;
+        for ((i, a), b) in prefix.char_indices().zip(s[].chars()) {
+            if a != b {
+                break
+            }
+            len = i + a.len_utf8_bytes();
+        }
+        prefix = prefix[..len];
+    }
+    prefix
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::{mod, IoResult, MemReader};
+
+    use terminfo::{TermInfo, TerminfoTerminal};
+    use terminfo::parser::compiled::msys_terminfo;
+    use super::{Completer, Editor, INTERRUPTED};
+    use {Terminal, UnwrappableTerminal};
+
+    fn run_with(editor: &mut Editor, ti: Box<TermInfo>, width: u16, keys: &str)
+                -> (IoResult<String>, String) {
+        let mut input = MemReader::new(keys.as_bytes().to_vec());
+        let mut term = TerminfoTerminal::new_with_terminfo(Vec::new(), ti);
+        let line = editor.read_line_with("> ", &mut input,
+                                         &mut term as &mut Terminal<Vec<u8>>, width);
+        (line, String::from_utf8(term.unwrap()).unwrap())
+    }
+
+    fn run(editor: &mut Editor, keys: &str) -> IoResult<String> {
+        let (line, _) = run_with(editor, msys_terminfo(), 80, keys);
+        line
+    }
+
+    fn edit(keys: &str) -> String {
+        run(&mut Editor::new(), keys).unwrap()
+    }
+
+    #[test]
+    fn test_editing() {
+        assert_eq!(edit("hello\r")[], "hello");
+        assert_eq!(edit("ello\x01h\x05!\r")[], "hello!");
+        assert_eq!(edit("hllo\x1b[D\x1b[D\x1b[De\n")[], "hello");
+        assert_eq!(edit("helo\x02\x02\x06l\x1b[F!\r")[], "hello!");
+        assert_eq!(edit("helllo\x7f\x02\x08\x04\r")[], "hel");
+        assert_eq!(edit("abc\x01\x1b[3~\r")[], "bc");
+        assert_eq!(edit("ab\x14\r")[], "ba");
+        assert_eq!(edit("abc\x02\x02\x14\r")[], "bac");
+    }
+
+    #[test]
+    fn test_cut_and_paste() {
+        assert_eq!(edit("one two\x17\x19\x19\r")[], "one twotwo");
+        assert_eq!(edit("one two\x01\x1bd\x19\x19\r")[], "oneone two");
+        assert_eq!(edit("one two three\x1bb\x1bb\x0b\r")[], "one ");
+        assert_eq!(edit("one two\x1bb\x15\x05 \x19\r")[], "two one ");
+        assert_eq!(edit("a-b c\x1b\x7f\r")[], "a-b ");
+    }
+
+    #[test]
+    fn test_utf8() {
+        assert_eq!(edit("h\u{e9}\u{4e2d}\x02\x02X\r")[], "hX\u{e9}\u{4e2d}");
+        assert_eq!(edit("\u{e9}\u{e9}\x7f\r")[], "\u{e9}");
+        assert_eq!(edit("a\u{1f600}\x14\r")[], "\u{1f600}a");
+    }
+
+    #[test]
+    fn test_history() {
+        let mut editor = Editor::new();
+        assert!(editor.add_history("one"));
+        assert!(editor.add_history("two"));
+        assert!(!editor.add_history("two"));
+        assert!(!editor.add_history(""));
+        assert_eq!(run(&mut editor, "\x10\x10\r").unwrap()[], "one");
+        assert_eq!(run(&mut editor, "\x1b[A\x1b[A\x1b[A\x0e\r").unwrap()[], "two");
+        assert_eq!(run(&mut editor, "x\x10\x0e\x0ey\r").unwrap()[], "xy");
+
+        editor.set_history_limit(1);
+        assert_eq!(editor.history(), ["two".to_string()][]);
+        editor.add_history("three");
+        assert_eq!(editor.history(), ["three".to_string()][]);
+    }
+
+    #[test]
+    fn test_history_file() {
+        let dir = io::TempDir::new("readline").unwrap();
+        let path = dir.path().join("history");
+        let mut editor = Editor::new();
+        editor.add_history("one");
+        editor.add_history("two \u{e9}");
+        editor.save_history(&path).unwrap();
+
+        let mut editor = Editor::new();
+        editor.load_history(&path).unwrap();
+        assert_eq!(editor.history(), ["one".to_string(), "two \u{e9}".to_string()][]);
+    }
+
+    struct Words(Vec<&'static str>);
+
+    impl Completer for Words {
+        fn complete(&self, line: &str, pos: uint) -> (uint, Vec<String>) {
+            let start = line[..pos].rfind(' ').map_or(0, |i| i + 1);
+            let Words(ref words) = *self;
+            (start, words.iter().filter(|w| w.starts_with(line[start..pos]))
+                                .map(|w| w.to_string()).collect())
+        }
+    }
+
+    #[test]
+    fn test_completion() {
+        let mut editor = Editor::new();
+        editor.set_completer(box Words(vec!["help", "hello", "quit"]));
+        assert_eq!(run(&mut editor, "q\t\r").unwrap()[], "quit");
+        assert_eq!(run(&mut editor, "say he\t\r").unwrap()[], "say hel");
+        assert_eq!(run(&mut editor, "x\t\r").unwrap()[], "x");
+
+        let (line, out) = run_with(&mut editor, msys_terminfo(), 80, "hel\t\r");
+        assert_eq!(line.unwrap()[], "hel");
+        assert!(out[].contains("\r\nhelp  hello\r\n"));
+    }
+
+    #[test]
+    fn test_end_of_input() {
+        assert_eq!(run(&mut Editor::new(), "").unwrap_err().kind, io::EndOfFile);
+        assert_eq!(run(&mut Editor::new(), "\x04").unwrap_err().kind, io::EndOfFile);
+        assert_eq!(edit("ab")[], "ab");
+        assert_eq!(run(&mut Editor::new(), "ab\x03").unwrap_err().desc, INTERRUPTED);
+    }
+
+    fn ansi() -> Box<TermInfo> {
+        let mut strings = HashMap::new();
+        strings.insert("el".to_string(), b"\x1b[K".to_vec());
+        strings.insert("cub".to_string(), b"\x1b[%p1%dD".to_vec());
+        box TermInfo {
+            names: vec!("ansi".to_string()),
+            bools: HashMap::new(),
+            numbers: HashMap::new(),
+            strings: strings
+        }
+    }
+
+    #[test]
+    fn test_drawing() {
+        let (_, out) = run_with(&mut Editor::new(), ansi(), 80, "ab\x02\r");
+        assert_eq!(out, "\r> \x1b[K\r> a\x1b[K\r> ab\x1b[K\r> ab\x1b[K\x1b[1D\
+                         \r> ab\x1b[K\r\n");
+
+        // Without capabilities, old text is blanked with spaces and the line
+        // written again up to the cursor.
+        let (_, out) = run_with(&mut Editor::new(), msys_terminfo(), 80, "ab\x7f\x02\r");
+        assert_eq!(out, "\r> \r> a\r> ab\r> a \r> a\r> a\r> \r> a\r\n");
+    }
+
+    #[test]
+    fn test_scrolling() {
+        // Eight columns leave five for the line after the prompt and cursor.
+        let (line, out) = run_with(&mut Editor::new(), ansi(), 8,
+                                   "abcdefg\x01\x05\u{4e2d}\r");
+        assert_eq!(line.unwrap()[], "abcdefg\u{4e2d}");
+        assert!(out[].contains("\r> cdefg\x1b[K\r> abcde\x1b[K\x1b[5D"));
+        assert!(out[].ends_with("\r> efg\u{4e2d}\x1b[K\r\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pty() {
+        use libc;
+        use libc::{c_char, c_int};
+        use std::io::pipe::PipeStream;
+        use tty;
+
+        extern {
+            fn posix_openpt(flags: c_int) -> c_int;
+            fn grantpt(fd: c_int) -> c_int;
+            fn unlockpt(fd: c_int) -> c_int;
+            fn ptsname(fd: c_int) -> *const c_char;
+        }
+
+        let (master, slave) = unsafe {
+            let master = posix_openpt(libc::O_RDWR);
+            assert!(master >= 0);
+            assert_eq!(grantpt(master), 0);
+            assert_eq!(unlockpt(master), 0);
+            let slave = libc::open(ptsname(master), libc::O_RDWR, 0);
+            assert!(slave >= 0);
+            (master, slave)
+        };
+        let mut master = PipeStream::open(master).unwrap();
+        let mut input = PipeStream::open(slave).unwrap();
+        let mut term = TerminfoTerminal::new_with_terminfo(input.clone(), ansi());
+
+        assert!(tty::isatty(tty::Fd(slave)));
+        {
+            let _raw = tty::raw_mode(tty::Fd(slave)).unwrap();
+            master.write(b"wrd\x02\x02o\x05!\r").unwrap();
+            let line = Editor::new().read_line_with("$ ", &mut input,
+                                                    &mut term as &mut Terminal<PipeStream>, 80);
+            assert_eq!(line.unwrap()[], "word!");
+        }
+
+        // With echo off, all that comes back is what the editor drew.
+        let mut out = Vec::new();
+        while !out[].ends_with(b"\r\n") {
+            let mut buf = [0u8; 256];
+            let n = master.read(&mut buf).unwrap();
+            out.push_all(buf[..n]);
+        }
+        assert!(out[].starts_with(b"\r$ \x1b[K\r$ w\x1b[K"));
+        assert!(out[].ends_with(b"\r$ word!\x1b[K\r\n"));
+    }
+}
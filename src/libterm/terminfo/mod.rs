@@ -20,7 +20,7 @@ use Terminal;
 use UnwrappableTerminal;
 use self::searcher::open;
 use self::parser::compiled::{parse, msys_terminfo};
-use self::parm::{expand, Number, Variables};
+use self::parm::{expand, Number, Param, Variables};
 
 
 /// A parsed terminfo database entry.
@@ -159,6 +159,30 @@ impl<T: Writer+Send> Terminal<T> for TerminfoTerminal<T> {
         Ok(())
     }
 
+    fn cursor_goto(&mut self, row: u16, col: u16) -> IoResult<bool> {
+        self.apply_cap("cup", &[Number(row as int), Number(col as int)])
+    }
+
+    fn cursor_up(&mut self, n: u16) -> IoResult<bool> { self.move_cursor("cuu", "cuu1", n) }
+
+    fn cursor_down(&mut self, n: u16) -> IoResult<bool> { self.move_cursor("cud", "cud1", n) }
+
+    fn cursor_left(&mut self, n: u16) -> IoResult<bool> { self.move_cursor("cub", "cub1", n) }
+
+    fn cursor_right(&mut self, n: u16) -> IoResult<bool> { self.move_cursor("cuf", "cuf1", n) }
+
+    fn carriage_return(&mut self) -> IoResult<bool> { self.apply_cap("cr", &[]) }
+
+    fn hide_cursor(&mut self) -> IoResult<bool> { self.apply_cap("civis", &[]) }
+
+    fn show_cursor(&mut self) -> IoResult<bool> { self.apply_cap("cnorm", &[]) }
+
+    fn clear_to_eol(&mut self) -> IoResult<bool> { self.apply_cap("el", &[]) }
+
+    fn clear_to_eos(&mut self) -> IoResult<bool> { self.apply_cap("ed", &[]) }
+
+    fn clear_screen(&mut self) -> IoResult<bool> { self.apply_cap("clear", &[]) }
+
     fn get_ref<'a>(&'a self) -> &'a T { &self.out }
 
     fn get_mut<'a>(&'a mut self) -> &'a mut T { &mut self.out }
@@ -201,15 +225,55 @@ impl<T: Writer+Send> TerminfoTerminal<T> {
             return None;
         }
 
-        let inf = ti.unwrap();
-        let nc = if inf.strings.get("setaf").is_some()
-                 && inf.strings.get("setab").is_some() {
-                     inf.numbers.get("colors").map_or(0, |&n| n)
+        return Some(box TerminfoTerminal::new_with_terminfo(out, ti.unwrap())
+                    as Box<Terminal<T>+Send>);
+    }
+
+    /// Creates a terminal writing to `out` using the capabilities in
+    /// `terminfo`, rather than looking up the entry for `TERM`.
+    pub fn new_with_terminfo(out: T, terminfo: Box<TermInfo>) -> TerminfoTerminal<T> {
+        let nc = if terminfo.strings.get("setaf").is_some()
+                 && terminfo.strings.get("setab").is_some() {
+                     terminfo.numbers.get("colors").map_or(0, |&n| n)
                  } else { 0 };
 
-        return Some(box TerminfoTerminal {out: out,
-                                          ti: inf,
-                                          num_colors: nc} as Box<Terminal<T>+Send>);
+        TerminfoTerminal {out: out, ti: terminfo, num_colors: nc}
+    }
+
+    /// Expands the string capability `cap` with `params` and writes it out.
+    /// Returns `Ok(false)` if the terminal doesn't have the capability.
+    fn apply_cap(&mut self, cap: &str, params: &[Param]) -> IoResult<bool> {
+        let s = match self.ti.strings.get(cap) {
+            Some(cap) => expand(cap.as_slice(), params, &mut Variables::new()),
+            None => return Ok(false),
+        };
+        match s {
+            Ok(s) => {
+                try!(self.out.write(s.as_slice()));
+                Ok(true)
+            }
+            Err(..) => Ok(false),
+        }
+    }
+
+    /// Moves the cursor `n` times in one direction, using the parameterized
+    /// capability `parm` if the terminal has it and repeating `single`
+    /// otherwise. Many terminals take a count of zero to mean one, so nothing
+    /// is written then.
+    fn move_cursor(&mut self, parm: &str, single: &str, n: u16) -> IoResult<bool> {
+        if n == 0 {
+            return Ok(true)
+        }
+        if self.ti.strings.contains_key(parm) {
+            return self.apply_cap(parm, &[Number(n as int)])
+        }
+        if !self.ti.strings.contains_key(single) {
+            return Ok(false)
+        }
+        for _ in range(0, n) {
+            try!(self.apply_cap(single, &[]));
+        }
+        Ok(true)
     }
 
     fn dim_if_necessary(&self, color: color::Color) -> color::Color {
@@ -230,3 +294,47 @@ impl<T: Writer> Writer for TerminfoTerminal<T> {
     }
 }
 
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{TermInfo, TerminfoTerminal};
+    use Terminal;
+
+    fn xterm() -> Box<TermInfo> {
+        let mut strings = HashMap::new();
+        for &(name, cap) in [("cup", "\x1B[%i%p1%d;%p2%dH"), ("cuu1", "\x1B[A"),
+                             ("cub", "\x1B[%p1%dD"), ("cr", "\r"),
+                             ("el", "\x1B[K"), ("clear", "\x1B[H\x1B[2J")].iter() {
+            strings.insert(name.to_string(), cap.as_bytes().to_vec());
+        }
+        box TermInfo {
+            names: vec!("xterm".to_string()),
+            bools: HashMap::new(),
+            numbers: HashMap::new(),
+            strings: strings
+        }
+    }
+
+    #[test]
+    fn test_cursor_and_clear() {
+        let mut t = TerminfoTerminal::new_with_terminfo(Vec::new(), xterm());
+        assert_eq!(t.cursor_goto(4, 9), Ok(true));
+        assert_eq!(t.cursor_up(2), Ok(true));
+        assert_eq!(t.cursor_left(3), Ok(true));
+        assert_eq!(t.carriage_return(), Ok(true));
+        assert_eq!(t.clear_to_eol(), Ok(true));
+        assert_eq!(t.clear_screen(), Ok(true));
+        assert_eq!(t.cursor_down(1), Ok(false));
+        assert_eq!(t.hide_cursor(), Ok(false));
+        assert_eq!(t.get_ref()[], b"\x1B[5;10H\x1B[A\x1B[A\x1B[3D\r\x1B[K\x1B[H\x1B[2J");
+    }
+
+    #[test]
+    fn test_cursor_zero() {
+        let mut t = TerminfoTerminal::new_with_terminfo(Vec::new(), xterm());
+        assert_eq!(t.cursor_left(0), Ok(true));
+        assert_eq!(t.cursor_up(0), Ok(true));
+        assert_eq!(t.get_ref()[], b"");
+    }
+}
@@ -0,0 +1,409 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Querying and configuring the terminal attached to a standard stream.
+//!
+//! The `Terminal` trait only deals with what is written to a terminal. This
+//! module covers the rest: whether a stream is a terminal at all, how large
+//! that terminal is, and switching its input out of the usual line-buffered,
+//! echoing mode.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use term::tty;
+//!
+//! let (width, _) = tty::size(tty::Stdout).unwrap_or((80, 24));
+//! println!("{}", String::from_char(width as uint, '-'));
+//!
+//! // Read a password without echoing it; echo comes back when `guard` is
+//! // dropped.
+//! let guard = tty::no_echo(tty::Stdin).unwrap();
+//! let password = std::io::stdin().read_line().unwrap();
+//! drop(guard);
+//! ```
+
+pub use self::Stream::*;
+
+use std::io::IoResult;
+use std::os;
+
+/// A stream which may be attached to a terminal.
+#[deriving(Copy, Clone, PartialEq, Show)]
+pub enum Stream {
+    /// Standard input
+    Stdin,
+    /// Standard output
+    Stdout,
+    /// Standard error
+    Stderr,
+    /// Any other file descriptor, such as one side of a pseudo-terminal
+    #[cfg(unix)]
+    Fd(::libc::c_int),
+}
+
+/// Returns whether `stream` is attached to a terminal.
+pub fn isatty(stream: Stream) -> bool {
+    imp::isatty(stream)
+}
+
+/// Returns the size of the terminal attached to `stream` as `(columns,
+/// rows)`.
+///
+/// If the terminal cannot be asked directly, the `COLUMNS` and `LINES`
+/// environment variables are used instead. Returns `None` if neither gives
+/// an answer.
+pub fn size(stream: Stream) -> Option<(u16, u16)> {
+    imp::size(stream).or_else(|| {
+        let cols = os::getenv("COLUMNS");
+        let rows = os::getenv("LINES");
+        size_from_env(cols.as_ref().map(|s| s[]), rows.as_ref().map(|s| s[]))
+    })
+}
+
+/// Parses the values of `COLUMNS` and `LINES` as a terminal size.
+fn size_from_env(columns: Option<&str>, lines: Option<&str>) -> Option<(u16, u16)> {
+    let cols = columns.and_then(|s| s.parse::<u16>());
+    let rows = lines.and_then(|s| s.parse::<u16>());
+    match (cols, rows) {
+        (Some(c), Some(r)) if c > 0 && r > 0 => Some((c, r)),
+        _ => None,
+    }
+}
+
+/// Puts the terminal attached to `stream` in raw mode.
+///
+/// In raw mode input is available byte by byte as soon as it is typed, is not
+/// echoed, and keys such as ^C and ^Z are delivered as input rather than
+/// raising signals. Output processing is turned off too, so a newline only
+/// moves the cursor down: write `"\r\n"` to start a new line.
+///
+/// The previous mode is restored when the returned guard is dropped.
+pub fn raw_mode(stream: Stream) -> IoResult<ModeGuard> {
+    imp::set_mode(stream, true).map(|saved| ModeGuard { stream: stream, saved: saved })
+}
+
+/// Turns off echoing of input on the terminal attached to `stream`, leaving
+/// it otherwise line-buffered.
+///
+/// The previous mode is restored when the returned guard is dropped.
+pub fn no_echo(stream: Stream) -> IoResult<ModeGuard> {
+    imp::set_mode(stream, false).map(|saved| ModeGuard { stream: stream, saved: saved })
+}
+
+/// Restores the input mode of a terminal when dropped.
+///
+/// Returned by `raw_mode` and `no_echo`.
+pub struct ModeGuard {
+    stream: Stream,
+    saved: imp::Mode,
+}
+
+impl ModeGuard {
+    /// Returns the stream whose mode this guard restores.
+    pub fn stream(&self) -> Stream { self.stream }
+}
+
+impl Drop for ModeGuard {
+    fn drop(&mut self) {
+        let _ = imp::restore(self.stream, &self.saved);
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use libc;
+    use libc::{c_int, c_ulong, c_ushort};
+    use std::io::{IoError, IoResult};
+    use std::mem;
+
+    use self::os::*;
+    use super::Stream;
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    mod os {
+        use libc::{c_uint, c_ulong};
+
+        // Android's termios is shorter than glibc's, so using this layout
+        // there only leaves the tail of the struct unused.
+        #[repr(C)]
+        #[deriving(Copy)]
+        pub struct termios {
+            pub c_iflag: tcflag_t,
+            pub c_oflag: tcflag_t,
+            pub c_cflag: tcflag_t,
+            pub c_lflag: tcflag_t,
+            pub c_line: u8,
+            pub c_cc: [u8; 32],
+            pub c_ispeed: c_uint,
+            pub c_ospeed: c_uint,
+        }
+
+        pub type tcflag_t = c_uint;
+
+        // MIPS keeps the ioctl numbers and a few of the flags and control
+        // character indices of the SVR4 ABI it started out with.
+        #[cfg(not(any(target_arch = "mips", target_arch = "mipsel")))]
+        pub const TIOCGWINSZ: c_ulong = 0x5413;
+        #[cfg(any(target_arch = "mips", target_arch = "mipsel"))]
+        pub const TIOCGWINSZ: c_ulong = 0x40087468;
+
+        pub const BRKINT: tcflag_t = 0o2;
+        pub const INPCK: tcflag_t = 0o20;
+        pub const ISTRIP: tcflag_t = 0o40;
+        pub const ICRNL: tcflag_t = 0o400;
+        pub const IXON: tcflag_t = 0o2000;
+        pub const OPOST: tcflag_t = 0o1;
+        pub const CSIZE: tcflag_t = 0o60;
+        pub const CS8: tcflag_t = 0o60;
+        pub const PARENB: tcflag_t = 0o400;
+        pub const ISIG: tcflag_t = 0o1;
+        pub const ICANON: tcflag_t = 0o2;
+        pub const ECHO: tcflag_t = 0o10;
+        #[cfg(not(any(target_arch = "mips", target_arch = "mipsel")))]
+        pub const IEXTEN: tcflag_t = 0o100000;
+        #[cfg(any(target_arch = "mips", target_arch = "mipsel"))]
+        pub const IEXTEN: tcflag_t = 0o400;
+        #[cfg(not(any(target_arch = "mips", target_arch = "mipsel")))]
+        pub const VTIME: uint = 5;
+        #[cfg(not(any(target_arch = "mips", target_arch = "mipsel")))]
+        pub const VMIN: uint = 6;
+        #[cfg(any(target_arch = "mips", target_arch = "mipsel"))]
+        pub const VMIN: uint = 4;
+        #[cfg(any(target_arch = "mips", target_arch = "mipsel"))]
+        pub const VTIME: uint = 5;
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios",
+              target_os = "freebsd", target_os = "dragonfly"))]
+    mod os {
+        use libc::c_ulong;
+
+        #[repr(C)]
+        #[deriving(Copy)]
+        pub struct termios {
+            pub c_iflag: tcflag_t,
+            pub c_oflag: tcflag_t,
+            pub c_cflag: tcflag_t,
+            pub c_lflag: tcflag_t,
+            pub c_cc: [u8; 20],
+            pub c_ispeed: speed_t,
+            pub c_ospeed: speed_t,
+        }
+
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        pub type tcflag_t = c_ulong;
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        type speed_t = c_ulong;
+        #[cfg(any(target_os = "freebsd", target_os = "dragonfly"))]
+        pub type tcflag_t = ::libc::c_uint;
+        #[cfg(any(target_os = "freebsd", target_os = "dragonfly"))]
+        type speed_t = ::libc::c_uint;
+
+        pub const TIOCGWINSZ: c_ulong = 0x40087468;
+
+        pub const BRKINT: tcflag_t = 0x2;
+        pub const INPCK: tcflag_t = 0x10;
+        pub const ISTRIP: tcflag_t = 0x20;
+        pub const ICRNL: tcflag_t = 0x100;
+        pub const IXON: tcflag_t = 0x200;
+        pub const OPOST: tcflag_t = 0x1;
+        pub const CSIZE: tcflag_t = 0x300;
+        pub const CS8: tcflag_t = 0x300;
+        pub const PARENB: tcflag_t = 0x1000;
+        pub const ECHO: tcflag_t = 0x8;
+        pub const ISIG: tcflag_t = 0x80;
+        pub const ICANON: tcflag_t = 0x100;
+        pub const IEXTEN: tcflag_t = 0x400;
+        pub const VMIN: uint = 16;
+        pub const VTIME: uint = 17;
+    }
+
+    #[repr(C)]
+    struct winsize {
+        ws_row: c_ushort,
+        ws_col: c_ushort,
+        ws_xpixel: c_ushort,
+        ws_ypixel: c_ushort,
+    }
+
+    const TCSAFLUSH: c_int = 2;
+
+    extern {
+        fn ioctl(fd: c_int, req: c_ulong, ...) -> c_int;
+        fn tcgetattr(fd: c_int, termios: *mut termios) -> c_int;
+        fn tcsetattr(fd: c_int, action: c_int, termios: *const termios) -> c_int;
+    }
+
+    pub type Mode = termios;
+
+    fn fd(stream: Stream) -> c_int {
+        match stream {
+            Stream::Stdin => 0,
+            Stream::Stdout => 1,
+            Stream::Stderr => 2,
+            Stream::Fd(fd) => fd,
+        }
+    }
+
+    pub fn isatty(stream: Stream) -> bool {
+        unsafe { libc::isatty(fd(stream)) != 0 }
+    }
+
+    pub fn size(stream: Stream) -> Option<(u16, u16)> {
+        unsafe {
+            let mut ws: winsize = mem::zeroed();
+            if ioctl(fd(stream), TIOCGWINSZ, &mut ws) == -1 ||
+               ws.ws_col == 0 || ws.ws_row == 0 {
+                None
+            } else {
+                Some((ws.ws_col as u16, ws.ws_row as u16))
+            }
+        }
+    }
+
+    pub fn set_mode(stream: Stream, raw: bool) -> IoResult<termios> {
+        unsafe {
+            let mut saved: termios = mem::zeroed();
+            if tcgetattr(fd(stream), &mut saved) == -1 {
+                return Err(IoError::last_error())
+            }
+            let mut t = saved;
+            if raw {
+                // The same changes as cfmakeraw(3), which isn't available
+                // everywhere.
+                t.c_iflag &= !(BRKINT | ICRNL | INPCK | ISTRIP | IXON);
+                t.c_oflag &= !OPOST;
+                t.c_cflag &= !(CSIZE | PARENB);
+                t.c_cflag |= CS8;
+                t.c_lflag &= !(ECHO | ICANON | IEXTEN | ISIG);
+                t.c_cc[VMIN] = 1;
+                t.c_cc[VTIME] = 0;
+            } else {
+                t.c_lflag &= !ECHO;
+            }
+            if tcsetattr(fd(stream), TCSAFLUSH, &t) == -1 {
+                return Err(IoError::last_error())
+            }
+            Ok(saved)
+        }
+    }
+
+    pub fn restore(stream: Stream, saved: &termios) -> IoResult<()> {
+        unsafe {
+            if tcsetattr(fd(stream), TCSAFLUSH, saved) == -1 {
+                Err(IoError::last_error())
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use libc;
+    use std::io::{IoError, IoResult};
+    use std::mem;
+
+    use super::Stream;
+    use win::{CONSOLE_SCREEN_BUFFER_INFO, GetStdHandle, GetConsoleScreenBufferInfo};
+
+    const ENABLE_PROCESSED_INPUT: libc::DWORD = 0x1;
+    const ENABLE_LINE_INPUT: libc::DWORD = 0x2;
+    const ENABLE_ECHO_INPUT: libc::DWORD = 0x4;
+
+    #[allow(non_snake_case)]
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetConsoleMode(handle: libc::HANDLE, mode: *mut libc::DWORD) -> libc::BOOL;
+        fn SetConsoleMode(handle: libc::HANDLE, mode: libc::DWORD) -> libc::BOOL;
+    }
+
+    pub type Mode = libc::DWORD;
+
+    fn handle(stream: Stream) -> libc::HANDLE {
+        // See http://msdn.microsoft.com/en-us/library/windows/desktop/ms683231%28v=vs.85%29.aspx
+        let which = match stream {
+            Stream::Stdin => -10,
+            Stream::Stdout => -11,
+            Stream::Stderr => -12,
+        };
+        unsafe { GetStdHandle(which) }
+    }
+
+    pub fn isatty(stream: Stream) -> bool {
+        let mut mode = 0;
+        unsafe { GetConsoleMode(handle(stream), &mut mode) != 0 }
+    }
+
+    pub fn size(stream: Stream) -> Option<(u16, u16)> {
+        unsafe {
+            let mut info: CONSOLE_SCREEN_BUFFER_INFO = mem::zeroed();
+            if GetConsoleScreenBufferInfo(handle(stream), &mut info) == 0 {
+                return None
+            }
+            // srWindow is [left, top, right, bottom], inclusive.
+            let cols = info.srWindow[2] - info.srWindow[0] + 1;
+            let rows = info.srWindow[3] - info.srWindow[1] + 1;
+            Some((cols as u16, rows as u16))
+        }
+    }
+
+    pub fn set_mode(stream: Stream, raw: bool) -> IoResult<libc::DWORD> {
+        let h = handle(stream);
+        let mut saved = 0;
+        unsafe {
+            if GetConsoleMode(h, &mut saved) == 0 {
+                return Err(IoError::last_error())
+            }
+            let mode = if raw {
+                saved & !(ENABLE_PROCESSED_INPUT | ENABLE_LINE_INPUT | ENABLE_ECHO_INPUT)
+            } else {
+                saved & !ENABLE_ECHO_INPUT
+            };
+            if SetConsoleMode(h, mode) == 0 {
+                return Err(IoError::last_error())
+            }
+        }
+        Ok(saved)
+    }
+
+    pub fn restore(stream: Stream, saved: &libc::DWORD) -> IoResult<()> {
+        unsafe {
+            if SetConsoleMode(handle(stream), *saved) == 0 {
+                Err(IoError::last_error())
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{isatty, size_from_env, raw_mode, Stdin};
+
+    #[test]
+    fn size_from_environment() {
+        assert_eq!(size_from_env(Some("100"), Some("30")), Some((100, 30)));
+        assert_eq!(size_from_env(Some("100"), Some("x")), None);
+        assert_eq!(size_from_env(Some("100"), None), None);
+        assert_eq!(size_from_env(None, Some("30")), None);
+        assert_eq!(size_from_env(Some("0"), Some("30")), None);
+        assert_eq!(size_from_env(Some("100000"), Some("30")), None);
+    }
+
+    #[test]
+    fn raw_mode_needs_a_terminal() {
+        if isatty(Stdin) { return }
+        assert!(raw_mode(Stdin).is_err());
+    }
+}
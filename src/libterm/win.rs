@@ -14,7 +14,9 @@
 
 extern crate libc;
 
+use std::cmp;
 use std::io::IoResult;
+use std::mem;
 
 use attr;
 use color;
@@ -31,21 +33,45 @@ pub struct WinConsole<T> {
 
 #[allow(non_snake_case)]
 #[repr(C)]
-struct CONSOLE_SCREEN_BUFFER_INFO {
-    dwSize: [libc::c_short; 2],
-    dwCursorPosition: [libc::c_short; 2],
-    wAttributes: libc::WORD,
-    srWindow: [libc::c_short; 4],
-    dwMaximumWindowSize: [libc::c_short; 2],
+pub struct CONSOLE_SCREEN_BUFFER_INFO {
+    pub dwSize: [libc::c_short; 2],
+    pub dwCursorPosition: [libc::c_short; 2],
+    pub wAttributes: libc::WORD,
+    pub srWindow: [libc::c_short; 4],
+    pub dwMaximumWindowSize: [libc::c_short; 2],
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+#[deriving(Copy)]
+struct COORD {
+    X: libc::c_short,
+    Y: libc::c_short,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+struct CONSOLE_CURSOR_INFO {
+    dwSize: libc::DWORD,
+    bVisible: libc::BOOL,
 }
 
 #[allow(non_snake_case)]
 #[link(name = "kernel32")]
 extern "system" {
     fn SetConsoleTextAttribute(handle: libc::HANDLE, attr: libc::WORD) -> libc::BOOL;
-    fn GetStdHandle(which: libc::DWORD) -> libc::HANDLE;
-    fn GetConsoleScreenBufferInfo(handle: libc::HANDLE,
-                                  info: *mut CONSOLE_SCREEN_BUFFER_INFO) -> libc::BOOL;
+    pub fn GetStdHandle(which: libc::DWORD) -> libc::HANDLE;
+    pub fn GetConsoleScreenBufferInfo(handle: libc::HANDLE,
+                                      info: *mut CONSOLE_SCREEN_BUFFER_INFO) -> libc::BOOL;
+    fn SetConsoleCursorPosition(handle: libc::HANDLE, pos: COORD) -> libc::BOOL;
+    fn GetConsoleCursorInfo(handle: libc::HANDLE,
+                            info: *mut CONSOLE_CURSOR_INFO) -> libc::BOOL;
+    fn SetConsoleCursorInfo(handle: libc::HANDLE,
+                            info: *const CONSOLE_CURSOR_INFO) -> libc::BOOL;
+    fn FillConsoleOutputCharacterW(handle: libc::HANDLE, c: libc::WCHAR, len: libc::DWORD,
+                                   pos: COORD, written: *mut libc::DWORD) -> libc::BOOL;
+    fn FillConsoleOutputAttribute(handle: libc::HANDLE, attr: libc::WORD, len: libc::DWORD,
+                                  pos: COORD, written: *mut libc::DWORD) -> libc::BOOL;
 }
 
 fn color_to_bits(color: color::Color) -> u16 {
@@ -108,13 +134,80 @@ impl<T: Writer+Send> WinConsole<T> {
         }
     }
 
+    /// Flushes pending output and returns the state of the console's screen
+    /// buffer, which the cursor and clearing operations work from.
+    fn buffer_info(&mut self) -> Option<CONSOLE_SCREEN_BUFFER_INFO> {
+        let _unused = self.buf.flush();
+        unsafe {
+            let mut info = mem::uninitialized();
+            if GetConsoleScreenBufferInfo(GetStdHandle(-11), &mut info) != 0 {
+                Some(info)
+            } else {
+                None
+            }
+        }
+    }
+
+    fn set_cursor(&mut self, x: i32, y: i32) -> IoResult<bool> {
+        let info = match self.buffer_info() {
+            Some(info) => info,
+            None => return Ok(false),
+        };
+        // Stay within the screen buffer, as terminals stop at the edges too.
+        let x = cmp::max(0, cmp::min(x, info.dwSize[0] as i32 - 1));
+        let y = cmp::max(0, cmp::min(y, info.dwSize[1] as i32 - 1));
+        let pos = COORD { X: x as libc::c_short, Y: y as libc::c_short };
+        unsafe { Ok(SetConsoleCursorPosition(GetStdHandle(-11), pos) != 0) }
+    }
+
+    fn move_cursor(&mut self, dx: i32, dy: i32) -> IoResult<bool> {
+        match self.buffer_info() {
+            Some(info) => {
+                let pos = info.dwCursorPosition;
+                self.set_cursor(pos[0] as i32 + dx, pos[1] as i32 + dy)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Blanks `len` cells starting at `(x, y)`, wrapping onto following rows.
+    fn fill(&mut self, x: i32, y: i32, len: i32) -> IoResult<bool> {
+        let info = match self.buffer_info() {
+            Some(info) => info,
+            None => return Ok(false),
+        };
+        let len = cmp::max(len, 0) as libc::DWORD;
+        let mut written = 0;
+        unsafe {
+            let out = GetStdHandle(-11);
+            let pos = COORD { X: x as libc::c_short, Y: y as libc::c_short };
+            Ok(FillConsoleOutputCharacterW(out, ' ' as libc::WCHAR, len, pos,
+                                           &mut written) != 0 &&
+               FillConsoleOutputAttribute(out, info.wAttributes, len, pos,
+                                          &mut written) != 0)
+        }
+    }
+
+    fn set_cursor_visible(&mut self, visible: bool) -> IoResult<bool> {
+        let _unused = self.buf.flush();
+        unsafe {
+            let out = GetStdHandle(-11);
+            let mut info = mem::uninitialized();
+            if GetConsoleCursorInfo(out, &mut info) == 0 {
+                return Ok(false)
+            }
+            info.bVisible = visible as libc::BOOL;
+            Ok(SetConsoleCursorInfo(out, &info) != 0)
+        }
+    }
+
     /// Returns `None` whenever the terminal cannot be created for some
     /// reason.
     pub fn new(out: T) -> Option<Box<Terminal<T>+Send+'static>> {
         let fg;
         let bg;
         unsafe {
-            let mut buffer_info = ::std::mem::uninitialized();
+            let mut buffer_info = mem::uninitialized();
             if GetConsoleScreenBufferInfo(GetStdHandle(-11), &mut buffer_info) != 0 {
                 fg = bits_to_color(buffer_info.wAttributes);
                 bg = bits_to_color(buffer_info.wAttributes >> 4);
@@ -187,6 +280,72 @@ impl<T: Writer+Send> Terminal<T> for WinConsole<T> {
         Ok(())
     }
 
+    fn cursor_goto(&mut self, row: u16, col: u16) -> IoResult<bool> {
+        // Rows and columns count from the top left of the visible window,
+        // which may be scrolled down the screen buffer.
+        match self.buffer_info() {
+            Some(info) => {
+                self.set_cursor(info.srWindow[0] as i32 + col as i32,
+                                info.srWindow[1] as i32 + row as i32)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn cursor_up(&mut self, n: u16) -> IoResult<bool> { self.move_cursor(0, -(n as i32)) }
+
+    fn cursor_down(&mut self, n: u16) -> IoResult<bool> { self.move_cursor(0, n as i32) }
+
+    fn cursor_left(&mut self, n: u16) -> IoResult<bool> { self.move_cursor(-(n as i32), 0) }
+
+    fn cursor_right(&mut self, n: u16) -> IoResult<bool> { self.move_cursor(n as i32, 0) }
+
+    fn carriage_return(&mut self) -> IoResult<bool> {
+        match self.buffer_info() {
+            Some(info) => self.set_cursor(0, info.dwCursorPosition[1] as i32),
+            None => Ok(false),
+        }
+    }
+
+    fn hide_cursor(&mut self) -> IoResult<bool> { self.set_cursor_visible(false) }
+
+    fn show_cursor(&mut self) -> IoResult<bool> { self.set_cursor_visible(true) }
+
+    fn clear_to_eol(&mut self) -> IoResult<bool> {
+        match self.buffer_info() {
+            Some(info) => {
+                let (x, y) = (info.dwCursorPosition[0] as i32, info.dwCursorPosition[1] as i32);
+                self.fill(x, y, info.dwSize[0] as i32 - x)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn clear_to_eos(&mut self) -> IoResult<bool> {
+        match self.buffer_info() {
+            Some(info) => {
+                let (x, y) = (info.dwCursorPosition[0] as i32, info.dwCursorPosition[1] as i32);
+                let (w, h) = (info.dwSize[0] as i32, info.dwSize[1] as i32);
+                self.fill(x, y, w * (h - y) - x)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn clear_screen(&mut self) -> IoResult<bool> {
+        match self.buffer_info() {
+            Some(info) => {
+                let (w, h) = (info.dwSize[0] as i32, info.dwSize[1] as i32);
+                if try!(self.fill(0, 0, w * h)) {
+                    self.set_cursor(0, 0)
+                } else {
+                    Ok(false)
+                }
+            }
+            None => Ok(false),
+        }
+    }
+
     fn get_ref<'a>(&'a self) -> &'a T { &self.buf }
 
     fn get_mut<'a>(&'a mut self) -> &'a mut T { &mut self.buf }
@@ -14,7 +14,8 @@
 //! Almost all user code will only be interested in `Bencher` and
 //! `black_box`. All other interactions (such as writing tests and
 //! benchmarks themselves) should be done via the `#[test]` and
-//! `#[bench]` attributes.
+//! `#[bench]` attributes. Property tests are written with the
+//! `quickcheck` module and its `quickcheck!` macro.
 //!
 //! See the [Testing Guide](../guide-testing.html) for more details.
 
@@ -45,6 +46,7 @@ extern crate term;
 pub use self::TestFn::*;
 pub use self::MetricChange::*;
 pub use self::ColorConfig::*;
+pub use self::OutputFormat::*;
 pub use self::TestResult::*;
 pub use self::TestName::*;
 use self::TestEvent::*;
@@ -55,6 +57,7 @@ use stats::Stats;
 use getopts::{OptGroup, optflag, optopt};
 use regex::Regex;
 use serialize::{json, Decodable, Encodable};
+use serialize::json::{Json, ToJson};
 use term::Terminal;
 use term::color::{Color, RED, YELLOW, GREEN, CYAN};
 
@@ -66,13 +69,15 @@ use std::fmt::Show;
 use std::fmt;
 use std::io::fs::PathExtensions;
 use std::io::stdio::StdWriter;
-use std::io::{File, ChanReader, ChanWriter};
+use std::io::{File, ChanWriter};
+use std::io::Timer;
+use std::io::process::{Command, InheritFd, ProcessOutput};
 use std::io;
 use std::iter::repeat;
 use std::num::{Float, FloatMath, Int};
 use std::os;
 use std::str::{FromStr, from_str};
-use std::sync::mpsc::{channel, Sender};
+use std::sync::mpsc::{channel, Sender, Receiver};
 use std::thread::{mod, Thread};
 use std::thunk::{Thunk, Invoke};
 use std::time::Duration;
@@ -85,10 +90,12 @@ pub mod test {
              MetricChange, Improvement, Regression, LikelyNoise,
              StaticTestFn, StaticTestName, DynTestName, DynTestFn,
              run_test, test_main, test_main_static, filter_tests,
-             parse_opts, StaticBenchFn, ShouldFail};
+             parse_opts, StaticBenchFn, ShouldFail, run_with_fixtures};
+    pub use quickcheck;
 }
 
 pub mod stats;
+pub mod quickcheck;
 
 // The name of a test. By convention this follows the rules for rust
 // paths; i.e. it should be a series of identifiers separated by double
@@ -209,6 +216,9 @@ pub struct TestDesc {
     pub name: TestName,
     pub ignore: bool,
     pub should_fail: ShouldFail,
+    /// The number of seconds after which the test fails, overriding
+    /// `--test-timeout`.
+    pub timeout: Option<u64>,
 }
 
 #[deriving(Show)]
@@ -251,6 +261,20 @@ pub enum MetricChange {
 
 pub type MetricDiff = BTreeMap<String,MetricChange>;
 
+/// The median time per iteration of a benchmark, with its confidence
+/// interval, in nanoseconds.
+#[deriving(Clone, RustcEncodable, RustcDecodable, PartialEq, Show, Copy)]
+pub struct BenchEstimate {
+    median: f64,
+    ci_low: f64,
+    ci_high: f64,
+}
+
+/// The benchmark results saved with `--save-baseline`, for later runs to
+/// compare against with `--compare-baseline`.
+#[deriving(Clone, PartialEq)]
+pub struct Baseline(BTreeMap<String,BenchEstimate>);
+
 // The default console test runner. It accepts the command line
 // arguments and a vector of test_descs.
 pub fn test_main(args: &[String], tests: Vec<TestDescAndFn> ) {
@@ -260,6 +284,16 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn> ) {
             Some(Err(msg)) => panic!("{}", msg),
             None => return
         };
+    match os::getenv(ISOLATED_TEST_VAR) {
+        Some(name) => {
+            match run_isolated_child(&opts, tests, name) {
+                Ok(()) => {}
+                Err(e) => panic!("io error when running tests: {}", e),
+            }
+            return
+        }
+        None => {}
+    }
     match run_tests_console(&opts, tests) {
         Ok(true) => {}
         Ok(false) => panic!("Some tests failed"),
@@ -292,6 +326,17 @@ pub enum ColorConfig {
     NeverColor,
 }
 
+/// How the results of a test run are reported.
+#[deriving(Copy, PartialEq, Show)]
+pub enum OutputFormat {
+    /// Human readable text, as written by `ConsoleTestState`.
+    PrettyFormat,
+    /// One JSON object per line for each event of the run.
+    JsonFormat,
+    /// A JUnit XML report, written once all tests have run.
+    JunitFormat,
+}
+
 pub struct TestOpts {
     pub filter: Option<Regex>,
     pub run_ignored: bool,
@@ -300,10 +345,16 @@ pub struct TestOpts {
     pub ratchet_metrics: Option<Path>,
     pub ratchet_noise_percent: Option<f64>,
     pub save_metrics: Option<Path>,
+    pub save_baseline: Option<String>,
+    pub compare_baseline: Option<String>,
     pub test_shard: Option<(uint,uint)>,
     pub logfile: Option<Path>,
     pub nocapture: bool,
+    pub isolate: bool,
     pub color: ColorConfig,
+    pub format: OutputFormat,
+    pub test_timeout: Option<Duration>,
+    pub warn_time: Option<Duration>,
     pub show_boxplot: bool,
     pub boxplot_width: uint,
     pub show_all_stats: bool,
@@ -320,10 +371,16 @@ impl TestOpts {
             ratchet_metrics: None,
             ratchet_noise_percent: None,
             save_metrics: None,
+            save_baseline: None,
+            compare_baseline: None,
             test_shard: None,
             logfile: None,
             nocapture: false,
+            isolate: false,
             color: AutoColor,
+            format: PrettyFormat,
+            test_timeout: None,
+            warn_time: None,
             show_boxplot: false,
             boxplot_width: 50,
             show_all_stats: false,
@@ -348,16 +405,33 @@ fn optgroups() -> Vec<getopts::OptGroup> {
       getopts::optopt("", "ratchet-noise-percent",
                      "Tests within N% of the recorded metrics will be \
                       considered as passing", "PERCENTAGE"),
+      getopts::optopt("", "save-baseline", "Save the benchmark results as the baseline NAME",
+                     "NAME"),
+      getopts::optopt("", "compare-baseline", "Report benchmarks which are significantly \
+                                               faster or slower than in the baseline NAME",
+                     "NAME"),
       getopts::optopt("", "logfile", "Write logs to the specified file instead \
                           of stdout", "PATH"),
       getopts::optopt("", "test-shard", "run shard A, of B shards, worth of the testsuite",
                      "A.B"),
       getopts::optflag("", "nocapture", "don't capture stdout/stderr of each \
                                          task, allow printing directly"),
+      getopts::optflag("", "isolate", "Run each test in its own process, so that tests \
+                                       which crash or exit only fail themselves"),
       getopts::optopt("", "color", "Configure coloring of output:
             auto   = colorize if stdout is a tty and tests are run on serially (default);
             always = always colorize output;
             never  = never colorize output;", "auto|always|never"),
+      getopts::optopt("", "format", "Configure the format of the output:
+            pretty = human readable text (default);
+            json   = one JSON object per line for each test event;
+            junit  = a JUnit XML report;
+            json and junit can't be combined with --nocapture or the
+            options that save or compare metrics and baselines", "pretty|json|junit"),
+      getopts::optopt("", "test-timeout", "Fail tests which run for longer than SECS \
+                                           seconds", "SECS"),
+      getopts::optopt("", "test-warn-time", "Report tests which run for longer than SECS \
+                                             seconds as slow", "SECS"),
       getopts::optflag("", "boxplot", "Display a boxplot of the benchmark statistics"),
       getopts::optopt("", "boxplot-width", "Set the boxplot width (default 50)", "WIDTH"),
       getopts::optflag("", "stats", "Display the benchmark min, max, and quartiles"))
@@ -375,7 +449,20 @@ RUST_TEST_TASKS environment variable when running tests (set it to 1).
 
 All tests have their standard output and standard error captured by default.
 This can be overridden with the --nocapture flag or the RUST_TEST_NOCAPTURE=1
-environment variable. Logging is not captured by default.
+environment variable, except with --format=json or junit, where the output of
+tests would break the report. Logging is not captured by default.
+
+Benchmark baselines are saved as NAME.json in the directory named by the
+RUST_BENCH_BASELINE_DIR environment variable, or in the current directory,
+and can only be saved or compared with --bench. A run in which no benchmark
+ran leaves the saved baseline as it was.
+A benchmark is only reported as faster or slower than its baseline when the
+confidence intervals of their median times do not overlap.
+
+With --isolate, the test executable runs itself once for each test, so that a
+test which aborts, segfaults or calls `exit` fails without taking down the
+others. A test process which runs for five seconds longer than the timeout of
+its test is killed, and the test fails.
 
 Test Attributes:
 
@@ -390,7 +477,18 @@ Test Attributes:
     #[ignore]      - When applied to a function which is already attributed as a
                      test, then the test runner will ignore these tests during
                      normal test runs. Running with --ignored will run these
-                     tests."#,
+                     tests.
+    #[case = "ARGS"]
+                   - This function (also labeled with #[test]) takes arguments,
+                     and is run as a separate test for each #[case], called
+                     with ARGS. A case may be named instead of being named
+                     after its arguments: #[case(name = "foo", args = "ARGS")].
+    #[before_each], #[after_each]
+                   - This function runs before or after each test in its
+                     module and the submodules of it. #[after_each] functions
+                     also run after failed tests.
+    #[timeout = N] - This function (also labeled with #[test]) fails if it runs
+                     for longer than N seconds, whatever --test-timeout says."#,
              usage = getopts::usage(message.as_slice(),
                                     optgroups().as_slice()));
 }
@@ -435,13 +533,15 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
     let save_metrics = matches.opt_str("save-metrics");
     let save_metrics = save_metrics.map(|s| Path::new(s));
 
+    let save_baseline = matches.opt_str("save-baseline");
+    let compare_baseline = matches.opt_str("compare-baseline");
+
     let test_shard = matches.opt_str("test-shard");
     let test_shard = opt_shard(test_shard);
 
-    let mut nocapture = matches.opt_present("nocapture");
-    if !nocapture {
-        nocapture = os::getenv("RUST_TEST_NOCAPTURE").is_some();
-    }
+    let nocapture = matches.opt_present("nocapture");
+
+    let isolate = matches.opt_present("isolate");
 
     let color = match matches.opt_str("color").as_ref().map(|s| s.as_slice()) {
         Some("auto") | None => AutoColor,
@@ -453,6 +553,58 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
                                             v))),
     };
 
+    let format = match matches.opt_str("format").as_ref().map(|s| s.as_slice()) {
+        Some("pretty") | None => PrettyFormat,
+        Some("json") => JsonFormat,
+        Some("junit") => JunitFormat,
+
+        Some(v) => return Some(Err(format!("argument for --format must be \
+                                            pretty, json, or junit (was {})",
+                                            v))),
+    };
+
+    // The structured formats take over stdout, and leave no room for the
+    // output of the tests themselves or the text these options write as the
+    // run finishes.
+    if format != PrettyFormat {
+        for opt in ["nocapture", "save-metrics", "ratchet-metrics",
+                    "save-baseline", "compare-baseline"].iter() {
+            if matches.opt_present(*opt) {
+                return Some(Err(format!("--{} can't be used with --format={}",
+                                        *opt, matches.opt_str("format").unwrap())));
+            }
+        }
+    }
+    // Without benchmarks to measure, a saved baseline would be empty.
+    if !run_benchmarks {
+        for opt in ["save-baseline", "compare-baseline"].iter() {
+            if matches.opt_present(*opt) {
+                return Some(Err(format!("--{} can only be used with --bench", *opt)));
+            }
+        }
+    }
+    let nocapture = if !nocapture && os::getenv("RUST_TEST_NOCAPTURE").is_some() {
+        if format == PrettyFormat {
+            true
+        } else {
+            let _ = writeln!(&mut io::stderr(),
+                             "warning: ignoring RUST_TEST_NOCAPTURE with --format={}",
+                             matches.opt_str("format").unwrap());
+            false
+        }
+    } else {
+        nocapture
+    };
+
+    let test_timeout = match opt_secs(&matches, "test-timeout") {
+        Ok(t) => t,
+        Err(e) => return Some(Err(e)),
+    };
+    let warn_time = match opt_secs(&matches, "test-warn-time") {
+        Ok(t) => t,
+        Err(e) => return Some(Err(e)),
+    };
+
     let show_boxplot = matches.opt_present("boxplot");
     let boxplot_width = match matches.opt_str("boxplot-width") {
         Some(width) => {
@@ -476,10 +628,16 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         ratchet_metrics: ratchet_metrics,
         ratchet_noise_percent: ratchet_noise_percent,
         save_metrics: save_metrics,
+        save_baseline: save_baseline,
+        compare_baseline: compare_baseline,
         test_shard: test_shard,
         logfile: logfile,
         nocapture: nocapture,
+        isolate: isolate,
         color: color,
+        format: format,
+        test_timeout: test_timeout,
+        warn_time: warn_time,
         show_boxplot: show_boxplot,
         boxplot_width: boxplot_width,
         show_all_stats: show_all_stats,
@@ -488,6 +646,18 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
     Some(Ok(test_opts))
 }
 
+// Parses the number of seconds given to the option `name`.
+fn opt_secs(matches: &getopts::Matches, name: &str) -> Result<Option<Duration>, String> {
+    match matches.opt_str(name) {
+        Some(secs) => match secs.as_slice().parse::<u64>() {
+            Some(secs) => Ok(Some(Duration::seconds(secs as i64))),
+            None => Err(format!("argument for --{} must be a number of seconds (was {})",
+                                name, secs)),
+        },
+        None => Ok(None),
+    }
+}
+
 pub fn opt_shard(maybestr: Option<String>) -> Option<(uint,uint)> {
     match maybestr {
         None => None,
@@ -513,9 +683,25 @@ pub fn opt_shard(maybestr: Option<String>) -> Option<(uint,uint)> {
 #[deriving(Clone, PartialEq)]
 pub struct BenchSamples {
     ns_iter_summ: stats::Summary<f64>,
+    // The 95% confidence interval of the median.
+    ns_iter_ci: (f64, f64),
+    outliers: stats::Outliers,
+    bytes: u64,
     mb_s: uint,
 }
 
+impl BenchSamples {
+    /// The number of bytes processed per second, if the benchmark set
+    /// `Bencher.bytes`.
+    pub fn throughput(&self) -> Option<f64> {
+        if self.bytes == 0 {
+            None
+        } else {
+            Some(self.bytes as f64 * 1e9 / self.ns_iter_summ.median.max(1.0))
+        }
+    }
+}
+
 #[deriving(Clone, PartialEq)]
 pub enum TestResult {
     TrOk,
@@ -543,7 +729,10 @@ struct ConsoleTestState<T> {
     ignored: uint,
     measured: uint,
     metrics: MetricMap,
+    baseline: Baseline,
     failures: Vec<(TestDesc, Vec<u8> )> ,
+    warn_time: Option<Duration>,
+    slow: Vec<(TestDesc, Duration)>,
     max_name_len: uint, // number of columns to fill when aligning names
 }
 
@@ -572,7 +761,10 @@ impl<T: Writer> ConsoleTestState<T> {
             ignored: 0u,
             measured: 0u,
             metrics: MetricMap::new(),
+            baseline: Baseline::new(),
             failures: Vec::new(),
+            warn_time: opts.warn_time,
+            slow: Vec::new(),
             max_name_len: 0u,
         })
     }
@@ -679,7 +871,8 @@ impl<T: Writer> ConsoleTestState<T> {
 
                     let s = String::from_utf8(wr).unwrap();
 
-                    try!(self.write_plain(format!(": {}", s).as_slice()));
+                    try!(self.write_plain(format!(": {} {}", s,
+                                                  fmt_bench_stats(bs)).as_slice()));
                 } else {
                     try!(self.write_plain(format!(": {}",
                                                   fmt_bench_samples(bs)).as_slice()));
@@ -693,19 +886,7 @@ impl<T: Writer> ConsoleTestState<T> {
 
     pub fn write_log(&mut self, test: &TestDesc,
                      result: &TestResult) -> io::IoResult<()> {
-        match self.log_out {
-            None => Ok(()),
-            Some(ref mut o) => {
-                let s = format!("{} {}\n", match *result {
-                        TrOk => "ok".to_string(),
-                        TrFailed => "failed".to_string(),
-                        TrIgnored => "ignored".to_string(),
-                        TrMetrics(ref mm) => fmt_metrics(mm),
-                        TrBench(ref bs) => fmt_bench_samples(bs)
-                    }, test.name.as_slice());
-                o.write(s.as_bytes())
-            }
-        }
+        write_log(&mut self.log_out, test, result)
     }
 
     pub fn write_failures(&mut self) -> io::IoResult<()> {
@@ -736,6 +917,25 @@ impl<T: Writer> ConsoleTestState<T> {
         Ok(())
     }
 
+    // Remembers tests running for longer than the warning threshold.
+    pub fn record_time(&mut self, test: &TestDesc, dur: Duration) {
+        if is_slow(self.warn_time, dur) {
+            self.slow.push((test.clone(), dur));
+        }
+    }
+
+    pub fn write_slow(&mut self) -> io::IoResult<()> {
+        try!(self.write_plain("\nslow tests:\n"));
+        self.slow.sort_by(|&(_, a), &(_, b)| b.cmp(&a));
+        let slow = self.slow.iter().map(|&(ref test, dur)| {
+            format!("    {} ({})\n", test.name, fmt_duration(dur))
+        }).collect::<Vec<String>>();
+        for line in slow.iter() {
+            try!(self.write_plain(line.as_slice()));
+        }
+        Ok(())
+    }
+
     pub fn write_metric_diff(&mut self, diff: &MetricDiff) -> io::IoResult<()> {
         let mut noise = 0u;
         let mut improved = 0u;
@@ -785,6 +985,37 @@ impl<T: Writer> ConsoleTestState<T> {
         Ok(())
     }
 
+    pub fn write_baseline_diff(&mut self, name: &str, old: &Baseline) -> io::IoResult<()> {
+        let mut noise = 0u;
+        let mut improved = 0u;
+        let mut regressed = 0u;
+        let mut changed = 0u;
+
+        try!(self.write_plain(format!("\ncomparing with baseline `{}`:\n", name).as_slice()));
+        let diff = self.baseline.compare_to_old(old);
+        for (k, v) in diff.iter() {
+            match *v {
+                LikelyNoise => noise += 1,
+                MetricAdded | MetricRemoved => changed += 1,
+                Improvement(pct) => {
+                    improved += 1;
+                    try!(self.write_plain(format!("{}: ", *k).as_slice()));
+                    try!(self.write_improved());
+                    try!(self.write_plain(format!(" by {:.2}%\n", pct).as_slice()));
+                }
+                Regression(pct) => {
+                    regressed += 1;
+                    try!(self.write_plain(format!("{}: ", *k).as_slice()));
+                    try!(self.write_regressed());
+                    try!(self.write_plain(format!(" by {:.2}%\n", pct).as_slice()));
+                }
+            }
+        }
+        self.write_plain(format!("result of comparison: {} improved, {} regressed, \
+                                  {} noise, {} not in both runs\n",
+                                 improved, regressed, noise, changed).as_slice())
+    }
+
     pub fn write_run_finish(&mut self,
                             ratchet_metrics: &Option<Path>,
                             ratchet_pct: Option<f64>) -> io::IoResult<bool> {
@@ -808,6 +1039,10 @@ impl<T: Writer> ConsoleTestState<T> {
             }
         };
 
+        if !self.slow.is_empty() {
+            try!(self.write_slow());
+        }
+
         let test_success = self.failed == 0u;
         if !test_success {
             try!(self.write_failures());
@@ -838,6 +1073,32 @@ pub fn fmt_metrics(mm: &MetricMap) -> String {
     v.connect(", ")
 }
 
+// Whether a test which ran for `dur` exceeds the warning threshold.
+fn is_slow(warn_time: Option<Duration>, dur: Duration) -> bool {
+    match warn_time {
+        Some(warn_time) => dur > warn_time,
+        None => false,
+    }
+}
+
+/// Formats a duration as a number of seconds, such as `1.500s`.
+pub fn fmt_duration(dur: Duration) -> String {
+    let ms = dur.num_milliseconds();
+    format!("{}.{:03}s", ms / 1000, ms % 1000)
+}
+
+// The confidence interval, outliers and throughput of a benchmark.
+fn fmt_bench_stats(bs: &BenchSamples) -> String {
+    let (lo, hi) = bs.ns_iter_ci;
+    let mut s = format!("(median in [{}, {}] ns/iter with 95% confidence, {} outliers)",
+                        lo as uint, hi as uint, bs.outliers.total());
+    match bs.throughput() {
+        Some(bytes_s) => s.push_str(format!(" = {:.1} MB/s", bytes_s / 1e6).as_slice()),
+        None => {}
+    }
+    s
+}
+
 pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
     if bs.mb_s != 0 {
         format!("{:>9} ns/iter (+/- {}) = {} MB/s",
@@ -851,14 +1112,266 @@ pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
     }
 }
 
+// Writes the line for one result to the `--logfile`, if there is one.
+fn write_log(log_out: &mut Option<File>, test: &TestDesc,
+             result: &TestResult) -> io::IoResult<()> {
+    match *log_out {
+        None => Ok(()),
+        Some(ref mut o) => {
+            let s = format!("{} {}\n", match *result {
+                    TrOk => "ok".to_string(),
+                    TrFailed => "failed".to_string(),
+                    TrIgnored => "ignored".to_string(),
+                    TrMetrics(ref mm) => fmt_metrics(mm),
+                    TrBench(ref bs) => fmt_bench_samples(bs)
+                }, test.name.as_slice());
+            o.write(s.as_bytes())
+        }
+    }
+}
+
+/// Reports a test run as JSON events or as a JUnit XML report, instead of
+/// the text written by `ConsoleTestState`.
+struct StructuredTestState<T> {
+    format: OutputFormat,
+    out: T,
+    warn_time: Option<Duration>,
+    total: uint,
+    passed: uint,
+    failed: uint,
+    ignored: uint,
+    measured: uint,
+    // The results collected for the JUnit report.
+    results: Vec<(TestDesc, TestResult, Vec<u8>, Duration)>,
+    log_out: Option<File>,
+}
+
+impl<T: Writer> StructuredTestState<T> {
+    pub fn new(format: OutputFormat,
+               warn_time: Option<Duration>,
+               out: T) -> StructuredTestState<T> {
+        StructuredTestState {
+            format: format,
+            out: out,
+            warn_time: warn_time,
+            total: 0u,
+            passed: 0u,
+            failed: 0u,
+            ignored: 0u,
+            measured: 0u,
+            results: Vec::new(),
+            log_out: None,
+        }
+    }
+
+    // Writes one event as a line of JSON. Nothing is written as the
+    // tests run for the JUnit report.
+    fn write_event(&mut self, fields: Vec<(&str, Json)>) -> io::IoResult<()> {
+        if self.format != JsonFormat {
+            return Ok(());
+        }
+        let event: BTreeMap<String, Json> = fields.into_iter().map(|(k, v)| {
+            (k.to_string(), v)
+        }).collect();
+        write!(&mut self.out, "{}\n", Json::Object(event))
+    }
+
+    pub fn write_run_start(&mut self, len: uint) -> io::IoResult<()> {
+        self.total = len;
+        self.write_event(vec![("type", "suite".to_json()),
+                              ("event", "started".to_json()),
+                              ("test_count", len.to_json())])
+    }
+
+    pub fn write_test_start(&mut self, test: &TestDesc) -> io::IoResult<()> {
+        self.write_event(vec![("type", "test".to_json()),
+                              ("event", "started".to_json()),
+                              ("name", test.name.as_slice().to_json())])
+    }
+
+    pub fn write_result(&mut self, test: TestDesc, result: TestResult,
+                        stdout: Vec<u8>, dur: Duration) -> io::IoResult<()> {
+        try!(write_log(&mut self.log_out, &test, &result));
+        match result {
+            TrOk => self.passed += 1,
+            TrFailed => self.failed += 1,
+            TrIgnored => self.ignored += 1,
+            TrMetrics(_) | TrBench(_) => self.measured += 1,
+        }
+
+        let mut fields = vec![("name", test.name.as_slice().to_json()),
+                              ("duration_ns", dur.num_nanoseconds().to_json())];
+        if is_slow(self.warn_time, dur) {
+            fields.push(("slow", true.to_json()));
+        }
+        match result {
+            TrOk | TrFailed | TrIgnored => {
+                let event = match result {
+                    TrOk => "ok",
+                    TrFailed => "failed",
+                    _ => "ignored",
+                };
+                fields.push(("type", "test".to_json()));
+                fields.push(("event", event.to_json()));
+            }
+            TrMetrics(ref mm) => {
+                let MetricMap(ref mm) = *mm;
+                let metrics: BTreeMap<String, Json> = mm.iter().map(|(k, v)| {
+                    let mut metric = BTreeMap::new();
+                    metric.insert("value".to_string(), v.value.to_json());
+                    metric.insert("noise".to_string(), v.noise.to_json());
+                    (k.clone(), Json::Object(metric))
+                }).collect();
+                fields.push(("type", "metric".to_json()));
+                fields.push(("metrics", Json::Object(metrics)));
+            }
+            TrBench(ref bs) => {
+                fields.push(("type", "bench".to_json()));
+                fields.push(("median", bs.ns_iter_summ.median.to_json()));
+                fields.push(("deviation",
+                             (bs.ns_iter_summ.max - bs.ns_iter_summ.min).to_json()));
+                fields.push(("mb_s", bs.mb_s.to_json()));
+                let (lo, hi) = bs.ns_iter_ci;
+                fields.push(("ci_low", lo.to_json()));
+                fields.push(("ci_high", hi.to_json()));
+                fields.push(("outliers", bs.outliers.total().to_json()));
+                fields.push(("bytes_per_sec", bs.throughput().to_json()));
+            }
+        }
+        if stdout.len() > 0 {
+            let output = String::from_utf8_lossy(stdout.as_slice()).into_owned();
+            fields.push(("stdout", output.to_json()));
+        }
+        try!(self.write_event(fields));
+
+        if self.format == JunitFormat {
+            self.results.push((test, result, stdout, dur));
+        }
+        Ok(())
+    }
+
+    pub fn write_run_finish(&mut self) -> io::IoResult<bool> {
+        assert!(self.passed + self.failed + self.ignored + self.measured == self.total);
+
+        let success = self.failed == 0u;
+        try!(self.write_event(vec![("type", "suite".to_json()),
+                                   ("event", (if success { "ok" } else { "failed" }).to_json()),
+                                   ("passed", self.passed.to_json()),
+                                   ("failed", self.failed.to_json()),
+                                   ("ignored", self.ignored.to_json()),
+                                   ("measured", self.measured.to_json())]));
+        if self.format == JunitFormat {
+            try!(self.write_junit());
+        }
+        Ok(success)
+    }
+
+    fn write_junit(&mut self) -> io::IoResult<()> {
+        fn secs(dur: Duration) -> String {
+            format!("{:.3}", dur.num_nanoseconds().unwrap_or(0) as f64 / 1e9)
+        }
+
+        let total = self.results.iter().fold(Duration::zero(), |t, &(_, _, _, dur)| t + dur);
+        try!(write!(&mut self.out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+        try!(write!(&mut self.out, "<testsuites>\n"));
+        try!(write!(&mut self.out,
+                    "  <testsuite name=\"test\" tests=\"{}\" failures=\"{}\" \
+                     errors=\"0\" skipped=\"{}\" time=\"{}\">\n",
+                    self.total, self.failed, self.ignored, secs(total)));
+        for &(ref test, ref result, ref stdout, dur) in self.results.iter() {
+            // `a::b::c` is reported as the test `c` of the class `a::b`.
+            let name = test.name.as_slice();
+            let (class, name) = match name.match_indices("::").last() {
+                Some((i, j)) => (name[..i], name[j..]),
+                None => ("", name),
+            };
+            try!(write!(&mut self.out,
+                        "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\"",
+                        xml_escape(class), xml_escape(name), secs(dur)));
+            let output = match *result {
+                TrMetrics(ref mm) => Some(fmt_metrics(mm)),
+                TrBench(ref bs) => Some(fmt_bench_samples(bs)),
+                _ if stdout.len() > 0 => {
+                    Some(String::from_utf8_lossy(stdout.as_slice()).into_owned())
+                }
+                _ => None,
+            };
+            if *result != TrFailed && *result != TrIgnored && output.is_none() {
+                try!(write!(&mut self.out, "/>\n"));
+                continue;
+            }
+            try!(write!(&mut self.out, ">\n"));
+            match *result {
+                TrFailed => {
+                    try!(write!(&mut self.out, "      <failure message=\"test failed\"/>\n"));
+                }
+                TrIgnored => try!(write!(&mut self.out, "      <skipped/>\n")),
+                _ => {}
+            }
+            match output {
+                Some(output) => {
+                    try!(write!(&mut self.out, "      <system-out>{}</system-out>\n",
+                                xml_escape(output.as_slice())));
+                }
+                None => {}
+            }
+            try!(write!(&mut self.out, "    </testcase>\n"));
+        }
+        try!(write!(&mut self.out, "  </testsuite>\n"));
+        write!(&mut self.out, "</testsuites>\n")
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Other control characters are not allowed in XML 1.0.
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => escaped.push('\u{FFFD}'),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Runs the tests, reporting them in one of the structured formats.
+fn run_tests_structured<T: Writer>(opts: &TestOpts,
+                                   tests: Vec<TestDescAndFn>,
+                                   mut st: StructuredTestState<T>) -> io::IoResult<bool> {
+    try!(run_tests(opts, tests, |event| {
+        match event {
+            TeFiltered(ref filtered_tests) => st.write_run_start(filtered_tests.len()),
+            TeWait(ref test, _) => st.write_test_start(test),
+            TeResult(test, result, stdout, dur) => st.write_result(test, result, stdout, dur),
+        }
+    }));
+    st.write_run_finish()
+}
+
 // A simple console test runner
 pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::IoResult<bool> {
+    if opts.format != PrettyFormat {
+        let mut st = StructuredTestState::new(opts.format, opts.warn_time,
+                                              io::stdio::stdout_raw());
+        st.log_out = match opts.logfile {
+            Some(ref path) => Some(try!(File::create(path))),
+            None => None
+        };
+        return run_tests_structured(opts, tests, st);
+    }
 
     fn callback<T: Writer>(event: &TestEvent, st: &mut ConsoleTestState<T>) -> io::IoResult<()> {
         match (*event).clone() {
             TeFiltered(ref filtered_tests) => st.write_run_start(filtered_tests.len()),
             TeWait(ref test, padding) => st.write_test_start(test, padding),
-            TeResult(test, result, stdout) => {
+            TeResult(test, result, stdout, dur) => {
+                st.record_time(&test, dur);
                 try!(st.write_log(&test, &result));
                 try!(st.write_result(&result));
                 match result {
@@ -881,6 +1394,9 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::IoR
                         st.metrics.insert_metric(test.name.as_slice(),
                                                  bs.ns_iter_summ.median,
                                                  bs.ns_iter_summ.max - bs.ns_iter_summ.min);
+                        st.baseline.insert(test.name.as_slice(),
+                                           bs.ns_iter_summ.median,
+                                           bs.ns_iter_ci);
                         st.measured += 1
                     }
                     TrFailed => {
@@ -908,6 +1424,26 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::IoR
         None => {}
     }
     try!(run_tests(opts, tests, |x| callback(&x, &mut st)));
+    match opts.compare_baseline {
+        None => (),
+        Some(ref name) => {
+            let old = try!(Baseline::load(&Baseline::path(name.as_slice())));
+            try!(st.write_baseline_diff(name.as_slice(), &old));
+        }
+    }
+    match opts.save_baseline {
+        None => (),
+        Some(ref name) if st.baseline.is_empty() => {
+            try!(st.write_plain(format!("\nno benchmarks ran, baseline `{}` not saved",
+                                          name).as_slice()));
+        }
+        Some(ref name) => {
+            let pth = Baseline::path(name.as_slice());
+            try!(st.baseline.save(&pth));
+            try!(st.write_plain(format!("\nbaseline saved to: {}",
+                                          pth.display()).as_slice()));
+        }
+    }
     match opts.save_metrics {
         None => (),
         Some(ref pth) => {
@@ -924,13 +1460,15 @@ fn should_sort_failures_before_printing_them() {
     let test_a = TestDesc {
         name: StaticTestName("a"),
         ignore: false,
-        should_fail: ShouldFail::No
+        should_fail: ShouldFail::No,
+        timeout: None,
     };
 
     let test_b = TestDesc {
         name: StaticTestName("b"),
         ignore: false,
-        should_fail: ShouldFail::No
+        should_fail: ShouldFail::No,
+        timeout: None,
     };
 
     let mut st = ConsoleTestState {
@@ -947,7 +1485,10 @@ fn should_sort_failures_before_printing_them() {
         measured: 0u,
         max_name_len: 10u,
         metrics: MetricMap::new(),
-        failures: vec!((test_b, Vec::new()), (test_a, Vec::new()))
+        baseline: Baseline::new(),
+        failures: vec!((test_b, Vec::new()), (test_a, Vec::new())),
+        warn_time: None,
+        slow: Vec::new(),
     };
 
     st.write_failures().unwrap();
@@ -973,10 +1514,11 @@ fn use_color(opts: &TestOpts) -> bool {
 enum TestEvent {
     TeFiltered(Vec<TestDesc> ),
     TeWait(TestDesc, NamePadding),
-    TeResult(TestDesc, TestResult, Vec<u8> ),
+    TeResult(TestDesc, TestResult, Vec<u8>, Duration),
 }
 
-pub type MonitorMsg = (TestDesc, TestResult, Vec<u8> );
+/// The outcome of a test, its captured output and how long it ran.
+pub type MonitorMsg = (TestDesc, TestResult, Vec<u8>, Duration);
 
 unsafe impl Send for MonitorMsg {}
 
@@ -1023,11 +1565,11 @@ fn run_tests<F>(opts: &TestOpts,
             pending += 1;
         }
 
-        let (desc, result, stdout) = rx.recv().unwrap();
+        let (desc, result, stdout, dur) = rx.recv().unwrap();
         if concurrency != 1 {
             try!(callback(TeWait(desc.clone(), PadNone)));
         }
-        try!(callback(TeResult(desc, result, stdout)));
+        try!(callback(TeResult(desc, result, stdout, dur)));
         pending -= 1;
     }
 
@@ -1036,8 +1578,8 @@ fn run_tests<F>(opts: &TestOpts,
     for b in filtered_benchs_and_metrics.into_iter() {
         try!(callback(TeWait(b.desc.clone(), b.testfn.padding())));
         run_test(opts, !opts.run_benchmarks, b, tx.clone());
-        let (test, result, stdout) = rx.recv().unwrap();
-        try!(callback(TeResult(test, result, stdout)));
+        let (test, result, stdout, dur) = rx.recv().unwrap();
+        try!(callback(TeResult(test, result, stdout, dur)));
     }
     Ok(())
 }
@@ -1113,17 +1655,17 @@ pub fn run_test(opts: &TestOpts,
     let TestDescAndFn {desc, testfn} = test;
 
     if force_ignore || desc.ignore {
-        monitor_ch.send((desc, TrIgnored, Vec::new())).unwrap();
+        monitor_ch.send((desc, TrIgnored, Vec::new(), Duration::zero())).unwrap();
         return;
     }
 
     fn run_test_inner(desc: TestDesc,
                       monitor_ch: Sender<MonitorMsg>,
                       nocapture: bool,
+                      timeout: Option<Duration>,
                       testfn: Thunk) {
         Thread::spawn(move || {
-            let (tx, rx) = channel();
-            let mut reader = ChanReader::new(rx);
+            let (tx, rx) = channel::<Vec<u8>>();
             let stdout = ChanWriter::new(tx.clone());
             let stderr = ChanWriter::new(tx);
             let mut cfg = thread::Builder::new().name(match desc.name {
@@ -1137,42 +1679,273 @@ pub fn run_test(opts: &TestOpts,
                 cfg = cfg.stderr(box stderr as Box<Writer + Send>);
             }
 
-            let result_guard = cfg.spawn(move || { testfn.invoke(()) });
-            let stdout = reader.read_to_end().unwrap().into_iter().collect();
-            let test_result = calc_result(&desc, result_guard.join());
-            monitor_ch.send((desc.clone(), test_result, stdout)).unwrap();
+            // The result of the test is `None` if it timed out, in which
+            // case it is left running.
+            let (done_tx, done_rx) = channel();
+            let (task_result, dur) = timed(move || {
+                Thread::spawn(move || {
+                    let result_guard = cfg.spawn(move || { testfn.invoke(()) });
+                    let _ = done_tx.send(result_guard.join());
+                }).detach();
+                match timeout {
+                    Some(timeout) => {
+                        // The timer is cancelled when dropped.
+                        let mut timer = Timer::new().unwrap();
+                        let timed_out = timer.oneshot(timeout);
+                        select! {
+                            result = done_rx.recv() => result.ok(),
+                            _ = timed_out.recv() => None
+                        }
+                    }
+                    None => done_rx.recv().ok(),
+                }
+            });
+
+            // Whatever the test wrote so far, which is everything unless
+            // it timed out.
+            let mut stdout = Vec::new();
+            loop {
+                match rx.try_recv() {
+                    Ok(bytes) => stdout.push_all(bytes.as_slice()),
+                    Err(_) => break,
+                }
+            }
+
+            let test_result = match task_result {
+                Some(task_result) => calc_result(&desc, task_result),
+                None => {
+                    let msg = format!("\ntest timed out after {}\n",
+                                      fmt_duration(timeout.unwrap()));
+                    stdout.push_all(msg.as_bytes());
+                    TrFailed
+                }
+            };
+            monitor_ch.send((desc.clone(), test_result, stdout, dur)).unwrap();
         }).detach();
     }
 
+    let timeout = match desc.timeout {
+        Some(secs) => Some(Duration::seconds(secs as i64)),
+        None => opts.test_timeout,
+    };
+
     match testfn {
         DynBenchFn(bencher) => {
-            let bs = ::bench::benchmark(|harness| bencher.run(harness));
-            monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
+            let (bs, dur) = timed(|| ::bench::benchmark(|harness| bencher.run(harness)));
+            monitor_ch.send((desc, TrBench(bs), Vec::new(), dur)).unwrap();
             return;
         }
         StaticBenchFn(benchfn) => {
-            let bs = ::bench::benchmark(|harness| (benchfn.clone())(harness));
-            monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
+            let (bs, dur) = timed(|| ::bench::benchmark(|harness| (benchfn.clone())(harness)));
+            monitor_ch.send((desc, TrBench(bs), Vec::new(), dur)).unwrap();
             return;
         }
         DynMetricFn(f) => {
             let mut mm = MetricMap::new();
-            f.invoke(&mut mm);
-            monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
+            let ((), dur) = {
+                let mm = &mut mm;
+                timed(move || f.invoke(mm))
+            };
+            monitor_ch.send((desc, TrMetrics(mm), Vec::new(), dur)).unwrap();
             return;
         }
         StaticMetricFn(f) => {
             let mut mm = MetricMap::new();
-            f(&mut mm);
-            monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
+            let ((), dur) = timed(|| f(&mut mm));
+            monitor_ch.send((desc, TrMetrics(mm), Vec::new(), dur)).unwrap();
             return;
         }
-        DynTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture, f),
-        StaticTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture,
+        DynTestFn(_) | StaticTestFn(_) if opts.isolate => {
+            run_test_isolated(opts, desc, timeout, monitor_ch)
+        }
+        DynTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture, timeout, f),
+        StaticTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture, timeout,
                                           Thunk::new(move|| f()))
     }
 }
 
+/// Runs a test between the `#[before_each]` and `#[after_each]` hooks of
+/// the modules enclosing it. The `after` hooks run even when the test
+/// fails. The test harness calls this for tests which have hooks.
+pub fn run_with_fixtures(before: &[fn()], test: fn(), after: &[fn()]) {
+    // Runs the hooks when dropped, so also when the test panics.
+    struct AfterEach {
+        hooks: Vec<fn()>,
+    }
+
+    impl Drop for AfterEach {
+        fn drop(&mut self) {
+            for hook in self.hooks.iter() {
+                (*hook)();
+            }
+        }
+    }
+
+    for hook in before.iter() {
+        (*hook)();
+    }
+    let _after = AfterEach { hooks: after.to_vec() };
+    test();
+}
+
+// In `--isolate` mode the test binary runs itself once per test, with this
+// variable naming the test to run. The child writes the captured output of
+// the test followed by `ISOLATED_RESULT` and `ok` or `failed` to its stdout.
+static ISOLATED_TEST_VAR: &'static str = "RUST_TEST_ISOLATED";
+static ISOLATED_RESULT: &'static str = "\n--- isolated test result: ";
+
+// How much longer than the timeout of a test the parent waits for its
+// process, which normally reports the timeout itself, before killing it.
+static ISOLATED_GRACE_MS: i64 = 5000;
+
+// Runs a single test in a child process, so that a test which aborts,
+// segfaults or exits only fails itself.
+fn run_test_isolated(opts: &TestOpts,
+                     desc: TestDesc,
+                     timeout: Option<Duration>,
+                     monitor_ch: Sender<MonitorMsg>) {
+    let mut cmd = match os::self_exe_name() {
+        Some(exe) => Command::new(exe),
+        None => {
+            let msg = b"could not find the test executable\n".to_vec();
+            monitor_ch.send((desc, TrFailed, msg, Duration::zero())).unwrap();
+            return;
+        }
+    };
+    cmd.env(ISOLATED_TEST_VAR, desc.name.as_slice());
+    match opts.test_timeout {
+        Some(timeout) => { cmd.arg("--test-timeout").arg(timeout.num_seconds().to_string()); }
+        None => {}
+    }
+    let nocapture = opts.nocapture;
+    if nocapture {
+        // Panic messages are written to stderr as they happen.
+        cmd.arg("--nocapture");
+        cmd.stderr(InheritFd(2));
+    }
+
+    Thread::spawn(move || {
+        let (output, dur) = timed(|| run_isolated_process(&cmd, timeout));
+        let (result, mut stdout) = match output {
+            Ok(Some(output)) => isolated_result(output),
+            Ok(None) => {
+                let msg = format!("\ntest process timed out after {} and was killed\n",
+                                  fmt_duration(timeout.unwrap()));
+                (TrFailed, msg.into_bytes())
+            }
+            Err(e) => {
+                (TrFailed, format!("failed to run the test process: {}\n", e).into_bytes())
+            }
+        };
+        if nocapture {
+            // The child had to write to a pipe to report its result, so
+            // pass on everything else it wrote now.
+            let _ = io::stdio::stdout_raw().write(stdout.as_slice());
+            stdout = Vec::new();
+        }
+        monitor_ch.send((desc, result, stdout, dur)).unwrap();
+    }).detach();
+}
+
+// Runs the process of an isolated test to completion. If it runs for
+// `ISOLATED_GRACE_MS` longer than `timeout`, it is killed and the result
+// is `None`.
+fn run_isolated_process(cmd: &Command,
+                        timeout: Option<Duration>) -> io::IoResult<Option<ProcessOutput>> {
+    // Reads a pipe of the child as the child writes to it, so that it never
+    // blocks on a full pipe.
+    fn read(stream: Option<io::PipeStream>) -> Receiver<Vec<u8>> {
+        let (tx, rx) = channel();
+        Thread::spawn(move || {
+            let bytes = match stream {
+                Some(mut stream) => stream.read_to_end().unwrap_or(Vec::new()),
+                None => Vec::new(),
+            };
+            let _ = tx.send(bytes);
+        }).detach();
+        rx
+    }
+
+    let mut process = try!(cmd.spawn());
+    let stdout = read(process.stdout.take());
+    let stderr = read(process.stderr.take());
+
+    process.set_timeout(timeout.map(|t| t + Duration::milliseconds(ISOLATED_GRACE_MS)));
+    let status = match process.wait() {
+        Ok(status) => status,
+        Err(ref e) if e.kind == io::TimedOut => {
+            try!(process.signal_kill());
+            process.set_timeout(None);
+            try!(process.wait());
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+    Ok(Some(ProcessOutput {
+        status: status,
+        output: stdout.recv().unwrap_or(Vec::new()),
+        error: stderr.recv().unwrap_or(Vec::new()),
+    }))
+}
+
+// Works out the result of a test from the output of its process. Anything
+// but a clean exit after reporting a result is a failure.
+fn isolated_result(output: ProcessOutput) -> (TestResult, Vec<u8>) {
+    let ProcessOutput { status, output: mut stdout, error } = output;
+    let result = ["ok", "failed"].iter().map(|&result| {
+        (result, format!("{}{}\n", ISOLATED_RESULT, result))
+    }).find(|&(_, ref marker)| stdout.ends_with(marker.as_bytes()));
+
+    match result {
+        Some((result, ref marker)) if status.success() => {
+            let len = stdout.len() - marker.len();
+            stdout.truncate(len);
+            stdout.push_all(error.as_slice());
+            (if result == "ok" { TrOk } else { TrFailed }, stdout)
+        }
+        _ => {
+            stdout.push_all(error.as_slice());
+            let msg = format!("\ntest process terminated abnormally ({})\n", status);
+            stdout.push_all(msg.as_bytes());
+            (TrFailed, stdout)
+        }
+    }
+}
+
+// The child side of `--isolate`: runs the test called `name` and reports
+// its result to the parent through stdout.
+fn run_isolated_child(opts: &TestOpts,
+                      tests: Vec<TestDescAndFn>,
+                      name: String) -> io::IoResult<()> {
+    let test = tests.into_iter().find(|test| test.desc.name.as_slice() == name.as_slice());
+    let (result, stdout) = match test {
+        Some(mut test) => {
+            // The parent has already decided to run it.
+            test.desc.ignore = false;
+            let (tx, rx) = channel();
+            run_test(opts, false, test, tx);
+            let (_, result, stdout, _) = rx.recv().unwrap();
+            (result, stdout)
+        }
+        None => (TrFailed, format!("no test named `{}`\n", name).into_bytes()),
+    };
+
+    let mut out = io::stdio::stdout_raw();
+    try!(out.write(stdout.as_slice()));
+    let result = if result == TrOk { "ok" } else { "failed" };
+    out.write_str(format!("{}{}\n", ISOLATED_RESULT, result).as_slice())
+}
+
+// Runs `f`, returning its result along with how long it took.
+fn timed<T, F>(f: F) -> (T, Duration) where F: FnOnce() -> T {
+    let mut result = None;
+    let dur = {
+        let result = &mut result;
+        Duration::span(move || *result = Some(f()))
+    };
+    (result.unwrap(), dur)
+}
+
 fn calc_result(desc: &TestDesc, task_result: Result<(), Box<Any+Send>>) -> TestResult {
     match (&desc.should_fail, task_result) {
         (&ShouldFail::No, Ok(())) |
@@ -1325,6 +2098,101 @@ impl MetricMap {
 }
 
 
+impl Baseline {
+
+    pub fn new() -> Baseline {
+        Baseline(BTreeMap::new())
+    }
+
+    /// The file holding the baseline called `name`: `name.json` in the
+    /// directory named by `RUST_BENCH_BASELINE_DIR`, or in the current
+    /// directory.
+    pub fn path(name: &str) -> Path {
+        let dir = match os::getenv("RUST_BENCH_BASELINE_DIR") {
+            Some(dir) => Path::new(dir),
+            None => Path::new("."),
+        };
+        dir.join(format!("{}.json", name))
+    }
+
+    /// Load a baseline from a file.
+    pub fn load(p: &Path) -> io::IoResult<Baseline> {
+        let mut f = try!(File::open(p));
+        let invalid = |e: String| io::IoError {
+            kind: io::InvalidInput,
+            desc: "invalid benchmark baseline",
+            detail: Some(e),
+        };
+        let value = try!(json::from_reader(&mut f as &mut io::Reader).map_err(|e| {
+            invalid(e.to_string())
+        }));
+        let mut decoder = json::Decoder::new(value);
+        match Decodable::decode(&mut decoder) {
+            Ok(map) => Ok(Baseline(map)),
+            Err(e) => Err(invalid(e.to_string())),
+        }
+    }
+
+    /// Write a baseline to a file.
+    pub fn save(&self, p: &Path) -> io::IoResult<()> {
+        let mut file = try!(File::create(p));
+        let Baseline(ref map) = *self;
+        write!(&mut file, "{}", json::as_json(map))
+    }
+
+    /// Whether no benchmark results have been inserted.
+    pub fn is_empty(&self) -> bool {
+        let Baseline(ref map) = *self;
+        map.is_empty()
+    }
+
+    /// Insert the median time per iteration of the benchmark `name`, along
+    /// with the confidence interval `ci` of the median.
+    pub fn insert(&mut self, name: &str, median: f64, ci: (f64, f64)) {
+        let (ci_low, ci_high) = ci;
+        let Baseline(ref mut map) = *self;
+        map.insert(name.to_string(), BenchEstimate {
+            median: median,
+            ci_low: ci_low,
+            ci_high: ci_high,
+        });
+    }
+
+    /// Compare against an older baseline. A benchmark has only improved or
+    /// regressed when the confidence intervals of its old and new medians
+    /// do not overlap, and the change is given as a percentage of the old
+    /// median.
+    pub fn compare_to_old(&self, old: &Baseline) -> MetricDiff {
+        let mut diff : MetricDiff = BTreeMap::new();
+        let Baseline(ref selfmap) = *self;
+        let Baseline(ref old) = *old;
+        for (k, vold) in old.iter() {
+            let r = match selfmap.get(k) {
+                None => MetricRemoved,
+                Some(v) => {
+                    let pct = (v.median - vold.median).abs() /
+                        vold.median.max(f64::EPSILON) * 100.0;
+                    if v.ci_low > vold.ci_high {
+                        Regression(pct)
+                    } else if v.ci_high < vold.ci_low {
+                        Improvement(pct)
+                    } else {
+                        LikelyNoise
+                    }
+                }
+            };
+            diff.insert((*k).clone(), r);
+        }
+        for (k, _) in selfmap.iter() {
+            if !diff.contains_key(k) {
+                diff.insert((*k).clone(), MetricAdded);
+            }
+        }
+        diff
+    }
+}
+
+
 // Benchmarking
 
 /// A function that is opaque to the optimizer, to allow benchmarks to
@@ -1368,7 +2236,15 @@ impl Bencher {
     }
 
     // This is a more statistics-driven benchmark algorithm
-    pub fn auto_bench<F>(&mut self, mut f: F) -> stats::Summary<f64> where F: FnMut(&mut Bencher) {
+    pub fn auto_bench<F>(&mut self, f: F) -> stats::Summary<f64> where F: FnMut(&mut Bencher) {
+        let (summ, _) = self.auto_bench_samples(f);
+        summ
+    }
+
+    // As `auto_bench`, also returning the samples behind the summary as
+    // they were before winsorizing.
+    fn auto_bench_samples<F>(&mut self, mut f: F) -> (stats::Summary<f64>, Vec<f64>)
+                             where F: FnMut(&mut Bencher) {
         // Initial bench run to get ballpark figure.
         let mut n = 1_u64;
         self.bench_n(n, |x| f(x));
@@ -1392,6 +2268,7 @@ impl Bencher {
         loop {
             let mut summ = None;
             let mut summ5 = None;
+            let mut samples5 = Vec::new();
 
             let loop_run = Duration::span(|| {
 
@@ -1408,6 +2285,7 @@ impl Bencher {
                     *p = self.ns_per_iter() as f64;
                 };
 
+                samples5 = samples.to_vec();
                 stats::winsorize(samples, 5.0);
                 summ5 = Some(stats::Summary::new(samples));
             });
@@ -1419,13 +2297,13 @@ impl Bencher {
             if loop_run.num_milliseconds() > 100 &&
                 summ.median_abs_dev_pct < 1.0 &&
                 summ.median - summ5.median < summ5.median_abs_dev {
-                return summ5;
+                return (summ5, samples5);
             }
 
             total_run = total_run + loop_run;
             // Longest we ever run for is 3s.
             if total_run.num_seconds() > 3 {
-                return summ5;
+                return (summ5, samples5);
             }
 
             n *= 2;
@@ -1436,6 +2314,7 @@ impl Bencher {
 pub mod bench {
     use std::cmp;
     use std::time::Duration;
+    use stats;
     use super::{Bencher, BenchSamples};
 
     pub fn benchmark<F>(f: F) -> BenchSamples where F: FnMut(&mut Bencher) {
@@ -1445,7 +2324,9 @@ pub mod bench {
             bytes: 0
         };
 
-        let ns_iter_summ = bs.auto_bench(f);
+        let (ns_iter_summ, samples) = bs.auto_bench_samples(f);
+        let ns_iter_ci = stats::bootstrap_median(samples.as_slice(), 1000, 95.0);
+        let outliers = stats::Outliers::new(samples.as_slice());
 
         let ns_iter = cmp::max(ns_iter_summ.median as u64, 1);
         let iter_s = 1_000_000_000 / ns_iter;
@@ -1453,6 +2334,9 @@ pub mod bench {
 
         BenchSamples {
             ns_iter_summ: ns_iter_summ,
+            ns_iter_ci: ns_iter_ci,
+            outliers: outliers,
+            bytes: bs.bytes,
             mb_s: mb_s as uint
         }
     }
@@ -1465,7 +2349,17 @@ mod tests {
                Metric, MetricMap, MetricAdded, MetricRemoved,
                Improvement, Regression, LikelyNoise,
                StaticTestName, DynTestName, DynTestFn, ShouldFail};
-    use std::io::TempDir;
+    use super::{StructuredTestState, OutputFormat, JsonFormat, JunitFormat};
+    use super::{isolated_result, Baseline, run_with_fixtures};
+    use std::cell::Cell;
+    use std::sync::mpsc::Sender;
+    use std::thread::Thread;
+    use serialize::json;
+    use serialize::json::Json;
+    use std::io::{File, TempDir};
+    use std::io::timer;
+    use std::io::process::{ProcessOutput, ExitStatus, ExitSignal};
+    use std::time::Duration;
     use std::thunk::Thunk;
     use std::sync::mpsc::channel;
 
@@ -1477,12 +2371,13 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: true,
                 should_fail: ShouldFail::No,
+                timeout: None,
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res != TrOk);
     }
 
@@ -1494,12 +2389,13 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: true,
                 should_fail: ShouldFail::No,
+                timeout: None,
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrIgnored);
     }
 
@@ -1510,16 +2406,57 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_fail: ShouldFail::Yes(None)
+                should_fail: ShouldFail::Yes(None),
+                timeout: None,
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
+    #[test]
+    fn test_timeout_fails() {
+        fn f() { loop { timer::sleep(Duration::milliseconds(10)); } }
+        let desc = TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("whatever"),
+                ignore: false,
+                should_fail: ShouldFail::No,
+                timeout: Some(1),
+            },
+            testfn: DynTestFn(Thunk::new(move|| f())),
+        };
+        let (tx, rx) = channel();
+        run_test(&TestOpts::new(), false, desc, tx);
+        let (_, res, stdout, _) = rx.recv().unwrap();
+        assert!(res == TrFailed);
+        let stdout = String::from_utf8(stdout).unwrap();
+        assert!(stdout.contains("test timed out after 1.000s"));
+    }
+
+    #[test]
+    fn test_global_timeout_fails() {
+        fn f() { loop { timer::sleep(Duration::milliseconds(10)); } }
+        let desc = TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("whatever"),
+                ignore: false,
+                should_fail: ShouldFail::No,
+                timeout: None,
+            },
+            testfn: DynTestFn(Thunk::new(move|| f())),
+        };
+        let mut opts = TestOpts::new();
+        opts.test_timeout = Some(Duration::milliseconds(100));
+        let (tx, rx) = channel();
+        run_test(&opts, false, desc, tx);
+        let (_, res, _, _) = rx.recv().unwrap();
+        assert!(res == TrFailed);
+    }
+
     #[test]
     fn test_should_fail_good_message() {
         fn f() { panic!("an error message"); }
@@ -1527,13 +2464,14 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_fail: ShouldFail::Yes(Some("error message"))
+                should_fail: ShouldFail::Yes(Some("error message")),
+                timeout: None,
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
@@ -1544,13 +2482,14 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_fail: ShouldFail::Yes(Some("foobar"))
+                should_fail: ShouldFail::Yes(Some("foobar")),
+                timeout: None,
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailed);
     }
 
@@ -1561,13 +2500,14 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_fail: ShouldFail::Yes(None)
+                should_fail: ShouldFail::Yes(None),
+                timeout: None,
             },
             testfn: DynTestFn(Thunk::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailed);
     }
 
@@ -1593,6 +2533,211 @@ mod tests {
         assert!((opts.run_ignored));
     }
 
+    #[test]
+    fn parse_format_option() {
+        let args = vec!("progname".to_string(),
+                        "--format".to_string(),
+                        "junit".to_string());
+        let opts = match parse_opts(args.as_slice()) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_format_option")
+        };
+        assert_eq!(opts.format, JunitFormat);
+
+        let args = vec!("progname".to_string(), "--format=xml".to_string());
+        assert!(parse_opts(args.as_slice()).unwrap().is_err());
+    }
+
+    #[test]
+    fn parse_format_rejects_text_only_options() {
+        for opt in ["--nocapture", "--save-metrics=m.json", "--ratchet-metrics=m.json",
+                    "--save-baseline=base", "--compare-baseline=base"].iter() {
+            let args = vec!("progname".to_string(),
+                            "--format=json".to_string(),
+                            opt.to_string());
+            match parse_opts(args.as_slice()) {
+                Some(Err(msg)) => {
+                    assert!(msg.as_slice().contains("can't be used with --format=json"))
+                }
+                _ => panic!("{} was accepted with --format=json", opt),
+            }
+
+            let args = vec!("progname".to_string(), "--bench".to_string(), opt.to_string());
+            assert!(parse_opts(args.as_slice()).unwrap().is_ok());
+        }
+
+        // The log is written to its own file.
+        let args = vec!("progname".to_string(),
+                        "--format=junit".to_string(),
+                        "--logfile=log".to_string());
+        let opts = parse_opts(args.as_slice()).unwrap().unwrap();
+        assert_eq!(opts.logfile, Some(Path::new("log")));
+    }
+
+    #[test]
+    fn parse_format_json_nocapture() {
+        let args = vec!("progname".to_string(),
+                        "--format=json".to_string(),
+                        "--nocapture".to_string());
+        match parse_opts(args.as_slice()) {
+            Some(Err(msg)) => {
+                assert_eq!(msg.as_slice(), "--nocapture can't be used with --format=json")
+            }
+            _ => panic!("--nocapture was accepted with --format=json"),
+        }
+    }
+
+    #[test]
+    fn fixtures_run_around_tests() {
+        thread_local!(static STATE: Cell<uint> = Cell::new(0));
+        fn before() { STATE.with(|s| { assert_eq!(s.get(), 0); s.set(1) }) }
+        fn test() { STATE.with(|s| { assert_eq!(s.get(), 1); s.set(2) }) }
+        fn fail() { STATE.with(|s| s.set(2)); panic!() }
+        fn after() { STATE.with(|s| { assert_eq!(s.get(), 2); s.set(3) }) }
+
+        run_with_fixtures(&[before], test, &[after]);
+        STATE.with(|s| assert_eq!(s.get(), 3));
+
+        // The `after` hooks also run when the test fails.
+        let (tx, rx) = channel();
+        let res = Thread::spawn(move|| {
+            let _send = ReportState(tx);
+            run_with_fixtures(&[before], fail, &[after]);
+        }).join();
+        assert!(res.is_err());
+        assert_eq!(rx.recv().unwrap(), 3);
+
+        // Reports the final state of the hooks on the way out of the thread.
+        struct ReportState(Sender<uint>);
+        impl Drop for ReportState {
+            fn drop(&mut self) {
+                let ReportState(ref tx) = *self;
+                tx.send(STATE.with(|s| s.get())).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn parse_isolate_flag() {
+        let args = vec!("progname".to_string(), "--isolate".to_string());
+        let opts = match parse_opts(args.as_slice()) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_isolate_flag")
+        };
+        assert!(opts.isolate);
+    }
+
+    #[test]
+    fn isolated_results() {
+        let output = |status, output: &str, error: &str| ProcessOutput {
+            status: status,
+            output: output.as_bytes().to_vec(),
+            error: error.as_bytes().to_vec(),
+        };
+
+        let (res, out) = isolated_result(output(ExitStatus(0),
+                                                "hi\n--- isolated test result: ok\n", ""));
+        assert!(res == TrOk);
+        assert_eq!(out, b"hi".to_vec());
+
+        let (res, out) = isolated_result(output(ExitStatus(0),
+                                                "\n--- isolated test result: failed\n",
+                                                "oops"));
+        assert!(res == TrFailed);
+        assert_eq!(out, b"oops".to_vec());
+
+        // A test which exits early never reports a result.
+        let (res, _) = isolated_result(output(ExitStatus(0), "bye\n", ""));
+        assert!(res == TrFailed);
+
+        let (res, out) = isolated_result(output(ExitSignal(11), "", ""));
+        assert!(res == TrFailed);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("terminated abnormally"));
+    }
+
+    #[test]
+    fn parse_timeout_options() {
+        let args = vec!("progname".to_string(),
+                        "--test-timeout".to_string(),
+                        "60".to_string(),
+                        "--test-warn-time=2".to_string());
+        let opts = match parse_opts(args.as_slice()) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_timeout_options")
+        };
+        assert_eq!(opts.test_timeout, Some(Duration::seconds(60)));
+        assert_eq!(opts.warn_time, Some(Duration::seconds(2)));
+
+        let args = vec!("progname".to_string(), "--test-timeout=soon".to_string());
+        assert!(parse_opts(args.as_slice()).unwrap().is_err());
+    }
+
+    fn structured_run(format: OutputFormat) -> String {
+        let desc = |name| TestDesc {
+            name: StaticTestName(name),
+            ignore: false,
+            should_fail: ShouldFail::No,
+            timeout: None,
+        };
+        let mut st = StructuredTestState::new(format, Some(Duration::seconds(1)), Vec::new());
+        st.write_run_start(3).unwrap();
+        st.write_test_start(&desc("a::pass")).unwrap();
+        st.write_result(desc("a::pass"), TrOk, Vec::new(),
+                        Duration::milliseconds(1500)).unwrap();
+        st.write_test_start(&desc("a::fail")).unwrap();
+        st.write_result(desc("a::fail"), TrFailed, b"<oops> & \"bye\"".to_vec(),
+                        Duration::milliseconds(2)).unwrap();
+        st.write_test_start(&desc("skip")).unwrap();
+        st.write_result(desc("skip"), TrIgnored, Vec::new(), Duration::zero()).unwrap();
+        assert!(!st.write_run_finish().unwrap());
+        String::from_utf8(st.out).unwrap()
+    }
+
+    #[test]
+    fn structured_json_events() {
+        let out = structured_run(JsonFormat);
+        let events: Vec<Json> = out.lines().map(|l| json::from_str(l).unwrap()).collect();
+        assert_eq!(events.len(), 8);
+        assert_eq!(events[0].find("event").and_then(|e| e.as_string()), Some("started"));
+        assert_eq!(events[0].find("test_count").and_then(|e| e.as_u64()), Some(3));
+        assert_eq!(events[1].find("name").and_then(|e| e.as_string()), Some("a::pass"));
+        assert_eq!(events[2].find("event").and_then(|e| e.as_string()), Some("ok"));
+        assert_eq!(events[2].find("duration_ns").and_then(|e| e.as_i64()),
+                   Some(1500000000));
+        assert_eq!(events[2].find("slow").and_then(|e| e.as_boolean()), Some(true));
+        assert!(events[4].find("slow").is_none());
+        assert_eq!(events[4].find("event").and_then(|e| e.as_string()), Some("failed"));
+        assert_eq!(events[4].find("stdout").and_then(|e| e.as_string()),
+                   Some("<oops> & \"bye\""));
+        assert_eq!(events[6].find("event").and_then(|e| e.as_string()), Some("ignored"));
+        assert_eq!(events[7].find("type").and_then(|e| e.as_string()), Some("suite"));
+        assert_eq!(events[7].find("event").and_then(|e| e.as_string()), Some("failed"));
+        assert_eq!(events[7].find("passed").and_then(|e| e.as_u64()), Some(1));
+    }
+
+    #[test]
+    fn structured_junit_report() {
+        let out = structured_run(JunitFormat);
+        let expected = [
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+            "<testsuites>",
+            "  <testsuite name=\"test\" tests=\"3\" failures=\"1\" errors=\"0\" \
+             skipped=\"1\" time=\"1.502\">",
+            "    <testcase classname=\"a\" name=\"pass\" time=\"1.500\"/>",
+            "    <testcase classname=\"a\" name=\"fail\" time=\"0.002\">",
+            "      <failure message=\"test failed\"/>",
+            "      <system-out>&lt;oops&gt; &amp; &quot;bye&quot;</system-out>",
+            "    </testcase>",
+            "    <testcase classname=\"\" name=\"skip\" time=\"0.000\">",
+            "      <skipped/>",
+            "    </testcase>",
+            "  </testsuite>",
+            "</testsuites>",
+        ];
+        assert_eq!(out.lines().collect::<Vec<&str>>(), expected.to_vec());
+    }
+
     #[test]
     pub fn filter_for_ignored_option() {
         // When we run ignored tests the test filter should filter out all the
@@ -1608,6 +2753,7 @@ mod tests {
                     name: StaticTestName("1"),
                     ignore: true,
                     should_fail: ShouldFail::No,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Thunk::new(move|| {})),
             },
@@ -1616,6 +2762,7 @@ mod tests {
                     name: StaticTestName("2"),
                     ignore: false,
                     should_fail: ShouldFail::No,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Thunk::new(move|| {})),
             });
@@ -1652,6 +2799,7 @@ mod tests {
                         name: DynTestName((*name).clone()),
                         ignore: false,
                         should_fail: ShouldFail::No,
+                        timeout: None,
                     },
                     testfn: DynTestFn(Thunk::new(testfn)),
                 };
@@ -1693,6 +2841,7 @@ mod tests {
                     name: DynTestName(name.to_string()),
                     ignore: false,
                     should_fail: ShouldFail::No,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Thunk::new(test_fn))
             }
@@ -1806,4 +2955,66 @@ mod tests {
         assert_eq!(*(m4.get(&"runtime".to_string()).unwrap()), Metric::new(1100.0, 2.0));
         assert_eq!(*(m4.get(&"throughput".to_string()).unwrap()), Metric::new(50.0, 2.0));
     }
+
+    #[test]
+    fn parse_baseline_options() {
+        let args = vec!("progname".to_string(),
+                        "--bench".to_string(),
+                        "--save-baseline".to_string(),
+                        "new".to_string(),
+                        "--compare-baseline=old".to_string());
+        let opts = match parse_opts(args.as_slice()) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_baseline_options")
+        };
+        assert_eq!(opts.save_baseline, Some("new".to_string()));
+        assert_eq!(opts.compare_baseline, Some("old".to_string()));
+
+        for opt in ["--save-baseline=new", "--compare-baseline=old"].iter() {
+            let args = vec!("progname".to_string(), opt.to_string());
+            match parse_opts(args.as_slice()) {
+                Some(Err(msg)) => assert!(msg.as_slice().contains("can only be used with --bench")),
+                _ => panic!("{} was accepted without --bench", opt),
+            }
+        }
+    }
+
+    #[test]
+    pub fn compare_baselines() {
+        let mut old = Baseline::new();
+        old.insert("faster", 1000.0, (990.0, 1010.0));
+        old.insert("slower", 1000.0, (990.0, 1010.0));
+        old.insert("noisy", 1000.0, (900.0, 1100.0));
+        old.insert("removed", 1000.0, (990.0, 1010.0));
+
+        let mut new = Baseline::new();
+        new.insert("faster", 500.0, (490.0, 510.0));
+        new.insert("slower", 1500.0, (1450.0, 1550.0));
+        new.insert("noisy", 1050.0, (1000.0, 1150.0));
+        new.insert("added", 1000.0, (990.0, 1010.0));
+
+        let diff = new.compare_to_old(&old);
+        assert_eq!(diff.len(), 5);
+        assert_eq!(*diff.get(&"faster".to_string()).unwrap(), Improvement(50.0));
+        assert_eq!(*diff.get(&"slower".to_string()).unwrap(), Regression(50.0));
+        assert_eq!(*diff.get(&"noisy".to_string()).unwrap(), LikelyNoise);
+        assert_eq!(*diff.get(&"removed".to_string()).unwrap(), MetricRemoved);
+        assert_eq!(*diff.get(&"added".to_string()).unwrap(), MetricAdded);
+    }
+
+    #[test]
+    pub fn save_and_load_baseline() {
+        let dpth = TempDir::new("test-baseline").ok().expect("missing test for baseline");
+        let pth = dpth.path().join("baseline.json");
+
+        assert!(Baseline::load(&pth).is_err());
+
+        let mut b = Baseline::new();
+        b.insert("bench", 1000.0, (990.0, 1010.0));
+        b.save(&pth).unwrap();
+        assert!(Baseline::load(&pth).unwrap() == b);
+
+        File::create(&pth).write_str("{").unwrap();
+        assert!(Baseline::load(&pth).is_err());
+    }
 }
@@ -0,0 +1,578 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! QuickCheck-style property testing.
+//!
+//! A property is a function from some input to `bool`. `quickcheck` checks
+//! it against randomly generated inputs and, when it finds one for which
+//! the property does not hold, shrinks that input to a minimal
+//! counterexample before panicking with it:
+//!
+//! ```rust
+//! use test::quickcheck::quickcheck;
+//!
+//! quickcheck(|xs: Vec<int>| {
+//!     let rev: Vec<int> = xs.iter().rev().map(|&x| x).collect();
+//!     let revrev: Vec<int> = rev.iter().rev().map(|&x| x).collect();
+//!     xs == revrev
+//! });
+//! ```
+//!
+//! Properties of several arguments take them as a tuple. The inputs are
+//! generated from a seed which is reported on failure; setting the
+//! `RUST_QUICKCHECK_SEED` environment variable to it tries the same inputs
+//! again. The `quickcheck!` macro declares properties as tests.
+
+use std::cmp;
+use std::fmt::Show;
+use std::num::{Float, Int};
+use std::os;
+use std::rand::{mod, Rng, SeedableRng, XorShiftRng};
+
+/// Declares property tests: functions of arbitrary arguments returning
+/// `bool`, each of which is checked with `quickcheck` by the test harness.
+///
+/// # Example
+///
+/// ```rust
+/// #![feature(phase)]
+/// #[phase(plugin, link)] extern crate test;
+///
+/// quickcheck! {
+///     fn prop_sort_is_idempotent(xs: Vec<int>) -> bool {
+///         let mut once = xs.clone();
+///         once.sort();
+///         let mut twice = once.clone();
+///         twice.sort();
+///         once == twice
+///     }
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! quickcheck {
+    ($(fn $name:ident($($arg:ident: $t:ty),*) -> bool $body:block)*) => ($(
+        #[test]
+        fn $name() {
+            fn prop($($arg: $t),*) -> bool $body
+            ::test::quickcheck::quickcheck(|args: ($($t,)*)| {
+                let ($($arg,)*) = args;
+                prop($($arg),*)
+            });
+        }
+    )*)
+}
+
+/// A source of randomness for `Arbitrary`, with a size bounding the values
+/// it should produce: the magnitude of numbers, the length of vectors and
+/// so on.
+pub trait Gen: Rng {
+    fn size(&self) -> uint;
+}
+
+/// A `Gen` backed by any random number generator.
+pub struct StdGen<R> {
+    rng: R,
+    size: uint,
+}
+
+impl<R: Rng> StdGen<R> {
+    pub fn new(rng: R, size: uint) -> StdGen<R> {
+        StdGen { rng: rng, size: size }
+    }
+}
+
+impl<R: Rng> Rng for StdGen<R> {
+    fn next_u32(&mut self) -> u32 { self.rng.next_u32() }
+    fn next_u64(&mut self) -> u64 { self.rng.next_u64() }
+    fn fill_bytes(&mut self, dest: &mut [u8]) { self.rng.fill_bytes(dest) }
+}
+
+impl<R: Rng> Gen for StdGen<R> {
+    fn size(&self) -> uint { self.size }
+}
+
+/// Types whose values can be generated at random, and shrunk to simpler
+/// values.
+pub trait Arbitrary: Clone {
+    /// Generate a random value.
+    fn arbitrary<G: Gen>(g: &mut G) -> Self;
+
+    /// Simpler values than this one, to try in its place when it makes a
+    /// property fail. The simplest come first.
+    fn shrink(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+impl Arbitrary for () {
+    fn arbitrary<G: Gen>(_: &mut G) -> () { () }
+}
+
+impl Arbitrary for bool {
+    fn arbitrary<G: Gen>(g: &mut G) -> bool { g.gen() }
+
+    fn shrink(&self) -> Vec<bool> {
+        if *self { vec![false] } else { Vec::new() }
+    }
+}
+
+// Candidates for shrinking `x` towards zero: zero itself, then `x` less
+// successively smaller halves of itself.
+fn shrink_int<T: Int>(x: T) -> Vec<T> {
+    let zero: T = Int::zero();
+    let two: T = Int::one() + Int::one();
+    let mut shrunk = Vec::new();
+    if x == zero {
+        return shrunk;
+    }
+    shrunk.push(zero);
+    let mut i = x / two;
+    while i != zero {
+        shrunk.push(x - i);
+        i = i / two;
+    }
+    shrunk
+}
+
+macro_rules! unsigned_arbitrary {
+    ($($t:ty),*) => ($(
+        impl Arbitrary for $t {
+            fn arbitrary<G: Gen>(g: &mut G) -> $t {
+                let max: $t = Int::max_value();
+                let size = cmp::min(g.size() as u64, max as u64);
+                g.gen_range(0, size + 1) as $t
+            }
+
+            fn shrink(&self) -> Vec<$t> {
+                shrink_int(*self)
+            }
+        }
+    )*)
+}
+
+macro_rules! signed_arbitrary {
+    ($($t:ty),*) => ($(
+        impl Arbitrary for $t {
+            fn arbitrary<G: Gen>(g: &mut G) -> $t {
+                let max: $t = Int::max_value();
+                let size = cmp::min(g.size() as i64, max as i64);
+                g.gen_range(-size, size + 1) as $t
+            }
+
+            fn shrink(&self) -> Vec<$t> {
+                let min: $t = Int::min_value();
+                let mut shrunk = shrink_int(*self);
+                if *self < 0 && *self != min {
+                    shrunk.insert(1, -*self);
+                }
+                shrunk
+            }
+        }
+    )*)
+}
+
+macro_rules! float_arbitrary {
+    ($($t:ty),*) => ($(
+        impl Arbitrary for $t {
+            fn arbitrary<G: Gen>(g: &mut G) -> $t {
+                let size = g.size() as $t;
+                if size == 0.0 { 0.0 } else { g.gen_range(-size, size) }
+            }
+
+            fn shrink(&self) -> Vec<$t> {
+                let x = *self;
+                let mut shrunk = Vec::new();
+                if x == 0.0 || x.is_nan() {
+                    return shrunk;
+                }
+                shrunk.push(0.0);
+                if x < 0.0 {
+                    shrunk.push(-x);
+                }
+                if x.trunc() != x {
+                    shrunk.push(x.trunc());
+                }
+                shrunk
+            }
+        }
+    )*)
+}
+
+unsigned_arbitrary!(u8, u16, u32, u64, uint);
+signed_arbitrary!(i8, i16, i32, i64, int);
+float_arbitrary!(f32, f64);
+
+impl Arbitrary for char {
+    fn arbitrary<G: Gen>(g: &mut G) -> char {
+        // Mostly printable ASCII, which makes for readable counterexamples.
+        if g.gen_weighted_bool(10) {
+            g.gen()
+        } else {
+            g.gen_range(0x20u8, 0x7f) as char
+        }
+    }
+
+    fn shrink(&self) -> Vec<char> {
+        ['a', 'b', 'A', '0', ' '].iter().map(|&c| c).filter(|&c| c < *self).collect()
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Vec<T> {
+    fn arbitrary<G: Gen>(g: &mut G) -> Vec<T> {
+        let size = g.size();
+        let len = g.gen_range(0, size + 1);
+        range(0, len).map(|_| Arbitrary::arbitrary(g)).collect()
+    }
+
+    fn shrink(&self) -> Vec<Vec<T>> {
+        let mut shrunk = Vec::new();
+        if self.is_empty() {
+            return shrunk;
+        }
+        shrunk.push(Vec::new());
+
+        // Remove successively smaller chunks...
+        let len = self.len();
+        let mut k = len / 2;
+        while k > 0 {
+            let mut start = 0;
+            while start + k <= len {
+                let mut v = self[..start].to_vec();
+                v.push_all(self[start + k..]);
+                shrunk.push(v);
+                start += k;
+            }
+            k /= 2;
+        }
+
+        // ...then shrink each element in place.
+        for (i, x) in self.iter().enumerate() {
+            for y in x.shrink().into_iter() {
+                let mut v = self.clone();
+                v.as_mut_slice()[i] = y;
+                shrunk.push(v);
+            }
+        }
+        shrunk
+    }
+}
+
+impl Arbitrary for String {
+    fn arbitrary<G: Gen>(g: &mut G) -> String {
+        let chars: Vec<char> = Arbitrary::arbitrary(g);
+        chars.into_iter().collect()
+    }
+
+    fn shrink(&self) -> Vec<String> {
+        let chars: Vec<char> = self.chars().collect();
+        chars.shrink().into_iter().map(|cs| cs.into_iter().collect()).collect()
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn arbitrary<G: Gen>(g: &mut G) -> Option<T> {
+        if g.gen_weighted_bool(4) {
+            None
+        } else {
+            Some(Arbitrary::arbitrary(g))
+        }
+    }
+
+    fn shrink(&self) -> Vec<Option<T>> {
+        match *self {
+            None => Vec::new(),
+            Some(ref x) => {
+                let mut shrunk = vec![None];
+                shrunk.extend(x.shrink().into_iter().map(Some));
+                shrunk
+            }
+        }
+    }
+}
+
+impl<T: Arbitrary, E: Arbitrary> Arbitrary for Result<T, E> {
+    fn arbitrary<G: Gen>(g: &mut G) -> Result<T, E> {
+        if g.gen() {
+            Ok(Arbitrary::arbitrary(g))
+        } else {
+            Err(Arbitrary::arbitrary(g))
+        }
+    }
+
+    fn shrink(&self) -> Vec<Result<T, E>> {
+        match *self {
+            Ok(ref x) => x.shrink().into_iter().map(Ok).collect(),
+            Err(ref e) => e.shrink().into_iter().map(Err).collect(),
+        }
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Box<T> {
+    fn arbitrary<G: Gen>(g: &mut G) -> Box<T> {
+        box Arbitrary::arbitrary(g)
+    }
+
+    fn shrink(&self) -> Vec<Box<T>> {
+        (**self).shrink().into_iter().map(|x| box x).collect()
+    }
+}
+
+impl<A: Arbitrary> Arbitrary for (A,) {
+    fn arbitrary<G: Gen>(g: &mut G) -> (A,) {
+        (Arbitrary::arbitrary(g),)
+    }
+
+    fn shrink(&self) -> Vec<(A,)> {
+        let (ref a,) = *self;
+        a.shrink().into_iter().map(|a| (a,)).collect()
+    }
+}
+
+impl<A: Arbitrary, B: Arbitrary> Arbitrary for (A, B) {
+    fn arbitrary<G: Gen>(g: &mut G) -> (A, B) {
+        let a = Arbitrary::arbitrary(g);
+        (a, Arbitrary::arbitrary(g))
+    }
+
+    fn shrink(&self) -> Vec<(A, B)> {
+        let (ref a, ref b) = *self;
+        let mut shrunk: Vec<(A, B)> = a.shrink().into_iter().map(|a| {
+            (a, b.clone())
+        }).collect();
+        shrunk.extend(b.shrink().into_iter().map(|b| (a.clone(), b)));
+        shrunk
+    }
+}
+
+impl<A: Arbitrary, B: Arbitrary, C: Arbitrary> Arbitrary for (A, B, C) {
+    fn arbitrary<G: Gen>(g: &mut G) -> (A, B, C) {
+        let a = Arbitrary::arbitrary(g);
+        let b = Arbitrary::arbitrary(g);
+        (a, b, Arbitrary::arbitrary(g))
+    }
+
+    fn shrink(&self) -> Vec<(A, B, C)> {
+        let (ref a, ref b, ref c) = *self;
+        let mut shrunk: Vec<(A, B, C)> = a.shrink().into_iter().map(|a| {
+            (a, b.clone(), c.clone())
+        }).collect();
+        shrunk.extend(b.shrink().into_iter().map(|b| (a.clone(), b, c.clone())));
+        shrunk.extend(c.shrink().into_iter().map(|c| (a.clone(), b.clone(), c)));
+        shrunk
+    }
+}
+
+impl<A: Arbitrary, B: Arbitrary, C: Arbitrary, D: Arbitrary> Arbitrary for (A, B, C, D) {
+    fn arbitrary<G: Gen>(g: &mut G) -> (A, B, C, D) {
+        let a = Arbitrary::arbitrary(g);
+        let b = Arbitrary::arbitrary(g);
+        let c = Arbitrary::arbitrary(g);
+        (a, b, c, Arbitrary::arbitrary(g))
+    }
+
+    fn shrink(&self) -> Vec<(A, B, C, D)> {
+        let (ref a, ref b, ref c, ref d) = *self;
+        let mut shrunk: Vec<(A, B, C, D)> = a.shrink().into_iter().map(|a| {
+            (a, b.clone(), c.clone(), d.clone())
+        }).collect();
+        shrunk.extend(b.shrink().into_iter().map(|b| (a.clone(), b, c.clone(), d.clone())));
+        shrunk.extend(c.shrink().into_iter().map(|c| (a.clone(), b.clone(), c, d.clone())));
+        shrunk.extend(d.shrink().into_iter().map(|d| (a.clone(), b.clone(), c.clone(), d)));
+        shrunk
+    }
+}
+
+/// How `check_with` tests a property.
+#[deriving(Copy, Clone, Show)]
+pub struct Config {
+    /// The number of random inputs to try.
+    pub tests: uint,
+    /// The size, as given by `Gen::size`, of the last inputs tried. Earlier
+    /// inputs are smaller.
+    pub size: uint,
+    /// The seed of the random inputs. A run with the seed of a failed run
+    /// tries the same inputs again.
+    pub seed: u64,
+}
+
+impl Config {
+    /// 100 tests with inputs up to size 100, seeded by the
+    /// `RUST_QUICKCHECK_SEED` environment variable or else at random.
+    pub fn new() -> Config {
+        let seed = os::getenv("RUST_QUICKCHECK_SEED").and_then(|s| s.parse::<u64>());
+        Config {
+            tests: 100,
+            size: 100,
+            seed: seed.unwrap_or_else(|| rand::random()),
+        }
+    }
+}
+
+/// An input for which a property does not hold.
+#[deriving(Clone, PartialEq, Show)]
+pub struct Failure<A> {
+    /// The seed of the run which found it.
+    pub seed: u64,
+    /// The number of inputs tried, including this one.
+    pub tests: uint,
+    /// The number of times the input was shrunk.
+    pub shrinks: uint,
+    /// The shrunk input.
+    pub input: A,
+}
+
+/// Check `prop` against `config.tests` random inputs, returning the number
+/// of inputs tried or the shrunk counterexample.
+pub fn check_with<A, F>(config: &Config, prop: F) -> Result<uint, Failure<A>> where
+    A: Arbitrary,
+    F: Fn(A) -> bool,
+{
+    // XorShiftRng must not be seeded with zeroes, hence the constants.
+    let seed = config.seed;
+    let rng: XorShiftRng = SeedableRng::from_seed([seed as u32, (seed >> 32) as u32,
+                                                   0x2545f491, 0x9e3779b9]);
+    let mut g = StdGen::new(rng, 0);
+    for i in range(0, config.tests) {
+        // Start small, so that simple counterexamples are found first.
+        g.size = config.size * (i + 1) / config.tests;
+        let input: A = Arbitrary::arbitrary(&mut g);
+        if !prop(input.clone()) {
+            let (input, shrinks) = shrink(input, &prop);
+            return Err(Failure {
+                seed: seed,
+                tests: i + 1,
+                shrinks: shrinks,
+                input: input,
+            });
+        }
+    }
+    Ok(config.tests)
+}
+
+// Shrink a counterexample for as long as one of its shrunk values also
+// makes the property fail.
+fn shrink<A, F>(mut input: A, prop: &F) -> (A, uint) where
+    A: Arbitrary,
+    F: Fn(A) -> bool,
+{
+    let mut shrinks = 0u;
+    loop {
+        match input.shrink().into_iter().find(|x| !(*prop)(x.clone())) {
+            Some(smaller) => {
+                input = smaller;
+                shrinks += 1;
+            }
+            None => return (input, shrinks),
+        }
+    }
+}
+
+/// Check a property with `Config::new()`, panicking with the shrunk
+/// counterexample and the seed which reproduces it if it does not hold.
+pub fn quickcheck<A, F>(prop: F) where
+    A: Arbitrary + Show,
+    F: Fn(A) -> bool,
+{
+    match check_with(&Config::new(), prop) {
+        Ok(_) => {}
+        Err(failure) => {
+            panic!("property does not hold for {} (after {} tests and {} shrinks); \
+                    rerun with RUST_QUICKCHECK_SEED={} to reproduce",
+                   failure.input, failure.tests, failure.shrinks, failure.seed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Arbitrary, Config, StdGen, Gen, check_with};
+    use std::rand::{SeedableRng, XorShiftRng};
+
+    fn config() -> Config {
+        Config { tests: 100, size: 100, seed: 42 }
+    }
+
+    #[test]
+    fn passing_property() {
+        assert_eq!(check_with(&config(), |x: uint| x + 0 == x), Ok(100));
+    }
+
+    #[test]
+    fn generated_values_respect_size() {
+        let rng: XorShiftRng = SeedableRng::from_seed([1, 2, 3, 4]);
+        let mut g = StdGen::new(rng, 10);
+        assert_eq!(g.size(), 10);
+        for _ in range(0u, 100) {
+            let x: i32 = Arbitrary::arbitrary(&mut g);
+            assert!(-10 <= x && x <= 10);
+            let xs: Vec<u8> = Arbitrary::arbitrary(&mut g);
+            assert!(xs.len() <= 10);
+        }
+    }
+
+    #[test]
+    fn shrinks_numbers() {
+        let failure = check_with(&config(), |x: uint| x < 10).unwrap_err();
+        assert_eq!(failure.input, 10);
+        let failure = check_with(&config(), |x: int| x > -10).unwrap_err();
+        assert_eq!(failure.input, -10);
+    }
+
+    #[test]
+    fn shrinks_vectors() {
+        let failure = check_with(&config(), |xs: Vec<int>| xs.len() < 3).unwrap_err();
+        assert_eq!(failure.input, vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn shrinks_strings() {
+        let failure = check_with(&config(), |s: String| !s.contains_char('z')).unwrap_err();
+        assert_eq!(failure.input, "z".to_string());
+    }
+
+    #[test]
+    fn shrinks_tuples() {
+        let failure = check_with(&config(), |(a, b): (int, int)| {
+            a < 5 || b > -3
+        }).unwrap_err();
+        assert_eq!(failure.input, (5, -3));
+    }
+
+    #[test]
+    fn seed_reproduces_failure() {
+        let first = check_with(&config(), |x: u64| x % 7 != 3).unwrap_err();
+        let second = check_with(&config(), |x: u64| x % 7 != 3).unwrap_err();
+        assert_eq!(first, second);
+        assert_eq!(first.seed, 42);
+    }
+
+    quickcheck! {
+        fn prop_reverse_reverse(xs: Vec<int>) -> bool {
+            let rev: Vec<int> = xs.iter().rev().map(|&x| x).collect();
+            let revrev: Vec<int> = rev.iter().rev().map(|&x| x).collect();
+            xs == revrev
+        }
+
+        fn prop_add_commutes(a: i32, b: i32) -> bool {
+            a + b == b + a
+        }
+
+        fn prop_no_arguments() -> bool {
+            true
+        }
+    }
+
+    #[test]
+    #[should_fail]
+    fn quickcheck_panics_on_failure() {
+        super::quickcheck(|x: u8| x < 5);
+    }
+}
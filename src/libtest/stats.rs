@@ -18,6 +18,7 @@ use std::hash::Hash;
 use std::io;
 use std::mem;
 use std::num::{Float, FloatMath, FromPrimitive};
+use std::rand::{Rng, SeedableRng, XorShiftRng};
 
 fn local_cmp<T:Float>(x: T, y: T) -> Ordering {
     // arbitrarily decide that NaNs are larger than everything.
@@ -331,6 +332,80 @@ pub fn winsorize<T: Float + FromPrimitive>(samples: &mut [T], pct: T) {
     }
 }
 
+/// Estimate a confidence interval of the median of a sample set by bootstrapping: taking the
+/// median of each of `resamples` random resamplings (with replacement) of the samples, and
+/// returning the percentiles of those medians which bound the central `confidence` percent of
+/// them. The resampling is seeded with a constant, so the estimate is reproducible.
+///
+/// See: http://en.wikipedia.org/wiki/Bootstrapping_(statistics)
+pub fn bootstrap_median<T: FloatMath + FromPrimitive>(samples: &[T],
+                                                      resamples: uint,
+                                                      confidence: T) -> (T, T) {
+    assert!(samples.len() != 0 && resamples != 0);
+    let mut rng: XorShiftRng = SeedableRng::from_seed([0x193a6754, 0xa8a7d469,
+                                                       0x97830e05, 0x113ba7bb]);
+    let mut resample = samples.to_vec();
+    let mut medians = Vec::with_capacity(resamples);
+    for _ in range(0, resamples) {
+        for samp in resample.iter_mut() {
+            *samp = samples[rng.gen_range(0, samples.len())];
+        }
+        medians.push(resample.as_slice().median());
+    }
+    local_sort(medians.as_mut_slice());
+    let hundred: T = FromPrimitive::from_uint(100).unwrap();
+    let two: T = FromPrimitive::from_uint(2).unwrap();
+    let tail = (hundred - confidence) / two;
+    (percentile_of_sorted(medians.as_slice(), tail),
+     percentile_of_sorted(medians.as_slice(), hundred - tail))
+}
+
+/// The number of samples in a sample set which lie outside Tukey's fences: mild outliers are
+/// more than 1.5 times the inter-quartile range beyond the 1st or 3rd quartile, and severe
+/// outliers more than 3 times.
+///
+/// See: http://en.wikipedia.org/wiki/Outlier#Tukey.27s_test
+#[deriving(Copy, Clone, PartialEq, Show)]
+pub struct Outliers {
+    pub low_severe: uint,
+    pub low_mild: uint,
+    pub high_mild: uint,
+    pub high_severe: uint,
+}
+
+impl Outliers {
+    /// Classify the outliers of a sample set.
+    pub fn new<T: FloatMath + FromPrimitive>(samples: &[T]) -> Outliers {
+        let (q1, _, q3) = samples.quartiles();
+        let iqr = q3 - q1;
+        let mild = iqr * FromPrimitive::from_f64(1.5).unwrap();
+        let severe = iqr * FromPrimitive::from_f64(3.0).unwrap();
+        let mut outliers = Outliers {
+            low_severe: 0,
+            low_mild: 0,
+            high_mild: 0,
+            high_severe: 0,
+        };
+        for &samp in samples.iter() {
+            if samp < q1 - severe {
+                outliers.low_severe += 1;
+            } else if samp < q1 - mild {
+                outliers.low_mild += 1;
+            } else if samp > q3 + severe {
+                outliers.high_severe += 1;
+            } else if samp > q3 + mild {
+                outliers.high_mild += 1;
+            }
+        }
+        outliers
+    }
+
+    /// The total number of outliers, mild or severe.
+    pub fn total(&self) -> uint {
+        self.low_severe + self.low_mild + self.high_mild + self.high_severe
+    }
+}
+
 /// Render writes the min, max and quartiles of the provided `Summary` to the provided `Writer`.
 pub fn write_5_number_summary<W: Writer, T: Float + Show>(w: &mut W,
                                                           s: &Summary<T>) -> io::IoResult<()> {
@@ -455,6 +530,7 @@ pub fn freq_count<T: Iterator<U>, U: Eq+Hash>(mut iter: T) -> hash_map::HashMap<
 mod tests {
     use stats::Stats;
     use stats::Summary;
+    use stats::{Outliers, bootstrap_median};
     use stats::write_5_number_summary;
     use stats::write_boxplot;
     use std::io;
@@ -1043,6 +1119,31 @@ mod tests {
 
     }
     #[test]
+    fn test_outliers() {
+        let samples = [-40.0f64, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 20.0, 100.0];
+        let outliers = Outliers::new(&samples);
+        assert_eq!(outliers, Outliers {
+            low_severe: 1,
+            low_mild: 0,
+            high_mild: 1,
+            high_severe: 1,
+        });
+        assert_eq!(outliers.total(), 3);
+        assert_eq!(Outliers::new(&[1.0f64, 2.0, 3.0]).total(), 0);
+    }
+    #[test]
+    fn test_bootstrap_median() {
+        let samples = Vec::from_fn(100, |i| i as f64);
+        let (lo, hi) = bootstrap_median(samples.as_slice(), 1000, 95.0);
+        assert!(lo < 49.5 && 49.5 < hi);
+        assert!(hi - lo < 30.0);
+        // The resampling is deterministic.
+        assert_eq!(bootstrap_median(samples.as_slice(), 1000, 95.0), (lo, hi));
+
+        let (lo, hi) = bootstrap_median(&[3.0f64, 3.0, 3.0], 100, 95.0);
+        assert_eq!((lo, hi), (3.0, 3.0));
+    }
+    #[test]
     fn test_sum_f64s() {
         assert_eq!([0.5f64, 3.2321f64, 1.5678f64].sum(), 5.2999);
     }
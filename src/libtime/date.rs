@@ -0,0 +1,898 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Calendar dates and date-times with arithmetic.
+//!
+//! `Date` is a day in the proleptic Gregorian calendar and `DateTime` an
+//! instant written as a date and wall clock time at a fixed offset from UTC.
+//! Unlike `Tm`, both are always valid and normalized, and support calendar
+//! arithmetic (`add_days`, `add_months`) as well as `Duration` arithmetic.
+//!
+//! `DateTime` reads and writes the RFC 3339 and RFC 2822 formats strictly:
+//!
+//! ```rust
+//! use time::DateTime;
+//! use std::time::Duration;
+//!
+//! let dt = DateTime::parse_rfc3339("2014-12-31T23:30:00-05:00").unwrap();
+//! let later = dt + Duration::hours(1);
+//! assert_eq!(later.to_rfc3339(), "2015-01-01T00:30:00-05:00");
+//! assert_eq!(later.to_rfc2822(), "Thu, 01 Jan 2015 00:30:00 -0500");
+//! ```
+
+use std::ascii::AsciiExt;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::time::Duration;
+
+use {Timespec, Tm, ParseError, NSEC_PER_SEC};
+use {InvalidDayOfMonth, InvalidDayOfWeek, InvalidHour, InvalidMinute,
+     InvalidMonth, InvalidSecond, InvalidTime, InvalidYear, InvalidZoneOffset,
+     UnexpectedCharacter};
+use {civil_from_days, days_from_civil, div_floor, is_leap_year, mod_floor};
+
+static SECS_PER_DAY: i64 = 86400;
+
+static MONTH_NAMES: [&'static str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// A day of the week.
+#[deriving(Clone, Copy, PartialEq, Eq, Show)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+static WEEKDAYS: [Weekday; 7] = [
+    Weekday::Monday, Weekday::Tuesday, Weekday::Wednesday, Weekday::Thursday,
+    Weekday::Friday, Weekday::Saturday, Weekday::Sunday,
+];
+
+static WEEKDAY_NAMES: [&'static str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+impl Weekday {
+    /// The ISO 8601 number of the day: 1 for Monday to 7 for Sunday.
+    pub fn number_from_monday(&self) -> u32 {
+        *self as u32 + 1
+    }
+
+    /// The following day.
+    pub fn succ(&self) -> Weekday {
+        WEEKDAYS[(*self as uint + 1) % 7]
+    }
+
+    /// The preceding day.
+    pub fn pred(&self) -> Weekday {
+        WEEKDAYS[(*self as uint + 6) % 7]
+    }
+
+    /// The three letter English abbreviation of the day, like "Mon".
+    pub fn abbreviation(&self) -> &'static str {
+        WEEKDAY_NAMES[*self as uint]
+    }
+}
+
+/// A date in the proleptic Gregorian calendar.
+#[deriving(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// The date `year`-`month`-`day`, with `month` from 1 to 12, or `None`
+    /// if there is no such day.
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Date> {
+        if month < 1 || month > 12 || day < 1 || day > days_in_month(year as i64, month) {
+            return None;
+        }
+        Some(Date { year: year, month: month, day: day })
+    }
+
+    /// The `ordinal`th day of `year`, counting January 1 as 1.
+    pub fn from_yo(year: i32, ordinal: u32) -> Option<Date> {
+        let len = if is_leap_year(year as i64) { 366 } else { 365 };
+        if ordinal < 1 || ordinal > len {
+            return None;
+        }
+        let days = days_from_civil(year as i64, 1, 1) + ordinal as i64 - 1;
+        Some(Date::from_days_since_epoch(days))
+    }
+
+    /// The given day of the given ISO 8601 week of `year`.
+    pub fn from_isoywd(year: i32, week: u32, weekday: Weekday) -> Option<Date> {
+        if week < 1 || week > iso_weeks_in_year(year as i64) {
+            return None;
+        }
+        // January 4 is always in week 1.
+        let jan4 = days_from_civil(year as i64, 1, 4);
+        let monday = jan4 - weekday_from_days(jan4) as i64;
+        let days = monday + (week as i64 - 1) * 7 + weekday as i64;
+        Some(Date::from_days_since_epoch(days))
+    }
+
+    /// The date `days` days after 1970-01-01.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the year doesn't fit in an `i32`.
+    pub fn from_days_since_epoch(days: i64) -> Date {
+        let (year, month, day) = civil_from_days(days);
+        assert!(year >= ::std::i32::MIN as i64 && year <= ::std::i32::MAX as i64,
+                "date out of range");
+        Date { year: year as i32, month: month as u32, day: day as u32 }
+    }
+
+    /// The number of days from 1970-01-01 to this date.
+    pub fn days_since_epoch(&self) -> i64 {
+        days_from_civil(self.year as i64, self.month as i64, self.day as i64)
+    }
+
+    pub fn year(&self) -> i32 { self.year }
+
+    /// The month, from 1 to 12.
+    pub fn month(&self) -> u32 { self.month }
+
+    /// The day of the month, from 1.
+    pub fn day(&self) -> u32 { self.day }
+
+    /// The day of the year, counting January 1 as 1.
+    pub fn ordinal(&self) -> u32 {
+        (self.days_since_epoch() - days_from_civil(self.year as i64, 1, 1)) as u32 + 1
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        WEEKDAYS[weekday_from_days(self.days_since_epoch())]
+    }
+
+    /// The ISO 8601 week-numbering year and week of this date. The first
+    /// week of a year is the one containing its first Thursday, so the few
+    /// days around New Year can belong to a week of the neighbouring year.
+    pub fn iso_week(&self) -> (i32, u32) {
+        let weekday = self.weekday().number_from_monday();
+        let week = (self.ordinal() as i64 - weekday as i64 + 10) / 7;
+        let year = self.year as i64;
+        if week < 1 {
+            (self.year - 1, iso_weeks_in_year(year - 1))
+        } else if week as u32 > iso_weeks_in_year(year) {
+            (self.year + 1, 1)
+        } else {
+            (self.year, week as u32)
+        }
+    }
+
+    pub fn is_leap_year(&self) -> bool {
+        is_leap_year(self.year as i64)
+    }
+
+    /// The number of days in this date's month.
+    pub fn days_in_month(&self) -> u32 {
+        days_in_month(self.year as i64, self.month)
+    }
+
+    /// The date `days` days later (or earlier, for negative `days`).
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days_since_epoch(self.days_since_epoch() + days)
+    }
+
+    /// The same day `months` months later (or earlier). If that month is too
+    /// short the result is its last day, so a month after January 31 is the
+    /// last day of February.
+    pub fn add_months(&self, months: i32) -> Date {
+        let month0 = self.month as i64 - 1 + months as i64;
+        let year = self.year as i64 + div_floor(month0, 12);
+        let month = mod_floor(month0, 12) as u32 + 1;
+        assert!(year >= ::std::i32::MIN as i64 && year <= ::std::i32::MAX as i64,
+                "date out of range");
+        let day = ::std::cmp::min(self.day, days_in_month(year, month));
+        Date { year: year as i32, month: month, day: day }
+    }
+
+    /// The same day `years` years later (or earlier); February 29 becomes
+    /// February 28 in a common year.
+    pub fn add_years(&self, years: i32) -> Date {
+        self.add_months(years * 12)
+    }
+
+    /// This date at the given time of day and offset from UTC.
+    pub fn and_time(&self, hour: u32, minute: u32, second: u32, nanosecond: u32,
+                    offset: i32) -> Option<DateTime> {
+        DateTime::new(*self, hour, minute, second, nanosecond, offset)
+    }
+}
+
+/// Formats as ISO 8601, like `2014-12-31`.
+impl fmt::Show for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.year >= 0 && self.year <= 9999 {
+            write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+        } else {
+            // ISO 8601 requires a sign on expanded years.
+            write!(f, "{:+05}-{:02}-{:02}", self.year, self.month, self.day)
+        }
+    }
+}
+
+/// Adds the whole days of the duration.
+impl Add<Duration, Date> for Date {
+    fn add(self, other: Duration) -> Date {
+        self.add_days(other.num_days())
+    }
+}
+
+/// Subtracts the whole days of the duration.
+impl Sub<Duration, Date> for Date {
+    fn sub(self, other: Duration) -> Date {
+        self.add_days(-other.num_days())
+    }
+}
+
+impl Sub<Date, Duration> for Date {
+    fn sub(self, other: Date) -> Duration {
+        Duration::days(self.days_since_epoch() - other.days_since_epoch())
+    }
+}
+
+/// An instant written as a date and time of day at a fixed offset from UTC,
+/// like `2014-12-31T18:30:00-05:00`.
+///
+/// Date-times compare by the instant they denote, so the same instant at two
+/// different offsets compares equal.
+#[deriving(Clone, Copy)]
+pub struct DateTime {
+    date: Date,
+    hour: u32,
+    minute: u32,
+    second: u32,
+    nanosecond: u32,
+    offset: i32,
+}
+
+impl DateTime {
+    /// A date-time from its parts, or `None` if they are out of range.
+    /// `second` may be 60 to represent a leap second, which is otherwise
+    /// treated as the first second of the next minute. `offset` is in
+    /// seconds east of UTC and must be less than a day.
+    pub fn new(date: Date, hour: u32, minute: u32, second: u32, nanosecond: u32,
+               offset: i32) -> Option<DateTime> {
+        if hour > 23 || minute > 59 || second > 60 || nanosecond >= NSEC_PER_SEC as u32 ||
+           offset <= -SECS_PER_DAY as i32 || offset >= SECS_PER_DAY as i32 {
+            return None;
+        }
+        Some(DateTime {
+            date: date,
+            hour: hour,
+            minute: minute,
+            second: second,
+            nanosecond: nanosecond,
+            offset: offset,
+        })
+    }
+
+    /// The instant `clock` as seen at `offset` seconds east of UTC.
+    pub fn from_timespec(clock: Timespec, offset: i32) -> DateTime {
+        assert!(offset > -SECS_PER_DAY as i32 && offset < SECS_PER_DAY as i32,
+                "offset out of range");
+        let local = clock.sec + offset as i64;
+        let secs = mod_floor(local, SECS_PER_DAY) as u32;
+        DateTime {
+            date: Date::from_days_since_epoch(div_floor(local, SECS_PER_DAY)),
+            hour: secs / 3600,
+            minute: secs / 60 % 60,
+            second: secs % 60,
+            nanosecond: clock.nsec as u32,
+            offset: offset,
+        }
+    }
+
+    /// The date-time `tm` describes, at its `tm_gmtoff`. Out of range fields
+    /// carry into the next larger unit, as with `Tm::to_timespec_at_offset`.
+    pub fn from_tm(tm: &Tm) -> DateTime {
+        DateTime::from_timespec(tm.to_timespec_at_offset(), tm.tm_gmtoff)
+    }
+
+    pub fn to_timespec(&self) -> Timespec {
+        let sec = self.date.days_since_epoch() * SECS_PER_DAY + self.hour as i64 * 3600 +
+                  self.minute as i64 * 60 + self.second as i64 - self.offset as i64;
+        Timespec::new(sec, self.nanosecond as i32)
+    }
+
+    /// The broken-down time, with `tm_isdst` set to 0.
+    pub fn to_tm(&self) -> Tm {
+        Tm {
+            tm_sec: self.second as i32,
+            tm_min: self.minute as i32,
+            tm_hour: self.hour as i32,
+            tm_mday: self.date.day as i32,
+            tm_mon: self.date.month as i32 - 1,
+            tm_year: self.date.year - 1900,
+            tm_wday: (self.date.weekday().number_from_monday() % 7) as i32,
+            tm_yday: self.date.ordinal() as i32 - 1,
+            tm_isdst: 0,
+            tm_gmtoff: self.offset,
+            tm_nsec: self.nanosecond as i32,
+        }
+    }
+
+    pub fn date(&self) -> Date { self.date }
+    pub fn hour(&self) -> u32 { self.hour }
+    pub fn minute(&self) -> u32 { self.minute }
+    pub fn second(&self) -> u32 { self.second }
+    pub fn nanosecond(&self) -> u32 { self.nanosecond }
+
+    /// The offset from UTC in seconds, positive east of Greenwich.
+    pub fn offset(&self) -> i32 { self.offset }
+
+    /// The same instant at another offset.
+    pub fn with_offset(&self, offset: i32) -> DateTime {
+        DateTime::from_timespec(self.to_timespec(), offset)
+    }
+
+    /// The same wall clock time `days` days later (or earlier).
+    pub fn add_days(&self, days: i64) -> DateTime {
+        DateTime { date: self.date.add_days(days), ..*self }
+    }
+
+    /// The same wall clock time `months` months later (or earlier), clamping
+    /// the day as `Date::add_months` does.
+    pub fn add_months(&self, months: i32) -> DateTime {
+        DateTime { date: self.date.add_months(months), ..*self }
+    }
+
+    /// Parses an RFC 3339 timestamp, like `1985-04-12T23:20:50.52Z` or
+    /// `1996-12-19T16:39:57-08:00`. The `T` and `Z` may be lower case, and
+    /// fractions of a second beyond nanoseconds are truncated. An offset of
+    /// `-00:00` is read as UTC.
+    pub fn parse_rfc3339(s: &str) -> Result<DateTime, ParseError> {
+        let mut p = Parser { s: s.as_bytes(), pos: 0 };
+        let year = try!(p.digits(4, 4, InvalidYear)) as i32;
+        try!(p.expect('-'));
+        let month = try!(p.digits(2, 2, InvalidMonth));
+        try!(p.expect('-'));
+        let day = try!(p.digits(2, 2, InvalidDayOfMonth));
+        let date = match Date::from_ymd(year, month, day) {
+            Some(date) => date,
+            None if month < 1 || month > 12 => return Err(InvalidMonth),
+            None => return Err(InvalidDayOfMonth),
+        };
+        if !p.eat('T') && !p.eat('t') {
+            return Err(p.unexpected('T'));
+        }
+        let hour = try!(p.digits(2, 2, InvalidHour));
+        try!(p.expect(':'));
+        let minute = try!(p.digits(2, 2, InvalidMinute));
+        try!(p.expect(':'));
+        let second = try!(p.digits(2, 2, InvalidSecond));
+        let nanosecond = if p.eat('.') { try!(p.fraction()) } else { 0 };
+
+        let offset = if p.eat('Z') || p.eat('z') {
+            0
+        } else {
+            let sign = match p.peek() {
+                Some(b'+') => 1,
+                Some(b'-') => -1,
+                _ => return Err(p.unexpected('Z')),
+            };
+            p.pos += 1;
+            let hours = try!(p.digits(2, 2, InvalidZoneOffset));
+            try!(p.expect(':'));
+            let minutes = try!(p.digits(2, 2, InvalidZoneOffset));
+            if hours > 23 || minutes > 59 {
+                return Err(InvalidZoneOffset);
+            }
+            sign * (hours * 3600 + minutes * 60) as i32
+        };
+        if !p.at_end() {
+            return Err(InvalidTime);
+        }
+        DateTime::checked(date, hour, minute, second, nanosecond, offset)
+    }
+
+    /// Parses an RFC 2822 date-time, like `Fri, 21 Nov 1997 09:55:06 -0600`.
+    /// The day of the week is optional but must match the date if given,
+    /// and seconds are optional. Besides numeric offsets the obsolete zones
+    /// `UT`, `GMT` and the US zones (`EST`, `PDT`, ...) are accepted; the
+    /// other obsolete forms, like two digit years and comments, are not.
+    pub fn parse_rfc2822(s: &str) -> Result<DateTime, ParseError> {
+        let mut p = Parser { s: s.as_bytes(), pos: 0 };
+        p.skip_space();
+        let weekday = match p.peek() {
+            Some(c) if is_ascii_letter(c) => {
+                let name = p.word();
+                match WEEKDAYS.iter().position(|d| d.abbreviation().eq_ignore_ascii_case(name)) {
+                    Some(i) => {
+                        p.skip_space();
+                        try!(p.expect(','));
+                        Some(WEEKDAYS[i])
+                    }
+                    None => return Err(InvalidDayOfWeek),
+                }
+            }
+            _ => None,
+        };
+        p.skip_space();
+        let day = try!(p.digits(1, 2, InvalidDayOfMonth));
+        try!(p.space());
+        let name = p.word();
+        let month = match MONTH_NAMES.iter().position(|m| m.eq_ignore_ascii_case(name)) {
+            Some(i) => i as u32 + 1,
+            None => return Err(InvalidMonth),
+        };
+        try!(p.space());
+        let year = try!(p.digits(4, 9, InvalidYear)) as i32;
+        let date = match Date::from_ymd(year, month, day) {
+            Some(date) => date,
+            None => return Err(InvalidDayOfMonth),
+        };
+        if weekday.map_or(false, |w| w != date.weekday()) {
+            return Err(InvalidDayOfWeek);
+        }
+
+        try!(p.space());
+        let hour = try!(p.digits(2, 2, InvalidHour));
+        try!(p.expect(':'));
+        let minute = try!(p.digits(2, 2, InvalidMinute));
+        let second = if p.eat(':') { try!(p.digits(2, 2, InvalidSecond)) } else { 0 };
+
+        try!(p.space());
+        let offset = match p.peek() {
+            Some(c) if c == b'+' || c == b'-' => {
+                p.pos += 1;
+                let hhmm = try!(p.digits(4, 4, InvalidZoneOffset));
+                if hhmm / 100 > 23 || hhmm % 100 > 59 {
+                    return Err(InvalidZoneOffset);
+                }
+                let offset = (hhmm / 100 * 3600 + hhmm % 100 * 60) as i32;
+                if c == b'-' { -offset } else { offset }
+            }
+            _ => match obsolete_zone_offset(p.word()) {
+                Some(offset) => offset,
+                None => return Err(InvalidZoneOffset),
+            },
+        };
+        p.skip_space();
+        if !p.at_end() {
+            return Err(InvalidTime);
+        }
+        DateTime::checked(date, hour, minute, second, 0, offset)
+    }
+
+    /// Formats as RFC 3339, like `2014-12-31T18:30:00.25-05:00`. The fraction
+    /// of a second is left out when it is zero, and seconds of the offset
+    /// are dropped.
+    pub fn to_rfc3339(&self) -> String {
+        let mut s = format!("{}T{:02}:{:02}:{:02}", self.date, self.hour, self.minute,
+                            self.second);
+        if self.nanosecond != 0 {
+            let frac = format!("{:09}", self.nanosecond);
+            s.push('.');
+            s.push_str(frac[].trim_right_matches('0'));
+        }
+        if self.offset == 0 {
+            s.push('Z');
+        } else {
+            let (sign, minutes) = if self.offset < 0 {
+                ('-', -self.offset / 60)
+            } else {
+                ('+', self.offset / 60)
+            };
+            s.push_str(format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)[]);
+        }
+        s
+    }
+
+    /// Formats as RFC 2822, like `Wed, 31 Dec 2014 18:30:00 -0500`. Fractions
+    /// of a second and seconds of the offset are dropped.
+    pub fn to_rfc2822(&self) -> String {
+        let (sign, minutes) = if self.offset < 0 {
+            ('-', -self.offset / 60)
+        } else {
+            ('+', self.offset / 60)
+        };
+        format!("{}, {:02} {} {:04} {:02}:{:02}:{:02} {}{:02}{:02}",
+                self.date.weekday().abbreviation(), self.date.day,
+                MONTH_NAMES[self.date.month as uint - 1], self.date.year,
+                self.hour, self.minute, self.second, sign, minutes / 60, minutes % 60)
+    }
+
+    fn checked(date: Date, hour: u32, minute: u32, second: u32, nanosecond: u32,
+               offset: i32) -> Result<DateTime, ParseError> {
+        if hour > 23 {
+            return Err(InvalidHour);
+        }
+        if minute > 59 {
+            return Err(InvalidMinute);
+        }
+        if second > 60 {
+            return Err(InvalidSecond);
+        }
+        match DateTime::new(date, hour, minute, second, nanosecond, offset) {
+            Some(dt) => Ok(dt),
+            None => Err(InvalidTime),
+        }
+    }
+}
+
+impl PartialEq for DateTime {
+    fn eq(&self, other: &DateTime) -> bool {
+        self.to_timespec() == other.to_timespec()
+    }
+}
+
+impl Eq for DateTime {}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &DateTime) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    fn cmp(&self, other: &DateTime) -> Ordering {
+        self.to_timespec().cmp(&other.to_timespec())
+    }
+}
+
+/// Formats as RFC 3339.
+impl fmt::Show for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.to_rfc3339().fmt(f)
+    }
+}
+
+impl Add<Duration, DateTime> for DateTime {
+    fn add(self, other: Duration) -> DateTime {
+        DateTime::from_timespec(self.to_timespec() + other, self.offset)
+    }
+}
+
+impl Sub<Duration, DateTime> for DateTime {
+    fn sub(self, other: Duration) -> DateTime {
+        DateTime::from_timespec(self.to_timespec() + -other, self.offset)
+    }
+}
+
+impl Sub<DateTime, Duration> for DateTime {
+    fn sub(self, other: DateTime) -> Duration {
+        self.to_timespec() - other.to_timespec()
+    }
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 => if is_leap_year(year) { 29 } else { 28 },
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The day of the week `days` days after 1970-01-01, 0 being Monday.
+fn weekday_from_days(days: i64) -> uint {
+    // 1970-01-01 was a Thursday.
+    mod_floor(days + 3, 7) as uint
+}
+
+/// 52, or 53 for years starting on a Thursday and leap years starting on a
+/// Wednesday.
+fn iso_weeks_in_year(year: i64) -> u32 {
+    let jan1 = weekday_from_days(days_from_civil(year, 1, 1));
+    if jan1 == 3 || (jan1 == 2 && is_leap_year(year)) { 53 } else { 52 }
+}
+
+/// The offsets of the obsolete RFC 2822 zone names.
+fn obsolete_zone_offset(name: &str) -> Option<i32> {
+    let hours = match name {
+        "UT" | "GMT" => 0,
+        "EDT" => -4,
+        "EST" | "CDT" => -5,
+        "CST" | "MDT" => -6,
+        "MST" | "PDT" => -7,
+        "PST" => -8,
+        _ => return None,
+    };
+    Some(hours * 3600)
+}
+
+fn is_ascii_letter(c: u8) -> bool {
+    (c >= b'a' && c <= b'z') || (c >= b'A' && c <= b'Z')
+}
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: uint,
+}
+
+impl<'a> Parser<'a> {
+    fn at_end(&self) -> bool {
+        self.pos == self.s.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        if self.at_end() { None } else { Some(self.s[self.pos]) }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c as u8) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The error for finding something other than `expected`.
+    fn unexpected(&self, expected: char) -> ParseError {
+        match self.peek() {
+            Some(c) => UnexpectedCharacter(expected, c as char),
+            None => InvalidTime,
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        if self.eat(c) { Ok(()) } else { Err(self.unexpected(c)) }
+    }
+
+    /// A number of `min` to `max` digits.
+    fn digits(&mut self, min: uint, max: uint, err: ParseError) -> Result<u32, ParseError> {
+        let start = self.pos;
+        let mut n = 0u32;
+        while self.pos - start < max {
+            match self.peek() {
+                Some(c) if c >= b'0' && c <= b'9' => {
+                    n = n * 10 + (c - b'0') as u32;
+                    self.pos += 1;
+                }
+                _ => break,
+            }
+        }
+        if self.pos - start < min { Err(err) } else { Ok(n) }
+    }
+
+    /// The digits after a decimal point, as nanoseconds.
+    fn fraction(&mut self) -> Result<u32, ParseError> {
+        let start = self.pos;
+        let mut nanos = 0u32;
+        let mut scale = NSEC_PER_SEC as u32;
+        loop {
+            match self.peek() {
+                Some(c) if c >= b'0' && c <= b'9' => {
+                    scale /= 10;
+                    nanos += (c - b'0') as u32 * scale;
+                    self.pos += 1;
+                }
+                _ => break,
+            }
+        }
+        if self.pos == start { Err(InvalidSecond) } else { Ok(nanos) }
+    }
+
+    /// A run of ASCII letters, which may be empty.
+    fn word(&mut self) -> &'a str {
+        let start = self.pos;
+        while self.peek().map_or(false, is_ascii_letter) {
+            self.pos += 1;
+        }
+        // Only ASCII letters were consumed.
+        unsafe { ::std::str::from_utf8_unchecked(self.s[start..self.pos]) }
+    }
+
+    fn skip_space(&mut self) {
+        while self.peek().map_or(false, |c| c == b' ' || c == b'\t') {
+            self.pos += 1;
+        }
+    }
+
+    /// Folding white space: at least one space or tab.
+    fn space(&mut self) -> Result<(), ParseError> {
+        if self.peek().map_or(false, |c| c == b' ' || c == b'\t') {
+            self.skip_space();
+            Ok(())
+        } else {
+            Err(self.unexpected(' '))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Date, DateTime, Weekday};
+    use {Timespec, at_utc, civil_from_days, days_from_civil};
+    use {InvalidDayOfMonth, InvalidDayOfWeek, InvalidHour, InvalidMonth, InvalidSecond,
+         InvalidTime, InvalidYear, InvalidZoneOffset, UnexpectedCharacter};
+    use std::time::Duration;
+
+    fn ymd(year: i32, month: u32, day: u32) -> Date {
+        Date::from_ymd(year, month, day).unwrap()
+    }
+
+    fn rfc3339(s: &str) -> DateTime {
+        DateTime::parse_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_civil_days() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        for &days in [-1000000i64, -719468, -1, 0, 59, 10000, 2932896].iter() {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+    }
+
+    #[test]
+    fn test_date_validation() {
+        assert!(Date::from_ymd(2014, 2, 29).is_none());
+        assert!(Date::from_ymd(2012, 2, 29).is_some());
+        assert!(Date::from_ymd(1900, 2, 29).is_none());
+        assert!(Date::from_ymd(2000, 2, 29).is_some());
+        assert!(Date::from_ymd(2014, 13, 1).is_none());
+        assert!(Date::from_ymd(2014, 4, 31).is_none());
+        assert!(Date::from_ymd(2014, 1, 0).is_none());
+        assert_eq!(Date::from_yo(2012, 366), Some(ymd(2012, 12, 31)));
+        assert!(Date::from_yo(2014, 366).is_none());
+    }
+
+    #[test]
+    fn test_date_fields() {
+        let d = ymd(2014, 12, 31);
+        assert_eq!(d.ordinal(), 365);
+        assert_eq!(d.weekday(), Weekday::Wednesday);
+        assert_eq!(d.days_in_month(), 31);
+        assert_eq!(ymd(1970, 1, 1).weekday(), Weekday::Thursday);
+        assert_eq!(ymd(1969, 12, 28).weekday(), Weekday::Sunday);
+        assert_eq!(Weekday::Sunday.succ(), Weekday::Monday);
+        assert_eq!(Weekday::Monday.pred(), Weekday::Sunday);
+        assert_eq!(Date::from_days_since_epoch(d.days_since_epoch()), d);
+        assert_eq!(d.to_string(), "2014-12-31");
+        assert_eq!(ymd(-1, 1, 1).to_string(), "-0001-01-01");
+    }
+
+    #[test]
+    fn test_iso_week() {
+        // Cases from the ISO 8601 article on Wikipedia.
+        assert_eq!(ymd(2005, 1, 1).iso_week(), (2004, 53));
+        assert_eq!(ymd(2005, 1, 2).iso_week(), (2004, 53));
+        assert_eq!(ymd(2005, 12, 31).iso_week(), (2005, 52));
+        assert_eq!(ymd(2007, 1, 1).iso_week(), (2007, 1));
+        assert_eq!(ymd(2007, 12, 30).iso_week(), (2007, 52));
+        assert_eq!(ymd(2007, 12, 31).iso_week(), (2008, 1));
+        assert_eq!(ymd(2008, 12, 29).iso_week(), (2009, 1));
+        assert_eq!(ymd(2009, 12, 31).iso_week(), (2009, 53));
+        assert_eq!(ymd(2010, 1, 3).iso_week(), (2009, 53));
+
+        assert_eq!(Date::from_isoywd(2009, 53, Weekday::Sunday), Some(ymd(2010, 1, 3)));
+        assert_eq!(Date::from_isoywd(2009, 1, Weekday::Monday), Some(ymd(2008, 12, 29)));
+        assert_eq!(Date::from_isoywd(2008, 53, Weekday::Monday), None);
+    }
+
+    #[test]
+    fn test_date_arithmetic() {
+        let d = ymd(2014, 1, 31);
+        assert_eq!(d.add_days(1), ymd(2014, 2, 1));
+        assert_eq!(d.add_days(-31), ymd(2013, 12, 31));
+        assert_eq!(d.add_months(1), ymd(2014, 2, 28));
+        assert_eq!(d.add_months(-2), ymd(2013, 11, 30));
+        assert_eq!(d.add_months(25), ymd(2016, 2, 29));
+        assert_eq!(ymd(2012, 2, 29).add_years(1), ymd(2013, 2, 28));
+        assert_eq!(d + Duration::days(365), ymd(2015, 1, 31));
+        assert_eq!(d - Duration::weeks(1), ymd(2014, 1, 24));
+        assert_eq!(ymd(2015, 1, 1) - ymd(2014, 1, 1), Duration::days(365));
+        assert!(ymd(2014, 1, 1) < ymd(2014, 1, 2));
+    }
+
+    #[test]
+    fn test_datetime_timespec() {
+        let clock = Timespec::new(1234567890, 54321);
+        let dt = DateTime::from_timespec(clock, -8 * 3600);
+        assert_eq!(dt.date(), ymd(2009, 2, 13));
+        assert_eq!((dt.hour(), dt.minute(), dt.second()), (15, 31, 30));
+        assert_eq!(dt.to_timespec(), clock);
+
+        let tm = dt.to_tm();
+        assert_eq!(tm.to_timespec_at_offset(), clock);
+        assert_eq!(tm.tm_wday, 5);
+        assert_eq!(tm.tm_yday, 43);
+        assert_eq!(DateTime::from_tm(&tm), dt);
+        assert_eq!(DateTime::from_tm(&at_utc(clock)).with_offset(-8 * 3600).hour(), 15);
+
+        let before_epoch = DateTime::from_timespec(Timespec::new(-1, 0), 0);
+        assert_eq!(before_epoch.to_rfc3339(), "1969-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn test_datetime_arithmetic() {
+        let dt = rfc3339("2014-12-31T23:30:00-05:00");
+        assert_eq!((dt + Duration::hours(1)).to_rfc3339(), "2015-01-01T00:30:00-05:00");
+        assert_eq!((dt - Duration::milliseconds(1500)).to_rfc3339(),
+                   "2014-12-31T23:29:58.5-05:00");
+        assert_eq!(rfc3339("2015-01-01T04:30:00Z") - dt, Duration::zero());
+        assert_eq!(dt.add_months(2).to_rfc3339(), "2015-02-28T23:30:00-05:00");
+        assert_eq!(dt.add_days(1).to_rfc3339(), "2015-01-01T23:30:00-05:00");
+
+        // The same instant at different offsets.
+        assert_eq!(dt, dt.with_offset(3600));
+        assert!(dt < rfc3339("2015-01-01T04:30:01Z"));
+    }
+
+    #[test]
+    fn test_rfc3339() {
+        let dt = rfc3339("1985-04-12T23:20:50.52Z");
+        assert_eq!(dt.nanosecond(), 520000000);
+        assert_eq!(dt.to_rfc3339(), "1985-04-12T23:20:50.52Z");
+
+        let dt = rfc3339("1996-12-19t16:39:57-08:00");
+        assert_eq!(dt.offset(), -8 * 3600);
+        assert_eq!(dt.to_timespec().sec, 851042397);
+        assert_eq!(dt.to_string(), "1996-12-19T16:39:57-08:00");
+
+        let leap = rfc3339("1990-12-31T23:59:60Z");
+        assert_eq!(leap.second(), 60);
+        assert_eq!(leap.to_timespec(), rfc3339("1991-01-01T00:00:00Z").to_timespec());
+
+        assert_eq!(rfc3339("1937-01-01T12:00:27.870000000123+00:20").nanosecond(), 870000000);
+        assert_eq!(rfc3339("2014-01-01T00:00:00-00:00").offset(), 0);
+
+        let cases = [
+            ("2014-13-01T00:00:00Z", InvalidMonth),
+            ("2014-02-29T00:00:00Z", InvalidDayOfMonth),
+            ("14-01-01T00:00:00Z", InvalidYear),
+            ("2014-01-01 00:00:00Z", UnexpectedCharacter('T', ' ')),
+            ("2014-01-01T24:00:00Z", InvalidHour),
+            ("2014-01-01T00:00:61Z", InvalidSecond),
+            ("2014-01-01T00:00:00.Z", InvalidSecond),
+            ("2014-01-01T00:00:00", InvalidTime),
+            ("2014-01-01T00:00:00+0100", UnexpectedCharacter(':', '0')),
+            ("2014-01-01T00:00:00+24:00", InvalidZoneOffset),
+            ("2014-01-01T00:00:00Z ", InvalidTime),
+        ];
+        for &(s, err) in cases.iter() {
+            assert_eq!(DateTime::parse_rfc3339(s), Err(err));
+        }
+    }
+
+    #[test]
+    fn test_rfc2822() {
+        let dt = DateTime::parse_rfc2822("Fri, 21 Nov 1997 09:55:06 -0600").unwrap();
+        assert_eq!(dt, rfc3339("1997-11-21T09:55:06-06:00"));
+        assert_eq!(dt.to_rfc2822(), "Fri, 21 Nov 1997 09:55:06 -0600");
+
+        let dt = DateTime::parse_rfc2822("1 jan 2015  00:30 +0000").unwrap();
+        assert_eq!(dt.to_rfc2822(), "Thu, 01 Jan 2015 00:30:00 +0000");
+        let dt = DateTime::parse_rfc2822("Thu, 13 Feb 1969 23:32:54 EST").unwrap();
+        assert_eq!(dt.offset(), -5 * 3600);
+
+        let cases = [
+            ("Thu, 21 Nov 1997 09:55:06 -0600", InvalidDayOfWeek),
+            ("Fry, 21 Nov 1997 09:55:06 -0600", InvalidDayOfWeek),
+            ("Fri, 21 Novem 1997 09:55:06 -0600", InvalidMonth),
+            ("Fri, 31 Nov 1997 09:55:06 -0600", InvalidDayOfMonth),
+            ("Fri, 21 Nov 97 09:55:06 -0600", InvalidYear),
+            ("Fri, 21 Nov 1997 25:55:06 -0600", InvalidHour),
+            ("Fri, 21 Nov 1997 09:55:06 A", InvalidZoneOffset),
+            ("Fri, 21 Nov 1997 09:55:06 -06:00", InvalidZoneOffset),
+            ("Fri, 21 Nov 1997 09:55:06 -0600 (CST)", InvalidTime),
+        ];
+        for &(s, err) in cases.iter() {
+            assert_eq!(DateTime::parse_rfc2822(s), Err(err));
+        }
+    }
+}
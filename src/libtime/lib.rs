@@ -22,7 +22,7 @@
        html_playground_url = "http://play.rust-lang.org/")]
 
 #![allow(unknown_features)]
-#![feature(phase, globs)]
+#![feature(phase, globs, macro_rules, slicing_syntax)]
 #![feature(old_orphan_check)]
 
 #[cfg(test)] #[phase(plugin, link)] extern crate log;
@@ -31,6 +31,7 @@ extern crate "serialize" as rustc_serialize;
 extern crate libc;
 
 pub use self::ParseError::*;
+pub use date::{Date, DateTime, Weekday};
 use self::Fmt::*;
 
 use std::fmt::{mod, Show};
@@ -38,6 +39,11 @@ use std::num::SignedInt;
 use std::ops::{Add, Sub};
 use std::time::Duration;
 
+use tz::TimeZone;
+
+pub mod date;
+pub mod tz;
+
 static NSEC_PER_SEC: i32 = 1_000_000_000_i32;
 
 mod rustrt {
@@ -128,6 +134,49 @@ impl Sub<Timespec, Duration> for Timespec {
     }
 }
 
+// Proleptic Gregorian calendar arithmetic, shared by the `date` and `tz`
+// modules. Days are counted from 1970-01-01; the conversions are exact for
+// any date whose day count fits in an i64.
+
+fn div_floor(a: i64, b: i64) -> i64 {
+    let d = a / b;
+    if (a % b != 0) && ((a < 0) != (b < 0)) { d - 1 } else { d }
+}
+
+fn mod_floor(a: i64, b: i64) -> i64 {
+    a - div_floor(a, b) * b
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Days since 1970-01-01 of the given date, with `month` in 1 to 12.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = div_floor(year, 400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * mod_floor(month + 9, 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// The (year, month, day) of the day `days` days after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = div_floor(days, 146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 -
+                       day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // Eras, and so the years above, start in March.
+    let march_based_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * march_based_month + 2) / 5 + 1;
+    let month = if march_based_month < 10 { march_based_month + 3 } else { march_based_month - 9 };
+    let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
 /// Returns the current time as a `timespec` containing the seconds and
 /// nanoseconds since 1970-01-01T00:00:00Z.
 pub fn get_time() -> Timespec {
@@ -345,6 +394,11 @@ impl Tm {
         at_utc(self.to_timespec())
     }
 
+    /// Convert time to the given time zone
+    pub fn to_zone(&self, zone: &TimeZone) -> Tm {
+        zone.at(self.to_timespec_at_offset())
+    }
+
     /// Returns a TmFmt that outputs according to the `asctime` format in ISO
     /// C, in the local timezone.
     ///
@@ -0,0 +1,858 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Time zones from the IANA time zone database.
+//!
+//! A `TimeZone` is loaded from a compiled zoneinfo (TZif) file, as found in
+//! `/usr/share/zoneinfo` on most Unix systems, or from a POSIX `TZ` string
+//! such as `EST5EDT,M3.2.0,M11.1.0`. It maps instants to the local time
+//! (offset, daylight saving flag and abbreviation) in effect in that zone,
+//! and back.
+//!
+//! ```rust,ignore
+//! use time::tz::TimeZone;
+//!
+//! let ny = TimeZone::named("America/New_York").unwrap();
+//! let tm = ny.at(time::get_time());
+//! println!("{} {}", tm.rfc3339(), ny.offset_at(time::get_time()).abbreviation);
+//! ```
+//!
+//! Leap second records in the file are ignored, so the `right/` variants of
+//! the zones will be off by the accumulated leap seconds.
+
+use std::fmt;
+use std::io::{File, IoError};
+use std::io::fs::PathExtensions;
+use std::os;
+
+use {Timespec, Tm, at_utc, get_time};
+use {days_from_civil, civil_from_days, div_floor, is_leap_year, mod_floor};
+
+/// The directories searched by `TimeZone::named` when `TZDIR` isn't set.
+static ZONEINFO_DIRS: &'static [&'static str] = &[
+    "/usr/share/zoneinfo",
+    "/usr/lib/zoneinfo",
+    "/usr/share/lib/zoneinfo",
+    "/etc/zoneinfo",
+];
+
+static SECS_PER_DAY: i64 = 86400;
+
+/// The rule used when a `TZ` string names a daylight saving zone but gives
+/// no rule for it: the US one, as in glibc.
+static DEFAULT_DST_RULE: &'static str = ",M3.2.0,M11.1.0";
+
+macro_rules! try_opt {
+    ($e:expr) => (match $e { Some(v) => v, None => return None })
+}
+
+/// An error loading a time zone.
+#[deriving(Clone, PartialEq)]
+pub enum TzError {
+    /// The zoneinfo file couldn't be read.
+    Io(IoError),
+    /// No zoneinfo file of that name exists.
+    UnknownZone(String),
+    /// The data doesn't start with the TZif magic number.
+    InvalidMagic,
+    /// The data ends in the middle of a record.
+    Truncated,
+    /// The data is well formed but inconsistent.
+    InvalidData(&'static str),
+    /// A POSIX `TZ` string, either given directly or as the footer of a
+    /// zoneinfo file, couldn't be parsed.
+    InvalidTzString(String),
+}
+
+impl fmt::Show for TzError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TzError::Io(ref err) => write!(f, "couldn't read time zone: {}", err),
+            TzError::UnknownZone(ref name) => write!(f, "unknown time zone: {}", name),
+            TzError::InvalidMagic => write!(f, "not a TZif file"),
+            TzError::Truncated => write!(f, "truncated TZif file"),
+            TzError::InvalidData(msg) => write!(f, "invalid TZif file: {}", msg),
+            TzError::InvalidTzString(ref s) => write!(f, "invalid TZ string: {}", s),
+        }
+    }
+}
+
+/// The local time in effect in a zone for some span of time.
+#[deriving(Clone, PartialEq, Show)]
+pub struct LocalTimeType {
+    /// Offset from UTC in seconds, positive east of Greenwich.
+    pub utoff: i32,
+    /// Whether this is daylight saving time.
+    pub is_dst: bool,
+    /// The abbreviation of the local time, like "EST" or "CEST".
+    pub abbreviation: String,
+}
+
+#[deriving(Clone, Copy, PartialEq, Show)]
+struct Transition {
+    /// The instant the transition happens, in seconds since the epoch.
+    at: i64,
+    /// Index of the local time type in effect from then on.
+    ty: uint,
+}
+
+/// A time zone: the history of a region's local time.
+#[deriving(Clone, PartialEq, Show)]
+pub struct TimeZone {
+    name: String,
+    transitions: Vec<Transition>,
+    types: Vec<LocalTimeType>,
+    /// Describes local time after the last transition.
+    rule: Option<PosixTz>,
+}
+
+impl TimeZone {
+    /// Coordinated Universal Time.
+    pub fn utc() -> TimeZone {
+        TimeZone {
+            name: "UTC".to_string(),
+            transitions: Vec::new(),
+            types: vec![LocalTimeType {
+                utoff: 0,
+                is_dst: false,
+                abbreviation: "UTC".to_string(),
+            }],
+            rule: None,
+        }
+    }
+
+    /// Loads the zone called `name`, like "Europe/Paris", from the system's
+    /// time zone database. The directory named by the `TZDIR` environment
+    /// variable is searched if it is set, and the usual zoneinfo
+    /// directories otherwise.
+    pub fn named(name: &str) -> Result<TimeZone, TzError> {
+        if name.is_empty() || name.starts_with("/") ||
+           name.split('/').any(|part| part.is_empty() || part == "..") {
+            return Err(TzError::UnknownZone(name.to_string()));
+        }
+
+        let dirs = match os::getenv("TZDIR") {
+            Some(dir) => vec![Path::new(dir)],
+            None => ZONEINFO_DIRS.iter().map(|dir| Path::new(*dir)).collect(),
+        };
+        for dir in dirs.iter() {
+            let path = dir.join(name);
+            if path.is_file() {
+                let data = try!(File::open(&path).read_to_end().map_err(TzError::Io));
+                return TimeZone::from_tzif(name, data[]);
+            }
+        }
+        Err(TzError::UnknownZone(name.to_string()))
+    }
+
+    /// Loads a zone from the zoneinfo file at `path`.
+    pub fn from_file(path: &Path) -> Result<TimeZone, TzError> {
+        let data = try!(File::open(path).read_to_end().map_err(TzError::Io));
+        TimeZone::from_tzif(path.display().to_string()[], data[])
+    }
+
+    /// Parses the contents of a zoneinfo file (RFC 8536). Versions 1 to 4 of
+    /// the format are supported; for version 2 and later the 64-bit data
+    /// and the footer's `TZ` string, which describes local time after the
+    /// last transition, are used.
+    pub fn from_tzif(name: &str, data: &[u8]) -> Result<TimeZone, TzError> {
+        let mut r = Reader { data: data, pos: 0 };
+        let header = try!(read_header(&mut r));
+        if header.version == 0 {
+            let mut zone = try!(read_body(&mut r, &header, 4));
+            zone.name = name.to_string();
+            return Ok(zone);
+        }
+
+        // Skip the 32-bit data, which is only there for old readers.
+        try!(r.take(header.body_len(4)));
+        let header = try!(read_header(&mut r));
+        let mut zone = try!(read_body(&mut r, &header, 8));
+        zone.name = name.to_string();
+
+        if try!(r.u8()) != b'\n' {
+            return Err(TzError::InvalidData("missing footer"));
+        }
+        let rest = r.data[r.pos..];
+        let footer = match rest.iter().position(|&b| b == b'\n') {
+            Some(end) => rest[..end],
+            None => return Err(TzError::Truncated),
+        };
+        if !footer.is_empty() {
+            let footer = String::from_utf8_lossy(footer).into_owned();
+            zone.rule = match PosixTz::parse(footer[]) {
+                Some(rule) => Some(rule),
+                None => return Err(TzError::InvalidTzString(footer)),
+            };
+        }
+        Ok(zone)
+    }
+
+    /// Builds a zone from a POSIX `TZ` string, like `CET-1CEST,M3.5.0,M10.5.0/3`
+    /// or `<+0530>-5:30`. If daylight saving time is named without a rule,
+    /// the US rule is used.
+    pub fn from_posix_tz(spec: &str) -> Result<TimeZone, TzError> {
+        let rule = match PosixTz::parse(spec) {
+            Some(rule) => rule,
+            None => return Err(TzError::InvalidTzString(spec.to_string())),
+        };
+        Ok(TimeZone {
+            name: spec.to_string(),
+            transitions: Vec::new(),
+            types: vec![rule.std.clone()],
+            rule: Some(rule),
+        })
+    }
+
+    /// The name the zone was loaded by.
+    pub fn name(&self) -> &str {
+        self.name[]
+    }
+
+    /// The local time type in effect in this zone at `clock`.
+    pub fn offset_at(&self, clock: Timespec) -> &LocalTimeType {
+        self.offset_at_secs(clock.sec)
+    }
+
+    /// Returns the specified time in this zone. `tm_gmtoff` and `tm_isdst`
+    /// are set from the local time type in effect.
+    pub fn at(&self, clock: Timespec) -> Tm {
+        let ty = self.offset_at(clock);
+        let mut tm = at_utc(Timespec::new(clock.sec + ty.utoff as i64, clock.nsec));
+        tm.tm_gmtoff = ty.utoff;
+        tm.tm_isdst = if ty.is_dst { 1 } else { 0 };
+        tm
+    }
+
+    /// Returns the current time in this zone.
+    pub fn now(&self) -> Tm {
+        self.at(get_time())
+    }
+
+    /// Converts a wall clock time in this zone to an instant, ignoring the
+    /// `tm_gmtoff`, `tm_wday` and `tm_yday` fields.
+    ///
+    /// A time which occurs twice, because clocks were turned back, is
+    /// resolved by `tm_isdst` when it is not negative, and to the earlier
+    /// instant otherwise. A time which never occurs, because clocks were
+    /// turned forward, is taken to be in the offset before the transition,
+    /// so that 02:30 on the day US clocks skip from 02:00 to 03:00 comes out
+    /// as 03:30 daylight time.
+    pub fn to_timespec(&self, tm: &Tm) -> Timespec {
+        let local = civil_seconds(tm);
+
+        // The offsets in effect a day either side bracket any transition
+        // near `local`; zones don't change twice in a day.
+        let before = self.offset_at_secs(local - SECS_PER_DAY);
+        let after = self.offset_at_secs(local + SECS_PER_DAY);
+        let mut candidates = Vec::new();
+        for ty in [before, after].iter() {
+            let t = local - ty.utoff as i64;
+            if self.offset_at_secs(t).utoff == ty.utoff && !candidates.contains(&t) {
+                candidates.push(t);
+            }
+        }
+
+        candidates.sort();
+        let sec = match candidates.len() {
+            0 => local - before.utoff as i64,
+            1 => candidates[0],
+            _ => {
+                let (earlier, later) = (candidates[0], candidates[1]);
+                let earlier_dst = self.offset_at_secs(earlier).is_dst;
+                if tm.tm_isdst >= 0 && earlier_dst != (tm.tm_isdst > 0) {
+                    later
+                } else {
+                    earlier
+                }
+            }
+        };
+        Timespec::new(sec, tm.tm_nsec)
+    }
+
+    fn offset_at_secs(&self, t: i64) -> &LocalTimeType {
+        let last = match self.transitions.last() {
+            Some(last) => last,
+            None => {
+                return match self.rule {
+                    Some(ref rule) => rule.offset_at(t),
+                    None => &self.types[0],
+                }
+            }
+        };
+        if t >= last.at {
+            return match self.rule {
+                Some(ref rule) => rule.offset_at(t),
+                None => &self.types[last.ty],
+            };
+        }
+        if t < self.transitions[0].at {
+            return &self.types[0];
+        }
+
+        // Find the last transition at or before `t`.
+        let (mut lo, mut hi) = (0u, self.transitions.len() - 1);
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if self.transitions[mid].at <= t { lo = mid } else { hi = mid }
+        }
+        &self.types[self.transitions[lo].ty]
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: uint,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: uint) -> Result<&'a [u8], TzError> {
+        if self.data.len() - self.pos < n {
+            return Err(TzError::Truncated);
+        }
+        let bytes = self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, TzError> {
+        Ok(try!(self.take(1))[0])
+    }
+
+    fn be(&mut self, n: uint) -> Result<u64, TzError> {
+        let bytes = try!(self.take(n));
+        Ok(bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64))
+    }
+
+    fn be_i32(&mut self) -> Result<i32, TzError> {
+        Ok(try!(self.be(4)) as u32 as i32)
+    }
+
+    fn be_u32(&mut self) -> Result<uint, TzError> {
+        Ok(try!(self.be(4)) as uint)
+    }
+
+    /// A transition time, in the 4 or 8 byte format of the data block.
+    fn time(&mut self, size: uint) -> Result<i64, TzError> {
+        if size == 4 {
+            Ok(try!(self.be_i32()) as i64)
+        } else {
+            Ok(try!(self.be(8)) as i64)
+        }
+    }
+}
+
+struct Header {
+    version: u8,
+    isutcnt: uint,
+    isstdcnt: uint,
+    leapcnt: uint,
+    timecnt: uint,
+    typecnt: uint,
+    charcnt: uint,
+}
+
+impl Header {
+    /// The length of the data block with `size`-byte times.
+    fn body_len(&self, size: uint) -> uint {
+        self.timecnt * size + self.timecnt + self.typecnt * 6 + self.charcnt +
+            self.leapcnt * (size + 4) + self.isstdcnt + self.isutcnt
+    }
+}
+
+fn read_header(r: &mut Reader) -> Result<Header, TzError> {
+    if r.data.len() - r.pos < 4 || r.data[r.pos..r.pos + 4] != b"TZif" {
+        return Err(TzError::InvalidMagic);
+    }
+    try!(r.take(4));
+    let version = match try!(r.u8()) {
+        0 => 0,
+        v @ b'2'...b'9' => v - b'0',
+        _ => return Err(TzError::InvalidData("unknown version")),
+    };
+    try!(r.take(15));
+    Ok(Header {
+        version: version,
+        isutcnt: try!(r.be_u32()),
+        isstdcnt: try!(r.be_u32()),
+        leapcnt: try!(r.be_u32()),
+        timecnt: try!(r.be_u32()),
+        typecnt: try!(r.be_u32()),
+        charcnt: try!(r.be_u32()),
+    })
+}
+
+fn read_body(r: &mut Reader, header: &Header, size: uint) -> Result<TimeZone, TzError> {
+    if header.typecnt == 0 {
+        return Err(TzError::InvalidData("no local time types"));
+    }
+    if (header.isutcnt != 0 && header.isutcnt != header.typecnt) ||
+       (header.isstdcnt != 0 && header.isstdcnt != header.typecnt) {
+        return Err(TzError::InvalidData("indicator count doesn't match type count"));
+    }
+
+    let mut times = Vec::with_capacity(header.timecnt);
+    for _ in range(0, header.timecnt) {
+        times.push(try!(r.time(size)));
+    }
+    if times.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(TzError::InvalidData("transitions out of order"));
+    }
+    let mut transitions = Vec::with_capacity(header.timecnt);
+    for &at in times.iter() {
+        let ty = try!(r.u8()) as uint;
+        if ty >= header.typecnt {
+            return Err(TzError::InvalidData("transition to unknown type"));
+        }
+        transitions.push(Transition { at: at, ty: ty });
+    }
+
+    let mut infos = Vec::with_capacity(header.typecnt);
+    for _ in range(0, header.typecnt) {
+        let utoff = try!(r.be_i32());
+        let is_dst = match try!(r.u8()) {
+            0 => false,
+            1 => true,
+            _ => return Err(TzError::InvalidData("invalid DST flag")),
+        };
+        let idx = try!(r.u8()) as uint;
+        infos.push((utoff, is_dst, idx));
+    }
+    let chars = try!(r.take(header.charcnt));
+    let mut types = Vec::with_capacity(header.typecnt);
+    for &(utoff, is_dst, idx) in infos.iter() {
+        if idx >= chars.len() {
+            return Err(TzError::InvalidData("abbreviation out of range"));
+        }
+        let abbr = chars[idx..];
+        let abbr = match abbr.iter().position(|&b| b == 0) {
+            Some(end) => abbr[..end],
+            None => return Err(TzError::InvalidData("unterminated abbreviation")),
+        };
+        types.push(LocalTimeType {
+            utoff: utoff,
+            is_dst: is_dst,
+            abbreviation: String::from_utf8_lossy(abbr).into_owned(),
+        });
+    }
+
+    // Leap seconds and the standard/wall and UT/local indicators aren't used.
+    try!(r.take(header.leapcnt * (size + 4) + header.isstdcnt + header.isutcnt));
+
+    Ok(TimeZone {
+        name: String::new(),
+        transitions: transitions,
+        types: types,
+        rule: None,
+    })
+}
+
+/// A day of the year in a POSIX `TZ` rule.
+#[deriving(Clone, Copy, PartialEq, Show)]
+enum RuleDay {
+    /// `Jn`: day 1 to 365, not counting February 29.
+    Julian1(i64),
+    /// `n`: day 0 to 365, counting February 29.
+    Julian0(i64),
+    /// `Mm.w.d`: weekday `d` (0 is Sunday) of week `w` (1 to 5, 5 meaning
+    /// the last) of month `m`.
+    MonthWeekDay(i64, i64, i64),
+}
+
+impl RuleDay {
+    /// The day this rule picks in `year`, as days since the epoch.
+    fn day_in(&self, year: i64) -> i64 {
+        match *self {
+            RuleDay::Julian1(n) => {
+                let leap = is_leap_year(year) && n >= 60;
+                days_from_civil(year, 1, 1) + n - 1 + if leap { 1 } else { 0 }
+            }
+            RuleDay::Julian0(n) => days_from_civil(year, 1, 1) + n,
+            RuleDay::MonthWeekDay(month, week, weekday) => {
+                let first = days_from_civil(year, month, 1);
+                let next = if month == 12 {
+                    days_from_civil(year + 1, 1, 1)
+                } else {
+                    days_from_civil(year, month + 1, 1)
+                };
+                // 1970-01-01 was a Thursday.
+                let first_weekday = mod_floor(first + 4, 7);
+                let mut day = first + mod_floor(weekday - first_weekday, 7) + (week - 1) * 7;
+                while day >= next {
+                    day -= 7;
+                }
+                day
+            }
+        }
+    }
+}
+
+#[deriving(Clone, PartialEq, Show)]
+struct DstRule {
+    dst: LocalTimeType,
+    start: RuleDay,
+    /// Local (standard) time of day daylight saving time starts, in seconds.
+    start_time: i64,
+    end: RuleDay,
+    /// Local (daylight) time of day daylight saving time ends, in seconds.
+    end_time: i64,
+}
+
+/// A POSIX `TZ` string: `std offset [dst [offset] [,start[/time],end[/time]]]`.
+#[deriving(Clone, PartialEq, Show)]
+struct PosixTz {
+    std: LocalTimeType,
+    dst: Option<DstRule>,
+}
+
+impl PosixTz {
+    fn parse(s: &str) -> Option<PosixTz> {
+        let mut p = TzStringParser { s: s.as_bytes(), pos: 0 };
+        let std_name = try_opt!(p.name());
+        // POSIX offsets count hours west of Greenwich.
+        let std = LocalTimeType {
+            utoff: -try_opt!(p.time(24)) as i32,
+            is_dst: false,
+            abbreviation: std_name,
+        };
+        if p.at_end() {
+            return Some(PosixTz { std: std, dst: None });
+        }
+
+        let dst_name = try_opt!(p.name());
+        let dst_utoff = match p.peek() {
+            Some(b',') | None => std.utoff + 3600,
+            Some(_) => -try_opt!(p.time(24)) as i32,
+        };
+        if p.at_end() {
+            p = TzStringParser { s: DEFAULT_DST_RULE.as_bytes(), pos: 0 };
+        }
+        if !p.eat(b',') { return None }
+        let start = try_opt!(p.rule_day());
+        let start_time = if p.eat(b'/') { try_opt!(p.time(167)) } else { 7200 };
+        if !p.eat(b',') { return None }
+        let end = try_opt!(p.rule_day());
+        let end_time = if p.eat(b'/') { try_opt!(p.time(167)) } else { 7200 };
+        if !p.at_end() { return None }
+
+        Some(PosixTz {
+            std: std,
+            dst: Some(DstRule {
+                dst: LocalTimeType {
+                    utoff: dst_utoff,
+                    is_dst: true,
+                    abbreviation: dst_name,
+                },
+                start: start,
+                start_time: start_time,
+                end: end,
+                end_time: end_time,
+            }),
+        })
+    }
+
+    fn offset_at(&self, t: i64) -> &LocalTimeType {
+        let rule = match self.dst {
+            Some(ref rule) => rule,
+            None => return &self.std,
+        };
+        let (year, _, _) = civil_from_days(div_floor(t + self.std.utoff as i64, SECS_PER_DAY));
+        let start = rule.start.day_in(year) * SECS_PER_DAY + rule.start_time -
+                    self.std.utoff as i64;
+        let end = rule.end.day_in(year) * SECS_PER_DAY + rule.end_time -
+                  rule.dst.utoff as i64;
+        let in_dst = if start <= end {
+            start <= t && t < end
+        } else {
+            // Southern hemisphere: daylight time spans the new year.
+            !(end <= t && t < start)
+        };
+        if in_dst { &rule.dst } else { &self.std }
+    }
+}
+
+struct TzStringParser<'a> {
+    s: &'a [u8],
+    pos: uint,
+}
+
+impl<'a> TzStringParser<'a> {
+    fn at_end(&self) -> bool {
+        self.pos == self.s.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        if self.at_end() { None } else { Some(self.s[self.pos]) }
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// A zone abbreviation: three or more letters, or anything but `>`
+    /// between angle brackets.
+    fn name(&mut self) -> Option<String> {
+        let start = self.pos;
+        let name = if self.eat(b'<') {
+            while self.peek().map_or(false, |c| c != b'>') {
+                self.pos += 1;
+            }
+            let name = self.s[start + 1..self.pos];
+            if !self.eat(b'>') { return None }
+            name
+        } else {
+            while self.peek().map_or(false, |c| (c as char).is_alphabetic()) {
+                self.pos += 1;
+            }
+            self.s[start..self.pos]
+        };
+        if name.len() < 3 { return None }
+        Some(String::from_utf8_lossy(name).into_owned())
+    }
+
+    fn number(&mut self) -> Option<i64> {
+        let start = self.pos;
+        let mut n = 0i64;
+        while is_digit(self.peek()) {
+            n = n * 10 + (self.s[self.pos] - b'0') as i64;
+            self.pos += 1;
+            if self.pos - start > 3 { return None }
+        }
+        if self.pos == start { None } else { Some(n) }
+    }
+
+    /// `[+-]hh[:mm[:ss]]`, in seconds, with at most `max_hours` hours.
+    fn time(&mut self, max_hours: i64) -> Option<i64> {
+        let sign = if self.eat(b'-') { -1 } else { self.eat(b'+'); 1 };
+        let hours = try_opt!(self.number());
+        let minutes = if self.eat(b':') { try_opt!(self.number()) } else { 0 };
+        let seconds = if self.eat(b':') { try_opt!(self.number()) } else { 0 };
+        if hours > max_hours || minutes > 59 || seconds > 59 { return None }
+        Some(sign * (hours * 3600 + minutes * 60 + seconds))
+    }
+
+    fn rule_day(&mut self) -> Option<RuleDay> {
+        if self.eat(b'J') {
+            let n = try_opt!(self.number());
+            if n < 1 || n > 365 { return None }
+            Some(RuleDay::Julian1(n))
+        } else if self.eat(b'M') {
+            let month = try_opt!(self.number());
+            if !self.eat(b'.') { return None }
+            let week = try_opt!(self.number());
+            if !self.eat(b'.') { return None }
+            let weekday = try_opt!(self.number());
+            if month < 1 || month > 12 || week < 1 || week > 5 || weekday > 6 {
+                return None
+            }
+            Some(RuleDay::MonthWeekDay(month, week, weekday))
+        } else {
+            let n = try_opt!(self.number());
+            if n > 365 { return None }
+            Some(RuleDay::Julian0(n))
+        }
+    }
+}
+
+fn is_digit(c: Option<u8>) -> bool {
+    c.map_or(false, |c| c >= b'0' && c <= b'9')
+}
+
+impl Tm {
+    /// Convert time to the seconds from January 1, 1970, taking it to be at
+    /// the offset `tm_gmtoff` from UTC
+    ///
+    /// `to_timespec` takes any time with a non-zero `tm_gmtoff` to be in the
+    /// local timezone, which is wrong for times from a `TimeZone`.
+    pub fn to_timespec_at_offset(&self) -> Timespec {
+        Timespec::new(civil_seconds(self) - self.tm_gmtoff as i64, self.tm_nsec)
+    }
+}
+
+/// The wall clock time in `tm` as seconds since the epoch, as if it were UTC.
+/// Out of range fields carry into the next larger unit.
+fn civil_seconds(tm: &Tm) -> i64 {
+    let month = tm.tm_mon as i64;
+    let year = tm.tm_year as i64 + 1900 + div_floor(month, 12);
+    let days = days_from_civil(year, mod_floor(month, 12) + 1, 1) + tm.tm_mday as i64 - 1;
+    days * SECS_PER_DAY + tm.tm_hour as i64 * 3600 + tm.tm_min as i64 * 60 + tm.tm_sec as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TimeZone, TzError};
+    use {Timespec, Tm, empty_tm};
+
+    static NEW_YORK: &'static [u8] = include_bin!("tzdata/America_New_York");
+    static SYDNEY: &'static [u8] = include_bin!("tzdata/Australia_Sydney");
+    static SLIM_EASTERN: &'static [u8] = include_bin!("tzdata/Test_Eastern");
+
+    fn zone(name: &str, data: &[u8]) -> TimeZone {
+        TimeZone::from_tzif(name, data).unwrap()
+    }
+
+    fn wall_clock(year: i32, mon: i32, mday: i32, hour: i32, min: i32, isdst: i32) -> Tm {
+        Tm {
+            tm_year: year - 1900,
+            tm_mon: mon - 1,
+            tm_mday: mday,
+            tm_hour: hour,
+            tm_min: min,
+            tm_isdst: isdst,
+            ..empty_tm()
+        }
+    }
+
+    fn check(zone: &TimeZone, sec: i64, hour: i32, utoff: i32, abbr: &str) {
+        let clock = Timespec::new(sec, 0);
+        let ty = zone.offset_at(clock);
+        assert_eq!(ty.utoff, utoff);
+        assert_eq!(ty.abbreviation[], abbr);
+        assert_eq!(ty.is_dst, abbr.ends_with("DT"));
+
+        let tm = zone.at(clock);
+        assert_eq!(tm.tm_hour, hour);
+        assert_eq!(tm.tm_gmtoff, utoff);
+        assert_eq!(tm.tm_isdst, if ty.is_dst { 1 } else { 0 });
+        assert_eq!(tm.to_timespec_at_offset(), clock);
+    }
+
+    #[test]
+    fn test_new_york() {
+        let ny = zone("America/New_York", NEW_YORK);
+        assert_eq!(ny.name(), "America/New_York");
+        check(&ny, 1389787200, 7, -18000, "EST"); // 2014-01-15T12:00:00Z
+        check(&ny, 1405425600, 8, -14400, "EDT"); // 2014-07-15T12:00:00Z
+
+        // Clocks went forward at 2014-03-09T07:00:00Z.
+        check(&ny, 1394348400 - 1, 1, -18000, "EST");
+        check(&ny, 1394348400, 3, -14400, "EDT");
+
+        // Past the file's last transition the footer's rule applies.
+        check(&ny, 2540289600, 8, -14400, "EDT"); // 2050-07-01T12:00:00Z
+
+        // Before the first transition, local mean time.
+        assert_eq!(ny.offset_at(Timespec::new(-3000000000, 0)).abbreviation[], "LMT");
+    }
+
+    #[test]
+    fn test_sydney() {
+        let sydney = zone("Australia/Sydney", SYDNEY);
+        check(&sydney, 1389744000, 11, 39600, "AEDT"); // 2014-01-15T00:00:00Z
+        check(&sydney, 1405382400, 10, 36000, "AEST"); // 2014-07-15T00:00:00Z
+        check(&sydney, 1396713600 - 1, 2, 39600, "AEDT");
+        check(&sydney, 1396713600, 2, 36000, "AEST");
+    }
+
+    #[test]
+    fn test_slim_file_uses_footer() {
+        // Built by `zic -b slim`: a single transition, with everything after
+        // it described by the footer `EST5EDT,M3.2.0,M11.1.0`.
+        let eastern = zone("Test/Eastern", SLIM_EASTERN);
+        check(&eastern, 1389787200, 7, -18000, "EST");
+        check(&eastern, 1405425600, 8, -14400, "EDT");
+        check(&eastern, 1394348400 - 1, 1, -18000, "EST");
+        check(&eastern, 1394348400, 3, -14400, "EDT");
+        check(&eastern, 2540289600, 8, -14400, "EDT");
+    }
+
+    #[test]
+    fn test_wall_clock_to_timespec() {
+        let ny = zone("America/New_York", NEW_YORK);
+        let at = |tm: Tm| ny.to_timespec(&tm).sec;
+
+        assert_eq!(at(wall_clock(2014, 1, 15, 7, 0, -1)), 1389787200);
+        assert_eq!(at(wall_clock(2014, 7, 15, 8, 0, -1)), 1405425600);
+
+        // 01:30 happened twice on 2014-11-02.
+        assert_eq!(at(wall_clock(2014, 11, 2, 1, 30, -1)), 1414906200);
+        assert_eq!(at(wall_clock(2014, 11, 2, 1, 30, 1)), 1414906200);
+        assert_eq!(at(wall_clock(2014, 11, 2, 1, 30, 0)), 1414909800);
+
+        // 02:30 never happened on 2014-03-09.
+        let skipped = ny.to_timespec(&wall_clock(2014, 3, 9, 2, 30, -1));
+        assert_eq!(skipped.sec, 1394350200);
+        assert_eq!(ny.at(skipped).tm_hour, 3);
+
+        // Out of range fields are normalized.
+        assert_eq!(at(wall_clock(2013, 13, 15, 7, 0, -1)), 1389787200);
+    }
+
+    #[test]
+    fn test_to_zone() {
+        let ny = zone("America/New_York", NEW_YORK);
+        let sydney = zone("Australia/Sydney", SYDNEY);
+        let clock = Timespec::new(1405425600, 123);
+
+        let tm = ny.at(clock).to_zone(&sydney);
+        assert_eq!(tm.tm_hour, 22);
+        assert_eq!(tm.tm_gmtoff, 36000);
+        assert_eq!(tm.tm_nsec, 123);
+        assert_eq!(tm.to_timespec_at_offset(), clock);
+        assert_eq!(tm.to_zone(&TimeZone::utc()).tm_hour, 12);
+    }
+
+    #[test]
+    fn test_posix_tz() {
+        let eastern = TimeZone::from_posix_tz("EST5EDT").unwrap();
+        check(&eastern, 1389787200, 7, -18000, "EST");
+        check(&eastern, 1405425600, 8, -14400, "EDT");
+
+        let sydney = TimeZone::from_posix_tz("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        check(&sydney, 1389744000, 11, 39600, "AEDT");
+        check(&sydney, 1405382400, 10, 36000, "AEST");
+        check(&sydney, 1396713600 - 1, 2, 39600, "AEDT");
+        check(&sydney, 1396713600, 2, 36000, "AEST");
+        check(&sydney, 1412438400 - 1, 1, 36000, "AEST");
+        check(&sydney, 1412438400, 3, 39600, "AEDT");
+
+        let india = TimeZone::from_posix_tz("<+0530>-5:30").unwrap();
+        let ty = india.offset_at(Timespec::new(0, 0));
+        assert_eq!(ty.utoff, 19800);
+        assert_eq!(ty.abbreviation[], "+0530");
+
+        for spec in ["", "EST", "E5", "EST5EDT,M3.2.0", "EST5EDT,M13.1.0,M11.1.0",
+                     "EST5EDT,J0,J365", "EST25", "<EST5"].iter() {
+            assert_eq!(TimeZone::from_posix_tz(*spec),
+                       Err(TzError::InvalidTzString(spec.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_invalid_tzif() {
+        assert_eq!(TimeZone::from_tzif("x", b"TZ"), Err(TzError::InvalidMagic));
+        assert_eq!(TimeZone::from_tzif("x", b"NOPE2"), Err(TzError::InvalidMagic));
+        assert_eq!(TimeZone::from_tzif("x", NEW_YORK[..100]), Err(TzError::Truncated));
+
+        let mut bad_version = NEW_YORK.to_vec();
+        bad_version[4] = b'x';
+        assert_eq!(TimeZone::from_tzif("x", bad_version[]),
+                   Err(TzError::InvalidData("unknown version")));
+
+        // Strip the footer's trailing newline.
+        let unterminated = NEW_YORK[..NEW_YORK.len() - 1];
+        assert_eq!(TimeZone::from_tzif("x", unterminated), Err(TzError::Truncated));
+    }
+
+    #[test]
+    fn test_named() {
+        assert_eq!(TimeZone::named("../etc/passwd"),
+                   Err(TzError::UnknownZone("../etc/passwd".to_string())));
+        assert_eq!(TimeZone::named("/etc/passwd"),
+                   Err(TzError::UnknownZone("/etc/passwd".to_string())));
+        assert_eq!(TimeZone::named("No/Such_Zone"),
+                   Err(TzError::UnknownZone("No/Such_Zone".to_string())));
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Runtime support for `-Z coverage`.
+//
+// LLVM's insert-gcov-profiling pass adds a counter to every edge of the
+// control flow graph and emits, per object file, a writeout function which
+// calls the `llvm_gcda_*` functions below to dump those counters to a .gcda
+// file. The object's constructor hands that function to `llvm_gcov_init`,
+// and we run every registered writeout function at exit.
+//
+// If a .gcda file already exists (from a previous run of the same binary)
+// its counters are added to ours, just like gcc's libgcov does, so running a
+// program several times accumulates counts.
+
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+typedef void (*writeout_fn)(void);
+typedef void (*flush_fn)(void);
+
+#define GCOV_TAG_FUNCTION 0x01000000
+#define GCOV_TAG_COUNTER_ARCS 0x01a10000
+#define GCOV_TAG_OBJECT_SUMMARY 0xa1000000
+#define GCOV_TAG_PROGRAM_SUMMARY 0xa3000000
+
+// The file currently being written. The contents of the previous .gcda file
+// (if any) are kept in `old`; since the same binary always writes the same
+// records in the same order, the old value for any word lives at the same
+// offset as the word we are about to write.
+static char *filename = NULL;
+static uint8_t *buf = NULL;
+static size_t buf_len = 0;
+static size_t buf_cap = 0;
+static uint8_t *old = NULL;
+static size_t old_len = 0;
+
+static struct fn_node {
+    writeout_fn writeout;
+    flush_fn flush;
+    struct fn_node *next;
+} *fn_list = NULL;
+
+static void
+write_bytes(const void *bytes, size_t len) {
+    if (buf_len + len > buf_cap) {
+        size_t cap = buf_cap == 0 ? 1024 : buf_cap;
+        while (buf_len + len > cap)
+            cap *= 2;
+        uint8_t *new_buf = realloc(buf, cap);
+        if (new_buf == NULL) {
+            fprintf(stderr, "profiling: %s: out of memory\n", filename);
+            abort();
+        }
+        buf = new_buf;
+        buf_cap = cap;
+    }
+    memcpy(buf + buf_len, bytes, len);
+    buf_len += len;
+}
+
+static void
+write_32bit_value(uint32_t value) {
+    write_bytes(&value, sizeof(value));
+}
+
+static void
+write_64bit_value(uint64_t value) {
+    write_32bit_value((uint32_t)(value & 0xffffffff));
+    write_32bit_value((uint32_t)(value >> 32));
+}
+
+static void
+write_string(const char *s) {
+    uint32_t len = strlen(s);
+    uint32_t words = len / 4 + 1;
+    uint32_t zero = 0;
+    write_32bit_value(words);
+    write_bytes(s, len);
+    write_bytes(&zero, words * 4 - len);
+}
+
+// Reads the word of the previous run at offset `pos`, or returns 0xffffffff
+// if the old file doesn't extend that far.
+static uint32_t
+read_32bit_value(size_t pos) {
+    uint32_t value;
+    if (old == NULL || pos + sizeof(value) > old_len)
+        return (uint32_t)-1;
+    memcpy(&value, old + pos, sizeof(value));
+    return value;
+}
+
+static uint64_t
+read_64bit_value(size_t pos) {
+    uint64_t lo = read_32bit_value(pos);
+    uint64_t hi = read_32bit_value(pos + 4);
+    return lo | (hi << 32);
+}
+
+static void
+read_old_file(void) {
+    FILE *f = fopen(filename, "rb");
+    long len;
+    if (f == NULL)
+        return;
+    if (fseek(f, 0, SEEK_END) == 0 && (len = ftell(f)) > 0 &&
+        fseek(f, 0, SEEK_SET) == 0) {
+        old = malloc(len);
+        if (old != NULL && fread(old, 1, len, f) == (size_t)len) {
+            old_len = len;
+        } else {
+            free(old);
+            old = NULL;
+        }
+    }
+    fclose(f);
+}
+
+void
+llvm_gcda_start_file(const char *orig_filename, const char version[4],
+                     uint32_t checksum) {
+    filename = strdup(orig_filename);
+    buf_len = 0;
+    read_old_file();
+
+    // A file written by a different build of the program can't be merged.
+    if (old != NULL && (old_len < 12 || memcmp(old + 4, version, 4) != 0 ||
+                        read_32bit_value(8) != checksum)) {
+        free(old);
+        old = NULL;
+        old_len = 0;
+    }
+
+    write_bytes("adcg", 4);
+    write_bytes(version, 4);
+    write_32bit_value(checksum);
+}
+
+void
+llvm_gcda_emit_function(uint32_t ident, const char *function_name,
+                        uint32_t func_checksum, uint8_t use_extra_checksum,
+                        uint32_t cfg_checksum) {
+    uint32_t len = 2;
+    if (use_extra_checksum)
+        len++;
+    if (function_name)
+        len += 1 + strlen(function_name) / 4 + 1;
+
+    write_32bit_value(GCOV_TAG_FUNCTION);
+    write_32bit_value(len);
+    write_32bit_value(ident);
+    write_32bit_value(func_checksum);
+    if (use_extra_checksum)
+        write_32bit_value(cfg_checksum);
+    if (function_name)
+        write_string(function_name);
+}
+
+void
+llvm_gcda_emit_arcs(uint32_t num_counters, uint64_t *counters) {
+    uint32_t i;
+    size_t pos = buf_len;
+    int merge = read_32bit_value(pos) == GCOV_TAG_COUNTER_ARCS &&
+                read_32bit_value(pos + 4) == num_counters * 2;
+
+    write_32bit_value(GCOV_TAG_COUNTER_ARCS);
+    write_32bit_value(num_counters * 2);
+    for (i = 0; i < num_counters; i++) {
+        uint64_t count = counters[i];
+        if (merge)
+            count += read_64bit_value(pos + 8 + i * 8);
+        write_64bit_value(count);
+    }
+}
+
+void
+llvm_gcda_summary_info(void) {
+    const uint32_t obj_summary_len = 9;
+    uint32_t i;
+    uint32_t runs = 1;
+    size_t pos = buf_len;
+
+    if (read_32bit_value(pos) == GCOV_TAG_OBJECT_SUMMARY &&
+        read_32bit_value(pos + 4) == obj_summary_len)
+        runs += read_32bit_value(pos + 16);
+
+    write_32bit_value(GCOV_TAG_OBJECT_SUMMARY);
+    write_32bit_value(obj_summary_len);
+    write_32bit_value(0); // checksum, unused
+    write_32bit_value(0); // number of counters, unused
+    write_32bit_value(runs);
+    for (i = 3; i < obj_summary_len; i++)
+        write_32bit_value(0);
+
+    write_32bit_value(GCOV_TAG_PROGRAM_SUMMARY);
+    write_32bit_value(0);
+}
+
+void
+llvm_gcda_end_file(void) {
+    FILE *f;
+    uint64_t eof = 0;
+    write_64bit_value(eof);
+
+    f = fopen(filename, "wb");
+    if (f == NULL || fwrite(buf, 1, buf_len, f) != buf_len) {
+        fprintf(stderr, "profiling: %s: cannot write coverage data\n",
+                filename);
+    }
+    if (f != NULL)
+        fclose(f);
+
+    free(filename);
+    filename = NULL;
+    free(old);
+    old = NULL;
+    old_len = 0;
+}
+
+void
+llvm_writeout_files(void) {
+    struct fn_node *node;
+    for (node = fn_list; node != NULL; node = node->next)
+        node->writeout();
+}
+
+// Writes out and then resets the counters of every instrumented object, e.g.
+// before a fork or exec.
+void
+__gcov_flush(void) {
+    struct fn_node *node;
+    for (node = fn_list; node != NULL; node = node->next)
+        node->flush();
+}
+
+void
+llvm_gcov_init(writeout_fn wfn, flush_fn ffn) {
+    static int registered = 0;
+    struct fn_node *node = malloc(sizeof(*node));
+    if (node == NULL)
+        return;
+    node->writeout = wfn;
+    node->flush = ffn;
+    node->next = fn_list;
+    fn_list = node;
+
+    if (!registered) {
+        atexit(llvm_writeout_files);
+        registered = 1;
+    }
+}
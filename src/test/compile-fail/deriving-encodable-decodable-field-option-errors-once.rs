@@ -0,0 +1,29 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Both derives read the options, but each error is reported once.
+
+extern crate serialize;
+
+#[derive(Encodable, Decodable)]
+struct A {
+    #[serialize(renamed = "b")] //~ ERROR unknown serialize option
+    a: uint,
+    #[serialize] //~ ERROR expected `#[serialize(...)]`
+    c: uint,
+}
+
+#[derive(Decodable)]
+struct B {
+    #[serialize(skip, unknown)] //~ ERROR unknown serialize option
+    a: uint,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate serialize;
+
+#[derive(Encodable, Decodable)]
+enum A {
+    B { #[serialize(skip)] b: uint }, //~ ERROR not supported on fields of enum variants
+    C { #[serialize(rename = "d")] c: uint }, //~ ERROR not supported on fields of enum variants
+}
+
+#[derive(Decodable)]
+enum E {
+    F { #[serialize(skip)] f: uint }, //~ ERROR not supported on fields of enum variants
+}
+
+#[derive(Encodable)]
+enum G {
+    H { #[serialize(skip)] h: uint }, //~ ERROR not supported on fields of enum variants
+}
+
+fn main() {}
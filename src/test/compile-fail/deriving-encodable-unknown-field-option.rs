@@ -0,0 +1,21 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate serialize;
+
+#[derive(Encodable)]
+struct A {
+    #[serialize(renamed = "b")] //~ ERROR unknown serialize option
+    a: uint,
+    #[serialize] //~ ERROR expected `#[serialize(...)]`
+    c: uint,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --test
+
+#[before_each]
+fn setup(x: int) { } //~ ERROR functions used as #[before_each] or #[after_each] hooks
+
+#[after_each]
+fn teardown() -> int { 0 } //~ ERROR functions used as #[before_each] or #[after_each] hooks
+
+#[test]
+fn foo() { }
@@ -0,0 +1,34 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn classify(x: int, counts: &mut [uint; 3]) {
+    if x < 0 {
+        counts[0] += 1; // count: 2
+    } else if x == 0 {
+        counts[1] += 1; // count: 1
+    } else {
+        counts[2] += 1; // count: 3
+    }
+}
+
+fn never_called(counts: &mut [uint; 3]) {
+    counts[0] = 0; // count: #####
+}
+
+fn main() {
+    let mut counts = [0u; 3]; // count: 1
+    for &x in [-2, -1, 0, 1, 2, 3].iter() {
+        classify(x, &mut counts);
+    }
+    if counts[0] > 10 {
+        never_called(&mut counts); // count: #####
+    }
+    assert_eq!(counts, [2, 1, 3]);
+}
@@ -0,0 +1,27 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn sum_to(n: uint) -> uint {
+    let mut total = 0; // count: 3
+    let mut i = 0;
+    while i < n {
+        total += i; // count: 15
+        i += 1;
+    }
+    total
+}
+
+fn main() {
+    let mut sums = Vec::new(); // count: 1
+    for &n in [5u, 10, 0].iter() {
+        sums.push(sum_to(n));
+    }
+    assert_eq!(sums, vec![10, 45, 0]);
+}
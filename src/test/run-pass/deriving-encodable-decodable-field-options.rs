@@ -0,0 +1,80 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test the `#[serialize(...)]` field options of deriving(Encodable, Decodable).
+
+#![feature(old_orphan_check)]
+
+extern crate serialize;
+
+use serialize::{Encodable, Decodable};
+use serialize::{json, msgpack};
+
+mod defaults {
+    pub fn port() -> u16 { 8080 }
+}
+
+#[derive(Encodable, Decodable, Clone, PartialEq, Show)]
+struct Config {
+    #[serialize(rename = "name")]
+    title: String,
+    #[serialize(default)]
+    retries: uint,
+    #[serialize(default = "defaults::port")]
+    port: u16,
+    #[serialize(skip)]
+    cache: Vec<u8>,
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Encodable, Decodable, PartialEq, Show)]
+struct Pair(uint, #[serialize(skip)] uint, String);
+
+fn main() {
+    let config = Config {
+        title: "example".to_string(),
+        retries: 3,
+        port: 80,
+        cache: vec![1, 2, 3],
+        tags: None,
+    };
+
+    // Renamed fields are stored under their new name, skipped ones not at all.
+    let s = json::encode(&config);
+    assert_eq!(s, r#"{"name":"example","retries":3,"port":80,"tags":null}"#);
+    let decoded: Config = json::decode(s.as_slice()).unwrap();
+    assert_eq!(decoded, Config { cache: vec![], ..config.clone() });
+
+    // Data written before fields were added still decodes.
+    let old: Config = json::decode(r#"{"name":"old"}"#).unwrap();
+    assert_eq!(old, Config {
+        title: "old".to_string(),
+        retries: 0,
+        port: 8080,
+        cache: vec![],
+        tags: None,
+    });
+    let old: Config = json::decode_stream(r#"{"name":"old"}"#).unwrap();
+    assert_eq!(old.port, 8080);
+
+    // Fields without a default are still required.
+    assert!(json::decode::<Config>(r#"{"retries":1}"#).is_err());
+
+    // Positional formats agree on the fields that are stored.
+    let bytes = msgpack::encode(&config);
+    let decoded: Config = msgpack::decode(bytes.as_slice()).unwrap();
+    assert_eq!(decoded, Config { cache: vec![], ..config.clone() });
+
+    let pair = Pair(1, 2, "x".to_string());
+    let s = json::encode(&pair);
+    assert_eq!(s, r#"{"_field0":1,"_field2":"x"}"#);
+    let decoded: Pair = json::decode(s.as_slice()).unwrap();
+    assert_eq!(decoded, Pair(1, 0, "x".to_string()));
+}
@@ -0,0 +1,62 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-android (FIXME #11419)
+// exec-env:RUST_LOG=info
+
+#![feature(phase)]
+
+#[phase(plugin, link)]
+extern crate log;
+
+use log::{set_logger, Logger, LogRecord};
+use log::json::JsonLogger;
+use std::sync::mpsc::channel;
+use std::io::{ChanReader, ChanWriter};
+use std::thread::Thread;
+
+struct MyWriter(ChanWriter);
+
+impl Logger for MyWriter {
+    fn log(&mut self, record: &LogRecord) {
+        let MyWriter(ref mut inner) = *self;
+        write!(inner, "{}", record.args);
+        for &(key, value) in record.kvs.iter() {
+            write!(inner, " {}={}", key, value);
+        }
+        write!(inner, "\n");
+    }
+}
+
+fn main() {
+    let (tx, rx) = channel();
+    let (mut r, w) = (ChanReader::new(rx), ChanWriter::new(tx));
+    let _t = Thread::spawn(move|| {
+        set_logger(box MyWriter(w) as Box<Logger+Send>);
+        let name = "alice".to_string();
+        info!(user = name, attempts = 3u, admin = false; "login {}", "failed");
+        info!("no fields {}", 1i);
+        debug!(hidden = 1i; "debug");
+    });
+    let s = r.read_to_string().unwrap();
+    assert_eq!(s, "login failed user=\"alice\" attempts=3 admin=false\nno fields 1\n");
+
+    let (tx, rx) = channel();
+    let (mut r, w) = (ChanReader::new(rx), ChanWriter::new(tx));
+    let _t = Thread::spawn(move|| {
+        set_logger(box JsonLogger::new(w) as Box<Logger+Send>);
+        warn!(ratio = 0.5f64; "json");
+    });
+    let s = r.read_to_string().unwrap();
+    assert!(s.starts_with("{\"fields\":{\"ratio\":0.5},"));
+    assert!(s.contains("\"level\":\"WARN\""));
+    assert!(s.contains("\"message\":\"json\""));
+    assert!(s.ends_with("}\n"));
+}
@@ -0,0 +1,70 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-android (FIXME #11419)
+// exec-env:RUST_LOG=warn
+
+#![feature(phase)]
+
+#[phase(plugin, link)]
+extern crate log;
+
+use log::{Logger, LogRecord};
+use std::sync::{Arc, Mutex};
+use std::thread::Thread;
+
+struct Collect(Arc<Mutex<Vec<String>>>);
+
+impl Logger for Collect {
+    fn log(&mut self, record: &LogRecord) {
+        let Collect(ref lines) = *self;
+        lines.lock().unwrap().push(format!("{}:{}", record.level, record.args));
+    }
+}
+
+fn main() {
+    let all = Arc::new(Mutex::new(Vec::new()));
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let all_id = log::add_sink(log::DEBUG, box Collect(all.clone()));
+    let errors_id = log::add_sink(log::ERROR, box Collect(errors.clone()));
+
+    // RUST_LOG=warn lets warnings through to both sinks' filters.
+    warn!("one");
+    info!("two");
+    error!("three");
+
+    // Raising the directives at runtime is seen by other tasks too.
+    log::set_directives("info");
+    Thread::spawn(move|| {
+        info!("four");
+        debug!("five");
+    }).join().ok().unwrap();
+
+    assert!(log::set_sink_level(errors_id, log::INFO));
+    info!("six");
+
+    assert!(log::remove_sink(all_id).is_some());
+    assert!(log::remove_sink(all_id).is_none());
+    assert!(!log::set_sink_level(all_id, log::INFO));
+    warn!("seven");
+
+    // A task-local logger still takes precedence over the sinks.
+    let local = Arc::new(Mutex::new(Vec::new()));
+    log::set_logger(box Collect(local.clone()));
+    error!("eight");
+
+    assert_eq!(*all.lock().unwrap(),
+               vec!["WARN:one".to_string(), "ERROR:three".to_string(),
+                    "INFO:four".to_string(), "INFO:six".to_string()]);
+    assert_eq!(*errors.lock().unwrap(),
+               vec!["ERROR:three".to_string(), "INFO:six".to_string(),
+                    "WARN:seven".to_string()]);
+    assert_eq!(*local.lock().unwrap(), vec!["ERROR:eight".to_string()]);
+}
@@ -0,0 +1,79 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --test
+// ignore-pretty: does not work well with `--test`
+
+#[test]
+#[case = "1, 2, 3"]
+#[case = "-1, 1, 0"]
+#[case(name = "zeroes", args = "0, 0, 0")]
+fn add(a: int, b: int, sum: int) {
+    assert_eq!(a + b, sum);
+}
+
+#[test]
+#[case = "\"abc\", 3"]
+#[case = "\"\", 0"]
+fn len(s: &str, n: uint) {
+    assert_eq!(s.len(), n);
+}
+
+#[test]
+#[should_fail]
+#[case = "1, 2"]
+fn different(a: int, b: int) {
+    assert_eq!(a, b);
+}
+
+mod fixtures {
+    use std::cell::Cell;
+
+    thread_local!(static STATE: Cell<uint> = Cell::new(0));
+
+    #[before_each]
+    fn setup() {
+        STATE.with(|s| s.set(1));
+    }
+
+    #[after_each]
+    fn teardown() {
+        STATE.with(|s| assert_eq!(s.get(), 2));
+    }
+
+    #[test]
+    fn runs_after_setup() {
+        STATE.with(|s| {
+            assert_eq!(s.get(), 1);
+            s.set(2);
+        });
+    }
+
+    #[test]
+    #[case = "2"]
+    fn cases_run_after_setup(n: uint) {
+        STATE.with(|s| {
+            assert_eq!(s.get(), 1);
+            s.set(n);
+        });
+    }
+
+    mod nested {
+        use super::STATE;
+
+        #[test]
+        fn inherits_hooks() {
+            STATE.with(|s| {
+                assert_eq!(s.get(), 1);
+                s.set(2);
+            });
+        }
+    }
+}
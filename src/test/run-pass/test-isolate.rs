@@ -0,0 +1,95 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-android: the test executable can't rerun itself there
+
+// Runs tests with `--isolate`, each in a child process which is this
+// executable run again.
+
+extern crate libc;
+extern crate test;
+
+use std::io::timer;
+use std::os;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+use test::{TestDesc, TestDescAndFn, StaticTestFn, StaticTestName, ShouldFail};
+use test::{TrOk, TrFailed};
+
+fn passes() {
+    println!("hello from the child");
+}
+
+fn exits() {
+    unsafe { libc::exit(0) }
+}
+
+fn hangs() {}
+
+fn tests() -> Vec<TestDescAndFn> {
+    let test = |name: &'static str, timeout: Option<u64>, f: fn()| TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName(name),
+            ignore: false,
+            should_fail: ShouldFail::No,
+            timeout: timeout,
+        },
+        testfn: StaticTestFn(f),
+    };
+    vec![test("passes", None, passes),
+         test("exits", None, exits),
+         test("hangs", Some(1), hangs)]
+}
+
+fn main() {
+    let args = vec!["test-isolate".to_string(), "--isolate".to_string()];
+
+    match os::getenv("RUST_TEST_ISOLATED") {
+        Some(ref name) if name.as_slice() == "hangs" => {
+            // Stuck before the test even starts, where only the parent's
+            // timeout can catch it.
+            loop {
+                timer::sleep(Duration::seconds(60));
+            }
+        }
+        Some(_) => {
+            test::test_main(args.as_slice(), tests());
+            return;
+        }
+        None => {}
+    }
+
+    let mut opts = test::parse_opts(args.as_slice()).unwrap().unwrap();
+    let run = |opts: &test::TestOpts, test: TestDescAndFn| {
+        let (tx, rx) = channel();
+        test::run_test(opts, false, test, tx);
+        let (_, result, stdout, _) = rx.recv().unwrap();
+        (result, String::from_utf8(stdout).unwrap())
+    };
+    let mut isolated = tests().into_iter();
+
+    let (result, stdout) = run(&opts, isolated.next().unwrap());
+    assert!(result == TrOk);
+    assert_eq!(stdout.as_slice(), "hello from the child\n");
+
+    let (result, stdout) = run(&opts, isolated.next().unwrap());
+    assert!(result == TrFailed);
+    assert!(stdout.as_slice().contains("terminated abnormally"));
+
+    let (result, stdout) = run(&opts, isolated.next().unwrap());
+    assert!(result == TrFailed);
+    assert!(stdout.as_slice().contains("timed out after 1.000s and was killed"));
+
+    // With --nocapture the child's output is passed on rather than kept.
+    opts.nocapture = true;
+    let (result, stdout) = run(&opts, tests().into_iter().next().unwrap());
+    assert!(result == TrOk);
+    assert_eq!(stdout.as_slice(), "");
+}